@@ -62,6 +62,8 @@ impl Module for UsersInfo {
     async fn init(&self, ctx: &ModuleCtx) -> anyhow::Result<()> {
         info!("Initializing {} module", Self::MODULE_NAME);
 
+        crate::errors::ErrorCode::register_catalog();
+
         // Load module configuration using new API
         let cfg: UsersInfoConfig = ctx.config()?;
         debug!(
@@ -1,15 +1,21 @@
 //! `OData` (filters) → `sea_orm::Condition` compiler (AST in, SQL out).
 //! Parsing belongs to API/gateway. This module only consumes `modkit_odata::ast::Expr`.
 
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 use bigdecimal::{BigDecimal, ToPrimitive};
 use chrono::{NaiveDate, NaiveTime, Utc};
-use modkit_odata::{CursorV1, Error as ODataError, ODataOrderBy, ODataQuery, SortDir, ast as core};
+use modkit_odata::{
+    ComputeExpr, ComputeOp, ComputeOperand, CursorV1, Error as ODataError, ODataOrderBy,
+    ODataQuery, OrderKey, SortDir, ast as core,
+};
 use rust_decimal::Decimal;
 use sea_orm::{
-    ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
-    sea_query::{Expr, Order},
+    ColumnTrait, Condition, DatabaseBackend, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    QueryTrait,
+    sea_query::{Asterisk, DynIden, Expr, Func, IntoIden, Order, Query, SimpleExpr},
 };
 use thiserror::Error;
 
@@ -28,10 +34,23 @@ pub struct Field<E: EntityTrait> {
     pub to_string_for_cursor: Option<CursorExtractor<E>>,
 }
 
+/// A one-to-many relation exposed to `$filter` as `name/$count`.
+#[derive(Clone)]
+pub struct CollectionField<E: EntityTrait> {
+    /// Table holding the collection elements.
+    pub table: DynIden,
+    /// Column of `table` referencing the parent row.
+    pub foreign_key: DynIden,
+    /// Parent column referenced by `foreign_key`.
+    pub parent_key: E::Column,
+}
+
 #[derive(Clone)]
 #[must_use]
 pub struct FieldMap<E: EntityTrait> {
     map: HashMap<String, Field<E>>,
+    collections: HashMap<String, CollectionField<E>>,
+    prefix_ranges: bool,
 }
 
 impl<E: EntityTrait> Default for FieldMap<E> {
@@ -44,8 +63,23 @@ impl<E: EntityTrait> FieldMap<E> {
     pub fn new() -> Self {
         Self {
             map: HashMap::new(),
+            collections: HashMap::new(),
+            prefix_ranges: false,
         }
     }
+
+    /// Compile `startswith(tolower(field), 'prefix')` to the range predicate
+    /// `LOWER(field) >= 'prefix' AND LOWER(field) < 'prefiy'` instead of
+    /// `LOWER(field) LIKE 'prefix%'`, so a functional index on `LOWER(field)`
+    /// can serve it on large tables.
+    ///
+    /// The range follows code-point order: the index must use a binary
+    /// collation (e.g. `C`, or `text_pattern_ops` on Postgres) for the range
+    /// to select exactly the rows the `LIKE` would.
+    pub fn with_prefix_ranges(mut self, enabled: bool) -> Self {
+        self.prefix_ranges = enabled;
+        self
+    }
     pub fn insert(mut self, api_name: impl Into<String>, col: E::Column, kind: FieldKind) -> Self {
         self.map.insert(
             api_name.into().to_lowercase(),
@@ -76,6 +110,25 @@ impl<E: EntityTrait> FieldMap<E> {
         self
     }
 
+    /// Register a collection whose elements are rows of `C` pointing back to
+    /// `parent_key` through `foreign_key`; `$filter` may then use `name/$count`.
+    pub fn insert_collection<C: EntityTrait>(
+        mut self,
+        api_name: impl Into<String>,
+        foreign_key: C::Column,
+        parent_key: E::Column,
+    ) -> Self {
+        self.collections.insert(
+            api_name.into().to_lowercase(),
+            CollectionField {
+                table: C::default().into_iden(),
+                foreign_key: foreign_key.into_iden(),
+                parent_key,
+            },
+        );
+        self
+    }
+
     pub fn encode_model_key(&self, model: &E::Model, field_name: &str) -> Option<String> {
         let f = self.get(field_name)?;
         f.to_string_for_cursor.map(|f| f(model))
@@ -84,6 +137,11 @@ impl<E: EntityTrait> FieldMap<E> {
     pub fn get(&self, name: &str) -> Option<&Field<E>> {
         self.map.get(&name.to_lowercase())
     }
+
+    #[must_use]
+    pub fn get_collection(&self, name: &str) -> Option<&CollectionField<E>> {
+        self.collections.get(&name.to_lowercase())
+    }
 }
 
 #[derive(Debug, Error, Clone)]
@@ -103,6 +161,13 @@ pub enum ODataBuildError {
     #[error("unsupported function or args: {0}()")]
     UnsupportedFn(String),
 
+    #[error("{function}() requires a string field, but {field} is {kind}")]
+    NonStringField {
+        function: String,
+        field: String,
+        kind: FieldKind,
+    },
+
     #[error("IN() list supports only literals")]
     NonLiteralInList,
 
@@ -112,6 +177,9 @@ pub enum ODataBuildError {
     #[error("bare literal not allowed")]
     BareLiteral,
 
+    #[error("$count is only supported on collection fields: {0}")]
+    NotACollection(String),
+
     #[error("{0}")]
     Other(&'static str),
 }
@@ -251,14 +319,37 @@ fn like_ends(s: &str) -> String {
     format!("%{}", like_escape(s))
 }
 
+/// Smallest string greater than every string starting with `prefix`, in
+/// code-point order; `None` when no such bound exists (empty prefix or only
+/// `char::MAX`).
+fn prefix_upper_bound(prefix: &str) -> Option<String> {
+    let mut chars: Vec<char> = prefix.chars().collect();
+    while let Some(last) = chars.pop() {
+        let next = match last {
+            '\u{D7FF}' => Some('\u{E000}'),
+            c => char::from_u32(u32::from(c) + 1),
+        };
+        if let Some(next) = next {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+    None
+}
+
 /* ---------- small guards ---------- */
 
 #[inline]
-fn ensure_string_field<E: EntityTrait>(f: &Field<E>, _field_name: &str) -> ODataBuildResult<()> {
+fn ensure_string_field<E: EntityTrait>(
+    f: &Field<E>,
+    function: &str,
+    field_name: &str,
+) -> ODataBuildResult<()> {
     if f.kind != FieldKind::String {
-        return Err(ODataBuildError::TypeMismatch {
-            expected: FieldKind::String,
-            got: "non-string field",
+        return Err(ODataBuildError::NonStringField {
+            function: function.to_owned(),
+            field: field_name.to_owned(),
+            kind: f.kind,
         });
     }
     Ok(())
@@ -484,6 +575,29 @@ where
             Condition::all().add(inner).not()
         }
 
+        // collection/$count op Number
+        X::Compare(lhs, op, rhs) if matches!(&**lhs, X::CollectionCount(_)) => {
+            let X::CollectionCount(name) = &**lhs else {
+                unreachable!("guarded by matches!")
+            };
+            let X::Value(rhs_val) = &**rhs else {
+                return Err(ODataBuildError::Other(
+                    "$count must be compared to a number",
+                ));
+            };
+            let count = collection_count_expr(fmap, name)?;
+            let value = coerce(FieldKind::I64, rhs_val)?;
+            let expr = match op {
+                Op::Eq => Expr::expr(count).eq(value),
+                Op::Ne => Expr::expr(count).ne(value),
+                Op::Gt => Expr::expr(count).gt(value),
+                Op::Ge => Expr::expr(count).gte(value),
+                Op::Lt => Expr::expr(count).lt(value),
+                Op::Le => Expr::expr(count).lte(value),
+            };
+            Condition::all().add(expr)
+        }
+
         // Identifier op Value
         X::Compare(lhs, op, rhs) => {
             let (name, rhs_val) = match (&**lhs, &**rhs) {
@@ -500,6 +614,11 @@ where
                 .ok_or_else(|| ODataBuildError::UnknownField(name.clone()))?;
             let col = field.col;
 
+            // booleans only support (in)equality
+            if field.kind == FieldKind::Bool && !matches!(op, Op::Eq | Op::Ne) {
+                return Err(ODataBuildError::UnsupportedOp(*op));
+            }
+
             // null handling
             if matches!(rhs_val, core::Value::Null) {
                 return Ok(match op {
@@ -547,33 +666,226 @@ where
                     let f = fmap
                         .get(name)
                         .ok_or_else(|| ODataBuildError::UnknownField(name.clone()))?;
-                    ensure_string_field(f, name)?;
+                    ensure_string_field(f, &n, name)?;
                     Condition::all().add(Expr::col(f.col).like(like_contains(s)))
                 }
                 ("startswith", [X::Identifier(name), X::Value(core::Value::String(s))]) => {
                     let f = fmap
                         .get(name)
                         .ok_or_else(|| ODataBuildError::UnknownField(name.clone()))?;
-                    ensure_string_field(f, name)?;
+                    ensure_string_field(f, &n, name)?;
                     Condition::all().add(Expr::col(f.col).like(like_starts(s)))
                 }
+                // Case-insensitive prefix search: startswith(tolower(field), 'prefix')
+                (
+                    "startswith",
+                    [
+                        X::Function(inner, inner_args),
+                        X::Value(core::Value::String(s)),
+                    ],
+                ) if inner.eq_ignore_ascii_case("tolower") => {
+                    let [X::Identifier(name)] = inner_args.as_slice() else {
+                        return Err(ODataBuildError::UnsupportedFn(inner.clone()));
+                    };
+                    let f = fmap
+                        .get(name)
+                        .ok_or_else(|| ODataBuildError::UnknownField(name.clone()))?;
+                    ensure_string_field(f, &n, name)?;
+                    let folded = SimpleExpr::from(Func::lower(Expr::col(f.col)));
+                    let prefix = s.to_lowercase();
+                    if fmap.prefix_ranges {
+                        let lower = Expr::expr(folded.clone()).gte(prefix.as_str());
+                        match prefix_upper_bound(&prefix) {
+                            Some(upper) => Condition::all()
+                                .add(lower)
+                                .add(Expr::expr(folded).lt(upper)),
+                            None => Condition::all().add(lower),
+                        }
+                    } else {
+                        Condition::all().add(Expr::expr(folded).like(like_starts(&prefix)))
+                    }
+                }
                 ("endswith", [X::Identifier(name), X::Value(core::Value::String(s))]) => {
                     let f = fmap
                         .get(name)
                         .ok_or_else(|| ODataBuildError::UnknownField(name.clone()))?;
-                    ensure_string_field(f, name)?;
+                    ensure_string_field(f, &n, name)?;
                     Condition::all().add(Expr::col(f.col).like(like_ends(s)))
                 }
                 _ => return Err(ODataBuildError::UnsupportedFn(fname.clone())),
             }
         }
 
-        // Leaf forms are not valid WHERE by themselves
-        X::Identifier(name) => return Err(ODataBuildError::BareIdentifier(name.clone())),
+        // A bare boolean field is shorthand for `field eq true`
+        X::Identifier(name) => match fmap.get(name) {
+            Some(f) if f.kind == FieldKind::Bool => Condition::all().add(Expr::col(f.col).eq(true)),
+            _ => return Err(ODataBuildError::BareIdentifier(name.clone())),
+        },
+
+        // Other leaf forms are not valid WHERE by themselves
+        X::CollectionCount(name) => {
+            return Err(ODataBuildError::BareIdentifier(format!("{name}/$count")));
+        }
         X::Value(_) => return Err(ODataBuildError::BareLiteral),
     })
 }
 
+/// `(SELECT COUNT(*) FROM child WHERE child.fk = parent.key)` for a registered collection.
+fn collection_count_expr<E: EntityTrait>(
+    fmap: &FieldMap<E>,
+    name: &str,
+) -> ODataBuildResult<SimpleExpr>
+where
+    E::Column: ColumnTrait + Copy,
+{
+    let Some(coll) = fmap.get_collection(name) else {
+        return Err(if fmap.get(name).is_some() {
+            ODataBuildError::NotACollection(name.to_owned())
+        } else {
+            ODataBuildError::UnknownField(name.to_owned())
+        });
+    };
+    let subquery = Query::select()
+        .expr(Expr::col(Asterisk).count())
+        .from(coll.table.clone())
+        .and_where(
+            Expr::col((coll.table.clone(), coll.foreign_key.clone()))
+                .equals((E::default(), coll.parent_key)),
+        )
+        .to_owned();
+    Ok(SimpleExpr::SubQuery(
+        None,
+        Box::new(subquery.into_sub_query_statement()),
+    ))
+}
+
+/* ---------- Expr (AST) -> parameterized SQL ---------- */
+
+/// SQL text with placeholders plus the values bound to them, in placeholder order.
+#[derive(Clone, Debug)]
+pub struct ParameterizedSql {
+    pub sql: String,
+    pub values: Vec<sea_orm::Value>,
+}
+
+/// Lower an `OData` filter expression to a parameterized `SELECT` for the given backend.
+///
+/// Filter literals are never interpolated into the SQL text: every literal is
+/// emitted as a backend placeholder (`$1` for Postgres, `?` for `MySQL`/`SQLite`)
+/// and returned separately in `values`.
+///
+/// # Errors
+/// Returns `ODataBuildError` if the expression contains unknown fields or unsupported operations.
+pub fn expr_to_parameterized_sql<E>(
+    expr: &core::Expr,
+    fmap: &FieldMap<E>,
+    backend: DatabaseBackend,
+) -> ODataBuildResult<ParameterizedSql>
+where
+    E: EntityTrait,
+    E::Column: ColumnTrait + Copy,
+{
+    let cond = expr_to_condition::<E>(expr, fmap)?;
+    let stmt = E::find().filter(cond).build(backend);
+    Ok(ParameterizedSql {
+        sql: stmt.sql,
+        values: stmt.values.map(|v| v.0).unwrap_or_default(),
+    })
+}
+
+/* ---------- $compute -> SELECT expressions ---------- */
+
+fn compute_operand_to_expr<E: EntityTrait>(
+    operand: &ComputeOperand,
+    fmap: &FieldMap<E>,
+) -> ODataBuildResult<sea_orm::sea_query::SimpleExpr>
+where
+    E::Column: ColumnTrait + Copy,
+{
+    match operand {
+        ComputeOperand::Field(name) => {
+            let f = fmap
+                .get(name)
+                .ok_or_else(|| ODataBuildError::UnknownField(name.clone()))?;
+            if !matches!(f.kind, FieldKind::I64 | FieldKind::F64 | FieldKind::Decimal) {
+                return Err(ODataBuildError::TypeMismatch {
+                    expected: FieldKind::Decimal,
+                    got: "non-numeric field in $compute",
+                });
+            }
+            Ok(sea_orm::IntoSimpleExpr::into_simple_expr(f.col))
+        }
+        ComputeOperand::Number(n) => {
+            let value = if n.is_integer() {
+                n.to_i64()
+                    .map(sea_orm::Value::from)
+                    .ok_or(ODataBuildError::Other("i64 out of range"))?
+            } else {
+                sea_orm::Value::from(bigdecimal_to_decimal(n)?)
+            };
+            Ok(sea_orm::sea_query::SimpleExpr::Value(value))
+        }
+    }
+}
+
+/// Lower one `$compute` item to a SQL arithmetic expression.
+///
+/// Only numeric fields (`I64`, `F64`, `Decimal`) present in the field map may be referenced.
+///
+/// # Errors
+/// Returns `ODataBuildError::UnknownField` for fields missing from the map and
+/// `ODataBuildError::TypeMismatch` for non-numeric fields.
+pub fn compute_to_expr<E: EntityTrait>(
+    item: &ComputeExpr,
+    fmap: &FieldMap<E>,
+) -> ODataBuildResult<sea_orm::sea_query::SimpleExpr>
+where
+    E::Column: ColumnTrait + Copy,
+{
+    use sea_orm::sea_query::{BinOper, SimpleExpr};
+
+    let left = compute_operand_to_expr(&item.left, fmap)?;
+    let right = compute_operand_to_expr(&item.right, fmap)?;
+    let op = match item.op {
+        ComputeOp::Add => BinOper::Add,
+        ComputeOp::Sub => BinOper::Sub,
+        ComputeOp::Mul => BinOper::Mul,
+        ComputeOp::Div => BinOper::Div,
+    };
+    Ok(SimpleExpr::Binary(Box::new(left), op, Box::new(right)))
+}
+
+/// Extension trait for projecting `$compute` items as aliased columns.
+pub trait ODataComputeExt<E: EntityTrait>: Sized {
+    /// Append each computed expression to the `SELECT` list under its alias.
+    ///
+    /// # Errors
+    /// Returns `ODataBuildError` if an item references an unknown or non-numeric field.
+    fn apply_odata_compute(
+        self,
+        compute: &[ComputeExpr],
+        fld_map: &FieldMap<E>,
+    ) -> ODataBuildResult<Self>;
+}
+
+impl<E> ODataComputeExt<E> for sea_orm::Select<E>
+where
+    E: EntityTrait,
+    E::Column: ColumnTrait + Copy,
+{
+    fn apply_odata_compute(
+        mut self,
+        compute: &[ComputeExpr],
+        fld_map: &FieldMap<E>,
+    ) -> ODataBuildResult<Self> {
+        for item in compute {
+            let expr = compute_to_expr(item, fld_map)?;
+            self = self.expr_as(expr, item.alias.as_str());
+        }
+        Ok(self)
+    }
+}
+
 /// Apply an optional `OData` filter (via wrapper) to a plain `SeaORM` Select<E>.
 ///
 /// This extension does NOT parse the filter string — it only consumes a parsed AST
@@ -766,6 +1078,145 @@ where
     }
 }
 
+/* ---------- explicit keyset ---------- */
+
+/// Explicit pagination keyset, independent of the entity's default order.
+///
+/// Rows are fetched, pages cut and cursors issued by the keyset, which should
+/// match an index. A requested `$orderby` only orders the items within each
+/// page. The keyset must include a unique field so every row has a distinct
+/// position.
+#[derive(Clone, Debug)]
+pub struct Keyset {
+    order: ODataOrderBy,
+    /// Index of the unique field within `order`.
+    unique: usize,
+}
+
+impl Keyset {
+    /// Build a keyset from `(field, direction)` pairs.
+    ///
+    /// `unique` names the field guaranteeing row uniqueness (typically the
+    /// primary key); it must be one of the keys.
+    ///
+    /// # Errors
+    /// Returns `ODataError::InvalidKeyset` if the keyset is empty, repeats a
+    /// field, or does not include `unique`.
+    pub fn new(keys: &[(&str, SortDir)], unique: &str) -> Result<Self, ODataError> {
+        if keys.is_empty() {
+            return Err(ODataError::InvalidKeyset("keyset is empty".to_owned()));
+        }
+        let mut seen = HashSet::new();
+        for (field, _) in keys {
+            if !seen.insert(field.to_lowercase()) {
+                return Err(ODataError::InvalidKeyset(format!(
+                    "field '{field}' appears more than once"
+                )));
+            }
+        }
+        let unique = keys
+            .iter()
+            .position(|(field, _)| field.eq_ignore_ascii_case(unique))
+            .ok_or_else(|| {
+                ODataError::InvalidKeyset(format!(
+                    "keyset does not include unique field '{unique}'"
+                ))
+            })?;
+
+        Ok(Self {
+            order: ODataOrderBy(
+                keys.iter()
+                    .map(|(field, dir)| OrderKey {
+                        field: (*field).to_owned(),
+                        dir: *dir,
+                    })
+                    .collect(),
+            ),
+            unique,
+        })
+    }
+
+    /// Keyset as an order specification.
+    pub fn order(&self) -> &ODataOrderBy {
+        &self.order
+    }
+
+    /// Check that every key is mapped in `fmap` with a cursor extractor.
+    ///
+    /// # Errors
+    /// Returns `ODataError::InvalidKeyset` naming the first unusable field.
+    pub fn validate_fields<E: EntityTrait>(&self, fmap: &FieldMap<E>) -> Result<(), ODataError> {
+        match self.order.0.iter().find(|key| {
+            fmap.get(&key.field)
+                .is_none_or(|f| f.to_string_for_cursor.is_none())
+        }) {
+            Some(key) => Err(ODataError::InvalidKeyset(format!(
+                "field '{}' is not mapped with a cursor extractor",
+                key.field
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Compare two cursor values of the same kind.
+fn compare_cursor_values(a: &sea_orm::Value, b: &sea_orm::Value) -> Ordering {
+    use sea_orm::Value as V;
+    match (a, b) {
+        (V::String(x), V::String(y)) => x.cmp(y),
+        (V::BigInt(x), V::BigInt(y)) => x.cmp(y),
+        (V::Double(x), V::Double(y)) => x.partial_cmp(y).unwrap_or(Ordering::Equal),
+        (V::Bool(x), V::Bool(y)) => x.cmp(y),
+        (V::Uuid(x), V::Uuid(y)) => x.cmp(y),
+        (V::ChronoDateTimeUtc(x), V::ChronoDateTimeUtc(y)) => x.cmp(y),
+        (V::ChronoDate(x), V::ChronoDate(y)) => x.cmp(y),
+        (V::ChronoTime(x), V::ChronoTime(y)) => x.cmp(y),
+        (V::Decimal(x), V::Decimal(y)) => x.cmp(y),
+        _ => Ordering::Equal,
+    }
+}
+
+/// Sort a page of rows in memory by `order`, using the field map extractors.
+fn sort_rows_by<E: EntityTrait>(
+    rows: Vec<E::Model>,
+    order: &ODataOrderBy,
+    fmap: &FieldMap<E>,
+) -> Result<Vec<E::Model>, ODataError> {
+    let mut keyed = rows
+        .into_iter()
+        .map(|model| {
+            let values = order
+                .0
+                .iter()
+                .map(|key| {
+                    let field = resolve_field(fmap, &key.field)?;
+                    let raw = fmap
+                        .encode_model_key(&model, &key.field)
+                        .ok_or_else(|| ODataError::InvalidOrderByField(key.field.clone()))?;
+                    parse_cursor_value(field.kind, &raw)
+                        .map_err(|_| ODataError::InvalidOrderByField(key.field.clone()))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((values, model))
+        })
+        .collect::<Result<Vec<_>, ODataError>>()?;
+
+    keyed.sort_by(|(a, _), (b, _)| {
+        order
+            .0
+            .iter()
+            .zip(a.iter().zip(b))
+            .map(|(key, (x, y))| match key.dir {
+                SortDir::Asc => compare_cursor_values(x, y),
+                SortDir::Desc => compare_cursor_values(y, x),
+            })
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+
+    Ok(keyed.into_iter().map(|(_, model)| model).collect())
+}
+
 /* ---------- pagination combiner ---------- */
 
 // Use unified pagination types from modkit-odata
@@ -784,6 +1235,26 @@ fn clamp_limit(req: Option<u64>, cfg: LimitCfg) -> u64 {
     l
 }
 
+/// Run a query future, cancelling it if it does not complete within `deadline`.
+///
+/// On expiry the future is dropped, which releases any pooled connection it had
+/// acquired back to the pool instead of holding it until the database responds.
+///
+/// # Errors
+/// Returns `ODataError::DeadlineExceeded` if the deadline elapses first, otherwise
+/// whatever the wrapped future returns.
+pub async fn with_deadline<T, Fut>(deadline: Option<Duration>, fut: Fut) -> Result<T, ODataError>
+where
+    Fut: Future<Output = Result<T, ODataError>>,
+{
+    match deadline {
+        Some(d) => tokio::time::timeout(d, fut)
+            .await
+            .map_err(|_| ODataError::DeadlineExceeded)?,
+        None => fut.await,
+    }
+}
+
 /// One-shot pagination combiner that handles filter → cursor predicate → order → overfetch/trim → build cursors.
 ///
 /// # Errors
@@ -803,14 +1274,90 @@ where
     F: Fn(E::Model) -> D + Copy,
     C: DBRunner,
 {
+    paginate(
+        select,
+        conn,
+        q,
+        fmap,
+        tiebreaker,
+        None,
+        limit_cfg,
+        model_to_domain,
+    )
+    .await
+}
+
+/// Like [`paginate_with_odata`], but rows are ordered and pages cut by an
+/// explicit [`Keyset`] rather than the requested `$orderby`.
+///
+/// # Errors
+/// Returns `ODataError::InvalidKeyset` if a keyset field is not usable,
+/// `ODataError::InvalidOrderByField` if `$orderby` is not a prefix of the
+/// keyset, and otherwise the same errors as [`paginate_with_odata`].
+pub async fn paginate_with_keyset<E, D, F, C>(
+    select: sea_orm::Select<E>,
+    conn: &C,
+    q: &ODataQuery,
+    fmap: &FieldMap<E>,
+    keyset: &Keyset,
+    limit_cfg: LimitCfg,
+    model_to_domain: F,
+) -> Result<Page<D>, ODataError>
+where
+    E: EntityTrait,
+    E::Column: ColumnTrait + Copy,
+    F: Fn(E::Model) -> D + Copy,
+    C: DBRunner,
+{
+    let unique = &keyset.order.0[keyset.unique];
+    let tiebreaker = (unique.field.as_str(), unique.dir);
+    paginate(
+        select,
+        conn,
+        q,
+        fmap,
+        tiebreaker,
+        Some(keyset),
+        limit_cfg,
+        model_to_domain,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn paginate<E, D, F, C>(
+    select: sea_orm::Select<E>,
+    conn: &C,
+    q: &ODataQuery,
+    fmap: &FieldMap<E>,
+    tiebreaker: (&str, SortDir),
+    keyset: Option<&Keyset>,
+    limit_cfg: LimitCfg,
+    model_to_domain: F,
+) -> Result<Page<D>, ODataError>
+where
+    E: EntityTrait,
+    E::Column: ColumnTrait + Copy,
+    F: Fn(E::Model) -> D + Copy,
+    C: DBRunner,
+{
+    if let Some(keyset) = keyset {
+        keyset.validate_fields(fmap)?;
+    }
+    if q.skip.is_some() && q.cursor.is_some() {
+        return Err(ODataError::SkipWithCursor);
+    }
+
     let limit = clamp_limit(q.limit, limit_cfg);
     let fetch = limit + 1;
-
     // Effective order derivation based on new policy
     let effective_order = if let Some(cur) = &q.cursor {
         // Derive order from the cursor's signed tokens
         modkit_odata::ODataOrderBy::from_signed_tokens(&cur.s)
             .map_err(|_| ODataError::InvalidCursor)?
+    } else if let Some(keyset) = keyset {
+        // The keyset orders the rows and cuts the pages
+        keyset.order.clone()
     } else {
         // Use client order; ensure tiebreaker
         q.order
@@ -818,6 +1365,20 @@ where
             .ensure_tiebreaker(tiebreaker.0, tiebreaker.1)
     };
 
+    // A cursor only continues the order it was issued for: with a keyset that
+    // is the keyset itself, otherwise a changed `$orderby` restarts pagination
+    if let Some(cur) = &q.cursor {
+        if let Some(keyset) = keyset {
+            modkit_odata::validate_cursor_against(cur, &keyset.order, None)?;
+        } else if !q.order.is_empty() {
+            let requested = q
+                .order
+                .clone()
+                .ensure_tiebreaker(tiebreaker.0, tiebreaker.1);
+            modkit_odata::validate_cursor_against(cur, &requested, None)?;
+        }
+    }
+
     // Validate cursor consistency (filter hash only) if cursor present
     if let Some(cur) = &q.cursor
         && let (Some(h), Some(cf)) = (q.filter_hash.as_deref(), cur.f.as_deref())
@@ -837,6 +1398,24 @@ where
         );
     }
 
+    // Count the filtered rows before the cursor and page bounds narrow them
+    let total = if q.count {
+        let count = s
+            .clone()
+            .select_only()
+            .column_as(Expr::col(Asterisk).count(), "total")
+            .into_tuple::<i64>();
+        #[allow(clippy::disallowed_methods)]
+        let total = match DBRunnerInternal::as_seaorm(conn) {
+            SeaOrmRunner::Conn(db) => count.one(db).await,
+            SeaOrmRunner::Tx(tx) => count.one(tx).await,
+        }
+        .map_err(|e| ODataError::Db(e.to_string()))?;
+        Some(total.map_or(0, |n| u64::try_from(n).unwrap_or(0)))
+    } else {
+        None
+    };
+
     // Check if we're paginating backward
     let is_backward = q.cursor.as_ref().is_some_and(|c| c.d == "bwd");
 
@@ -856,7 +1435,10 @@ where
     };
     s = s.apply_odata_order_page(&query_order, fmap)?;
 
-    // Apply limit
+    // Apply offset ($skip) and limit
+    if let Some(skip) = q.skip {
+        s = s.offset(skip);
+    }
     s = s.limit(fetch);
 
     #[allow(clippy::disallowed_methods)]
@@ -901,10 +1483,10 @@ where
     let next_cursor = if is_backward {
         // Going backward: always have items forward (unless this was the initial query)
         // Build cursor from last item to go forward
-        build_cursor(&rows, &effective_order, fmap, tiebreaker, q, true, "fwd")?
+        build_cursor(&rows, &effective_order, fmap, tiebreaker, q, "fwd")?
     } else if has_more {
         // Going forward: only have more if has_more is true
-        build_cursor(&rows, &effective_order, fmap, tiebreaker, q, true, "fwd")?
+        build_cursor(&rows, &effective_order, fmap, tiebreaker, q, "fwd")?
     } else {
         None
     };
@@ -912,18 +1494,23 @@ where
     let prev_cursor = if is_backward {
         // Going backward: only have more backward if has_more is true
         if has_more {
-            build_cursor(&rows, &effective_order, fmap, tiebreaker, q, false, "bwd")?
+            build_cursor(&rows, &effective_order, fmap, tiebreaker, q, "bwd")?
         } else {
             None
         }
     } else if q.cursor.is_some() {
         // Going forward: have items backward only if this is NOT the initial query
         // If q.cursor is None, we're at the start of the dataset
-        build_cursor(&rows, &effective_order, fmap, tiebreaker, q, false, "bwd")?
+        build_cursor(&rows, &effective_order, fmap, tiebreaker, q, "bwd")?
     } else {
         None
     };
 
+    // With a keyset, rows arrive in keyset order; present them in the requested order
+    if keyset.is_some() && !q.order.is_empty() {
+        rows = sort_rows_by(rows, &q.order, fmap)?;
+    }
+
     let items = rows.into_iter().map(model_to_domain).collect();
 
     Ok(Page {
@@ -932,30 +1519,35 @@ where
             next_cursor,
             prev_cursor,
             limit,
+            total,
         },
     })
 }
 
+/// Build the cursor at the last (`"fwd"`) or first (`"bwd"`) row of a page.
 fn build_cursor<E: EntityTrait>(
     rows: &[E::Model],
     effective_order: &ODataOrderBy,
     fmap: &FieldMap<E>,
     tiebreaker: (&str, SortDir),
     q: &ODataQuery,
-    last: bool,
     direction: &str,
 ) -> Result<Option<String>, ODataError> {
-    if last { rows.last() } else { rows.first() }
-        .map(|m| {
-            build_cursor_for_model::<E>(
-                m,
-                effective_order,
-                fmap,
-                tiebreaker.1,
-                q.filter_hash.clone(),
-                direction,
-            )
-            .and_then(|c| c.encode().map_err(|_| ODataError::InvalidCursor))
-        })
-        .transpose()
+    if direction == "fwd" {
+        rows.last()
+    } else {
+        rows.first()
+    }
+    .map(|m| {
+        build_cursor_for_model::<E>(
+            m,
+            effective_order,
+            fmap,
+            tiebreaker.1,
+            q.filter_hash.clone(),
+            direction,
+        )
+        .and_then(|c| c.encode().map_err(|_| ODataError::InvalidCursor))
+    })
+    .transpose()
 }
@@ -104,11 +104,14 @@
 //! - Applies filters at the database level (not in application memory)
 //! - Supports indexed columns via field mappings for optimal query performance
 
-use crate::odata::{FieldMap, LimitCfg, paginate_with_odata};
+use crate::odata::{
+    FieldMap, Keyset, LimitCfg, paginate_with_keyset, paginate_with_odata, with_deadline,
+};
 use crate::secure::{DBRunner, ScopableEntity, SecureEntityExt};
 use modkit_odata::{Error as ODataError, ODataQuery, Page, SortDir};
 use modkit_security::AccessScope;
 use sea_orm::{ColumnTrait, EntityTrait};
+use std::time::Duration;
 
 /// Minimal fluent builder for Secure + `OData` pagination.
 ///
@@ -148,6 +151,8 @@ where
     fmap: &'a FieldMap<E>,
     tiebreaker: (&'a str, SortDir),
     limits: LimitCfg,
+    deadline: Option<Duration>,
+    keyset: Option<Keyset>,
 }
 
 impl<'a, E, C> OPager<'a, E, C>
@@ -184,6 +189,8 @@ where
                 default: 25,
                 max: 1000,
             },
+            deadline: None,
+            keyset: None,
         }
     }
 
@@ -228,6 +235,37 @@ where
         self
     }
 
+    /// Cancel the query if it does not complete within `deadline` (default: none).
+    ///
+    /// On expiry `fetch` returns `ODataError::DeadlineExceeded` (mapped to 504) and
+    /// the in-flight query is dropped, releasing its pooled connection.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// pager.deadline(Duration::from_secs(5))
+    /// ```
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Paginate by an explicit keyset instead of the requested order (default: none).
+    ///
+    /// Page boundaries and cursors follow `keyset` (typically an indexed column
+    /// set ending in the primary key) while `$orderby` only orders items within
+    /// each page. The tiebreaker is ignored in favour of the keyset's unique field.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// pager.keyset(Keyset::new(&[("created_at", SortDir::Desc), ("id", SortDir::Desc)], "id")?)
+    /// ```
+    pub fn keyset(mut self, keyset: Keyset) -> Self {
+        self.keyset = Some(keyset);
+        self
+    }
+
     /// Execute paging and map models to domain DTOs.
     ///
     /// This is the terminal operation that:
@@ -255,6 +293,7 @@ where
     /// - `OData` filter is invalid
     /// - Database query fails
     /// - Cursor is malformed or inconsistent
+    /// - The configured deadline elapses before the query completes
     ///
     /// # Example
     ///
@@ -276,14 +315,33 @@ where
         let select = E::find().secure().scope_with(self.scope).inner;
 
         // Now apply OData filters, cursor, order, and limits
-        paginate_with_odata::<E, D, _, _>(
-            select,
-            self.conn,
-            q,
-            self.fmap,
-            self.tiebreaker,
-            self.limits,
-            map,
+        if let Some(keyset) = &self.keyset {
+            return with_deadline(
+                self.deadline,
+                paginate_with_keyset::<E, D, _, _>(
+                    select,
+                    self.conn,
+                    q,
+                    self.fmap,
+                    keyset,
+                    self.limits,
+                    map,
+                ),
+            )
+            .await;
+        }
+
+        with_deadline(
+            self.deadline,
+            paginate_with_odata::<E, D, _, _>(
+                select,
+                self.conn,
+                q,
+                self.fmap,
+                self.tiebreaker,
+                self.limits,
+                map,
+            ),
         )
         .await
     }
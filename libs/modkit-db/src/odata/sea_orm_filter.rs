@@ -13,7 +13,7 @@ use modkit_odata::filter::{
 use modkit_odata::{CursorV1, Error as ODataError, ODataOrderBy, Page, PageInfo, SortDir};
 use sea_orm::{
     Condition, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
-    sea_query::{Expr, Order},
+    sea_query::{Asterisk, Expr, Order},
 };
 
 use crate::secure::{DBRunner, DBRunnerInternal, SeaOrmRunner};
@@ -186,10 +186,7 @@ fn build_binary_condition<C>(
 where
     C: sea_orm::Iden + sea_orm::ColumnTrait + sea_orm::IntoSimpleExpr + Clone + 'static,
 {
-    // Convert ODataValue to sea_orm::Value
-    let sea_value = odata_value_to_sea_value(value)?;
-
-    // Handle NULL specially
+    // Handle NULL specially; it has no sea_orm::Value counterpart
     if matches!(value, ODataValue::Null) {
         return Ok(match op {
             FilterOp::Eq => Condition::all().add(Expr::col(column).is_null()),
@@ -198,6 +195,9 @@ where
         });
     }
 
+    // Convert ODataValue to sea_orm::Value
+    let sea_value = odata_value_to_sea_value(value)?;
+
     // Build the expression based on the operator
     let expr = match op {
         FilterOp::Eq => Expr::col(column).eq(sea_value),
@@ -462,6 +462,10 @@ where
     Mapper: Fn(E::Model) -> D,
     C: DBRunner,
 {
+    if query.skip.is_some() && query.cursor.is_some() {
+        return Err(ODataError::SkipWithCursor);
+    }
+
     let limit = clamp_limit(query.limit, limit_cfg);
     let fetch = limit + 1;
 
@@ -475,6 +479,18 @@ where
             .ensure_tiebreaker(tiebreaker.0, tiebreaker.1)
     };
 
+    // A cursor only continues the order it was issued for; a changed
+    // `$orderby` must restart pagination
+    if let Some(cur) = &query.cursor
+        && !query.order.is_empty()
+    {
+        let requested = query
+            .order
+            .clone()
+            .ensure_tiebreaker(tiebreaker.0, tiebreaker.1);
+        modkit_odata::validate_cursor_against(cur, &requested, None)?;
+    }
+
     // Validate cursor consistency (filter hash only)
     if let Some(cur) = &query.cursor
         && let (Some(h), Some(cf)) = (query.filter_hash.as_deref(), cur.f.as_deref())
@@ -495,6 +511,24 @@ where
         );
     }
 
+    // Count the filtered rows before the cursor and page bounds narrow them
+    let total = if query.count {
+        let count = s
+            .clone()
+            .select_only()
+            .column_as(Expr::col(Asterisk).count(), "total")
+            .into_tuple::<i64>();
+        #[allow(clippy::disallowed_methods)]
+        let total = match DBRunnerInternal::as_seaorm(conn) {
+            SeaOrmRunner::Conn(db) => count.one(db).await,
+            SeaOrmRunner::Tx(tx) => count.one(tx).await,
+        }
+        .map_err(|e| ODataError::Db(e.to_string()))?;
+        Some(total.map_or(0, |n| u64::try_from(n).unwrap_or(0)))
+    } else {
+        None
+    };
+
     let is_backward = query.cursor.as_ref().is_some_and(|c| c.d == "bwd");
 
     // Apply cursor predicate
@@ -521,6 +555,9 @@ where
         s = s.order_by(column, sea_order);
     }
 
+    if let Some(skip) = query.skip {
+        s = s.offset(skip);
+    }
     s = s.limit(fetch);
 
     #[allow(clippy::disallowed_methods)]
@@ -587,6 +624,7 @@ where
             next_cursor,
             prev_cursor,
             limit,
+            total,
         },
     })
 }
@@ -704,3 +742,85 @@ where
         d: direction.to_owned(),
     })
 }
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use sea_orm::{DbBackend, QueryTrait};
+
+    mod ent {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "items")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i64,
+            pub name: Option<String>,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    enum ItemField {
+        Name,
+    }
+
+    impl FilterField for ItemField {
+        const FIELDS: &'static [Self] = &[Self::Name];
+
+        fn name(&self) -> &'static str {
+            "name"
+        }
+
+        fn kind(&self) -> FieldKind {
+            FieldKind::String
+        }
+    }
+
+    struct ItemMapper;
+
+    impl FieldToColumn<ItemField> for ItemMapper {
+        type Column = ent::Column;
+
+        fn map_field(_field: ItemField) -> ent::Column {
+            ent::Column::Name
+        }
+    }
+
+    fn sql_for(op: FilterOp, value: ODataValue) -> Result<String, String> {
+        let filter = FilterNode::Binary {
+            field: ItemField::Name,
+            op,
+            value,
+        };
+        let cond = filter_node_to_condition::<ItemField, ItemMapper>(&filter)?;
+        Ok(ent::Entity::find()
+            .filter(cond)
+            .build(DbBackend::Sqlite)
+            .to_string())
+    }
+
+    #[test]
+    fn eq_null_becomes_is_null() {
+        let sql = sql_for(FilterOp::Eq, ODataValue::Null).unwrap();
+        assert!(sql.contains(r#""name" IS NULL"#), "{sql}");
+    }
+
+    #[test]
+    fn ne_null_becomes_is_not_null() {
+        let sql = sql_for(FilterOp::Ne, ODataValue::Null).unwrap();
+        assert!(sql.contains(r#""name" IS NOT NULL"#), "{sql}");
+    }
+
+    #[test]
+    fn ordering_against_null_is_rejected() {
+        let err = sql_for(FilterOp::Gt, ODataValue::Null).unwrap_err();
+        assert!(err.contains("Unsupported operator for NULL"), "{err}");
+    }
+}
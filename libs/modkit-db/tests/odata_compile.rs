@@ -6,9 +6,12 @@ mod tests {
     use sea_orm::entity::prelude::*;
     use std::str::FromStr;
 
-    use modkit_db::odata::{FieldMap, expr_to_condition};
+    use modkit_db::odata::{
+        FieldMap, ODataBuildError, ODataComputeExt, expr_to_condition, expr_to_parameterized_sql,
+    };
     use modkit_odata::ast::{CompareOperator, Expr, Value};
     use modkit_odata::filter::FieldKind;
+    use sea_orm::{DatabaseBackend, QueryTrait};
 
     // Simple test entity for compilation tests
     #[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
@@ -19,6 +22,7 @@ mod tests {
         pub name: String,
         pub score: i64,
         pub email: String,
+        pub is_active: bool,
     }
 
     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -26,12 +30,31 @@ mod tests {
 
     impl ActiveModelBehavior for ActiveModel {}
 
+    mod role {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "test_user_roles")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: uuid::Uuid,
+            pub user_id: uuid::Uuid,
+            pub role: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
     fn setup_field_map() -> FieldMap<Entity> {
         FieldMap::<Entity>::new()
             .insert("id", Column::Id, FieldKind::Uuid)
             .insert("name", Column::Name, FieldKind::String)
             .insert("score", Column::Score, FieldKind::I64)
             .insert("email", Column::Email, FieldKind::String)
+            .insert("isActive", Column::IsActive, FieldKind::Bool)
     }
 
     #[test]
@@ -125,4 +148,299 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("unknown field"));
     }
+
+    #[test]
+    fn test_filter_literal_is_bound_not_interpolated() {
+        let payload = "x'; DROP TABLE test_users; --";
+        let ast = Expr::Compare(
+            Box::new(Expr::Identifier("name".to_owned())),
+            CompareOperator::Eq,
+            Box::new(Expr::Value(Value::String(payload.to_owned()))),
+        );
+
+        let fmap = setup_field_map();
+        for backend in [
+            DatabaseBackend::Postgres,
+            DatabaseBackend::MySql,
+            DatabaseBackend::Sqlite,
+        ] {
+            let lowered = expr_to_parameterized_sql::<Entity>(&ast, &fmap, backend).unwrap();
+
+            assert!(!lowered.sql.contains("DROP TABLE"), "{}", lowered.sql);
+            assert!(!lowered.sql.contains(payload), "{}", lowered.sql);
+            assert_eq!(
+                lowered.values,
+                vec![sea_orm::Value::String(Some(Box::new(payload.to_owned())))]
+            );
+        }
+    }
+
+    #[test]
+    fn test_parameterized_sql_uses_backend_placeholders() {
+        let ast = Expr::And(
+            Box::new(Expr::Compare(
+                Box::new(Expr::Identifier("score".to_owned())),
+                CompareOperator::Gt,
+                Box::new(Expr::Value(Value::Number(
+                    BigDecimal::from_str("10").unwrap(),
+                ))),
+            )),
+            Box::new(Expr::Function(
+                "startswith".to_owned(),
+                vec![
+                    Expr::Identifier("email".to_owned()),
+                    Expr::Value(Value::String("admin".to_owned())),
+                ],
+            )),
+        );
+
+        let fmap = setup_field_map();
+        let pg =
+            expr_to_parameterized_sql::<Entity>(&ast, &fmap, DatabaseBackend::Postgres).unwrap();
+        assert!(pg.sql.contains("$1") && pg.sql.contains("$2"), "{}", pg.sql);
+        assert_eq!(pg.values.len(), 2);
+
+        let lite =
+            expr_to_parameterized_sql::<Entity>(&ast, &fmap, DatabaseBackend::Sqlite).unwrap();
+        assert!(lite.sql.contains('?'), "{}", lite.sql);
+        assert!(!lite.sql.contains("admin"), "{}", lite.sql);
+        assert_eq!(
+            lite.values[1],
+            sea_orm::Value::String(Some(Box::new("admin%".to_owned())))
+        );
+    }
+
+    #[test]
+    fn test_compute_projects_arithmetic_alias() {
+        let compute = modkit_odata::parse_compute("score mul 2 as doubled").unwrap();
+        let fmap = setup_field_map();
+
+        let sql = Entity::find()
+            .apply_odata_compute(&compute, &fmap)
+            .unwrap()
+            .build(DatabaseBackend::Sqlite)
+            .to_string();
+
+        assert!(
+            sql.contains(r#""test_users"."score" * 2 AS "doubled""#),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn test_compute_rejects_disallowed_fields() {
+        let fmap = setup_field_map();
+
+        let unknown = modkit_odata::parse_compute("salary mul 2 as doubled").unwrap();
+        let err = Entity::find()
+            .apply_odata_compute(&unknown, &fmap)
+            .unwrap_err();
+        assert!(matches!(err, ODataBuildError::UnknownField(ref f) if f == "salary"));
+
+        let non_numeric = modkit_odata::parse_compute("name add 1 as bumped").unwrap();
+        let err = Entity::find()
+            .apply_odata_compute(&non_numeric, &fmap)
+            .unwrap_err();
+        assert!(matches!(err, ODataBuildError::TypeMismatch { .. }));
+    }
+
+    fn count_gt_one(collection: &str) -> Expr {
+        Expr::Compare(
+            Box::new(Expr::CollectionCount(collection.to_owned())),
+            CompareOperator::Gt,
+            Box::new(Expr::Value(Value::Number(BigDecimal::from(1)))),
+        )
+    }
+
+    #[test]
+    fn test_collection_count_compiles_to_subquery() {
+        let fmap = setup_field_map().insert_collection::<role::Entity>(
+            "roles",
+            role::Column::UserId,
+            Column::Id,
+        );
+
+        let lowered = expr_to_parameterized_sql::<Entity>(
+            &count_gt_one("roles"),
+            &fmap,
+            DatabaseBackend::Sqlite,
+        )
+        .unwrap();
+        assert!(
+            lowered.sql.contains(
+                r#"(SELECT COUNT(*) FROM "test_user_roles" WHERE "test_user_roles"."user_id" = "test_users"."id") > ?"#
+            ),
+            "{}",
+            lowered.sql
+        );
+        assert_eq!(lowered.values, vec![sea_orm::Value::BigInt(Some(1))]);
+    }
+
+    #[test]
+    fn test_collection_count_rejects_scalar_fields() {
+        let fmap = setup_field_map();
+        let err = expr_to_condition::<Entity>(&count_gt_one("name"), &fmap).unwrap_err();
+        assert!(matches!(err, ODataBuildError::NotACollection(ref f) if f == "name"));
+
+        let err = expr_to_condition::<Entity>(&count_gt_one("tags"), &fmap).unwrap_err();
+        assert!(matches!(err, ODataBuildError::UnknownField(ref f) if f == "tags"));
+    }
+
+    #[test]
+    fn test_boolean_fields_compile() {
+        let fmap = setup_field_map();
+        let explicit = Expr::Compare(
+            Box::new(Expr::Identifier("isActive".to_owned())),
+            CompareOperator::Eq,
+            Box::new(Expr::Value(Value::Bool(true))),
+        );
+        let implicit = Expr::Identifier("isActive".to_owned());
+
+        for ast in [&explicit, &implicit] {
+            let lowered =
+                expr_to_parameterized_sql::<Entity>(ast, &fmap, DatabaseBackend::Sqlite).unwrap();
+            assert!(
+                lowered.sql.contains(r#"WHERE "is_active" = ?"#),
+                "{}",
+                lowered.sql
+            );
+            assert_eq!(lowered.values, vec![sea_orm::Value::Bool(Some(true))]);
+        }
+
+        let err =
+            expr_to_condition::<Entity>(&Expr::Identifier("name".to_owned()), &fmap).unwrap_err();
+        assert!(matches!(err, ODataBuildError::BareIdentifier(ref f) if f == "name"));
+    }
+
+    #[test]
+    fn test_boolean_fields_reject_ordering() {
+        let ast = Expr::Compare(
+            Box::new(Expr::Identifier("isActive".to_owned())),
+            CompareOperator::Gt,
+            Box::new(Expr::Value(Value::Bool(true))),
+        );
+        let err = expr_to_condition::<Entity>(&ast, &setup_field_map()).unwrap_err();
+        assert!(matches!(
+            err,
+            ODataBuildError::UnsupportedOp(CompareOperator::Gt)
+        ));
+    }
+
+    fn startswith(field: Expr, prefix: &str) -> Expr {
+        Expr::Function(
+            "startswith".to_owned(),
+            vec![field, Expr::Value(Value::String(prefix.to_owned()))],
+        )
+    }
+
+    fn tolower(name: &str) -> Expr {
+        Expr::Function(
+            "tolower".to_owned(),
+            vec![Expr::Identifier(name.to_owned())],
+        )
+    }
+
+    fn string(s: &str) -> sea_orm::Value {
+        sea_orm::Value::String(Some(Box::new(s.to_owned())))
+    }
+
+    fn string_fn(name: &str, field: &str, arg: &str) -> Expr {
+        Expr::Function(
+            name.to_owned(),
+            vec![
+                Expr::Identifier(field.to_owned()),
+                Expr::Value(Value::String(arg.to_owned())),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_string_functions_compile_to_escaped_like() {
+        let fmap = setup_field_map();
+        for (function, pattern) in [
+            ("contains", "%50\\%%"),
+            ("startswith", "50\\%%"),
+            ("endswith", "%50\\%"),
+        ] {
+            let lowered = expr_to_parameterized_sql::<Entity>(
+                &string_fn(function, "name", "50%"),
+                &fmap,
+                DatabaseBackend::Sqlite,
+            )
+            .unwrap();
+            assert!(lowered.sql.contains(r#""name" LIKE ?"#), "{}", lowered.sql);
+            assert_eq!(lowered.values, vec![string(pattern)], "{function}");
+        }
+    }
+
+    #[test]
+    fn test_string_functions_reject_non_string_fields() {
+        let err =
+            expr_to_condition::<Entity>(&string_fn("contains", "score", "1"), &setup_field_map())
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "contains() requires a string field, but score is I64"
+        );
+    }
+
+    #[test]
+    fn test_case_folded_prefix_compiles_to_range() {
+        let fmap = setup_field_map().with_prefix_ranges(true);
+        let lowered = expr_to_parameterized_sql::<Entity>(
+            &startswith(tolower("name"), "Ann"),
+            &fmap,
+            DatabaseBackend::Postgres,
+        )
+        .unwrap();
+        assert!(
+            lowered
+                .sql
+                .contains(r#"LOWER("name") >= $1 AND LOWER("name") < $2"#),
+            "{}",
+            lowered.sql
+        );
+        assert!(!lowered.sql.contains("LIKE"), "{}", lowered.sql);
+        assert_eq!(lowered.values, vec![string("ann"), string("ano")]);
+
+        // The last character that can still be incremented is bumped.
+        let lowered = expr_to_parameterized_sql::<Entity>(
+            &startswith(tolower("name"), "z\u{10FFFF}"),
+            &fmap,
+            DatabaseBackend::Sqlite,
+        )
+        .unwrap();
+        assert_eq!(lowered.values[1], string("{"));
+    }
+
+    #[test]
+    fn test_other_prefix_patterns_use_like() {
+        let ranged = setup_field_map().with_prefix_ranges(true);
+        let lowered = expr_to_parameterized_sql::<Entity>(
+            &startswith(Expr::Identifier("name".to_owned()), "Ann"),
+            &ranged,
+            DatabaseBackend::Sqlite,
+        )
+        .unwrap();
+        assert!(lowered.sql.contains("LIKE"), "{}", lowered.sql);
+        assert_eq!(lowered.values, vec![string("Ann%")]);
+
+        // Without the option the case-folded form is a LIKE on LOWER().
+        let lowered = expr_to_parameterized_sql::<Entity>(
+            &startswith(tolower("name"), "Ann"),
+            &setup_field_map(),
+            DatabaseBackend::Sqlite,
+        )
+        .unwrap();
+        assert!(
+            lowered.sql.contains(r#"LOWER("name") LIKE ?"#),
+            "{}",
+            lowered.sql
+        );
+        assert_eq!(lowered.values, vec![string("ann%")]);
+
+        let err =
+            expr_to_condition::<Entity>(&startswith(tolower("score"), "1"), &ranged).unwrap_err();
+        assert!(matches!(err, ODataBuildError::NonStringField { .. }));
+    }
 }
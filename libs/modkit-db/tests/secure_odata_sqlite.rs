@@ -9,16 +9,18 @@
 
 use anyhow::anyhow;
 use modkit_db::migration_runner::run_migrations_for_testing;
-use modkit_db::odata::FieldMap;
 use modkit_db::odata::pager::OPager;
+use modkit_db::odata::{FieldMap, Keyset, with_deadline};
 use modkit_db::secure::{Db, DbConn, ScopableEntity, secure_insert};
 use modkit_db::{ConnectOpts, connect_db};
-use modkit_odata::ODataQuery;
 use modkit_odata::filter::FieldKind;
+use modkit_odata::{CursorV1, ODataOrderBy, ODataQuery, OrderKey, SortDir};
 use modkit_security::{AccessScope, pep_properties};
 use sea_orm::Set;
 use sea_orm::entity::prelude::*;
 use sea_orm_migration::prelude as mig;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 mod ent {
@@ -190,3 +192,286 @@ async fn paginate_odata_works_with_secure_conn() {
 
     assert_eq!(page.items.len(), 2, "page size");
 }
+
+#[tokio::test]
+async fn skip_offsets_the_page_and_excludes_cursors() {
+    let test_db = TestDb::new().await;
+    let conn = test_db.conn();
+    seed(&conn, test_db.tenant_id, &test_db.scope).await;
+
+    let fmap: FieldMap<ent::Entity> = FieldMap::new()
+        .insert_with_extractor("id", ent::Column::Id, FieldKind::I64, |m: &ent::Model| {
+            m.id.to_string()
+        })
+        .insert("name", ent::Column::Name, FieldKind::String);
+    let pager = || OPager::<ent::Entity, _>::new(&test_db.scope, &conn, &fmap);
+
+    let order = ODataOrderBy(vec![OrderKey {
+        field: "id".to_owned(),
+        dir: SortDir::Asc,
+    }]);
+    let q = ODataQuery::new()
+        .with_order(order)
+        .with_limit(2)
+        .with_skip(1);
+    let page = pager().fetch(&q, |m| m.name).await.expect("fetch");
+    assert_eq!(page.items, ["bob", "charlie"]);
+
+    let cursor = page.page_info.next_cursor.expect("more rows after charlie");
+    let q = ODataQuery::new()
+        .with_cursor(CursorV1::decode(&cursor).unwrap())
+        .with_skip(1);
+    let err = pager().fetch(&q, |m| m.name).await.unwrap_err();
+    assert!(matches!(err, modkit_odata::Error::SkipWithCursor));
+}
+
+#[tokio::test]
+async fn cursor_continues_only_the_order_it_was_issued_for() {
+    let test_db = TestDb::new().await;
+    let conn = test_db.conn();
+    seed(&conn, test_db.tenant_id, &test_db.scope).await;
+
+    let fmap: FieldMap<ent::Entity> = FieldMap::new()
+        .insert_with_extractor("id", ent::Column::Id, FieldKind::I64, |m: &ent::Model| {
+            m.id.to_string()
+        })
+        .insert_with_extractor(
+            "score",
+            ent::Column::Score,
+            FieldKind::I64,
+            |m: &ent::Model| m.score.to_string(),
+        )
+        .insert("name", ent::Column::Name, FieldKind::String);
+    let pager = || OPager::<ent::Entity, _>::new(&test_db.scope, &conn, &fmap);
+
+    // Two sort keys with mixed directions: score desc, then the id tiebreaker asc
+    let order = ODataOrderBy::from_signed_tokens("-score,+id").unwrap();
+    let q = ODataQuery::new().with_order(order.clone()).with_limit(2);
+    let first = pager().fetch(&q, |m| m.name).await.expect("first page");
+    assert_eq!(first.items, ["dave", "charlie"]);
+
+    let cursor = CursorV1::decode(first.page_info.next_cursor.as_deref().unwrap()).unwrap();
+    assert_eq!(cursor.k.len(), 2);
+    assert_eq!(cursor.s, "-score,+id");
+
+    // Repeating the same order alongside the cursor continues the listing
+    let q = ODataQuery::new()
+        .with_order(order)
+        .with_cursor(cursor.clone())
+        .with_limit(2);
+    let second = pager().fetch(&q, |m| m.name).await.expect("second page");
+    assert_eq!(second.items, ["bob", "alice"]);
+
+    // Changing the order mid-pagination is rejected
+    let changed = |signed: &str| {
+        ODataQuery::new()
+            .with_order(ODataOrderBy::from_signed_tokens(signed).unwrap())
+            .with_cursor(cursor.clone())
+            .with_limit(2)
+    };
+    let err = pager()
+        .fetch(&changed("+name"), |m| m.name)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, modkit_odata::Error::OrderMismatch), "{err:?}");
+    let err = pager()
+        .fetch(&changed("+score"), |m| m.name)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, modkit_odata::Error::CursorInvalidDirection),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn count_reports_filtered_total_only_when_requested() {
+    use modkit_odata::ast::{CompareOperator, Expr, Value};
+
+    let test_db = TestDb::new().await;
+    let conn = test_db.conn();
+    seed(&conn, test_db.tenant_id, &test_db.scope).await;
+
+    let fmap: FieldMap<ent::Entity> = FieldMap::new()
+        .insert_with_extractor("id", ent::Column::Id, FieldKind::I64, |m: &ent::Model| {
+            m.id.to_string()
+        })
+        .insert("score", ent::Column::Score, FieldKind::I64);
+    let pager = || OPager::<ent::Entity, _>::new(&test_db.scope, &conn, &fmap);
+
+    // score gt 10 matches bob, charlie and dave
+    let filter = Expr::Compare(
+        Box::new(Expr::Identifier("score".to_owned())),
+        CompareOperator::Gt,
+        Box::new(Expr::Value(Value::Number(10.into()))),
+    );
+    let q = ODataQuery::new().with_filter(filter).with_limit(1);
+
+    let page = pager().fetch(&q, |m| m.name).await.expect("fetch");
+    assert_eq!(page.page_info.total, None);
+
+    let q = q.with_count(true);
+    let page = pager().fetch(&q, |m| m.name).await.expect("fetch");
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.page_info.total, Some(3));
+
+    // The total is unaffected by the cursor position
+    let cursor = CursorV1::decode(&page.page_info.next_cursor.unwrap()).unwrap();
+    let q = ODataQuery {
+        order: ODataOrderBy::empty(),
+        ..q.with_cursor(cursor)
+    };
+    let page = pager().fetch(&q, |m| m.name).await.expect("fetch");
+    assert_eq!(page.page_info.total, Some(3));
+}
+
+#[tokio::test]
+async fn opager_deadline_allows_fast_queries() {
+    let test_db = TestDb::new().await;
+    let conn = test_db.conn();
+    seed(&conn, test_db.tenant_id, &test_db.scope).await;
+
+    let fmap: FieldMap<ent::Entity> = FieldMap::new().insert_with_extractor(
+        "id",
+        ent::Column::Id,
+        FieldKind::I64,
+        |m: &ent::Model| m.id.to_string(),
+    );
+
+    let page = OPager::<ent::Entity, _>::new(&test_db.scope, &conn, &fmap)
+        .deadline(Duration::from_secs(30))
+        .fetch(&ODataQuery::default(), |m| m.name)
+        .await
+        .expect("fetch");
+
+    assert_eq!(page.items.len(), 4);
+}
+
+#[tokio::test]
+async fn slow_query_is_cancelled_at_deadline() {
+    // Stands in for a pooled connection: dropped only when the query future is dropped.
+    let held = Arc::new(());
+    let in_flight = Arc::clone(&held);
+    let slow_query = async move {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        drop(in_flight);
+        Ok::<_, modkit_odata::Error>(())
+    };
+
+    let started = Instant::now();
+    let err = with_deadline(Some(Duration::from_millis(50)), slow_query)
+        .await
+        .unwrap_err();
+
+    assert!(
+        started.elapsed() < Duration::from_secs(5),
+        "not cancelled in time"
+    );
+    assert!(matches!(err, modkit_odata::Error::DeadlineExceeded));
+    assert_eq!(
+        modkit_odata::errors::ErrorCode::odata_errors_query_timeout_v1().status(),
+        504
+    );
+    assert_eq!(Arc::strong_count(&held), 1, "connection was not released");
+}
+
+#[tokio::test]
+async fn keyset_pagination_orders_pages_separately_from_the_display_order() {
+    let test_db = TestDb::new().await;
+    let conn = test_db.conn();
+    seed(&conn, test_db.tenant_id, &test_db.scope).await;
+
+    let fmap: FieldMap<ent::Entity> = FieldMap::new()
+        .insert_with_extractor("id", ent::Column::Id, FieldKind::I64, |m: &ent::Model| {
+            m.id.to_string()
+        })
+        .insert_with_extractor(
+            "name",
+            ent::Column::Name,
+            FieldKind::String,
+            |m: &ent::Model| m.name.clone(),
+        )
+        .insert_with_extractor(
+            "score",
+            ent::Column::Score,
+            FieldKind::I64,
+            |m: &ent::Model| m.score.to_string(),
+        );
+    let keyset = Keyset::new(&[("score", SortDir::Desc), ("id", SortDir::Desc)], "id").unwrap();
+    let order = |field: &str, dir| {
+        ODataOrderBy(vec![OrderKey {
+            field: field.to_owned(),
+            dir,
+        }])
+    };
+    let fetch = |q: ODataQuery| {
+        let keyset = keyset.clone();
+        let (scope, conn, fmap) = (&test_db.scope, &conn, &fmap);
+        async move {
+            OPager::<ent::Entity, _>::new(scope, conn, fmap)
+                .keyset(keyset)
+                .fetch(&q, |m| m.name)
+                .await
+        }
+    };
+
+    // Pages are cut by the keyset (highest scores first); `$orderby` only
+    // orders the items within each page
+    let by_name = order("name", SortDir::Asc);
+    let first = fetch(ODataQuery::new().with_order(by_name.clone()).with_limit(2))
+        .await
+        .expect("first page");
+    assert_eq!(first.items, vec!["charlie", "dave"]);
+
+    let cursor = CursorV1::decode(first.page_info.next_cursor.as_deref().unwrap()).unwrap();
+    let second = fetch(
+        ODataQuery::new()
+            .with_order(by_name)
+            .with_cursor(cursor)
+            .with_limit(2),
+    )
+    .await
+    .expect("second page");
+    assert_eq!(second.items, vec!["alice", "bob"]);
+    assert!(second.page_info.next_cursor.is_none());
+
+    // The cursor is checked against the keyset, not the display order
+    let first = fetch(
+        ODataQuery::new()
+            .with_order(order("score", SortDir::Asc))
+            .with_limit(2),
+    )
+    .await
+    .expect("first page");
+    assert_eq!(first.items, vec!["charlie", "dave"]);
+    let cursor = CursorV1::decode(first.page_info.next_cursor.as_deref().unwrap()).unwrap();
+    let second = fetch(
+        ODataQuery::new()
+            .with_order(order("score", SortDir::Asc))
+            .with_cursor(cursor)
+            .with_limit(2),
+    )
+    .await
+    .expect("second page");
+    assert_eq!(second.items, vec!["alice", "bob"]);
+}
+
+#[tokio::test]
+async fn keyset_must_be_unique_and_mapped() {
+    assert!(Keyset::new(&[], "id").is_err());
+    assert!(Keyset::new(&[("score", SortDir::Asc)], "id").is_err());
+    assert!(Keyset::new(&[("id", SortDir::Asc), ("ID", SortDir::Desc)], "id").is_err());
+
+    let test_db = TestDb::new().await;
+    let conn = test_db.conn();
+    let fmap: FieldMap<ent::Entity> = FieldMap::new().insert("id", ent::Column::Id, FieldKind::I64);
+    let err = OPager::<ent::Entity, _>::new(&test_db.scope, &conn, &fmap)
+        .keyset(Keyset::new(&[("id", SortDir::Asc)], "id").unwrap())
+        .fetch(&ODataQuery::default(), |m| m.name)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, modkit_odata::Error::InvalidKeyset(_)),
+        "{err:?}"
+    );
+}
@@ -15,13 +15,31 @@
 //!     vis = "pub"
 //! }
 //! ```
+//!
+//! Single error types can derive their definition instead of listing it in a
+//! catalog file:
+//!
+//! ```rust,ignore
+//! #[derive(GtsError)]
+//! #[gts_error(
+//!     status = 404,
+//!     title = "Not Found",
+//!     code = "gts.hx.core.errors.err.v1~hx.users.errors.not_found.v1"
+//! )]
+//! pub struct UserNotFound;
+//!
+//! let problem = UserNotFound::ERR_DEF.as_problem("no such user");
+//! ```
+
+use std::collections::BTreeMap;
 
 use proc_macro::TokenStream;
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
 use serde::Deserialize;
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
-use syn::{LitStr, Token, parse_macro_input};
+use syn::{DeriveInput, LitStr, Token, parse_macro_input};
 
 /// JSON schema for a single error definition
 #[derive(Debug, Clone, Deserialize)]
@@ -33,6 +51,44 @@ struct ErrorEntry {
     type_url: Option<String>,
     #[serde(default)]
     alias: Option<String>,
+    #[serde(default)]
+    help_url: Option<String>,
+    /// Constant metadata attached to every problem of this type
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
+    /// Overrides the status-derived transient classification
+    #[serde(default)]
+    transient: Option<bool>,
+    /// Overrides the status-derived severity
+    #[serde(default)]
+    severity: Option<Severity>,
+    /// Code of the stable entry presented instead of this experimental one
+    #[serde(default)]
+    fallback: Option<String>,
+    /// Localized titles keyed by language tag
+    #[serde(default)]
+    titles: BTreeMap<String, String>,
+}
+
+/// Severity values accepted in the catalog, mirroring `modkit_errors::Severity`
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl Severity {
+    fn variant(self) -> &'static str {
+        match self {
+            Self::Info => "Info",
+            Self::Warning => "Warning",
+            Self::Error => "Error",
+            Self::Critical => "Critical",
+        }
+    }
 }
 
 /// Parsed macro input
@@ -95,6 +151,215 @@ pub fn declare_errors(input: TokenStream) -> TokenStream {
     }
 }
 
+/// Derive the catalog definition of a single error type.
+///
+/// Generates an associated `ERR_DEF: ErrDef` constant from the `#[gts_error(..)]`
+/// attribute, identical to the definition `declare_errors!` builds for the same
+/// catalog entry, and a `gts_type_uri()` accessor returning `gts://{code}`. The
+/// URI is formatted at expansion time into a per-type `static`, so every call
+/// returns the same pointer without locking or allocating.
+///
+/// Accepted keys: `status`, `title` and `code` (required), and `type`,
+/// `help_url`, `transient` and `severity` (optional, as in the JSON catalog).
+/// `status` must be an HTTP status code and `code` a GTS identifier; both are
+/// checked at compile time.
+///
+/// The remaining catalog fields take lists and paths:
+/// - `metadata(key = "value", ..)` attaches constant metadata;
+/// - `titles(de = "..", "pt-BR" = "..")` adds localized titles, keyed by
+///   language tag (quote tags that are not identifiers);
+/// - `fallback = StableError` names the stable type presented instead of
+///   this experimental one. It must derive `GtsError` and have no fallback
+///   of its own, which is checked at compile time wherever `ERR_DEF` is used.
+#[proc_macro_derive(GtsError, attributes(gts_error))]
+pub fn derive_gts_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match generate_gts_error(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn generate_gts_error(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let (entry, fallback) = parse_gts_error_attr(input)?;
+    let def = if let Some(fallback) = &fallback {
+        let def = err_def_literal(&entry, &quote! { Some(&#fallback::ERR_DEF) });
+        quote! {
+            const _: () = assert!(
+                #fallback::ERR_DEF.fallback.is_none(),
+                "the fallback of a GtsError type must not have a fallback of its own"
+            );
+            #def
+        }
+    } else {
+        err_def_literal(&entry, &quote! { None })
+    };
+    let uri = format!("gts://{}", entry.code);
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Catalog definition of this error type
+            pub const ERR_DEF: ::modkit_errors::catalog::ErrDef = {
+                use ::modkit_errors::catalog::ErrDef;
+                #def
+            };
+
+            /// `gts://` URI of this error type; stable across calls
+            pub fn gts_type_uri() -> &'static str {
+                static URI: &str = #uri;
+                URI
+            }
+        }
+    })
+}
+
+/// Catalog entry described by the `#[gts_error(..)]` attribute of `input`,
+/// with the path of its fallback type
+fn parse_gts_error_attr(input: &DeriveInput) -> syn::Result<(ErrorEntry, Option<syn::Path>)> {
+    let attr = input
+        .attrs
+        .iter()
+        .find(|a| a.path().is_ident("gts_error"))
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                &input.ident,
+                "#[derive(GtsError)] requires a #[gts_error(status = .., title = .., code = ..)] attribute",
+            )
+        })?;
+
+    let mut status = None;
+    let mut title = None;
+    let mut code = None;
+    let mut fallback = None;
+    let mut entry = ErrorEntry {
+        status: 0,
+        title: String::new(),
+        code: String::new(),
+        type_url: None,
+        alias: None,
+        help_url: None,
+        metadata: BTreeMap::new(),
+        transient: None,
+        severity: None,
+        fallback: None,
+        titles: BTreeMap::new(),
+    };
+
+    attr.parse_nested_meta(|meta| {
+        let key = meta
+            .path
+            .get_ident()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        match key.as_str() {
+            "status" => {
+                let lit: syn::LitInt = meta.value()?.parse()?;
+                let value = lit
+                    .base10_parse::<u16>()
+                    .ok()
+                    .filter(|s| (100..=599).contains(s))
+                    .ok_or_else(|| {
+                        syn::Error::new(
+                            lit.span(),
+                            format!(
+                                "invalid HTTP status code {lit}: expected an integer in 100..=599"
+                            ),
+                        )
+                    })?;
+                status = Some(value);
+            }
+            "title" => {
+                let lit: LitStr = meta.value()?.parse()?;
+                if lit.value().trim().is_empty() {
+                    return Err(syn::Error::new(lit.span(), "title must not be empty"));
+                }
+                title = Some(lit.value());
+            }
+            "code" => {
+                let lit: LitStr = meta.value()?.parse()?;
+                validate_gts_format(&lit.value())
+                    .map_err(|e| syn::Error::new(lit.span(), e.to_string()))?;
+                code = Some(lit.value());
+            }
+            "type" => {
+                let lit: LitStr = meta.value()?.parse()?;
+                entry.type_url = Some(lit.value());
+            }
+            "help_url" => {
+                let lit: LitStr = meta.value()?.parse()?;
+                entry.help_url = Some(lit.value());
+            }
+            "transient" => {
+                let lit: syn::LitBool = meta.value()?.parse()?;
+                entry.transient = Some(lit.value);
+            }
+            "severity" => {
+                let lit: LitStr = meta.value()?.parse()?;
+                let severity = serde_json::from_value(serde_json::Value::String(lit.value()))
+                    .map_err(|_| {
+                        syn::Error::new(
+                            lit.span(),
+                            "severity must be one of \"info\", \"warning\", \"error\", \"critical\"",
+                        )
+                    })?;
+                entry.severity = Some(severity);
+            }
+            "metadata" => parse_string_pairs(&meta, &mut entry.metadata)?,
+            "titles" => parse_string_pairs(&meta, &mut entry.titles)?,
+            "fallback" => {
+                fallback = Some(meta.value()?.parse::<syn::Path>()?);
+            }
+            _ => return Err(meta.error("unknown gts_error key")),
+        }
+        Ok(())
+    })?;
+
+    let missing =
+        |name: &str| syn::Error::new_spanned(attr, format!("missing `{name}` in #[gts_error(..)]"));
+    entry.status = status.ok_or_else(|| missing("status"))?;
+    entry.title = title.ok_or_else(|| missing("title"))?;
+    entry.code = code.ok_or_else(|| missing("code"))?;
+    Ok((entry, fallback))
+}
+
+/// Parse `(key = "value", ..)` into `pairs`. Keys are identifiers or string
+/// literals, so language tags such as `"pt-BR"` can be written too.
+fn parse_string_pairs(
+    meta: &syn::meta::ParseNestedMeta<'_>,
+    pairs: &mut BTreeMap<String, String>,
+) -> syn::Result<()> {
+    let content;
+    syn::parenthesized!(content in meta.input);
+    while !content.is_empty() {
+        let key = if content.peek(LitStr) {
+            content.parse::<LitStr>()?.value()
+        } else {
+            content.call(syn::Ident::parse_any)?.to_string()
+        };
+        content.parse::<Token![=]>()?;
+        let value: LitStr = content.parse()?;
+        if value.value().trim().is_empty() {
+            return Err(syn::Error::new(
+                value.span(),
+                format!("value of `{key}` must not be empty"),
+            ));
+        }
+        if pairs.insert(key.clone(), value.value()).is_some() {
+            return Err(syn::Error::new(
+                value.span(),
+                format!("duplicate key `{key}`"),
+            ));
+        }
+        if !content.is_empty() {
+            content.parse::<Token![,]>()?;
+        }
+    }
+    Ok(())
+}
+
 fn generate_errors(input: &DeclareErrorsInput) -> syn::Result<TokenStream2> {
     // Load and parse JSON file
     let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
@@ -134,6 +399,13 @@ fn generate_errors(input: &DeclareErrorsInput) -> syn::Result<TokenStream2> {
     let json_file_path = &input.path;
 
     let enum_variants = generate_enum_variants(&entries);
+    let all_variants: Vec<TokenStream2> = entries
+        .iter()
+        .map(|e| {
+            let variant = code_to_ident(&e.code);
+            quote! { ErrorCode::#variant }
+        })
+        .collect();
     let const_defs = generate_const_defs(&entries);
     let impl_methods = generate_impl_methods(&entries);
     let short_accessors = generate_short_accessors(&entries, &short_names);
@@ -159,6 +431,9 @@ fn generate_errors(input: &DeclareErrorsInput) -> syn::Result<TokenStream2> {
         }
 
         impl ErrorCode {
+            /// Every error code declared in the catalog, in declaration order
+            pub const ALL: &'static [ErrorCode] = &[#(#all_variants),*];
+
             /// Get the HTTP status code for this error
             pub const fn status(&self) -> u16 {
                 match self {
@@ -173,6 +448,12 @@ fn generate_errors(input: &DeclareErrorsInput) -> syn::Result<TokenStream2> {
                 }
             }
 
+            /// Register every definition of this catalog for
+            /// `Problem::checked_against_catalog`
+            pub fn register_catalog() {
+                ::modkit_errors::catalog::register_catalog(Self::ALL.iter().map(Self::def));
+            }
+
             /// Convert to Problem with detail (without instance/trace)
             pub fn as_problem(&self, detail: impl Into<String>) -> Problem {
                 self.def().as_problem(detail)
@@ -274,6 +555,29 @@ fn validate_entries(entries: &[ErrorEntry]) -> syn::Result<()> {
         // Strict GTS validation
         validate_gts_format(&entry.code)?;
 
+        // A fallback must name a stable entry of the same catalog
+        if let Some(fallback) = &entry.fallback {
+            let target = entries.iter().find(|e| &e.code == fallback);
+            match target {
+                Some(target) if target.fallback.is_none() => {}
+                Some(_) => {
+                    return Err(syn::Error::new(
+                        Span::call_site(),
+                        format!(
+                            "Fallback '{fallback}' of error '{}' is itself experimental",
+                            entry.code
+                        ),
+                    ));
+                }
+                None => {
+                    return Err(syn::Error::new(
+                        Span::call_site(),
+                        format!("Unknown fallback '{fallback}' for error '{}'", entry.code),
+                    ));
+                }
+            }
+        }
+
         // Optional: Detect redundancy (same title+status)
         let key = (entry.title.trim(), entry.status);
         if let Some(existing_code) = titles_and_statuses.get(&key) {
@@ -423,26 +727,75 @@ fn generate_impl_methods(entries: &[ErrorEntry]) -> Vec<TokenStream2> {
         .iter()
         .map(|e| {
             let variant = code_to_ident(&e.code);
-            let status = e.status;
-            let title = &e.title;
-            let code = &e.code;
-            let type_url = match &e.type_url {
-                Some(s) => s.clone(),
-                None => format!("https://errors.example.com/{}", e.code),
+            // Validated to exist and to have no fallback of its own
+            let fallback = if let Some(target) = entries
+                .iter()
+                .find(|target| Some(&target.code) == e.fallback.as_ref())
+            {
+                let def = err_def_literal(target, &quote! { None });
+                quote! { Some(&#def) }
+            } else {
+                quote! { None }
             };
+            let def = err_def_literal(e, &fallback);
 
             quote! {
-                ErrorCode::#variant => ErrDef {
-                    status: #status,
-                    title: #title,
-                    code: #code,
-                    type_url: #type_url,
-                }
+                ErrorCode::#variant => #def
             }
         })
         .collect()
 }
 
+/// `ErrDef { .. }` literal for a catalog entry
+fn err_def_literal(e: &ErrorEntry, fallback: &TokenStream2) -> TokenStream2 {
+    let status = e.status;
+    let title = &e.title;
+    let code = &e.code;
+    let type_url = match &e.type_url {
+        Some(s) => s.clone(),
+        None => format!("https://errors.example.com/{}", e.code),
+    };
+    let help_url = if let Some(url) = &e.help_url {
+        quote! { Some(#url) }
+    } else {
+        quote! { None }
+    };
+    let metadata = e
+        .metadata
+        .iter()
+        .map(|(key, value)| quote! { (#key, #value) });
+    let titles = e
+        .titles
+        .iter()
+        .map(|(language, title)| quote! { (#language, #title) });
+    let severity = if let Some(severity) = e.severity {
+        let variant = syn::Ident::new(severity.variant(), Span::call_site());
+        quote! { Some(::modkit_errors::problem::Severity::#variant) }
+    } else {
+        quote! { None }
+    };
+    let transient = if let Some(transient) = e.transient {
+        quote! { Some(#transient) }
+    } else {
+        quote! { None }
+    };
+
+    quote! {
+        ErrDef {
+            status: #status,
+            title: #title,
+            code: #code,
+            type_url: #type_url,
+            help_url: #help_url,
+            metadata: &[#(#metadata),*],
+            transient: #transient,
+            severity: #severity,
+            fallback: #fallback,
+            titles: &[#(#titles),*],
+        }
+    }
+}
+
 fn generate_macro_rules_single(
     entries: &[ErrorEntry],
     namespace: &syn::Ident,
@@ -0,0 +1,141 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+//! `#[derive(GtsError)]` produces the same definition as a hand-written `ErrDef`.
+
+use modkit_errors::catalog::ErrDef;
+use modkit_errors::problem::Severity;
+use modkit_errors_macro::GtsError;
+
+const MANUAL_NOT_FOUND: ErrDef = ErrDef {
+    status: 404,
+    title: "Not Found",
+    code: "gts.hx.core.errors.err.v1~hx.users.errors.not_found.v1",
+    type_url: "https://errors.example.com/gts.hx.core.errors.err.v1~hx.users.errors.not_found.v1",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+#[derive(GtsError)]
+#[gts_error(
+    status = 404,
+    title = "Not Found",
+    code = "gts.hx.core.errors.err.v1~hx.users.errors.not_found.v1"
+)]
+struct UserNotFound;
+
+#[derive(GtsError)]
+#[gts_error(
+    status = 503,
+    title = "Storage Unavailable",
+    code = "gts.hx.core.errors.err.v1~hx.users.errors.storage_unavailable.v1",
+    type = "gts.hx.core.errors.err.v1~hx.users.errors.storage_unavailable.v1~",
+    help_url = "https://docs.example.com/errors/storage",
+    transient = false,
+    severity = "critical"
+)]
+#[allow(dead_code)]
+enum StorageUnavailable {
+    Primary,
+    Replica,
+}
+
+#[derive(GtsError)]
+#[gts_error(
+    status = 404,
+    title = "User Archived",
+    code = "gts.hx.core.errors.err.v1~hx.users.errors.archived.v1",
+    metadata(resource = "user", scope = "tenant"),
+    titles(de = "Benutzer archiviert", "pt-BR" = "Usuario arquivado"),
+    fallback = UserNotFound
+)]
+struct UserArchived;
+
+#[test]
+fn derived_def_matches_manual_def() {
+    assert_eq!(UserNotFound::ERR_DEF, MANUAL_NOT_FOUND);
+    assert_eq!(
+        UserNotFound::ERR_DEF.as_problem("no such user"),
+        MANUAL_NOT_FOUND.as_problem("no such user")
+    );
+}
+
+#[test]
+fn optional_keys_are_carried_over() {
+    let def = StorageUnavailable::ERR_DEF;
+    assert_eq!(def.status, 503);
+    assert_eq!(def.title, "Storage Unavailable");
+    assert_eq!(
+        def.type_url,
+        "gts.hx.core.errors.err.v1~hx.users.errors.storage_unavailable.v1~"
+    );
+    assert_eq!(
+        def.help_url,
+        Some("https://docs.example.com/errors/storage")
+    );
+    assert!(!def.is_transient());
+    assert_eq!(def.severity(), Severity::Critical);
+}
+
+#[test]
+fn metadata_and_titles_are_carried_over() {
+    let def = UserArchived::ERR_DEF;
+    assert_eq!(def.metadata, &[("resource", "user"), ("scope", "tenant")]);
+    assert_eq!(
+        def.titles,
+        &[
+            ("de", "Benutzer archiviert"),
+            ("pt-BR", "Usuario arquivado")
+        ]
+    );
+
+    let problem = def.as_problem("archived");
+    assert_eq!(problem.metadata.as_ref().unwrap()["resource"], "user");
+    assert_eq!(problem.localized("de").title, "Benutzer archiviert");
+}
+
+#[test]
+fn fallback_names_the_stable_type() {
+    assert_eq!(UserArchived::ERR_DEF.fallback, Some(&UserNotFound::ERR_DEF));
+
+    let stable = UserArchived::ERR_DEF.as_problem("archived").into_fallback();
+    assert_eq!(stable.code, UserNotFound::ERR_DEF.code);
+    assert_eq!(stable.title, "Not Found");
+}
+
+#[test]
+fn test_gts_type_uri_is_stable() {
+    let first = UserNotFound::gts_type_uri();
+    assert_eq!(
+        first,
+        "gts://gts.hx.core.errors.err.v1~hx.users.errors.not_found.v1"
+    );
+    assert!(std::ptr::eq(first, UserNotFound::gts_type_uri()));
+    assert_ne!(first, StorageUnavailable::gts_type_uri());
+}
+
+#[test]
+fn gts_type_uri_is_shared_across_threads() {
+    let expected = UserNotFound::gts_type_uri().as_ptr() as usize;
+    let handles: Vec<_> = (0..16)
+        .map(|_| {
+            std::thread::spawn(|| {
+                (0..10_000)
+                    .map(|_| UserNotFound::gts_type_uri().as_ptr() as usize)
+                    .collect::<std::collections::HashSet<_>>()
+            })
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), [expected].into());
+    }
+}
+
+#[test]
+fn invalid_attributes_fail_to_compile() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/fail/*.rs");
+}
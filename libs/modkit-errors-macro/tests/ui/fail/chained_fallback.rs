@@ -0,0 +1,31 @@
+use modkit_errors_macro::GtsError;
+
+#[derive(GtsError)]
+#[gts_error(
+    status = 404,
+    title = "Not Found",
+    code = "gts.hx.core.errors.err.v1~hx.users.errors.not_found.v1"
+)]
+struct UserNotFound;
+
+#[derive(GtsError)]
+#[gts_error(
+    status = 404,
+    title = "User Archived",
+    code = "gts.hx.core.errors.err.v1~hx.users.errors.archived.v1",
+    fallback = UserNotFound
+)]
+struct UserArchived;
+
+#[derive(GtsError)]
+#[gts_error(
+    status = 404,
+    title = "User Purged",
+    code = "gts.hx.core.errors.err.v1~hx.users.errors.purged.v1",
+    fallback = UserArchived
+)]
+struct UserPurged;
+
+fn main() {
+    let _ = UserPurged::ERR_DEF;
+}
@@ -0,0 +1,12 @@
+use modkit_errors_macro::GtsError;
+
+#[derive(GtsError)]
+#[gts_error(
+    status = 404,
+    title = "Not Found",
+    code = "gts.hx.core.errors.err.v1~hx.users.errors.not_found.v1",
+    titles(de = "Nicht gefunden", de = "Unbekannt")
+)]
+struct UserNotFound;
+
+fn main() {}
@@ -0,0 +1,7 @@
+use modkit_errors_macro::GtsError;
+
+#[derive(GtsError)]
+#[gts_error(status = 404, title = "Not Found", code = "USERS_NOT_FOUND")]
+struct UserNotFound;
+
+fn main() {}
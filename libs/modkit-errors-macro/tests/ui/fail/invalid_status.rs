@@ -0,0 +1,11 @@
+use modkit_errors_macro::GtsError;
+
+#[derive(GtsError)]
+#[gts_error(
+    status = 1000,
+    title = "Not Found",
+    code = "gts.hx.core.errors.err.v1~hx.users.errors.not_found.v1"
+)]
+struct UserNotFound;
+
+fn main() {}
@@ -0,0 +1,7 @@
+use modkit_errors_macro::GtsError;
+
+#[derive(GtsError)]
+#[gts_error(status = 404, code = "gts.hx.core.errors.err.v1~hx.users.errors.not_found.v1")]
+struct UserNotFound;
+
+fn main() {}
@@ -0,0 +1,165 @@
+//! `arbitrary::Arbitrary` for `Problem` (feature `arbitrary`)
+//!
+//! Generated problems are valid ones: the status has a canonical reason, the
+//! trace id is either absent or 32 lowercase hex digits, and metadata holds at
+//! most [`MAX_METADATA_ENTRIES`] JSON scalars. Every generated value survives a
+//! JSON round-trip unchanged, which makes the generator suitable for fuzzing
+//! and property-based tests of serialization code.
+
+use std::collections::BTreeMap;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use http::StatusCode;
+
+use crate::problem::{Problem, RemediationAction, ValidationViolation};
+
+/// Upper bound on generated metadata entries.
+pub const MAX_METADATA_ENTRIES: usize = 4;
+
+/// Upper bound on generated validation violations.
+pub const MAX_VIOLATIONS: usize = 3;
+
+fn arbitrary_status(u: &mut Unstructured<'_>) -> Result<StatusCode> {
+    let canonical: Vec<StatusCode> = (100..=599)
+        .filter_map(|code| StatusCode::from_u16(code).ok())
+        .filter(|status| status.canonical_reason().is_some())
+        .collect();
+    u.choose(&canonical).copied()
+}
+
+fn arbitrary_trace_id(u: &mut Unstructured<'_>) -> Result<Option<String>> {
+    if !u.arbitrary::<bool>()? {
+        return Ok(None);
+    }
+    let id: u128 = u.arbitrary()?;
+    Ok(Some(format!("{id:032x}")))
+}
+
+fn arbitrary_scalar(u: &mut Unstructured<'_>) -> Result<serde_json::Value> {
+    Ok(match u.int_in_range(0..=3u8)? {
+        0 => serde_json::Value::Null,
+        1 => serde_json::Value::Bool(u.arbitrary()?),
+        2 => serde_json::Value::from(u.arbitrary::<i64>()?),
+        _ => serde_json::Value::String(u.arbitrary()?),
+    })
+}
+
+fn arbitrary_metadata(
+    u: &mut Unstructured<'_>,
+) -> Result<Option<BTreeMap<String, serde_json::Value>>> {
+    if !u.arbitrary::<bool>()? {
+        return Ok(None);
+    }
+    let len = u.int_in_range(0..=MAX_METADATA_ENTRIES)?;
+    let mut metadata = BTreeMap::new();
+    for _ in 0..len {
+        metadata.insert(u.arbitrary::<String>()?, arbitrary_scalar(u)?);
+    }
+    Ok(Some(metadata))
+}
+
+impl<'a> Arbitrary<'a> for ValidationViolation {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            field: u.arbitrary()?,
+            message: u.arbitrary()?,
+            code: u.arbitrary()?,
+            pointer: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for RemediationAction {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(if u.arbitrary::<bool>()? {
+            Self::RefreshToken
+        } else {
+            Self::RetryAfter {
+                seconds: u.arbitrary()?,
+            }
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Problem {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let errors = if u.arbitrary::<bool>()? {
+            let len = u.int_in_range(0..=MAX_VIOLATIONS)?;
+            Some(
+                (0..len)
+                    .map(|_| u.arbitrary())
+                    .collect::<Result<Vec<ValidationViolation>>>()?,
+            )
+        } else {
+            None
+        };
+        Ok(Self {
+            type_url: u.arbitrary()?,
+            title: u.arbitrary()?,
+            status: arbitrary_status(u)?,
+            detail: u.arbitrary()?,
+            instance: u.arbitrary()?,
+            code: u.arbitrary()?,
+            trace_id: arbitrary_trace_id(u)?,
+            errors,
+            help: u.arbitrary()?,
+            actions: u.arbitrary_iter()?.take(2).collect::<Result<Vec<_>>>()?,
+            metadata: arbitrary_metadata(u)?,
+            suppress_log: false,
+            language: None,
+            fallback: None,
+            titles: &[],
+        })
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    /// Number of generated problems checked per property.
+    const CASES: u64 = 512;
+
+    /// Deterministic pseudo-random input bytes for case `seed` (splitmix64).
+    fn input(seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..512)
+            .flat_map(|_| {
+                state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+                let mut z = state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+                (z ^ (z >> 31)).to_le_bytes()
+            })
+            .collect()
+    }
+
+    fn problems() -> impl Iterator<Item = Problem> {
+        (0..CASES).map(|seed| {
+            let bytes = input(seed);
+            Problem::arbitrary(&mut Unstructured::new(&bytes)).unwrap()
+        })
+    }
+
+    #[test]
+    fn serde_round_trip_preserves_problem() {
+        for problem in problems() {
+            let json = serde_json::to_string(&problem).unwrap();
+            let back: Problem = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, problem, "{json}");
+        }
+    }
+
+    #[test]
+    fn generated_problems_are_valid() {
+        for problem in problems() {
+            assert!(problem.status.canonical_reason().is_some());
+            if let Some(trace_id) = &problem.trace_id {
+                assert_eq!(trace_id.len(), 32);
+                assert!(trace_id.bytes().all(|b| b.is_ascii_hexdigit()));
+            }
+            assert!(problem.metadata.map_or(0, |m| m.len()) <= MAX_METADATA_ENTRIES);
+        }
+    }
+}
@@ -1,26 +1,447 @@
 //! Error catalog support (`ErrDef` for use with `declare_errors`! macro)
 
-use crate::problem::Problem;
-use http::StatusCode;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+
+use crate::problem::{
+    Problem, RemediationAction, Severity, TRANSIENT_METADATA_KEY, is_transient_status,
+    normalize_status, validate_trace_id,
+};
 
 /// Static error definition from catalog
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ErrDef {
     pub status: u16,
     pub title: &'static str,
     pub code: &'static str,
     pub type_url: &'static str,
+    /// Optional documentation URL emitted as the problem's `help` member.
+    pub help_url: Option<&'static str>,
+    /// Constant metadata entries attached to every problem of this type.
+    pub metadata: &'static [(&'static str, &'static str)],
+    /// Whether a retry may help; `None` derives it from `status`
+    /// (see [`is_transient_status`]).
+    pub transient: Option<bool>,
+    /// Severity for log and alert routing; `None` derives it from `status`
+    /// (see [`Severity::for_status`]).
+    pub severity: Option<Severity>,
+    /// Stable definition presented instead of this one to clients that do not
+    /// accept experimental errors; `None` for stable types.
+    pub fallback: Option<&'static ErrDef>,
+    /// Localized titles as `(language tag, title)` pairs, served instead of
+    /// `title` to clients preferring one of these languages.
+    pub titles: &'static [(&'static str, &'static str)],
 }
 
 impl ErrDef {
-    /// Convert this error definition into a Problem with the given detail
+    /// Convert this error definition into a Problem with the given detail.
+    ///
+    /// Remediation actions implied by the status (see `RemediationAction::defaults_for`)
+    /// and the type's default metadata are attached automatically.
     #[inline]
     pub fn as_problem(&self, detail: impl Into<String>) -> Problem {
-        // Convert u16 to StatusCode, using INTERNAL_SERVER_ERROR as fallback for invalid codes
-        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
-        Problem::new(status, self.title, detail.into())
+        self.base_problem(detail)
+            .merge_metadata(self.default_metadata())
+    }
+
+    /// Like [`ErrDef::as_problem`], additionally attaching call-site context
+    /// (the attempted value, a hint, ...) as problem metadata.
+    ///
+    /// Call-site entries override the type's default metadata on key collisions.
+    #[inline]
+    pub fn as_problem_with(
+        &self,
+        detail: impl Into<String>,
+        extra: HashMap<String, serde_json::Value>,
+    ) -> Problem {
+        self.base_problem(detail)
+            .merge_metadata(extra)
+            .merge_metadata(self.default_metadata())
+    }
+
+    /// Like [`ErrDef::as_problem`], additionally attaching `trace_id`; an empty
+    /// trace id attaches none.
+    ///
+    /// # Errors
+    /// Returns the validation error of a malformed trace id
+    /// (see [`validate_trace_id`]).
+    pub fn as_problem_with_trace(
+        &self,
+        detail: impl Into<String>,
+        trace_id: impl AsRef<str>,
+    ) -> Result<Problem, String> {
+        let trace_id = trace_id.as_ref();
+        let problem = self.as_problem(detail);
+        if trace_id.is_empty() {
+            return Ok(problem);
+        }
+        validate_trace_id(trace_id)?;
+        Ok(problem.with_trace_id(trace_id))
+    }
+
+    /// Constant metadata of this type, independent of any instance, in key
+    /// order.
+    #[must_use]
+    pub fn default_metadata(&self) -> BTreeMap<String, serde_json::Value> {
+        self.metadata
+            .iter()
+            .map(|&(key, value)| (key.to_owned(), serde_json::Value::from(value)))
+            .collect()
+    }
+
+    /// Transient-vs-permanent classification of this type.
+    #[must_use]
+    pub const fn is_transient(&self) -> bool {
+        match self.transient {
+            Some(transient) => transient,
+            None => is_transient_status(self.status),
+        }
+    }
+
+    /// Severity of this type.
+    #[must_use]
+    pub const fn severity(&self) -> Severity {
+        match self.severity {
+            Some(severity) => severity,
+            None => Severity::for_status(self.status),
+        }
+    }
+
+    fn base_problem(&self, detail: impl Into<String>) -> Problem {
+        let (status, _) = normalize_status(self.status);
+        let mut problem = Problem::new(status, self.title, detail.into())
             .with_code(self.code)
-            .with_type(self.type_url)
+            .with_type(self.type_url);
+        problem.actions = RemediationAction::defaults_for(status);
+        // Only an override needs recording; `Problem::is_retryable` derives the rest.
+        if self.is_transient() != is_transient_status(status.as_u16()) {
+            problem = problem.with_metadata(TRANSIENT_METADATA_KEY, self.is_transient());
+        }
+        if self.severity() != Severity::for_status(status.as_u16()) {
+            problem = problem.with_severity(self.severity());
+        }
+        if let Some(fallback) = self.fallback {
+            problem = problem.with_fallback(fallback);
+        }
+        if !self.titles.is_empty() {
+            problem = problem.with_titles(self.titles);
+        }
+        if let Some(url) = self.help_url {
+            problem.with_help(url)
+        } else {
+            problem
+        }
+    }
+}
+
+/// Framework error for a malformed request, e.g. unparsable JSON or query
+/// string (HTTP 400).
+pub const BAD_REQUEST_V1: ErrDef = ErrDef {
+    status: 400,
+    title: "Bad Request",
+    code: "BAD_REQUEST",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.bad_request.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Framework error for a request body over the accepted size (HTTP 413).
+pub const PAYLOAD_TOO_LARGE_V1: ErrDef = ErrDef {
+    status: 413,
+    title: "Payload Too Large",
+    code: "PAYLOAD_TOO_LARGE",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.payload_too_large.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Framework error for a request body of a media type the endpoint does not
+/// accept (HTTP 415).
+pub const UNSUPPORTED_MEDIA_TYPE_V1: ErrDef = ErrDef {
+    status: 415,
+    title: "Unsupported Media Type",
+    code: "UNSUPPORTED_MEDIA_TYPE",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.unsupported_media_type.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Framework error for requests lacking valid credentials (HTTP 401).
+///
+/// Problems of this type suggest [`RemediationAction::RefreshToken`].
+pub const UNAUTHORIZED_V1: ErrDef = ErrDef {
+    status: 401,
+    title: "Unauthorized",
+    code: "UNAUTHORIZED",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.unauthorized.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Framework error for throttled requests (HTTP 429).
+///
+/// Attach the back-off delay with [`Problem::with_retry_after`]; it is then
+/// also emitted as a `Retry-After` header.
+pub const TOO_MANY_REQUESTS_V1: ErrDef = ErrDef {
+    status: 429,
+    title: "Too Many Requests",
+    code: "TOO_MANY_REQUESTS",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.too_many_requests.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Metadata key listing the license features a [`PAYMENT_REQUIRED_V1`]
+/// problem's caller lacks.
+pub const MISSING_FEATURES_METADATA_KEY: &str = "missing_features";
+
+/// Framework error for requests needing a license feature the caller has not
+/// purchased (HTTP 402), as opposed to one they may not use
+/// ([`FORBIDDEN_V1`]). The missing features are listed under
+/// [`MISSING_FEATURES_METADATA_KEY`].
+pub const PAYMENT_REQUIRED_V1: ErrDef = ErrDef {
+    status: 402,
+    title: "Payment Required",
+    code: "PAYMENT_REQUIRED",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.payment_required.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Framework error for authenticated requests the caller may not make, e.g.
+/// for lack of a license feature (HTTP 403).
+pub const FORBIDDEN_V1: ErrDef = ErrDef {
+    status: 403,
+    title: "Forbidden",
+    code: "FORBIDDEN",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.forbidden.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Framework error for a resource that does not exist (HTTP 404).
+pub const NOT_FOUND_V1: ErrDef = ErrDef {
+    status: 404,
+    title: "Not Found",
+    code: "NOT_FOUND",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Framework error for a well-formed request whose content is invalid
+/// (HTTP 422). Attach one [`ValidationViolation`] per offending field with
+/// [`Problem::with_errors`].
+///
+/// [`ValidationViolation`]: crate::ValidationViolation
+pub const VALIDATION_FAILED_V1: ErrDef = ErrDef {
+    status: 422,
+    title: "Validation Failed",
+    code: "VALIDATION_FAILED",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.validation_failed.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Framework error for a request conflicting with the current state of a
+/// resource, such as a duplicate unique key (HTTP 409).
+pub const CONFLICT_V1: ErrDef = ErrDef {
+    status: 409,
+    title: "Conflict",
+    code: "CONFLICT",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.conflict.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Framework error for failures the client cannot act on (HTTP 500). The
+/// detail must not reveal internals; log them server-side instead.
+pub const INTERNAL_ERROR_V1: ErrDef = ErrDef {
+    status: 500,
+    title: "Internal Server Error",
+    code: "INTERNAL_ERROR",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.internal_error.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Framework error for an upstream service failing or answering unexpectedly
+/// (HTTP 502), as built by [`Problem::from_upstream_response`].
+pub const BAD_GATEWAY_V1: ErrDef = ErrDef {
+    status: 502,
+    title: "Bad Gateway",
+    code: "BAD_GATEWAY",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.bad_gateway.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Framework error for a request the service cannot handle right now, e.g.
+/// because a dependency it must consult is down (HTTP 503).
+pub const SERVICE_UNAVAILABLE_V1: ErrDef = ErrDef {
+    status: 503,
+    title: "Service Unavailable",
+    code: "SERVICE_UNAVAILABLE",
+    type_url: "gts.cf.core.errors.err.v1~cf.core.errors.service_unavailable.v1~",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+/// Every error definition provided by the framework itself.
+pub const FRAMEWORK_ERRORS: &[ErrDef] = &[
+    BAD_REQUEST_V1,
+    UNAUTHORIZED_V1,
+    TOO_MANY_REQUESTS_V1,
+    PAYMENT_REQUIRED_V1,
+    FORBIDDEN_V1,
+    NOT_FOUND_V1,
+    PAYLOAD_TOO_LARGE_V1,
+    UNSUPPORTED_MEDIA_TYPE_V1,
+    VALIDATION_FAILED_V1,
+    CONFLICT_V1,
+    INTERNAL_ERROR_V1,
+    BAD_GATEWAY_V1,
+    SERVICE_UNAVAILABLE_V1,
+];
+
+static CATALOG: RwLock<BTreeMap<&'static str, ErrDef>> = RwLock::new(BTreeMap::new());
+
+/// Register error definitions, keyed by type URI, for
+/// [`Problem::checked_against_catalog`]. A later definition replaces an
+/// earlier one with the same type URI.
+pub fn register_catalog(defs: impl IntoIterator<Item = ErrDef>) {
+    let mut catalog = CATALOG
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    for def in defs {
+        catalog.insert(def.type_url, def);
+    }
+}
+
+/// Registered definition for `type_url`, if any.
+#[must_use]
+pub fn registered_def(type_url: &str) -> Option<ErrDef> {
+    CATALOG
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .get(type_url)
+        .copied()
+}
+
+/// Every registered definition, in type URI order.
+pub(crate) fn registered_defs() -> Vec<ErrDef> {
+    CATALOG
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .values()
+        .copied()
+        .collect()
+}
+
+/// A hand-built problem disagreeing with the catalog definition of its type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogMismatch {
+    pub type_url: String,
+    /// Problem member that differs: `"title"` or `"status"`.
+    pub field: &'static str,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl std::fmt::Display for CatalogMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} is '{}', catalog defines '{}'",
+            self.field, self.type_url, self.actual, self.expected
+        )
+    }
+}
+
+impl std::error::Error for CatalogMismatch {}
+
+impl Problem {
+    /// Cross-check a hand-built problem against the registered catalog.
+    ///
+    /// When `type_url` belongs to a registered [`ErrDef`], `title` and `status`
+    /// must match that definition. Problems of unregistered types pass as is.
+    ///
+    /// # Errors
+    /// Returns the first [`CatalogMismatch`] found, title before status.
+    pub fn checked_against_catalog(self) -> Result<Self, CatalogMismatch> {
+        let Some(def) = registered_def(&self.type_url) else {
+            return Ok(self);
+        };
+        let mismatch = |field, expected: String, actual: String| CatalogMismatch {
+            type_url: self.type_url.clone(),
+            field,
+            expected,
+            actual,
+        };
+        if self.title != def.title {
+            return Err(mismatch("title", def.title.to_owned(), self.title.clone()));
+        }
+        let (status, _) = normalize_status(def.status);
+        if self.status != status {
+            return Err(mismatch(
+                "status",
+                status.as_u16().to_string(),
+                self.status.as_u16().to_string(),
+            ));
+        }
+        Ok(self)
     }
 }
 
@@ -28,16 +449,35 @@ impl ErrDef {
 #[cfg_attr(coverage_nightly, coverage(off))]
 mod tests {
     use super::*;
+    use crate::problem::ValidationViolation;
+
+    /// An `about:blank` definition with every optional member unset.
+    const fn test_def(status: u16, title: &'static str, code: &'static str) -> ErrDef {
+        ErrDef {
+            status,
+            title,
+            code,
+            type_url: "about:blank",
+            help_url: None,
+            metadata: &[],
+            transient: None,
+            severity: None,
+            fallback: None,
+            titles: &[],
+        }
+    }
 
     #[test]
     fn err_def_to_problem_works() {
         use http::StatusCode;
 
         let def = ErrDef {
-            status: StatusCode::NOT_FOUND.as_u16(),
-            title: "Not Found",
-            code: "TEST_NOT_FOUND",
             type_url: "https://errors.example.com/TEST_NOT_FOUND",
+            ..test_def(
+                StatusCode::NOT_FOUND.as_u16(),
+                "Not Found",
+                "TEST_NOT_FOUND",
+            )
         };
 
         let problem = def.as_problem("Resource missing");
@@ -50,4 +490,424 @@ mod tests {
             "https://errors.example.com/TEST_NOT_FOUND"
         );
     }
+
+    #[test]
+    fn err_def_to_problem_with_extra_metadata() {
+        use http::StatusCode;
+        use serde_json::json;
+
+        let def = ErrDef {
+            type_url: "https://errors.example.com/TEST_NOT_FOUND",
+            ..test_def(
+                StatusCode::NOT_FOUND.as_u16(),
+                "Not Found",
+                "TEST_NOT_FOUND",
+            )
+        };
+
+        let extra = HashMap::from([
+            ("attempted_id".to_owned(), json!("u-42")),
+            ("hint".to_owned(), json!("check the tenant")),
+        ]);
+        let problem = def.as_problem_with("User missing", extra);
+        let metadata = problem.metadata.as_ref().unwrap();
+        assert_eq!(problem.status, StatusCode::NOT_FOUND);
+        assert_eq!(metadata["attempted_id"], "u-42");
+        assert_eq!(metadata["hint"], "check the tenant");
+
+        // Entries set by the producer win over call-site extras.
+        let problem = def
+            .as_problem("User missing")
+            .with_metadata("resource", "user")
+            .merge_metadata(HashMap::from([
+                ("resource".to_owned(), json!("overridden")),
+                ("hint".to_owned(), json!("retry")),
+            ]));
+        let metadata = problem.metadata.unwrap();
+        assert_eq!(metadata["resource"], "user");
+        assert_eq!(metadata["hint"], "retry");
+    }
+
+    #[test]
+    fn empty_extra_metadata_is_not_emitted() {
+        let def = test_def(404, "Not Found", "TEST_NOT_FOUND");
+        let problem = def.as_problem_with("missing", HashMap::new());
+        assert!(problem.metadata.is_none());
+    }
+
+    #[test]
+    fn trace_id_is_validated_and_attached() {
+        let def = UNAUTHORIZED_V1;
+
+        let problem = def
+            .as_problem_with_trace("token expired", "4bf92f3577b34da6a3ce929d0e0e4736")
+            .unwrap();
+        assert_eq!(
+            problem.trace_id.as_deref(),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+        assert_eq!(
+            problem,
+            def.as_problem("token expired")
+                .with_trace_id("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+
+        let problem = def.as_problem_with_trace("token expired", "").unwrap();
+        assert!(problem.trace_id.is_none());
+
+        let err = def
+            .as_problem_with_trace("token expired", "trace id\r\nx-injected: 1")
+            .unwrap_err();
+        assert!(err.contains("invalid character"), "{err}");
+        assert!(
+            def.as_problem_with_trace("token expired", "a".repeat(129))
+                .is_err()
+        );
+        assert!(
+            def.as_problem_with_trace("token expired", "0".repeat(32))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn help_url_is_emitted_only_when_defined() {
+        let with_help = ErrDef {
+            help_url: Some("https://docs.example.com/errors/not-found"),
+            ..test_def(404, "Not Found", "TEST_NOT_FOUND")
+        };
+        let json = serde_json::to_value(with_help.as_problem("missing")).unwrap();
+        assert_eq!(json["help"], "https://docs.example.com/errors/not-found");
+
+        let without_help = ErrDef {
+            help_url: None,
+            ..with_help
+        };
+        let json = serde_json::to_value(without_help.as_problem("missing")).unwrap();
+        assert!(json.get("help").is_none());
+    }
+
+    #[test]
+    fn validation_failed_json_lists_field_errors_only_when_present() {
+        let problem = VALIDATION_FAILED_V1.as_problem("invalid user");
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(json["status"], 422);
+        assert_eq!(json["code"], "VALIDATION_FAILED");
+        assert!(json.get("errors").is_none(), "{json}");
+
+        let problem = problem.with_errors(vec![
+            ValidationViolation::at_pointer("/email", "must be an email").with_code("FORMAT"),
+            ValidationViolation::at_pointer("/age", "must be positive"),
+        ]);
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(
+            json["errors"],
+            serde_json::json!([
+                {"field": "email", "message": "must be an email", "code": "FORMAT", "pointer": "/email"},
+                {"field": "age", "message": "must be positive", "pointer": "/age"}
+            ])
+        );
+
+        let back: Problem = serde_json::from_value(json).unwrap();
+        assert_eq!(back, problem);
+    }
+
+    #[test]
+    fn catalog_metadata_round_trips_in_key_order() {
+        let def = ErrDef {
+            metadata: &[
+                ("retryable", "no"),
+                ("area", "billing"),
+                ("owner", "team-a"),
+            ],
+            ..UNAUTHORIZED_V1
+        };
+        let extra = HashMap::from([
+            ("zone".to_owned(), serde_json::json!("eu-1")),
+            ("attempt".to_owned(), serde_json::json!(3)),
+        ]);
+        let first = serde_json::to_string(&def.as_problem_with("gone", extra.clone())).unwrap();
+        let second = serde_json::to_string(&def.as_problem_with("gone", extra)).unwrap();
+        assert_eq!(first, second);
+        assert!(
+            first.contains(
+                r#""metadata":{"area":"billing","attempt":3,"owner":"team-a","retryable":"no","zone":"eu-1"}"#
+            ),
+            "{first}"
+        );
+
+        let back: Problem = serde_json::from_str(&first).unwrap();
+        assert_eq!(back.status, http::StatusCode::UNAUTHORIZED);
+        assert_eq!(serde_json::to_string(&back).unwrap(), first);
+    }
+
+    #[test]
+    fn unauthorized_json() {
+        let problem = UNAUTHORIZED_V1.as_problem("missing bearer token");
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(json["status"], 401);
+        assert_eq!(json["title"], "Unauthorized");
+        assert_eq!(json["detail"], "missing bearer token");
+        assert_eq!(
+            json["type"],
+            "gts.cf.core.errors.err.v1~cf.core.errors.unauthorized.v1~"
+        );
+        assert_eq!(json["code"], "UNAUTHORIZED");
+
+        let back: Problem = serde_json::from_value(json).unwrap();
+        assert_eq!(back, problem);
+        assert_eq!(back.actions, vec![RemediationAction::RefreshToken]);
+    }
+
+    #[test]
+    fn too_many_requests_json() {
+        let problem = TOO_MANY_REQUESTS_V1
+            .as_problem("quota exhausted")
+            .with_retry_after(60);
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(json["status"], 429);
+        assert_eq!(json["title"], "Too Many Requests");
+        assert_eq!(
+            json["type"],
+            "gts.cf.core.errors.err.v1~cf.core.errors.too_many_requests.v1~"
+        );
+        assert_eq!(json["metadata"]["retry_after_seconds"], 60);
+        assert_eq!(
+            json["actions"],
+            serde_json::json!([{"action": "retry_after", "seconds": 60}])
+        );
+
+        let back: Problem = serde_json::from_value(json).unwrap();
+        assert_eq!(back, problem);
+    }
+
+    #[test]
+    fn experimental_problem_falls_back_to_stable_type() {
+        static STABLE: ErrDef = ErrDef {
+            type_url: "gts.cf.core.errors.err.v1~cf.core.errors.bad_request.v1~",
+            help_url: Some("https://docs.example.com/errors/bad-request"),
+            ..test_def(400, "Bad Request", "TEST_BAD_REQUEST")
+        };
+        let experimental = ErrDef {
+            type_url: "gts.cf.core.errors.err.v1~cf.core.errors.quota_policy.v1~",
+            metadata: &[("policy", "beta")],
+            fallback: Some(&STABLE),
+            ..test_def(422, "Quota Policy Violated", "TEST_QUOTA_POLICY")
+        };
+
+        let problem = experimental
+            .as_problem("quota policy rejected the request")
+            .with_instance("/quotas/7");
+        assert_eq!(problem.fallback, Some(&STABLE));
+        assert_eq!(problem.code, "TEST_QUOTA_POLICY");
+
+        let stable = problem.into_fallback();
+        assert_eq!(stable.type_url, STABLE.type_url);
+        assert_eq!(stable.title, "Bad Request");
+        assert_eq!(stable.status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(stable.code, "TEST_BAD_REQUEST");
+        assert_eq!(stable.help.as_deref(), STABLE.help_url);
+        assert_eq!(stable.detail, "quota policy rejected the request");
+        assert_eq!(stable.instance, "/quotas/7");
+        assert!(stable.fallback.is_none());
+
+        // Stable problems are left alone.
+        let plain = STABLE.as_problem("x");
+        assert_eq!(plain.clone().into_fallback(), plain);
+    }
+
+    #[test]
+    fn status_derived_remediation_actions() {
+        let _lock = crate::GLOBAL_STATE_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let too_many = test_def(429, "Too Many Requests", "TEST_RATE_LIMITED");
+        let problem = too_many.as_problem("slow down");
+        assert_eq!(
+            problem.actions,
+            vec![RemediationAction::RetryAfter {
+                seconds: crate::problem::DEFAULT_RETRY_AFTER_SECS
+            }]
+        );
+
+        let unauthorized = ErrDef {
+            status: 401,
+            title: "Unauthorized",
+            ..too_many
+        };
+        assert_eq!(
+            unauthorized.as_problem("expired").actions,
+            vec![RemediationAction::RefreshToken]
+        );
+
+        let not_found = ErrDef {
+            status: 404,
+            title: "Not Found",
+            ..too_many
+        };
+        assert!(not_found.as_problem("missing").actions.is_empty());
+    }
+
+    #[test]
+    fn call_site_metadata_overrides_catalog_defaults() {
+        let def = ErrDef {
+            metadata: &[("kind", "expired_token")],
+            ..UNAUTHORIZED_V1
+        };
+
+        let p = def
+            .as_problem("expired")
+            .with_metadata("kind", "revoked_token");
+        assert_eq!(p.metadata.as_ref().unwrap()["kind"], "revoked_token");
+
+        let mut p = def.as_problem("expired");
+        p.insert_metadata("kind", "unknown_key");
+        assert_eq!(p.metadata.as_ref().unwrap()["kind"], "unknown_key");
+    }
+
+    #[test]
+    fn default_metadata_is_merged_under_instance_fields() {
+        use serde_json::json;
+
+        let def = ErrDef {
+            metadata: &[
+                ("kind", "conflict"),
+                ("docs", "https://docs.example.com/errors/conflict"),
+            ],
+            ..test_def(409, "Conflict", "TEST_CONFLICT")
+        };
+
+        let metadata = def.as_problem("taken").metadata.unwrap();
+        assert_eq!(metadata["kind"], "conflict");
+        assert_eq!(metadata["docs"], "https://docs.example.com/errors/conflict");
+
+        let extra = HashMap::from([
+            ("kind".to_owned(), json!("version_conflict")),
+            ("attempted_id".to_owned(), json!("u-42")),
+        ]);
+        let metadata = def.as_problem_with("taken", extra).metadata.unwrap();
+        assert_eq!(metadata["kind"], "version_conflict");
+        assert_eq!(metadata["attempted_id"], "u-42");
+        assert_eq!(metadata["docs"], "https://docs.example.com/errors/conflict");
+    }
+
+    #[test]
+    fn nonstandard_status_falls_back_to_its_class() {
+        use http::StatusCode;
+
+        let def = test_def(499, "Client Closed Request", "TEST_CLIENT_CLOSED");
+        assert_eq!(def.as_problem("gone").status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn transient_classification_defaults_to_status() {
+        let def = test_def(500, "Error", "TEST_ERROR");
+        for (status, transient) in [
+            (400, false),
+            (404, false),
+            (408, true),
+            (409, false),
+            (429, true),
+            (500, true),
+            (501, false),
+            (502, true),
+            (503, true),
+            (504, true),
+        ] {
+            let def = ErrDef { status, ..def };
+            assert_eq!(def.is_transient(), transient, "status {status}");
+
+            let problem = def.as_problem("x");
+            assert_eq!(problem.is_retryable(), transient, "status {status}");
+            assert!(problem.metadata.is_none(), "status {status}");
+        }
+    }
+
+    #[test]
+    fn transient_override_is_surfaced_in_metadata() {
+        let permanent_unavailable = ErrDef {
+            transient: Some(false),
+            ..test_def(503, "Service Unavailable", "TEST_DECOMMISSIONED")
+        };
+        assert!(!permanent_unavailable.is_transient());
+
+        let problem = permanent_unavailable.as_problem("this endpoint was retired");
+        assert!(!problem.is_retryable());
+        assert_eq!(
+            problem.metadata.as_ref().unwrap()[TRANSIENT_METADATA_KEY],
+            false
+        );
+
+        // The override survives serialization.
+        let json = serde_json::to_string(&problem).unwrap();
+        let back: Problem = serde_json::from_str(&json).unwrap();
+        assert!(!back.is_retryable());
+    }
+
+    #[test]
+    fn severity_defaults_to_status_and_types_can_escalate() {
+        let def = test_def(500, "Error", "TEST_ERROR");
+        for (status, severity) in [
+            (404, Severity::Warning),
+            (422, Severity::Warning),
+            (500, Severity::Error),
+            (503, Severity::Error),
+        ] {
+            let def = ErrDef { status, ..def };
+            assert_eq!(def.severity(), severity, "status {status}");
+
+            let problem = def.as_problem("x");
+            assert_eq!(problem.severity(), severity, "status {status}");
+            assert!(problem.metadata.is_none(), "status {status}");
+        }
+
+        let ledger_corrupted = ErrDef {
+            code: "TEST_LEDGER_CORRUPTED",
+            severity: Some(Severity::Critical),
+            ..def
+        };
+        let problem = ledger_corrupted.as_problem("ledger checksum mismatch");
+        assert_eq!(problem.severity(), Severity::Critical);
+        assert_eq!(
+            problem.metadata.as_ref().unwrap()[crate::problem::SEVERITY_METADATA_KEY],
+            "critical"
+        );
+    }
+
+    #[test]
+    fn hand_built_problems_are_checked_against_the_catalog() {
+        use http::StatusCode;
+
+        let def = ErrDef {
+            type_url: "gts.cf.core.errors.err.v1~cf.test.errors.user_not_found.v1~",
+            ..test_def(404, "User Not Found", "TEST_USER_NOT_FOUND")
+        };
+        register_catalog([def]);
+
+        let consistent = Problem::new(StatusCode::NOT_FOUND, "User Not Found", "no user 7")
+            .with_type(def.type_url)
+            .checked_against_catalog();
+        assert!(consistent.is_ok());
+
+        let err = Problem::new(StatusCode::NOT_FOUND, "User not found", "no user 7")
+            .with_type(def.type_url)
+            .checked_against_catalog()
+            .unwrap_err();
+        assert_eq!(err.field, "title");
+        assert_eq!(err.expected, "User Not Found");
+
+        let err = Problem::new(StatusCode::GONE, "User Not Found", "no user 7")
+            .with_type(def.type_url)
+            .checked_against_catalog()
+            .unwrap_err();
+        assert_eq!(err.field, "status");
+        assert_eq!((err.expected.as_str(), err.actual.as_str()), ("404", "410"));
+
+        // Types outside the catalog are not constrained.
+        assert!(
+            Problem::new(StatusCode::GONE, "Anything", "x")
+                .with_type("gts.cf.core.errors.err.v1~cf.test.errors.unregistered.v1~")
+                .checked_against_catalog()
+                .is_ok()
+        );
+    }
 }
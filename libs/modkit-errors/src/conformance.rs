@@ -0,0 +1,181 @@
+//! Shape conformance checks for serialized problems
+//!
+//! `tests/catalog_conformance.rs` runs these checks over every error of every
+//! catalog, so a new error type cannot silently emit an unexpected member or
+//! drop a required one, or introduce an error code clients cannot match
+//! reliably.
+
+use serde_json::Value;
+
+/// Members every serialized `Problem` must carry.
+pub const REQUIRED_PROBLEM_KEYS: &[&str] =
+    &["type", "title", "status", "detail", "instance", "code"];
+
+/// Members a serialized `Problem` may carry.
+pub const OPTIONAL_PROBLEM_KEYS: &[&str] = &["trace_id", "errors", "help", "actions", "metadata"];
+
+/// Check that a serialized problem has exactly the allowed top-level members.
+///
+/// # Errors
+/// Returns one message per violation: body is not an object, a required member
+/// is missing, `status` is not a 4xx/5xx number, or an unknown member is present.
+pub fn check_problem_shape(body: &Value) -> Result<(), Vec<String>> {
+    let Some(obj) = body.as_object() else {
+        return Err(vec!["problem body is not a JSON object".to_owned()]);
+    };
+
+    let mut violations: Vec<String> = REQUIRED_PROBLEM_KEYS
+        .iter()
+        .filter(|key| !obj.contains_key(**key))
+        .map(|key| format!("missing required member `{key}`"))
+        .collect();
+
+    if let Some(status) = obj.get("status")
+        && !status.as_u64().is_some_and(|s| (400..=599).contains(&s))
+    {
+        violations.push(format!("`status` is not a 4xx/5xx code: {status}"));
+    }
+
+    violations.extend(
+        obj.keys()
+            .filter(|key| {
+                !REQUIRED_PROBLEM_KEYS.contains(&key.as_str())
+                    && !OPTIONAL_PROBLEM_KEYS.contains(&key.as_str())
+            })
+            .map(|key| format!("unexpected member `{key}`")),
+    );
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+/// Check that an error code or GTS error type id follows the GTS error
+/// identifier grammar: `gts.<ns>.core.errors.err.v<N>~<ns>.<segment>...v<N>`,
+/// optionally ending in `~` as type ids do.
+///
+/// The part up to and including the first `~` is the schema id of the base
+/// error type; the rest identifies the concrete error. All segments are
+/// lowercase alphanumeric (with underscores).
+///
+/// # Errors
+/// Returns one message per violation.
+pub fn check_error_code(code: &str) -> Result<(), Vec<String>> {
+    let mut violations = Vec::new();
+
+    if code.chars().any(|c| c.is_ascii_uppercase()) {
+        violations.push(format!("`{code}` is not lowercase"));
+    }
+
+    let Some((base, instance)) = code.split_once('~') else {
+        violations.push(format!(
+            "`{code}` has no `~` after the base error schema id"
+        ));
+        return Err(violations);
+    };
+
+    let base: Vec<&str> = base.split('.').collect();
+    let base_ok = matches!(
+        base.as_slice(),
+        ["gts", ns, "core", "errors", "err", version] if is_segment(ns) && is_version(version)
+    );
+    if !base_ok {
+        violations.push(format!(
+            "`{code}` does not start with `gts.<ns>.core.errors.err.v<N>~`"
+        ));
+    }
+
+    let instance = instance.strip_suffix('~').unwrap_or(instance);
+    let segments: Vec<&str> = instance.split('.').collect();
+    if segments.len() < 3 || !segments.iter().all(|s| is_segment(s)) {
+        violations.push(format!(
+            "`{code}` has a malformed error identifier after `~`"
+        ));
+    } else if !segments.last().is_some_and(|s| is_version(s)) {
+        violations.push(format!("`{code}` does not end with a version `v<N>`"));
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+fn is_segment(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn is_version(s: &str) -> bool {
+    s.strip_prefix('v')
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::Problem;
+    use http::StatusCode;
+    use serde_json::json;
+
+    #[test]
+    fn problem_conforms() {
+        let p = Problem::new(StatusCode::NOT_FOUND, "Not Found", "missing")
+            .with_metadata("resource", "user");
+        check_problem_shape(&serde_json::to_value(p).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn non_conforming_fixture_is_rejected() {
+        let fixture = json!({
+            "type": "about:blank",
+            "title": "Broken",
+            "detail": "no status",
+            "instance": "",
+            "code": "BROKEN",
+            "message": "legacy member",
+        });
+
+        let violations = check_problem_shape(&fixture).unwrap_err();
+        assert_eq!(
+            violations,
+            vec![
+                "missing required member `status`".to_owned(),
+                "unexpected member `message`".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn non_error_status_is_rejected() {
+        let mut body = serde_json::to_value(Problem::new(StatusCode::NOT_FOUND, "x", "y")).unwrap();
+        body["status"] = json!(200);
+        assert!(check_problem_shape(&body).is_err());
+    }
+
+    #[test]
+    fn well_formed_error_code_is_accepted() {
+        check_error_code("gts.hx.core.errors.err.v1~hx.odata.errors.invalid_filter.v1").unwrap();
+        check_error_code("gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~").unwrap();
+    }
+
+    #[test]
+    fn malformed_error_codes_are_rejected() {
+        for code in [
+            "gts.hx.core.errors.err.v1~hx.odata.errors.Invalid_Filter.v1",
+            "gts.hx.core.errors.err.v1.hx.odata.errors.invalid_filter.v1",
+            "gts.hx.core.errors.v1~hx.odata.errors.invalid_filter.v1",
+            "gts.hx.core.errors.err.v1~hx.odata.errors.invalid_filter",
+            "gts.hx.core.errors.err.v1~hx.odata.errors.invalid_filter.v1~~",
+            "gts.hx.core.errors.err.v1~hx.odata.errors.invalid_filter~",
+            "gts.hx.core.errors.err.v1~",
+        ] {
+            assert!(check_error_code(code).is_err(), "{code} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! `tonic::Status` for problems and catalog errors (feature `tonic`)
+//!
+//! A gRPC surface reports the same error taxonomy as the REST API: the HTTP
+//! status of a [`Problem`] selects the gRPC code (see [`grpc_code`]), the
+//! detail becomes the status message and the problem itself, serialized as
+//! `application/problem+json`, becomes the status details. Clients thus get
+//! the GTS type URI, code, trace id and metadata of the error from
+//! [`tonic::Status::details`]. Errors derived with `#[derive(GtsError)]` or
+//! listed in a catalog convert through their [`ErrDef`].
+
+use http::StatusCode;
+use tonic::{Code, Status};
+
+use crate::catalog::ErrDef;
+use crate::problem::Problem;
+
+/// gRPC code reporting a failure with HTTP status `status`.
+#[must_use]
+pub fn grpc_code(status: StatusCode) -> Code {
+    match status.as_u16() {
+        400 | 422 => Code::InvalidArgument,
+        401 => Code::Unauthenticated,
+        403 => Code::PermissionDenied,
+        404 => Code::NotFound,
+        409 => Code::AlreadyExists,
+        412 => Code::FailedPrecondition,
+        429 => Code::ResourceExhausted,
+        499 => Code::Cancelled,
+        501 => Code::Unimplemented,
+        502 | 503 => Code::Unavailable,
+        408 | 504 => Code::DeadlineExceeded,
+        500 => Code::Internal,
+        _ => Code::Unknown,
+    }
+}
+
+impl From<&Problem> for Status {
+    fn from(problem: &Problem) -> Self {
+        let message = if problem.detail.is_empty() {
+            problem.title.clone()
+        } else {
+            problem.detail.clone()
+        };
+        // Serializing a `Problem` cannot fail: its metadata keys are strings
+        let details = serde_json::to_vec(problem).unwrap_or_default();
+        Status::with_details(grpc_code(problem.status), message, details.into())
+    }
+}
+
+impl From<Problem> for Status {
+    fn from(problem: Problem) -> Self {
+        Status::from(&problem)
+    }
+}
+
+impl ErrDef {
+    /// gRPC status for an occurrence of this error, like
+    /// [`ErrDef::as_problem`] on the REST side.
+    #[must_use]
+    pub fn as_grpc_status(&self, detail: impl Into<String>) -> Status {
+        self.as_problem(detail).into()
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::catalog::{CONFLICT_V1, INTERNAL_ERROR_V1, NOT_FOUND_V1};
+
+    fn def(status: u16) -> ErrDef {
+        ErrDef {
+            status,
+            title: "Test",
+            code: "TEST",
+            type_url: "gts.cf.core.errors.err.v1~cf.test.errors.test.v1~",
+            help_url: None,
+            metadata: &[],
+            transient: None,
+            severity: None,
+            fallback: None,
+            titles: &[],
+        }
+    }
+
+    #[test]
+    fn http_statuses_map_to_grpc_codes() {
+        for (status, code) in [
+            (404, Code::NotFound),
+            (409, Code::AlreadyExists),
+            (422, Code::InvalidArgument),
+            (403, Code::PermissionDenied),
+            (500, Code::Internal),
+            (503, Code::Unavailable),
+        ] {
+            let status_code = StatusCode::from_u16(status).unwrap();
+            assert_eq!(grpc_code(status_code), code, "{status}");
+            assert_eq!(def(status).as_grpc_status("boom").code(), code, "{status}");
+        }
+        assert_eq!(grpc_code(StatusCode::IM_A_TEAPOT), Code::Unknown);
+    }
+
+    #[test]
+    fn catalog_errors_convert() {
+        assert_eq!(NOT_FOUND_V1.as_grpc_status("x").code(), Code::NotFound);
+        assert_eq!(CONFLICT_V1.as_grpc_status("x").code(), Code::AlreadyExists);
+        let status = INTERNAL_ERROR_V1.as_grpc_status("");
+        assert_eq!(status.code(), Code::Internal);
+        assert_eq!(status.message(), INTERNAL_ERROR_V1.title);
+    }
+
+    #[test]
+    fn problem_survives_into_details() {
+        let problem = NOT_FOUND_V1
+            .as_problem("no such user")
+            .with_trace_id("req-1")
+            .with_metadata("user_id", 42)
+            .with_metadata("tenant", "acme");
+        let status = Status::from(&problem);
+        assert_eq!(status.message(), "no such user");
+
+        let details: serde_json::Value = serde_json::from_slice(status.details()).unwrap();
+        assert_eq!(details["type"], NOT_FOUND_V1.type_url);
+        assert_eq!(details["code"], NOT_FOUND_V1.code);
+        assert_eq!(details["trace_id"], "req-1");
+        assert_eq!(details["metadata"]["user_id"], 42);
+        assert_eq!(details["metadata"]["tenant"], "acme");
+
+        let back: Problem = serde_json::from_slice(status.details()).unwrap();
+        assert_eq!(back, problem);
+    }
+}
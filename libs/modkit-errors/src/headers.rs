@@ -0,0 +1,44 @@
+//! Typed names of the HTTP headers the framework reads and writes
+//!
+//! Each constant is built from the matching string constant in
+//! [`crate::problem`], so both spellings always agree.
+
+use http::HeaderName;
+
+use crate::problem;
+
+/// Response header carrying the problem's `trace_id`; also read from requests.
+pub const TRACE_ID: HeaderName = HeaderName::from_static(problem::TRACE_ID_HEADER);
+
+/// Response header carrying the problem's machine-readable `code`.
+pub const ERROR_CODE: HeaderName = HeaderName::from_static(problem::ERROR_CODE_HEADER);
+
+/// Request header whose value is reused as the trace id when `x-trace-id` is
+/// absent.
+pub const REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// W3C Trace Context header.
+pub const TRACEPARENT: HeaderName = HeaderName::from_static("traceparent");
+
+/// Request header opting into experimental error types.
+pub const ACCEPT_EXPERIMENTAL_ERRORS: HeaderName =
+    HeaderName::from_static(problem::ACCEPT_EXPERIMENTAL_ERRORS_HEADER);
+
+/// Content type of Problem Details bodies.
+pub use crate::problem::{APPLICATION_PROBLEM_JSON, APPLICATION_PROBLEM_JSON_VERSIONED};
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_names_match_their_wire_spelling() {
+        assert_eq!(TRACE_ID, "x-trace-id");
+        assert_eq!(ERROR_CODE, "x-error-code");
+        assert_eq!(REQUEST_ID, "x-request-id");
+        assert_eq!(TRACEPARENT, "traceparent");
+        assert_eq!(ACCEPT_EXPERIMENTAL_ERRORS, "x-accept-experimental-errors");
+        assert_eq!(APPLICATION_PROBLEM_JSON, "application/problem+json");
+    }
+}
@@ -4,26 +4,80 @@
 //! on HTTP frameworks. It includes:
 //! - RFC 9457 Problem Details (`Problem`)
 //! - Error catalog support (`ErrDef`)
+//! - Typed names of framework headers (`headers`)
+//! - Reverse lookup of catalog definitions by type URI (`ProblemRegistry`)
+//! - Edge remapping of error codes (`CodeRemapper`)
+//! - Pluggable reporting of server-side problems (`ProblemReporter`)
+//! - Axum extractor rejections as problems (`axum` feature)
+//! - gRPC statuses for problems and catalog errors (`tonic` feature)
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
 pub mod catalog;
+pub mod conformance;
+#[cfg(feature = "tonic")]
+pub mod grpc;
+pub mod headers;
 pub mod problem;
+pub mod registry;
+#[cfg(feature = "axum")]
+pub mod rejection;
+pub mod remap;
+pub mod reporter;
 
 // Re-export commonly used types
-pub use catalog::ErrDef;
+pub use catalog::{
+    BAD_GATEWAY_V1, BAD_REQUEST_V1, CONFLICT_V1, CatalogMismatch, ErrDef, FORBIDDEN_V1,
+    FRAMEWORK_ERRORS, INTERNAL_ERROR_V1, MISSING_FEATURES_METADATA_KEY, NOT_FOUND_V1,
+    PAYLOAD_TOO_LARGE_V1, PAYMENT_REQUIRED_V1, SERVICE_UNAVAILABLE_V1, TOO_MANY_REQUESTS_V1,
+    UNAUTHORIZED_V1, UNSUPPORTED_MEDIA_TYPE_V1, VALIDATION_FAILED_V1, register_catalog,
+};
 pub use problem::{
-    APPLICATION_PROBLEM_JSON, Problem, ValidationError, ValidationErrorResponse,
-    ValidationViolation,
+    ACCEPT_EXPERIMENTAL_ERRORS_HEADER, APPLICATION_PROBLEM_JSON,
+    APPLICATION_PROBLEM_JSON_VERSIONED, BAD_GATEWAY_TYPE, DEFAULT_MAX_ERROR_HEADER_LEN,
+    DEFAULT_PROBLEM_LANGUAGE, DEFAULT_RETRY_AFTER_SECS, ERROR_CODE_HEADER, MAX_TRACE_ID_LEN,
+    PROBLEM_SCHEMA_VERSION, PRODUCTION_SAFE_DETAIL, PRODUCTION_SAFE_METADATA_KEYS, Problem,
+    ProblemBuilder, RATE_LIMIT_METADATA_KEYS, RETRY_AFTER_METADATA_KEY, RemediationAction,
+    SCHEMA_ID_METADATA_KEY, SEVERITY_METADATA_KEY, Severity, TOO_MANY_REQUESTS_TYPE_SUFFIX,
+    TRACE_ID_HEADER, TRANSIENT_METADATA_KEY, UPSTREAM_METADATA_KEYS, ValidationError,
+    ValidationErrorResponse, ValidationViolation, default_retry_after_secs, is_production_mode,
+    is_transient_status, json_pointer, max_error_header_len, normalize_status,
+    set_default_retry_after_secs, set_max_error_header_len, set_production_mode, validate_trace_id,
+};
+#[cfg(feature = "axum")]
+pub use problem::{FallbackProblem, LocalizableProblem};
+pub use registry::ProblemRegistry;
+pub use remap::{CodeRemapper, RemapRule, clear_code_remapper, set_code_remapper};
+pub use reporter::{
+    LoggingReporter, NoopReporter, ProblemReporter, ReportContext, report_problem,
+    set_problem_reporter,
 };
 
+/// Serializes tests that change process-wide settings (such as the problem
+/// reporter, production mode or the default retry delay) with the tests
+/// depending on them, so one test's setting cannot leak into another.
+///
+/// Exported with the `test-utils` feature for the tests of other crates.
+#[cfg(any(test, feature = "test-utils"))]
+pub static GLOBAL_STATE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
 /// Helper to attach instance and `trace_id` to a Problem
 ///
 /// This is a convenience function for enriching Problem instances with
 /// request-specific context before returning them as HTTP responses.
+/// Server errors (5xx) are forwarded to the registered `ProblemReporter`.
 pub fn finalize(mut p: Problem, instance: &str, trace_id: Option<String>) -> Problem {
     p = p.with_instance(instance);
-    if let Some(tid) = trace_id {
+    if let Some(tid) = trace_id.clone() {
         p = p.with_trace_id(tid);
     }
+    report_problem(
+        &p,
+        &ReportContext {
+            instance: instance.to_owned(),
+            trace_id,
+        },
+    );
     p
 }
@@ -1,13 +1,44 @@
 //! RFC 9457 Problem Details for HTTP APIs (pure data model, no HTTP framework dependencies)
 
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
 use http::StatusCode;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[cfg(feature = "utoipa")]
 use utoipa::ToSchema;
 
+// Literal sources of the constants below, so `concat!` can build the
+// versioned content type from them at compile time.
+macro_rules! problem_json_media_type {
+    () => {
+        "application/problem+json"
+    };
+}
+macro_rules! problem_schema_version {
+    () => {
+        1
+    };
+}
+
 /// Content type for Problem Details as per RFC 9457.
-pub const APPLICATION_PROBLEM_JSON: &str = "application/problem+json";
+pub const APPLICATION_PROBLEM_JSON: &str = problem_json_media_type!();
+
+/// Version of the Problem body shape produced by this crate.
+///
+/// Bumped whenever members are added or their semantics change, so consumers
+/// can negotiate which fields to expect.
+pub const PROBLEM_SCHEMA_VERSION: u32 = problem_schema_version!();
+
+/// Content type emitted on Problem responses, carrying the schema version as a
+/// media-type parameter. Clients that ignore parameters still see
+/// `application/problem+json`.
+pub const APPLICATION_PROBLEM_JSON_VERSIONED: &str = concat!(
+    problem_json_media_type!(),
+    "; version=",
+    problem_schema_version!()
+);
 
 /// Custom serializer for `StatusCode` to u16
 #[allow(clippy::trivially_copy_pass_by_ref)] // serde requires &T signature
@@ -18,17 +49,110 @@ where
     serializer.serialize_u16(status.as_u16())
 }
 
-/// Custom deserializer for `StatusCode` from u16
-fn deserialize_status_code<'de, D>(deserializer: D) -> Result<StatusCode, D::Error>
+/// Map a raw status code onto a known `StatusCode`.
+///
+/// Standard codes are returned as-is. A code without a canonical reason but
+/// within 100..=599 (e.g. 499) falls back to the generic code of its class
+/// (`400` for 4xx, `500` for 5xx, ...); anything outside that range becomes
+/// `500`. The flag is `true` when the code was coerced, and every coercion is
+/// logged.
+#[must_use]
+pub fn normalize_status(code: u16) -> (StatusCode, bool) {
+    if let Ok(status) = StatusCode::from_u16(code)
+        && status.canonical_reason().is_some()
+    {
+        return (status, false);
+    }
+
+    let fallback = match code {
+        100..=199 => StatusCode::CONTINUE,
+        200..=299 => StatusCode::OK,
+        300..=399 => StatusCode::MULTIPLE_CHOICES,
+        400..=499 => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    tracing::warn!(
+        status = code,
+        coerced_to = fallback.as_u16(),
+        "non-standard HTTP status coerced"
+    );
+    (fallback, true)
+}
+
+/// Strict deserializer for `StatusCode`: accepts numeric codes only.
+///
+/// # Errors
+/// Fails when the input is not a `u16`.
+pub fn deserialize_status_code<'de, D>(deserializer: D) -> Result<StatusCode, D::Error>
 where
     D: Deserializer<'de>,
 {
     let code = u16::deserialize(deserializer)?;
-    StatusCode::from_u16(code).map_err(serde::de::Error::custom)
+    Ok(normalize_status(code).0)
+}
+
+/// Lenient deserializer for `StatusCode`, used by [`Problem`].
+///
+/// Accepts a numeric code (`404`), a numeric string (`"404"`) or a canonical
+/// reason phrase in any case (`"Not Found"`), as emitted by some upstreams.
+///
+/// # Errors
+/// Fails on any other input, e.g. an unknown reason phrase.
+pub fn deserialize_status_code_lenient<'de, D>(deserializer: D) -> Result<StatusCode, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct StatusVisitor;
+
+    impl serde::de::Visitor<'_> for StatusVisitor {
+        type Value = StatusCode;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("an HTTP status code or its reason phrase")
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<StatusCode, E> {
+            u16::try_from(v)
+                .map(|code| normalize_status(code).0)
+                .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+        }
+
+        fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<StatusCode, E> {
+            u16::try_from(v)
+                .map(|code| normalize_status(code).0)
+                .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(v), &self))
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<StatusCode, E> {
+            let v = v.trim();
+            if let Ok(code) = v.parse::<u16>() {
+                return Ok(normalize_status(code).0);
+            }
+            status_from_reason(v)
+                .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+        }
+    }
+
+    deserializer.deserialize_any(StatusVisitor)
+}
+
+/// Status whose canonical reason phrase is `reason`, ignoring case.
+fn status_from_reason(reason: &str) -> Option<StatusCode> {
+    (100..=599)
+        .filter_map(|code| StatusCode::from_u16(code).ok())
+        .find(|status| {
+            status
+                .canonical_reason()
+                .is_some_and(|r| r.eq_ignore_ascii_case(reason))
+        })
 }
 
 /// RFC 9457 Problem Details for HTTP APIs.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Equality compares every field, including those never serialized, and
+/// metadata by content regardless of insertion order. `Problem` is not `Eq`:
+/// metadata values are JSON and may hold floats.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[cfg_attr(
     feature = "utoipa",
@@ -49,7 +173,7 @@ pub struct Problem {
     /// Serializes as u16 for RFC 9457 compatibility.
     #[serde(
         serialize_with = "serialize_status_code",
-        deserialize_with = "deserialize_status_code"
+        deserialize_with = "deserialize_status_code_lenient"
     )]
     #[cfg_attr(feature = "utoipa", schema(value_type = u16))]
     pub status: StatusCode,
@@ -61,12 +185,98 @@ pub struct Problem {
     pub code: String,
     /// Optional trace id useful for tracing.
     pub trace_id: Option<String>,
-    /// Optional validation errors for 4xx problems.
+    /// Optional validation errors for 4xx problems, one per offending field;
+    /// omitted from the body when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub errors: Option<Vec<ValidationViolation>>,
+    /// Optional documentation URL for this problem type (a "learn more" link).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    /// Machine-readable remediation hints a client can act on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<RemediationAction>,
+    /// Optional extension members with machine-readable context for this occurrence.
+    ///
+    /// Kept sorted by key so serialized problems are byte-for-byte deterministic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<Object>))]
+    pub metadata: Option<BTreeMap<String, serde_json::Value>>,
+    /// Skip reporting and logging of this problem (never serialized).
+    ///
+    /// Meant for expected errors such as a health-probe 404 that would only
+    /// add noise; see [`Problem::with_log_suppressed`].
+    #[serde(skip)]
+    pub suppress_log: bool,
+    /// Language tag of the locale `title` and `detail` were rendered in, as
+    /// negotiated from `Accept-Language` (never serialized).
+    ///
+    /// Emitted as `Content-Language`; [`DEFAULT_PROBLEM_LANGUAGE`] when unset.
+    #[serde(skip)]
+    pub language: Option<String>,
+    /// Stable type to present instead of this (experimental) one to clients
+    /// that did not opt in (never serialized); see [`Problem::into_fallback`].
+    #[serde(skip)]
+    pub fallback: Option<&'static crate::catalog::ErrDef>,
+    /// Localized titles as `(language tag, title)` pairs (never serialized);
+    /// see [`Problem::localized`].
+    #[serde(skip)]
+    pub titles: &'static [(&'static str, &'static str)],
+}
+
+/// Language of problems whose locale was not negotiated.
+pub const DEFAULT_PROBLEM_LANGUAGE: &str = "en";
+
+/// Default for [`set_default_retry_after_secs`].
+///
+/// A rate-limited caller usually has no better hint than the status code, so
+/// the delay errs on the long side: retrying sooner mostly gets throttled
+/// again.
+pub const DEFAULT_RETRY_AFTER_SECS: u64 = 30;
+
+static DEFAULT_RETRY_AFTER: AtomicU64 = AtomicU64::new(DEFAULT_RETRY_AFTER_SECS);
+
+/// Set process-wide the delay suggested by the `retry_after` action derived
+/// for 429 problems that carry no explicit delay
+/// (see [`RemediationAction::defaults_for`]).
+pub fn set_default_retry_after_secs(seconds: u64) {
+    DEFAULT_RETRY_AFTER.store(seconds, Ordering::Relaxed);
+}
+
+/// The current delay set by [`set_default_retry_after_secs`].
+#[must_use]
+pub fn default_retry_after_secs() -> u64 {
+    DEFAULT_RETRY_AFTER.load(Ordering::Relaxed)
+}
+
+/// Structured remediation hint, e.g. `{"action":"retry_after","seconds":30}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RemediationAction {
+    /// Obtain a fresh access token and retry.
+    RefreshToken,
+    /// Retry the request after the given number of seconds.
+    RetryAfter { seconds: u64 },
+}
+
+impl RemediationAction {
+    /// Actions implied by a status code: 401 suggests `refresh_token`,
+    /// 429 suggests `retry_after` the [`default_retry_after_secs`]. Other
+    /// statuses imply none.
+    #[must_use]
+    pub fn defaults_for(status: StatusCode) -> Vec<Self> {
+        match status {
+            StatusCode::UNAUTHORIZED => vec![Self::RefreshToken],
+            StatusCode::TOO_MANY_REQUESTS => vec![Self::RetryAfter {
+                seconds: default_retry_after_secs(),
+            }],
+            _ => Vec::new(),
+        }
+    }
 }
 
 /// Individual validation violation for a specific field or property.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[cfg_attr(feature = "utoipa", schema(title = "ValidationViolation"))]
 pub struct ValidationViolation {
@@ -77,6 +287,72 @@ pub struct ValidationViolation {
     /// Optional machine-readable error code
     #[serde(skip_serializing_if = "Option::is_none")]
     pub code: Option<String>,
+    /// RFC 6901 JSON Pointer to the offending value in the submitted document,
+    /// e.g. "/address/postalCode" or "/items/0/sku"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pointer: Option<String>,
+}
+
+impl ValidationViolation {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+            code: None,
+            pointer: None,
+        }
+    }
+
+    /// Violation located by a JSON Pointer, as reported by JSON-schema
+    /// validators. The human-readable `field` is derived from the pointer
+    /// (`/address/postalCode` becomes `address.postalCode`).
+    pub fn at_pointer(pointer: impl Into<String>, message: impl Into<String>) -> Self {
+        let pointer = pointer.into();
+        let field = pointer
+            .split('/')
+            .skip(1)
+            .map(unescape_pointer_token)
+            .collect::<Vec<_>>()
+            .join(".");
+        Self {
+            field,
+            message: message.into(),
+            code: None,
+            pointer: Some(pointer),
+        }
+    }
+
+    #[must_use]
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_pointer(mut self, pointer: impl Into<String>) -> Self {
+        self.pointer = Some(pointer.into());
+        self
+    }
+}
+
+/// Build an RFC 6901 JSON Pointer from path segments, escaping `~` and `/`.
+#[must_use]
+pub fn json_pointer<I, S>(segments: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    segments
+        .into_iter()
+        .fold(String::new(), |mut pointer, segment| {
+            pointer.push('/');
+            pointer.push_str(&segment.as_ref().replace('~', "~0").replace('/', "~1"));
+            pointer
+        })
+}
+
+fn unescape_pointer_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
 }
 
 /// Collection of validation errors for 422 responses.
@@ -113,6 +389,54 @@ impl Problem {
             code: String::new(),
             trace_id: None,
             errors: None,
+            help: None,
+            actions: Vec::new(),
+            metadata: None,
+            suppress_log: false,
+            language: None,
+            fallback: None,
+            titles: &[],
+        }
+    }
+
+    /// Mark this problem as expected so `finalize`/`map_error_to_problem` emit
+    /// no log or report event for it, whatever its status.
+    pub fn with_log_suppressed(mut self) -> Self {
+        self.suppress_log = true;
+        self
+    }
+
+    /// Record the locale chosen for `title`/`detail` from `Accept-Language`,
+    /// including the fallback locale when no requested one was available.
+    ///
+    /// The response then carries `Content-Language` with this tag and
+    /// `Vary: Accept-Language`.
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Attach localized titles as `(language tag, title)` pairs.
+    pub fn with_titles(mut self, titles: &'static [(&'static str, &'static str)]) -> Self {
+        self.titles = titles;
+        self
+    }
+
+    /// Render `title` in the language of [`Problem::titles`] that best matches
+    /// `accept_language`, an `Accept-Language` header value.
+    ///
+    /// Language ranges are tried by decreasing quality; each matches a title of
+    /// the same tag, or else of its primary subtag (`fr-CA` falls back to `fr`).
+    /// Without a match the default title is kept. The chosen language, or
+    /// [`DEFAULT_PROBLEM_LANGUAGE`] for the default title, is recorded with
+    /// [`Problem::with_language`].
+    pub fn localized(mut self, accept_language: &str) -> Self {
+        match best_title(self.titles, accept_language) {
+            Some((language, title)) => {
+                title.clone_into(&mut self.title);
+                self.with_language(language)
+            }
+            None => self.with_language(DEFAULT_PROBLEM_LANGUAGE),
         }
     }
 
@@ -140,78 +464,1662 @@ impl Problem {
         self.errors = Some(errors);
         self
     }
+
+    pub fn with_help(mut self, url: impl Into<String>) -> Self {
+        self.help = Some(url.into());
+        self
+    }
+
+    /// Parse a bulk error envelope `{ "errors": [ <problem>, ... ] }` into problems.
+    ///
+    /// A single bare problem (an object with a `status` member) is accepted too
+    /// and returned as a one-element list. Note that a bare problem's own
+    /// `errors` member holds validation violations, not nested problems.
+    ///
+    /// # Errors
+    /// Returns a `serde_json::Error` if the input is not valid JSON or any
+    /// entry is not a valid problem.
+    pub fn parse_envelope(input: &str) -> Result<Vec<Problem>, serde_json::Error> {
+        let value: serde_json::Value = serde_json::from_str(input)?;
+        let is_envelope = value.get("status").is_none()
+            && value.get("errors").is_some_and(serde_json::Value::is_array);
+        if is_envelope {
+            let errors = value
+                .get("errors")
+                .cloned()
+                .unwrap_or(serde_json::Value::Null);
+            serde_json::from_value(errors)
+        } else {
+            serde_json::from_value(value).map(|p| vec![p])
+        }
+    }
+
+    /// Append a remediation action.
+    pub fn with_action(mut self, action: RemediationAction) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    /// Attach one metadata entry, replacing any previous value under `key`
+    /// (last write wins, including over catalog defaults).
+    pub fn with_metadata(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        self.insert_metadata(key, value);
+        self
+    }
+
+    /// In-place form of [`Problem::with_metadata`], for enriching a problem
+    /// held by reference.
+    pub fn insert_metadata(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> &mut Self {
+        self.metadata
+            .get_or_insert_with(BTreeMap::new)
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Merge call-site context into `metadata`.
+    ///
+    /// Entries already present on the problem take precedence over `extra` on key collisions.
+    pub fn merge_metadata(
+        mut self,
+        extra: impl IntoIterator<Item = (String, serde_json::Value)>,
+    ) -> Self {
+        let mut extra = extra.into_iter().peekable();
+        if extra.peek().is_none() {
+            return self;
+        }
+        let metadata = self.metadata.get_or_insert_with(BTreeMap::new);
+        for (key, value) in extra {
+            metadata.entry(key).or_insert(value);
+        }
+        self
+    }
+
+    /// Record the rate-limit state of a throttled request.
+    ///
+    /// The values are stored under [`RATE_LIMIT_METADATA_KEYS`] and emitted as
+    /// `RateLimit-Limit`, `RateLimit-Remaining` and `RateLimit-Reset` response
+    /// headers when the problem becomes a response.
+    pub fn with_rate_limit(self, limit: u64, remaining: u64, reset_secs: u64) -> Self {
+        let [limit_key, remaining_key, reset_key] = RATE_LIMIT_METADATA_KEYS;
+        self.with_metadata(limit_key, limit)
+            .with_metadata(remaining_key, remaining)
+            .with_metadata(reset_key, reset_secs)
+    }
+
+    /// Tell the client how long to back off before retrying.
+    ///
+    /// Stored under [`RETRY_AFTER_METADATA_KEY`] (emitted as a `Retry-After`
+    /// header for [`TOO_MANY_REQUESTS_TYPE_SUFFIX`] types) and replaces any
+    /// `retry_after` remediation action with one carrying `seconds`.
+    pub fn with_retry_after(mut self, seconds: u64) -> Self {
+        self.actions
+            .retain(|action| !matches!(action, RemediationAction::RetryAfter { .. }));
+        self.actions.push(RemediationAction::RetryAfter { seconds });
+        self.with_metadata(RETRY_AFTER_METADATA_KEY, seconds)
+    }
+
+    /// Record the `$id` of the schema a payload failed to validate against.
+    ///
+    /// Stored under [`SCHEMA_ID_METADATA_KEY`] so clients can fetch the schema
+    /// to understand the violated constraints.
+    pub fn with_schema_id(self, schema_id: impl Into<String>) -> Self {
+        self.with_metadata(SCHEMA_ID_METADATA_KEY, schema_id.into())
+    }
 }
 
-/// Axum integration: make Problem directly usable as a response.
+/// Builder for a [`Problem`] assembled in several steps.
 ///
-/// Automatically enriches the Problem with `trace_id` from the current
-/// tracing span if not already set.
-#[cfg(feature = "axum")]
-impl axum::response::IntoResponse for Problem {
-    fn into_response(self) -> axum::response::Response {
-        use axum::http::HeaderValue;
+/// Unset, the status is 500, the title its canonical reason and the type
+/// `about:blank`. A malformed trace id is reported by [`ProblemBuilder::build`]
+/// rather than by the setter, so the calls chain without intermediate checks.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct ProblemBuilder {
+    problem: Problem,
+    title: Option<String>,
+    error: Option<String>,
+}
 
-        // Enrich with trace_id from current span if not already set
-        let problem = if self.trace_id.is_none() {
-            match tracing::Span::current().id() {
-                Some(span_id) => self.with_trace_id(span_id.into_u64().to_string()),
-                _ => self,
+impl Default for ProblemBuilder {
+    fn default() -> Self {
+        Self {
+            problem: Problem::new(StatusCode::INTERNAL_SERVER_ERROR, "", ""),
+            title: None,
+            error: None,
+        }
+    }
+}
+
+impl Problem {
+    /// Start a [`ProblemBuilder`].
+    pub fn builder() -> ProblemBuilder {
+        ProblemBuilder::default()
+    }
+}
+
+impl ProblemBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.problem.status = status;
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.problem.detail = detail.into();
+        self
+    }
+
+    pub fn type_url(mut self, type_url: impl Into<String>) -> Self {
+        self.problem.type_url = type_url.into();
+        self
+    }
+
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.problem.instance = instance.into();
+        self
+    }
+
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.problem.code = code.into();
+        self
+    }
+
+    /// Attach `trace_id`, checked with [`validate_trace_id`] when building; an
+    /// empty trace id attaches none.
+    pub fn trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        let trace_id = trace_id.into();
+        if trace_id.is_empty() {
+            self.problem.trace_id = None;
+            return self;
+        }
+        if let Err(e) = validate_trace_id(&trace_id) {
+            self.error.get_or_insert(e);
+        }
+        self.problem.trace_id = Some(trace_id);
+        self
+    }
+
+    /// Attach one metadata entry, replacing any previous value under `key`.
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+        self.problem = self.problem.with_metadata(key, value);
+        self
+    }
+
+    /// Finish the problem.
+    ///
+    /// # Errors
+    /// Returns the validation error of a malformed trace id.
+    pub fn build(self) -> Result<Problem, String> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let mut problem = self.problem;
+        problem.title = self.title.unwrap_or_else(|| {
+            problem
+                .status
+                .canonical_reason()
+                .unwrap_or_default()
+                .to_owned()
+        });
+        Ok(problem)
+    }
+}
+
+impl Problem {
+    /// Render this problem as a JSON:API error document, for clients that do
+    /// not read `application/problem+json`.
+    ///
+    /// Each error object carries the status (as a string), the type URI as
+    /// `code`, the title, the detail, the trace id as `id` and the metadata as
+    /// `meta`. A problem with validation violations yields one error object per
+    /// violation, located by `source.pointer` (derived from the field path when
+    /// the violation has no pointer) and coded by the violation's own code when
+    /// it has one.
+    #[must_use]
+    pub fn to_jsonapi(&self) -> serde_json::Value {
+        let code = if self.type_url == "about:blank" && !self.code.is_empty() {
+            &self.code
+        } else {
+            &self.type_url
+        };
+        let error = |code: &str, detail: &str, pointer: Option<String>| {
+            let mut object = serde_json::Map::new();
+            object.insert("status".to_owned(), self.status.as_u16().to_string().into());
+            object.insert("code".to_owned(), code.into());
+            object.insert("title".to_owned(), self.title.clone().into());
+            if !detail.is_empty() {
+                object.insert("detail".to_owned(), detail.into());
+            }
+            if let Some(trace_id) = &self.trace_id {
+                object.insert("id".to_owned(), trace_id.clone().into());
+            }
+            if let Some(pointer) = pointer {
+                object.insert(
+                    "source".to_owned(),
+                    serde_json::json!({ "pointer": pointer }),
+                );
             }
+            if let Some(metadata) = &self.metadata {
+                object.insert("meta".to_owned(), serde_json::json!(metadata));
+            }
+            serde_json::Value::Object(object)
+        };
+
+        let errors: Vec<serde_json::Value> = match self.errors.as_deref() {
+            Some(violations) if !violations.is_empty() => violations
+                .iter()
+                .map(|v| {
+                    let pointer = v
+                        .pointer
+                        .clone()
+                        .unwrap_or_else(|| json_pointer(v.field.split('.')));
+                    error(v.code.as_deref().unwrap_or(code), &v.message, Some(pointer))
+                })
+                .collect(),
+            _ => vec![error(code, &self.detail, None)],
+        };
+        serde_json::json!({ "errors": errors })
+    }
+}
+
+/// Type URI of the problem synthesized by [`Problem::from_upstream_response`],
+/// that of [`BAD_GATEWAY_V1`].
+///
+/// [`BAD_GATEWAY_V1`]: crate::catalog::BAD_GATEWAY_V1
+pub const BAD_GATEWAY_TYPE: &str = crate::catalog::BAD_GATEWAY_V1.type_url;
+
+/// Metadata keys describing the upstream response wrapped by
+/// [`Problem::from_upstream_response`]: status, type, code and trace id.
+pub const UPSTREAM_METADATA_KEYS: [&str; 4] = [
+    "upstream_status",
+    "upstream_type",
+    "upstream_code",
+    "upstream_trace_id",
+];
+
+impl Problem {
+    /// Build the gateway-level problem for an upstream service's error response.
+    ///
+    /// The result is always a `502 Bad Gateway` of type [`BAD_GATEWAY_TYPE`]
+    /// carrying the upstream status under `upstream_status`. When the upstream
+    /// body is `application/problem+json`, its `detail` is kept and its `type`,
+    /// `code` and `trace_id` are preserved under [`UPSTREAM_METADATA_KEYS`].
+    /// Any other body is not echoed back. To propagate an upstream problem
+    /// unchanged instead, deserialize the body directly.
+    pub fn from_upstream_response(status: StatusCode, content_type: &str, body: &[u8]) -> Self {
+        use crate::catalog::BAD_GATEWAY_V1;
+
+        let [status_key, type_key, code_key, trace_id_key] = UPSTREAM_METADATA_KEYS;
+        let is_problem = content_type
+            .split(';')
+            .next()
+            .is_some_and(|mime| mime.trim().eq_ignore_ascii_case(APPLICATION_PROBLEM_JSON));
+        let upstream = if is_problem {
+            serde_json::from_slice::<Problem>(body).ok()
         } else {
-            self
+            None
         };
 
-        let status = problem.status;
-        let mut resp = axum::Json(problem).into_response();
-        *resp.status_mut() = status;
-        resp.headers_mut().insert(
-            axum::http::header::CONTENT_TYPE,
-            HeaderValue::from_static(APPLICATION_PROBLEM_JSON),
-        );
-        resp
+        let Some(upstream) = upstream else {
+            return BAD_GATEWAY_V1
+                .as_problem("Upstream service returned an unexpected response")
+                .with_metadata(status_key, status.as_u16());
+        };
+
+        let mut problem = BAD_GATEWAY_V1
+            .as_problem(upstream.detail)
+            .with_metadata(status_key, status.as_u16())
+            .with_metadata(type_key, upstream.type_url);
+        if !upstream.code.is_empty() {
+            problem = problem.with_metadata(code_key, upstream.code);
+        }
+        if let Some(trace_id) = upstream.trace_id {
+            problem = problem.with_metadata(trace_id_key, trace_id);
+        }
+        problem
     }
 }
 
-#[cfg(test)]
-#[cfg_attr(coverage_nightly, coverage(off))]
-mod tests {
-    use super::*;
+/// Coarse severity of a problem for log and alert routing, independent of its status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
 
-    #[test]
-    fn problem_builder_pattern() {
-        let p = Problem::new(
-            StatusCode::UNPROCESSABLE_ENTITY,
-            "Validation Failed",
-            "Input validation errors",
-        )
-        .with_code("VALIDATION_ERROR")
-        .with_instance("/users/123")
-        .with_trace_id("req-456")
-        .with_errors(vec![ValidationViolation {
-            message: "Email is required".to_owned(),
-            field: "email".to_owned(),
-            code: None,
-        }]);
+impl Severity {
+    /// Default severity of a status: `error` for 5xx, `warning` for 4xx,
+    /// `info` otherwise. `critical` is never derived; error types opt into it.
+    #[must_use]
+    pub const fn for_status(status: u16) -> Self {
+        match status {
+            500..=599 => Self::Error,
+            400..=499 => Self::Warning,
+            _ => Self::Info,
+        }
+    }
 
-        assert_eq!(p.status, StatusCode::UNPROCESSABLE_ENTITY);
-        assert_eq!(p.code, "VALIDATION_ERROR");
-        assert_eq!(p.instance, "/users/123");
-        assert_eq!(p.trace_id, Some("req-456".to_owned()));
-        assert!(p.errors.is_some());
-        assert_eq!(p.errors.as_ref().unwrap().len(), 1);
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+            Self::Critical => "critical",
+        }
     }
+}
 
-    #[test]
-    fn problem_serializes_status_as_u16() {
-        let p = Problem::new(StatusCode::NOT_FOUND, "Not Found", "Resource not found");
-        let json = serde_json::to_string(&p).unwrap();
-        assert!(json.contains("\"status\":404"));
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
+}
 
-    #[test]
-    fn problem_deserializes_status_from_u16() {
-        let json = r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"Resource not found","instance":"","code":"","trace_id":null,"errors":null}"#;
-        let p: Problem = serde_json::from_str(json).unwrap();
-        assert_eq!(p.status, StatusCode::NOT_FOUND);
+/// Request header by which a client opts into experimental error types.
+pub const ACCEPT_EXPERIMENTAL_ERRORS_HEADER: &str = "x-accept-experimental-errors";
+
+impl Problem {
+    /// Mark this problem as experimental, with `fallback` as its stable form.
+    pub fn with_fallback(mut self, fallback: &'static crate::catalog::ErrDef) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Stable form of an experimental problem: type, title, status and code
+    /// come from the fallback definition, everything else is kept. Problems
+    /// without a fallback are returned unchanged.
+    pub fn into_fallback(mut self) -> Self {
+        let Some(def) = self.fallback.take() else {
+            return self;
+        };
+        self.type_url = def.type_url.to_owned();
+        self.title = def.title.to_owned();
+        self.status = normalize_status(def.status).0;
+        self.code = def.code.to_owned();
+        if let Some(url) = def.help_url {
+            self.help = Some(url.to_owned());
+        }
+        self.titles = def.titles;
+        self
+    }
+}
+
+/// Localized title from `titles` best matching the `Accept-Language` value
+/// `accept_language`; see [`Problem::localized`].
+fn best_title(
+    titles: &'static [(&'static str, &'static str)],
+    accept_language: &str,
+) -> Option<(&'static str, &'static str)> {
+    let mut ranges: Vec<(&str, f32)> = accept_language
+        .split(',')
+        .filter_map(|item| {
+            let mut params = item.split(';');
+            let range = params.next()?.trim();
+            let quality = match params.find_map(|p| p.trim().strip_prefix("q=")) {
+                Some(q) => q.trim().parse().ok()?,
+                None => 1.0,
+            };
+            (!range.is_empty() && range != "*" && quality > 0.0).then_some((range, quality))
+        })
+        .collect();
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let find = |tag: &str| {
+        titles
+            .iter()
+            .find(|(language, _)| language.eq_ignore_ascii_case(tag))
+            .copied()
+    };
+    ranges.into_iter().find_map(|(range, _)| {
+        find(range).or_else(|| range.split_once('-').and_then(|(primary, _)| find(primary)))
+    })
+}
+
+/// Response extension carrying a problem with localized titles.
+///
+/// Set by `IntoResponse` for problems with [`Problem::titles`]; a response
+/// layer may re-render it with [`Problem::localized`] for the request's
+/// `Accept-Language`.
+#[cfg(feature = "axum")]
+#[derive(Debug, Clone)]
+pub struct LocalizableProblem(pub Problem);
+
+/// Response extension carrying the stable rendering of an experimental problem.
+///
+/// Set by `IntoResponse` for problems with a fallback; a response layer may
+/// replace the response with it for clients that did not send
+/// [`ACCEPT_EXPERIMENTAL_ERRORS_HEADER`].
+#[cfg(feature = "axum")]
+#[derive(Debug, Clone)]
+pub struct FallbackProblem(pub Problem);
+
+/// Metadata key overriding the status-derived severity of a problem.
+pub const SEVERITY_METADATA_KEY: &str = "severity";
+
+impl Problem {
+    /// Record a severity differing from the status-derived default.
+    pub fn with_severity(self, severity: Severity) -> Self {
+        self.with_metadata(SEVERITY_METADATA_KEY, severity.as_str())
+    }
+
+    /// Severity for log and alert routing.
+    ///
+    /// A valid severity under [`SEVERITY_METADATA_KEY`] wins; otherwise the
+    /// status decides (see [`Severity::for_status`]).
+    #[must_use]
+    pub fn severity(&self) -> Severity {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.get(SEVERITY_METADATA_KEY))
+            .and_then(|v| Severity::deserialize(v).ok())
+            .unwrap_or_else(|| Severity::for_status(self.status.as_u16()))
+    }
+}
+
+/// Metadata key overriding the status-derived retry classification of a problem.
+pub const TRANSIENT_METADATA_KEY: &str = "transient";
+
+/// Default transient-vs-permanent classification of a status code.
+///
+/// `408`, `429` and server errors are transient (a retry may succeed), except
+/// `501 Not Implemented` and `505 HTTP Version Not Supported`. Everything else
+/// is permanent.
+#[must_use]
+pub const fn is_transient_status(status: u16) -> bool {
+    matches!(status, 408 | 429 | 500 | 502..=504 | 506..=599)
+}
+
+impl Problem {
+    /// Whether retrying the request may succeed.
+    ///
+    /// A boolean under [`TRANSIENT_METADATA_KEY`] wins; otherwise the status
+    /// decides (see [`is_transient_status`]).
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.get(TRANSIENT_METADATA_KEY))
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or_else(|| is_transient_status(self.status.as_u16()))
+    }
+}
+
+/// Metadata key set by [`Problem::with_schema_id`].
+pub const SCHEMA_ID_METADATA_KEY: &str = "schema_id";
+
+/// Malformed JSON parsed by hand (outside the `Json` extractor) becomes a
+/// 400 problem pinpointing the failure by `line` and `column` in metadata.
+///
+/// The message is derived from the error category only: `serde_json` messages
+/// may quote offending input values, which must not be echoed back.
+impl From<serde_json::Error> for Problem {
+    fn from(err: serde_json::Error) -> Self {
+        use serde_json::error::Category;
+
+        let (code, message) = match err.classify() {
+            Category::Syntax => ("INVALID_JSON", "malformed JSON syntax"),
+            Category::Eof => ("INVALID_JSON", "unexpected end of JSON input"),
+            Category::Data => (
+                "INVALID_JSON_DATA",
+                "JSON does not match the expected structure",
+            ),
+            Category::Io => ("INVALID_JSON", "failed to read JSON input"),
+        };
+        Problem::new(
+            StatusCode::BAD_REQUEST,
+            "Bad Request",
+            "Request body is not valid JSON",
+        )
+        .with_code(code)
+        .with_metadata("line", err.line())
+        .with_metadata("column", err.column())
+        .with_metadata("message", message)
+    }
+}
+
+/// Response header carrying the problem's `trace_id`.
+pub const TRACE_ID_HEADER: &str = "x-trace-id";
+
+/// Longest trace id accepted by [`validate_trace_id`].
+pub const MAX_TRACE_ID_LEN: usize = 128;
+
+/// Check that `trace_id` can be echoed in the [`TRACE_ID_HEADER`] header: at
+/// most [`MAX_TRACE_ID_LEN`] visible ASCII characters, and not all zeros (the
+/// value W3C Trace Context reserves as invalid, which tracing backends drop).
+///
+/// # Errors
+/// Returns a description of the first rule `trace_id` breaks.
+pub fn validate_trace_id(trace_id: &str) -> Result<(), String> {
+    if trace_id.len() > MAX_TRACE_ID_LEN {
+        return Err(format!(
+            "trace id is {} bytes long, at most {MAX_TRACE_ID_LEN} are allowed",
+            trace_id.len()
+        ));
+    }
+    if let Some(c) = trace_id.chars().find(|c| !c.is_ascii_graphic()) {
+        return Err(format!("trace id contains invalid character {c:?}"));
+    }
+    if !trace_id.is_empty() && trace_id.bytes().all(|b| b == b'0') {
+        return Err(
+            "trace id is all zeros, which W3C Trace Context reserves as invalid".to_owned(),
+        );
+    }
+    Ok(())
+}
+
+/// Response header carrying the problem's machine-readable `code`.
+pub const ERROR_CODE_HEADER: &str = "x-error-code";
+
+/// Metadata keys set by [`Problem::with_rate_limit`]: limit, remaining, reset.
+pub const RATE_LIMIT_METADATA_KEYS: [&str; 3] =
+    ["ratelimit_limit", "ratelimit_remaining", "ratelimit_reset"];
+
+/// Metadata key set by [`Problem::with_retry_after`].
+pub const RETRY_AFTER_METADATA_KEY: &str = "retry_after_seconds";
+
+/// Type URI suffix of rate-limit problems whose retry delay becomes a
+/// `Retry-After` response header.
+pub const TOO_MANY_REQUESTS_TYPE_SUFFIX: &str = "too_many_requests.v1~";
+
+/// Metadata keys kept on 5xx problems by [`Problem::production_safe`].
+pub const PRODUCTION_SAFE_METADATA_KEYS: &[&str] = &["incident_id", "correlation_id"];
+
+/// Detail served instead of the original one on 5xx problems by
+/// [`Problem::production_safe`].
+pub const PRODUCTION_SAFE_DETAIL: &str =
+    "The server could not complete the request. Quote the trace id when reporting it.";
+
+static PRODUCTION_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable production mode process-wide.
+///
+/// In production mode every `Problem` turned into a response goes through
+/// [`Problem::production_safe`] first.
+pub fn set_production_mode(enabled: bool) {
+    PRODUCTION_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether production mode is enabled.
+#[must_use]
+pub fn is_production_mode() -> bool {
+    PRODUCTION_MODE.load(Ordering::Relaxed)
+}
+
+/// Default for [`set_max_error_header_len`].
+pub const DEFAULT_MAX_ERROR_HEADER_LEN: usize = 256;
+
+static MAX_ERROR_HEADER_LEN: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_ERROR_HEADER_LEN);
+
+/// Set process-wide the longest value a `Problem` response echoes in a
+/// header such as [`TRACE_ID_HEADER`] or [`ERROR_CODE_HEADER`].
+///
+/// Longer values are dropped rather than truncated: a cut trace id or code
+/// would point at nothing.
+pub fn set_max_error_header_len(len: usize) {
+    MAX_ERROR_HEADER_LEN.store(len, Ordering::Relaxed);
+}
+
+/// The current bound set by [`set_max_error_header_len`].
+#[must_use]
+pub fn max_error_header_len() -> usize {
+    MAX_ERROR_HEADER_LEN.load(Ordering::Relaxed)
+}
+
+/// `value` as a header value, or `None` when it is empty, longer than
+/// `max_len` bytes, or contains control or non-ASCII characters.
+#[cfg(feature = "axum")]
+fn bounded_header_value(value: &str, max_len: usize) -> Option<axum::http::HeaderValue> {
+    if value.is_empty()
+        || value.len() > max_len
+        || !value.chars().all(|c| c == ' ' || c.is_ascii_graphic())
+    {
+        return None;
+    }
+    axum::http::HeaderValue::from_str(value).ok()
+}
+
+impl Problem {
+    /// Scrub a server error (5xx) for exposure to clients.
+    ///
+    /// The title is replaced with the catalog title of the problem's type, or
+    /// the generic reason phrase of the status for unregistered types, the
+    /// detail with [`PRODUCTION_SAFE_DETAIL`], validation errors are dropped and
+    /// metadata is dropped except for [`PRODUCTION_SAFE_METADATA_KEYS`].
+    /// Client errors (4xx) are returned unchanged.
+    pub fn production_safe(mut self) -> Self {
+        if !self.status.is_server_error() {
+            return self;
+        }
+        self.title = crate::catalog::registered_def(&self.type_url).map_or_else(
+            || {
+                self.status
+                    .canonical_reason()
+                    .unwrap_or("Internal Server Error")
+                    .to_owned()
+            },
+            |def| def.title.to_owned(),
+        );
+        PRODUCTION_SAFE_DETAIL.clone_into(&mut self.detail);
+        self.errors = None;
+        self.metadata = self.metadata.and_then(|mut metadata| {
+            metadata.retain(|key, _| PRODUCTION_SAFE_METADATA_KEYS.contains(&key.as_str()));
+            (!metadata.is_empty()).then_some(metadata)
+        });
+        self
+    }
+}
+
+/// Axum integration: make Problem directly usable as a response.
+///
+/// Automatically enriches the Problem with `trace_id` from the current
+/// tracing span if not already set, applies the registered code remapper
+/// (see [`crate::remap::set_code_remapper`]), and scrubs server errors when
+/// production mode is enabled (see [`set_production_mode`]). Header values
+/// taken from the problem are dropped when they exceed
+/// [`max_error_header_len`] or could not be sent as-is.
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for Problem {
+    fn into_response(self) -> axum::response::Response {
+        use axum::http::HeaderValue;
+
+        let fallback = self
+            .fallback
+            .is_some()
+            .then(|| FallbackProblem(self.clone().into_fallback()));
+
+        let problem = crate::remap::remap_problem(self);
+        let problem = if is_production_mode() {
+            problem.production_safe()
+        } else {
+            problem
+        };
+
+        // Enrich with trace_id from current span if not already set
+        let problem = if problem.trace_id.is_none() {
+            match tracing::Span::current().id() {
+                Some(span_id) => problem.with_trace_id(format!("{:032x}", span_id.into_u64())),
+                _ => problem,
+            }
+        } else {
+            problem
+        };
+
+        let localizable = (!problem.titles.is_empty()).then(|| LocalizableProblem(problem.clone()));
+
+        // Side-channel: rate-limit metadata is mirrored into RateLimit-* headers
+        let rate_limit_headers: Vec<(&str, u64)> = problem
+            .metadata
+            .as_ref()
+            .map(|metadata| {
+                ["ratelimit-limit", "ratelimit-remaining", "ratelimit-reset"]
+                    .into_iter()
+                    .zip(RATE_LIMIT_METADATA_KEYS)
+                    .filter_map(|(header, key)| {
+                        metadata
+                            .get(key)
+                            .and_then(serde_json::Value::as_u64)
+                            .map(|v| (header, v))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Rate-limit problems tell clients when to come back
+        let retry_after = problem
+            .type_url
+            .ends_with(TOO_MANY_REQUESTS_TYPE_SUFFIX)
+            .then(|| problem.metadata.as_ref()?.get(RETRY_AFTER_METADATA_KEY))
+            .flatten()
+            .and_then(serde_json::Value::as_u64);
+
+        // Correlation headers readable without parsing the body
+        let max_len = max_error_header_len();
+        let correlation_headers: Vec<(axum::http::HeaderName, HeaderValue)> = [
+            (crate::headers::TRACE_ID, problem.trace_id.as_deref()),
+            (crate::headers::ERROR_CODE, Some(problem.code.as_str())),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            value
+                .and_then(|v| bounded_header_value(v, max_len))
+                .map(|v| (name, v))
+        })
+        .collect();
+
+        // A negotiated locale makes the body depend on Accept-Language
+        let negotiated = problem.language.is_some();
+        let content_language = problem
+            .language
+            .as_deref()
+            .and_then(|lang| bounded_header_value(lang, max_len))
+            .unwrap_or(HeaderValue::from_static(DEFAULT_PROBLEM_LANGUAGE));
+
+        let status = problem.status;
+        let mut resp = axum::Json(problem).into_response();
+        if let Some(fallback) = fallback {
+            resp.extensions_mut().insert(fallback);
+        }
+        if let Some(localizable) = localizable {
+            resp.extensions_mut().insert(localizable);
+        }
+        *resp.status_mut() = status;
+        let headers = resp.headers_mut();
+        headers.insert(
+            axum::http::header::CONTENT_TYPE,
+            HeaderValue::from_static(APPLICATION_PROBLEM_JSON_VERSIONED),
+        );
+        for (name, value) in rate_limit_headers {
+            headers.insert(name, HeaderValue::from(value));
+        }
+        if let Some(seconds) = retry_after {
+            headers.insert(axum::http::header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        for (name, value) in correlation_headers {
+            headers.insert(name, value);
+        }
+        headers.insert(axum::http::header::CONTENT_LANGUAGE, content_language);
+        if negotiated {
+            headers.append(
+                axum::http::header::VARY,
+                HeaderValue::from_static("accept-language"),
+            );
+        }
+        resp
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_zero_trace_id_is_rejected() {
+        let err = validate_trace_id(&"0".repeat(32)).unwrap_err();
+        assert!(err.contains("all zeros"), "{err}");
+
+        validate_trace_id("4bf92f3577b34da6a3ce929d0e0e4736").unwrap();
+        validate_trace_id("4BF92F3577b34DA6a3ce929d0e0e4736").unwrap();
+        validate_trace_id("00000000000000000000000000000001").unwrap();
+        validate_trace_id("").unwrap();
+    }
+
+    #[test]
+    fn problem_builder_pattern() {
+        let p = Problem::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Validation Failed",
+            "Input validation errors",
+        )
+        .with_code("VALIDATION_ERROR")
+        .with_instance("/users/123")
+        .with_trace_id("req-456")
+        .with_errors(vec![ValidationViolation {
+            message: "Email is required".to_owned(),
+            field: "email".to_owned(),
+            code: None,
+            pointer: None,
+        }]);
+
+        assert_eq!(p.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(p.code, "VALIDATION_ERROR");
+        assert_eq!(p.instance, "/users/123");
+        assert_eq!(p.trace_id, Some("req-456".to_owned()));
+        assert!(p.errors.is_some());
+        assert_eq!(p.errors.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn problem_builder_builds_a_complete_problem() {
+        let built = Problem::builder()
+            .status(StatusCode::CONFLICT)
+            .title("Email Taken")
+            .detail("ann@example.com is already registered")
+            .type_url("gts.x.core.errors.err.v1~x.users.errors.email_taken.v1")
+            .instance("/users")
+            .code("USERS_EMAIL_TAKEN")
+            .trace_id("req-456")
+            .metadata("field", "email")
+            .build()
+            .unwrap();
+
+        let expected = Problem::new(
+            StatusCode::CONFLICT,
+            "Email Taken",
+            "ann@example.com is already registered",
+        )
+        .with_type("gts.x.core.errors.err.v1~x.users.errors.email_taken.v1")
+        .with_instance("/users")
+        .with_code("USERS_EMAIL_TAKEN")
+        .with_trace_id("req-456")
+        .with_metadata("field", "email");
+        assert_eq!(built, expected);
+    }
+
+    #[test]
+    fn problem_builder_defaults_to_canonical_reason() {
+        let problem = ProblemBuilder::new()
+            .status(StatusCode::NOT_FOUND)
+            .trace_id("")
+            .build()
+            .unwrap();
+        assert_eq!(problem.title, "Not Found");
+        assert_eq!(problem.type_url, "about:blank");
+        assert_eq!(problem.trace_id, None);
+    }
+
+    #[test]
+    fn problem_builder_rejects_bad_trace_id() {
+        let err = Problem::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .trace_id("has space")
+            .trace_id("fine-later")
+            .build()
+            .unwrap_err();
+        assert!(err.contains("invalid character"), "{err}");
+
+        let err = Problem::builder()
+            .trace_id("a".repeat(MAX_TRACE_ID_LEN + 1))
+            .build()
+            .unwrap_err();
+        assert!(err.contains("at most"), "{err}");
+
+        let err = Problem::builder()
+            .trace_id("0".repeat(32))
+            .build()
+            .unwrap_err();
+        assert!(err.contains("all zeros"), "{err}");
+    }
+
+    fn sample(trace_id: &str) -> Problem {
+        Problem::new(StatusCode::NOT_FOUND, "Not Found", "no such user")
+            .with_code("USERS_NOT_FOUND")
+            .with_trace_id(trace_id)
+            .with_errors(vec![ValidationViolation::new("id", "unknown")])
+    }
+
+    #[test]
+    fn identical_problems_are_equal() {
+        assert_eq!(sample("req-1"), sample("req-1"));
+
+        let a = sample("req-1")
+            .with_metadata("a", 1)
+            .with_metadata("b", "two");
+        let b = sample("req-1")
+            .with_metadata("b", "two")
+            .with_metadata("a", 1);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn differing_problems_are_unequal() {
+        assert_ne!(sample("req-1"), sample("req-2"));
+        assert_ne!(sample("req-1"), sample("req-1").with_log_suppressed());
+        assert_ne!(
+            sample("req-1").with_metadata("a", 1),
+            sample("req-1").with_metadata("a", 1.0)
+        );
+    }
+
+    #[test]
+    fn jsonapi_renders_not_found() {
+        let problem = crate::catalog::NOT_FOUND_V1
+            .as_problem("no such user")
+            .with_trace_id("req-1")
+            .with_metadata("user_id", 42);
+        assert_eq!(
+            problem.to_jsonapi(),
+            serde_json::json!({
+                "errors": [{
+                    "status": "404",
+                    "code": "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~",
+                    "title": "Not Found",
+                    "detail": "no such user",
+                    "id": "req-1",
+                    "meta": { "user_id": 42 }
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn jsonapi_renders_one_error_per_violation() {
+        use crate::catalog::VALIDATION_FAILED_V1;
+
+        let problem = VALIDATION_FAILED_V1
+            .as_problem("2 fields are invalid")
+            .with_errors(vec![
+                ValidationViolation::new("address.zip", "must be 5 digits"),
+                ValidationViolation::at_pointer("/items/0/sku", "unknown sku")
+                    .with_code("UNKNOWN_SKU"),
+            ]);
+        assert_eq!(
+            problem.to_jsonapi(),
+            serde_json::json!({
+                "errors": [
+                    {
+                        "status": "422",
+                        "code": VALIDATION_FAILED_V1.type_url,
+                        "title": "Validation Failed",
+                        "detail": "must be 5 digits",
+                        "source": { "pointer": "/address/zip" }
+                    },
+                    {
+                        "status": "422",
+                        "code": "UNKNOWN_SKU",
+                        "title": "Validation Failed",
+                        "detail": "unknown sku",
+                        "source": { "pointer": "/items/0/sku" }
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn nested_violation_carries_json_pointer() {
+        let pointer = json_pointer(["address", "postalCode"]);
+        assert_eq!(pointer, "/address/postalCode");
+
+        let p = Problem::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Validation Failed",
+            "Input validation errors",
+        )
+        .with_errors(vec![
+            ValidationViolation::at_pointer(pointer, "must match ^[0-9]{5}$").with_code("pattern"),
+            ValidationViolation::new("email", "Email is required"),
+        ]);
+
+        let json = serde_json::to_value(&p).unwrap();
+        assert_eq!(json["errors"][0]["pointer"], "/address/postalCode");
+        assert_eq!(json["errors"][0]["field"], "address.postalCode");
+        assert_eq!(json["errors"][0]["code"], "pattern");
+        assert!(json["errors"][1].get("pointer").is_none());
+
+        let back: Problem = serde_json::from_value(json).unwrap();
+        assert_eq!(back, p);
+    }
+
+    #[test]
+    fn json_pointer_escapes_special_characters() {
+        let pointer = json_pointer(["labels", "a/b", "c~d", "0"]);
+        assert_eq!(pointer, "/labels/a~1b/c~0d/0");
+        assert_eq!(
+            ValidationViolation::at_pointer(pointer, "invalid").field,
+            "labels.a/b.c~d.0"
+        );
+        assert_eq!(json_pointer(Vec::<&str>::new()), "");
+        assert_eq!(ValidationViolation::at_pointer("", "invalid").field, "");
+    }
+
+    #[test]
+    fn metadata_is_omitted_when_absent() {
+        let p = Problem::new(StatusCode::NOT_FOUND, "Not Found", "Resource not found");
+        let json = serde_json::to_value(&p).unwrap();
+        assert!(json.get("metadata").is_none());
+
+        let p = p.with_metadata("resource", "user");
+        let json = serde_json::to_value(&p).unwrap();
+        assert_eq!(json["metadata"]["resource"], "user");
+    }
+
+    #[test]
+    fn insert_metadata_creates_the_map_and_last_write_wins() {
+        let mut p = Problem::new(StatusCode::NOT_FOUND, "Not Found", "Resource not found");
+        assert!(p.metadata.is_none());
+
+        p.insert_metadata("resource_id", "u-1")
+            .insert_metadata("resource_id", "u-2");
+        let metadata = p.metadata.as_ref().unwrap();
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(metadata["resource_id"], "u-2");
+    }
+
+    #[test]
+    fn configured_retry_delay_is_suggested_for_rate_limits() {
+        let actions = {
+            let _lock = crate::GLOBAL_STATE_LOCK
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            set_default_retry_after_secs(5);
+            let actions = RemediationAction::defaults_for(StatusCode::TOO_MANY_REQUESTS);
+            set_default_retry_after_secs(DEFAULT_RETRY_AFTER_SECS);
+            actions
+        };
+        assert_eq!(actions, vec![RemediationAction::RetryAfter { seconds: 5 }]);
+    }
+
+    #[test]
+    fn remediation_actions_serialize_as_tagged_objects() {
+        let p = Problem::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too Many Requests",
+            "slow down",
+        )
+        .with_action(RemediationAction::RetryAfter { seconds: 5 });
+        let json = serde_json::to_value(&p).unwrap();
+        assert_eq!(
+            json["actions"],
+            serde_json::json!([{"action": "retry_after", "seconds": 5}])
+        );
+
+        let p = Problem::new(StatusCode::NOT_FOUND, "Not Found", "missing");
+        let json = serde_json::to_value(&p).unwrap();
+        assert!(json.get("actions").is_none());
+    }
+
+    #[test]
+    fn parse_envelope_reads_errors_array() {
+        let body = r#"{"errors":[
+            {"type":"about:blank","title":"Not Found","status":404,"detail":"a","instance":"","code":"A"},
+            {"type":"about:blank","title":"Conflict","status":409,"detail":"b","instance":"","code":"B","errors":[{"field":"email","message":"taken"}]}
+        ]}"#;
+
+        let problems = Problem::parse_envelope(body).unwrap();
+
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].status, StatusCode::NOT_FOUND);
+        assert_eq!(problems[1].code, "B");
+        assert_eq!(problems[1].errors.as_ref().unwrap()[0].field, "email");
+    }
+
+    #[test]
+    fn parse_envelope_accepts_single_bare_problem() {
+        let body = r#"{"type":"about:blank","title":"Bad Request","status":400,"detail":"x","instance":"","code":"C","errors":[{"field":"name","message":"required"}]}"#;
+
+        let problems = Problem::parse_envelope(body).unwrap();
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].status, StatusCode::BAD_REQUEST);
+        assert_eq!(problems[0].errors.as_ref().unwrap().len(), 1);
+        assert!(Problem::parse_envelope(r#"{"errors":[{"title":1}]}"#).is_err());
+    }
+
+    #[test]
+    fn problem_serializes_status_as_u16() {
+        let p = Problem::new(StatusCode::NOT_FOUND, "Not Found", "Resource not found");
+        let json = serde_json::to_string(&p).unwrap();
+        assert!(json.contains("\"status\":404"));
+    }
+
+    #[test]
+    fn versioned_content_type_matches_schema_version() {
+        assert_eq!(
+            APPLICATION_PROBLEM_JSON_VERSIONED,
+            format!("{APPLICATION_PROBLEM_JSON}; version={PROBLEM_SCHEMA_VERSION}")
+        );
+    }
+
+    #[test]
+    fn problem_deserialization_tolerates_schema_version_member() {
+        let json = r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"Resource not found","instance":"","code":"","trace_id":null,"errors":null,"schema_version":1}"#;
+        let p: Problem = serde_json::from_str(json).unwrap();
+        assert_eq!(p.status, StatusCode::NOT_FOUND);
+        assert_eq!(p.title, "Not Found");
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn problem_response_advertises_schema_version() {
+        use axum::response::IntoResponse;
+
+        let resp = Problem::new(StatusCode::BAD_REQUEST, "Bad Request", "nope").into_response();
+        let ct = resp
+            .headers()
+            .get(axum::http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        assert_eq!(ct, "application/problem+json; version=1");
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn unsafe_header_values_are_dropped() {
+        assert!(bounded_header_value("USERS_NOT_FOUND", 64).is_some());
+        assert!(bounded_header_value(&"x".repeat(65), 64).is_none());
+        assert!(bounded_header_value("", 64).is_none());
+        for value in ["a\r\nset-cookie: x", "tab\there", "nul\0", "caf\u{e9}"] {
+            assert!(bounded_header_value(value, 64).is_none(), "{value:?}");
+        }
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn over_long_or_control_char_values_are_not_echoed_in_headers() {
+        use axum::response::IntoResponse;
+
+        let long_type = format!(
+            "gts.hx.core.errors.err.v1~hx.{}.v1",
+            "x".repeat(DEFAULT_MAX_ERROR_HEADER_LEN)
+        );
+        let resp = Problem::new(StatusCode::NOT_FOUND, "Not Found", "x")
+            .with_type(long_type.clone())
+            .with_code(long_type)
+            .with_trace_id("trace\u{7}1")
+            .into_response();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert!(!resp.headers().contains_key(ERROR_CODE_HEADER));
+        assert!(!resp.headers().contains_key(TRACE_ID_HEADER));
+
+        let resp = Problem::new(StatusCode::NOT_FOUND, "Not Found", "x")
+            .with_code("USERS_NOT_FOUND")
+            .with_trace_id("trace-1")
+            .into_response();
+        assert_eq!(resp.headers()[ERROR_CODE_HEADER], "USERS_NOT_FOUND");
+        assert_eq!(resp.headers()[TRACE_ID_HEADER], "trace-1");
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn production_mode_scrubs_responses() {
+        use axum::response::IntoResponse;
+
+        /// Production mode for the guard's lifetime, under the crate's global
+        /// state lock.
+        struct ProductionMode {
+            _lock: std::sync::MutexGuard<'static, ()>,
+        }
+
+        impl Drop for ProductionMode {
+            fn drop(&mut self) {
+                set_production_mode(false);
+            }
+        }
+
+        let resp = {
+            let _production = ProductionMode {
+                _lock: crate::GLOBAL_STATE_LOCK
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner),
+            };
+            set_production_mode(true);
+            Problem::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "leaky title",
+                "pool exhausted on pg-primary-3",
+            )
+            .with_metadata("stack", "at db.rs:42")
+            .with_errors(vec![ValidationViolation {
+                field: "dsn".to_owned(),
+                message: "postgres://admin@10.0.0.7".to_owned(),
+                code: None,
+                pointer: None,
+            }])
+            .into_response()
+        };
+        assert!(!is_production_mode());
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["title"], "Internal Server Error");
+        assert_eq!(json["detail"], PRODUCTION_SAFE_DETAIL);
+        assert!(json.get("errors").is_none());
+        assert!(json.get("metadata").is_none());
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn registered_remapper_rewrites_responses() {
+        use crate::remap::{CodeRemapper, RemapRule, clear_code_remapper, set_code_remapper};
+        use axum::response::IntoResponse;
+
+        let source = "gts.x.core.errors.err.v1~x.remap.errors.internal_conflict.v1";
+        set_code_remapper(CodeRemapper::new().with_rule(
+            source,
+            RemapRule::new("https://errors.example.com/conflict").with_status(409),
+        ));
+        let remapped = Problem::new(StatusCode::UNPROCESSABLE_ENTITY, "Conflict", "x")
+            .with_type(source)
+            .into_response();
+        let untouched = Problem::new(StatusCode::UNPROCESSABLE_ENTITY, "Other", "x")
+            .with_type("gts.x.core.errors.err.v1~x.remap.errors.other.v1")
+            .into_response();
+        clear_code_remapper();
+
+        assert_eq!(remapped.status(), StatusCode::CONFLICT);
+        assert_eq!(untouched.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(remapped.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["type"], "https://errors.example.com/conflict");
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn trace_id_and_code_become_headers() {
+        use axum::response::IntoResponse;
+
+        let resp = Problem::new(StatusCode::NOT_FOUND, "Not Found", "missing")
+            .with_code("USERS_NOT_FOUND")
+            .with_trace_id("trace-42")
+            .into_response();
+        assert_eq!(resp.headers()[TRACE_ID_HEADER], "trace-42");
+        assert_eq!(resp.headers()[ERROR_CODE_HEADER], "USERS_NOT_FOUND");
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn negotiated_language_becomes_content_language() {
+        use axum::http::header::{CONTENT_LANGUAGE, VARY};
+        use axum::response::IntoResponse;
+
+        let resp = Problem::new(
+            StatusCode::NOT_FOUND,
+            "Ressource introuvable",
+            "aucun utilisateur 7",
+        )
+        .with_language("fr")
+        .into_response();
+        assert_eq!(resp.headers()[CONTENT_LANGUAGE], "fr");
+        assert_eq!(resp.headers()[VARY], "accept-language");
+
+        // No requested locale was available: English was served as fallback.
+        let resp = Problem::new(StatusCode::NOT_FOUND, "Not Found", "no user 7")
+            .with_language(DEFAULT_PROBLEM_LANGUAGE)
+            .into_response();
+        assert_eq!(resp.headers()[CONTENT_LANGUAGE], "en");
+        assert_eq!(resp.headers()[VARY], "accept-language");
+
+        // Not negotiated at all.
+        let resp = Problem::new(StatusCode::NOT_FOUND, "Not Found", "no user 7").into_response();
+        assert_eq!(resp.headers()[CONTENT_LANGUAGE], "en");
+        assert!(resp.headers().get(VARY).is_none());
+    }
+
+    const TITLES: &[(&str, &str)] = &[("fr", "Introuvable"), ("de", "Nicht gefunden")];
+
+    fn localizable() -> Problem {
+        Problem::new(StatusCode::NOT_FOUND, "Not Found", "no user 7").with_titles(TITLES)
+    }
+
+    #[test]
+    fn localized_title_matches_exact_language() {
+        let p = localizable().localized("fr");
+        assert_eq!(p.title, "Introuvable");
+        assert_eq!(p.language.as_deref(), Some("fr"));
+
+        // Ranges are tried by decreasing quality.
+        let p = localizable().localized("fr;q=0.5, DE, en;q=0.9");
+        assert_eq!(p.title, "Nicht gefunden");
+        assert_eq!(p.language.as_deref(), Some("de"));
+    }
+
+    #[test]
+    fn localized_title_falls_back_to_primary_subtag() {
+        let p = localizable().localized("fr-CA, en;q=0.8");
+        assert_eq!(p.title, "Introuvable");
+        assert_eq!(p.language.as_deref(), Some("fr"));
+    }
+
+    #[test]
+    fn unmatched_language_keeps_default_title() {
+        for accept_language in ["es-ES, it;q=0.5", "*", "fr;q=0", ""] {
+            let p = localizable().localized(accept_language);
+            assert_eq!(p.title, "Not Found", "{accept_language}");
+            assert_eq!(p.language.as_deref(), Some(DEFAULT_PROBLEM_LANGUAGE));
+        }
+    }
+
+    #[test]
+    fn titles_are_not_serialized() {
+        let json = serde_json::to_value(localizable()).unwrap();
+        assert!(json.get("titles").is_none());
+        assert_eq!(json["title"], "Not Found");
+    }
+
+    #[test]
+    fn severity_defaults_to_status_and_can_be_overridden() {
+        let severity = |status| Problem::new(status, "t", "d").severity();
+        assert_eq!(severity(StatusCode::OK), Severity::Info);
+        assert_eq!(severity(StatusCode::NOT_FOUND), Severity::Warning);
+        assert_eq!(severity(StatusCode::TOO_MANY_REQUESTS), Severity::Warning);
+        assert_eq!(severity(StatusCode::INTERNAL_SERVER_ERROR), Severity::Error);
+        assert_eq!(severity(StatusCode::SERVICE_UNAVAILABLE), Severity::Error);
+
+        let p = Problem::new(StatusCode::INTERNAL_SERVER_ERROR, "t", "d")
+            .with_severity(Severity::Critical);
+        assert_eq!(p.severity(), Severity::Critical);
+        assert_eq!(
+            p.metadata.as_ref().unwrap()[SEVERITY_METADATA_KEY],
+            "critical"
+        );
+
+        // Unknown values fall back to the status.
+        let p = Problem::new(StatusCode::NOT_FOUND, "t", "d")
+            .with_metadata(SEVERITY_METADATA_KEY, "apocalyptic");
+        assert_eq!(p.severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn language_is_not_serialized() {
+        let p = Problem::new(StatusCode::NOT_FOUND, "Introuvable", "x").with_language("fr");
+        let json = serde_json::to_value(&p).unwrap();
+        assert!(json.get("language").is_none());
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn rate_limit_metadata_becomes_headers() {
+        use axum::response::IntoResponse;
+
+        let resp = Problem::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too Many Requests",
+            "slow down",
+        )
+        .with_rate_limit(100, 0, 42)
+        .into_response();
+
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap()
+                .to_owned()
+        };
+        assert_eq!(header("RateLimit-Limit"), "100");
+        assert_eq!(header("RateLimit-Remaining"), "0");
+        assert_eq!(header("RateLimit-Reset"), "42");
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["metadata"]["ratelimit_limit"], 100);
+        assert_eq!(json["metadata"]["ratelimit_remaining"], 0);
+        assert_eq!(json["metadata"]["ratelimit_reset"], 42);
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn too_many_requests_emits_retry_after_header() {
+        use axum::response::IntoResponse;
+
+        let resp = crate::catalog::TOO_MANY_REQUESTS_V1
+            .as_problem("quota exhausted")
+            .with_retry_after(120)
+            .into_response();
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resp.headers()["retry-after"], "120");
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["metadata"]["retry_after_seconds"], 120);
+        assert_eq!(
+            json["actions"],
+            serde_json::json!([{"action": "retry_after", "seconds": 120}])
+        );
+
+        // Other types keep the delay in the body only.
+        let resp = Problem::new(StatusCode::TOO_MANY_REQUESTS, "Too Many Requests", "x")
+            .with_retry_after(5)
+            .into_response();
+        assert!(resp.headers().get("retry-after").is_none());
+    }
+
+    #[test]
+    fn schema_id_is_recorded_in_metadata() {
+        let p = Problem::new(StatusCode::UNPROCESSABLE_ENTITY, "Validation failed", "x")
+            .with_schema_id("gts.x.core.events.type.v1~");
+        let json = serde_json::to_value(&p).unwrap();
+        assert_eq!(
+            json["metadata"][SCHEMA_ID_METADATA_KEY],
+            "gts.x.core.events.type.v1~"
+        );
+
+        let p = Problem::new(StatusCode::UNPROCESSABLE_ENTITY, "Validation failed", "x");
+        let json = serde_json::to_value(&p).unwrap();
+        assert!(json.get("metadata").is_none());
+    }
+
+    #[test]
+    fn json_parse_error_reports_position_without_input() {
+        let err =
+            serde_json::from_str::<serde_json::Value>("{\n  \"token\": \"s3cret\",\n  oops\n}")
+                .unwrap_err();
+        let p = Problem::from(err);
+        assert_eq!(p.status, StatusCode::BAD_REQUEST);
+        assert_eq!(p.code, "INVALID_JSON");
+        let metadata = p.metadata.as_ref().unwrap();
+        assert_eq!(metadata["line"], 3);
+        assert_eq!(metadata["column"], 3);
+        assert_eq!(metadata["message"], "malformed JSON syntax");
+
+        let err = serde_json::from_str::<Vec<u32>>(r#"["s3cret"]"#).unwrap_err();
+        let p = Problem::from(err);
+        assert_eq!(p.code, "INVALID_JSON_DATA");
+        assert_eq!(p.metadata.as_ref().unwrap()["line"], 1);
+        let body = serde_json::to_string(&p).unwrap();
+        assert!(!body.contains("s3cret"), "input echoed: {body}");
+    }
+
+    #[test]
+    fn metadata_serializes_deterministically_in_key_order() {
+        let build = || {
+            Problem::new(StatusCode::CONFLICT, "Conflict", "taken")
+                .with_metadata("zeta", 1)
+                .with_metadata("alpha", 2)
+                .merge_metadata(std::collections::HashMap::from([
+                    ("mid".to_owned(), serde_json::json!(3)),
+                    ("beta".to_owned(), serde_json::json!(4)),
+                    ("omega".to_owned(), serde_json::json!(5)),
+                ]))
+        };
+        let first = serde_json::to_string(&build()).unwrap();
+        let second = serde_json::to_string(&build()).unwrap();
+        assert_eq!(first, second);
+        assert!(
+            first.contains(r#""metadata":{"alpha":2,"beta":4,"mid":3,"omega":5,"zeta":1}"#),
+            "{first}"
+        );
+    }
+
+    #[test]
+    fn production_safe_scrubs_server_errors() {
+        let p = Problem::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "db pool exhausted on pg-primary-3",
+            "Internal error",
+        )
+        .with_metadata("sql", "SELECT * FROM users")
+        .with_metadata("incident_id", "inc-7")
+        .production_safe();
+
+        assert_eq!(p.title, "Internal Server Error");
+        assert_eq!(p.detail, PRODUCTION_SAFE_DETAIL);
+        let metadata = p.metadata.unwrap();
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(metadata["incident_id"], "inc-7");
+
+        let p = Problem::new(StatusCode::BAD_GATEWAY, "upstream 10.0.0.7 died", "x")
+            .with_metadata("host", "10.0.0.7")
+            .production_safe();
+        assert_eq!(p.title, "Bad Gateway");
+        assert!(p.metadata.is_none());
+    }
+
+    #[test]
+    fn production_safe_leaves_client_errors_untouched() {
+        let p = Problem::new(StatusCode::NOT_FOUND, "User Not Found", "missing")
+            .with_metadata("resource", "user")
+            .production_safe();
+        assert_eq!(p.title, "User Not Found");
+        assert_eq!(p.metadata.unwrap()["resource"], "user");
+    }
+
+    #[test]
+    fn status_accepts_codes_and_reason_phrases() {
+        let status = |value: serde_json::Value| {
+            serde_json::from_value::<Problem>(serde_json::json!({
+                "type": "about:blank",
+                "title": "Not Found",
+                "status": value,
+                "detail": "x",
+                "instance": "",
+                "code": "",
+                "trace_id": null,
+                "errors": null
+            }))
+            .map(|p| p.status)
+        };
+        assert_eq!(
+            status(serde_json::json!(404)).unwrap(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(serde_json::json!("404")).unwrap(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(serde_json::json!("Not Found")).unwrap(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(serde_json::json!("service unavailable")).unwrap(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert!(status(serde_json::json!("Mostly Fine")).is_err());
+        assert!(status(serde_json::json!(-1)).is_err());
+    }
+
+    #[test]
+    fn strict_status_deserializer_rejects_reason_phrases() {
+        #[derive(Debug, Deserialize)]
+        struct Strict {
+            #[serde(deserialize_with = "deserialize_status_code")]
+            status: StatusCode,
+        }
+
+        let strict: Strict = serde_json::from_str(r#"{"status":404}"#).unwrap();
+        assert_eq!(strict.status, StatusCode::NOT_FOUND);
+        assert!(serde_json::from_str::<Strict>(r#"{"status":"Not Found"}"#).is_err());
+    }
+
+    #[test]
+    fn normalize_status_keeps_standard_codes() {
+        assert_eq!(normalize_status(404), (StatusCode::NOT_FOUND, false));
+        assert_eq!(normalize_status(504), (StatusCode::GATEWAY_TIMEOUT, false));
+    }
+
+    #[test]
+    fn normalize_status_coerces_nonstandard_and_out_of_range_codes() {
+        assert_eq!(normalize_status(499), (StatusCode::BAD_REQUEST, true));
+        assert_eq!(
+            normalize_status(599),
+            (StatusCode::INTERNAL_SERVER_ERROR, true)
+        );
+        assert_eq!(
+            normalize_status(42),
+            (StatusCode::INTERNAL_SERVER_ERROR, true)
+        );
+        assert_eq!(
+            normalize_status(1000),
+            (StatusCode::INTERNAL_SERVER_ERROR, true)
+        );
+    }
+
+    #[test]
+    fn nonstandard_status_deserializes_to_its_class() {
+        let json = r#"{"type":"about:blank","title":"Client Closed","status":499,"detail":"","instance":"","code":""}"#;
+        let p: Problem = serde_json::from_str(json).unwrap();
+        assert_eq!(p.status, StatusCode::BAD_REQUEST);
+
+        let json = r#"{"type":"about:blank","title":"Bogus","status":1000,"detail":"","instance":"","code":""}"#;
+        let p: Problem = serde_json::from_str(json).unwrap();
+        assert_eq!(p.status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn problem_deserializes_status_from_u16() {
+        let json = r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"Resource not found","instance":"","code":"","trace_id":null,"errors":null}"#;
+        let p: Problem = serde_json::from_str(json).unwrap();
+        assert_eq!(p.status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn upstream_problem_is_wrapped_as_bad_gateway() {
+        let body = serde_json::json!({
+            "type": "gts.x.core.errors.err.v1~x.users.errors.not_found.v1",
+            "title": "User not found",
+            "status": 404,
+            "detail": "user 42 does not exist",
+            "instance": "/users/42",
+            "code": "USERS_NOT_FOUND",
+            "trace_id": "4bf92f3577b34da6a3ce929d0e0e4736"
+        })
+        .to_string();
+
+        let p = Problem::from_upstream_response(
+            StatusCode::NOT_FOUND,
+            "application/problem+json; charset=utf-8",
+            body.as_bytes(),
+        );
+        assert_eq!(p.status, StatusCode::BAD_GATEWAY);
+        assert_eq!(p.type_url, BAD_GATEWAY_TYPE);
+        assert_eq!(p.code, "BAD_GATEWAY");
+        assert_eq!(p.detail, "user 42 does not exist");
+
+        let metadata = p.metadata.unwrap();
+        assert_eq!(metadata["upstream_status"], 404);
+        assert_eq!(
+            metadata["upstream_type"],
+            "gts.x.core.errors.err.v1~x.users.errors.not_found.v1"
+        );
+        assert_eq!(metadata["upstream_code"], "USERS_NOT_FOUND");
+        assert_eq!(
+            metadata["upstream_trace_id"],
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+    }
+
+    #[test]
+    fn non_problem_upstream_body_yields_bad_gateway() {
+        let p = Problem::from_upstream_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "text/html",
+            b"<html>stack trace at db.internal:5432</html>",
+        );
+        assert_eq!(p.status, StatusCode::BAD_GATEWAY);
+        assert_eq!(p.type_url, BAD_GATEWAY_TYPE);
+        assert!(!p.detail.contains("db.internal"));
+
+        let metadata = p.metadata.unwrap();
+        assert_eq!(metadata["upstream_status"], 500);
+        assert!(!metadata.contains_key("upstream_type"));
+
+        // A problem content type with an unparsable body is treated the same way.
+        let p = Problem::from_upstream_response(
+            StatusCode::BAD_GATEWAY,
+            APPLICATION_PROBLEM_JSON,
+            b"{not json",
+        );
+        assert_eq!(p.status, StatusCode::BAD_GATEWAY);
+        assert!(!p.metadata.unwrap().contains_key("upstream_type"));
     }
 }
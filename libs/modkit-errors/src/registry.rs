@@ -0,0 +1,180 @@
+//! Reverse lookup from a problem's type URI to its catalog definition
+//!
+//! A `Problem` received over the wire (e.g. proxied from another service)
+//! only carries strings. A `ProblemRegistry` maps type URIs back to their
+//! [`ErrDef`], so callers can branch on the error category, or rebuild a
+//! problem exactly as the catalog would construct it, without string matching
+//! on titles. [`ProblemRegistry::from_catalog`] builds one from the registered
+//! catalog (see `register_catalog`).
+
+use std::collections::HashMap;
+
+use crate::catalog::{ErrDef, FRAMEWORK_ERRORS, registered_defs};
+use crate::problem::Problem;
+
+/// Error definitions keyed by type URI.
+#[derive(Debug, Clone, Default)]
+pub struct ProblemRegistry {
+    defs: HashMap<&'static str, ErrDef>,
+}
+
+impl ProblemRegistry {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot of the framework's own definitions ([`FRAMEWORK_ERRORS`])
+    /// and of every definition registered with `register_catalog` so far;
+    /// a registered definition replaces a framework one of the same type URI.
+    #[must_use]
+    pub fn from_catalog() -> Self {
+        Self::new()
+            .with_defs(FRAMEWORK_ERRORS.iter().copied())
+            .with_defs(registered_defs())
+    }
+
+    /// Add (or replace) the definition for `def.type_url`.
+    pub fn register(&mut self, def: ErrDef) {
+        self.defs.insert(def.type_url, def);
+    }
+
+    /// Builder form of [`register`](Self::register) for several definitions,
+    /// e.g. `ErrorCode::ALL.iter().map(ErrorCode::def)` of a declared catalog.
+    #[must_use]
+    pub fn with_defs(mut self, defs: impl IntoIterator<Item = ErrDef>) -> Self {
+        for def in defs {
+            self.register(def);
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.defs.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+
+    /// Definition registered for `type_url`, if any.
+    #[must_use]
+    pub fn get(&self, type_url: &str) -> Option<&ErrDef> {
+        self.defs.get(type_url)
+    }
+
+    /// Definition of the type `problem` claims to be, if registered.
+    ///
+    /// Only the type URI is consulted; status and title of `problem` may have
+    /// been rewritten on the way (see `Problem::checked_against_catalog` to
+    /// validate them).
+    #[must_use]
+    pub fn classify(&self, problem: &Problem) -> Option<&ErrDef> {
+        self.get(&problem.type_url)
+    }
+
+    /// Rebuild `problem` through its registered definition.
+    ///
+    /// Catalog members (type, title, status, code, help, default metadata and
+    /// remediation actions) come from the definition; detail, instance,
+    /// trace id, validation errors and metadata of the received problem are
+    /// kept. Returns `None` for unregistered types.
+    #[must_use]
+    pub fn reconstruct(&self, problem: &Problem) -> Option<Problem> {
+        let def = self.classify(problem)?;
+        let mut rebuilt = def.as_problem(problem.detail.clone());
+        rebuilt.instance.clone_from(&problem.instance);
+        rebuilt.trace_id.clone_from(&problem.trace_id);
+        rebuilt.errors.clone_from(&problem.errors);
+        if let Some(metadata) = &problem.metadata {
+            rebuilt
+                .metadata
+                .get_or_insert_with(Default::default)
+                .extend(metadata.clone());
+        }
+        Some(rebuilt)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::catalog::{UNAUTHORIZED_V1, register_catalog};
+    use http::StatusCode;
+
+    const NOT_FOUND_V1: ErrDef = ErrDef {
+        status: 404,
+        title: "Not Found",
+        code: "NOT_FOUND",
+        type_url: "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~",
+        help_url: None,
+        metadata: &[("resource", "user")],
+        transient: None,
+        severity: None,
+        fallback: None,
+        titles: &[],
+    };
+
+    #[test]
+    fn wire_problem_classifies_back_to_its_definition() {
+        let registry = ProblemRegistry::new().with_defs([NOT_FOUND_V1]);
+        let sent = NOT_FOUND_V1
+            .as_problem("user 42 does not exist")
+            .with_instance("/users/42")
+            .with_trace_id("trace-1");
+
+        let json = serde_json::to_string(&sent).unwrap();
+        let received: Problem = serde_json::from_str(&json).unwrap();
+
+        let def = registry.classify(&received).unwrap();
+        assert_eq!(def.status, 404);
+        assert_eq!(def.title, "Not Found");
+        assert_eq!(registry.reconstruct(&received).unwrap(), sent);
+    }
+
+    #[test]
+    fn unknown_types_are_not_classified() {
+        let received = Problem::new(StatusCode::NOT_FOUND, "Not Found", "x")
+            .with_type("https://errors.example.com/elsewhere");
+        let registry = ProblemRegistry::from_catalog();
+        assert!(registry.classify(&received).is_none());
+        assert!(registry.reconstruct(&received).is_none());
+    }
+
+    #[test]
+    fn catalog_registry_holds_framework_errors() {
+        let registry = ProblemRegistry::from_catalog();
+        for def in FRAMEWORK_ERRORS {
+            assert_eq!(registry.get(def.type_url), Some(def));
+        }
+
+        // A proxy may have dropped everything but the type and detail.
+        let received = Problem::new(StatusCode::BAD_GATEWAY, "Bad Gateway", "token expired")
+            .with_type(UNAUTHORIZED_V1.type_url);
+        let rebuilt = registry.reconstruct(&received).unwrap();
+        assert_eq!(rebuilt.status, StatusCode::UNAUTHORIZED);
+        assert_eq!(rebuilt.title, "Unauthorized");
+        assert_eq!(rebuilt.code, UNAUTHORIZED_V1.code);
+        assert_eq!(rebuilt.detail, "token expired");
+    }
+
+    #[test]
+    fn catalog_registry_holds_registered_definitions() {
+        const ORDER_NOT_FOUND_V1: ErrDef = ErrDef {
+            title: "Order Not Found",
+            code: "TEST_ORDER_NOT_FOUND",
+            type_url: "gts.cf.core.errors.err.v1~cf.test.errors.order_not_found.v1~",
+            ..NOT_FOUND_V1
+        };
+        register_catalog([ORDER_NOT_FOUND_V1]);
+
+        let registry = ProblemRegistry::from_catalog();
+        assert_eq!(
+            registry.get(ORDER_NOT_FOUND_V1.type_url),
+            Some(&ORDER_NOT_FOUND_V1)
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Axum extractor rejections as RFC 9457 problems
+//!
+//! `Json`, `Query` and `Path` rejections are converted into the framework
+//! catalog problem matching axum's status code, carrying a stable
+//! machine-readable `code`. The rejection text is not echoed back since it may
+//! quote the offending input.
+
+use axum::extract::rejection::{JsonRejection, PathRejection, QueryRejection};
+use http::StatusCode;
+
+use crate::catalog::{
+    BAD_REQUEST_V1, ErrDef, INTERNAL_ERROR_V1, PAYLOAD_TOO_LARGE_V1, UNSUPPORTED_MEDIA_TYPE_V1,
+    VALIDATION_FAILED_V1,
+};
+use crate::problem::Problem;
+
+/// Framework catalog definition for a rejection status.
+fn def_for(status: StatusCode) -> ErrDef {
+    match status {
+        StatusCode::PAYLOAD_TOO_LARGE => PAYLOAD_TOO_LARGE_V1,
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => UNSUPPORTED_MEDIA_TYPE_V1,
+        StatusCode::UNPROCESSABLE_ENTITY => VALIDATION_FAILED_V1,
+        s if s.is_server_error() => INTERNAL_ERROR_V1,
+        _ => BAD_REQUEST_V1,
+    }
+}
+
+fn rejection_problem(status: StatusCode, code: &str, detail: &str) -> Problem {
+    def_for(status).as_problem(detail).with_code(code)
+}
+
+impl From<JsonRejection> for Problem {
+    fn from(rejection: JsonRejection) -> Self {
+        let (code, detail) = match &rejection {
+            JsonRejection::JsonSyntaxError(_) => ("INVALID_JSON", "Request body is not valid JSON"),
+            JsonRejection::JsonDataError(_) => (
+                "INVALID_JSON_DATA",
+                "Request body does not match the expected schema",
+            ),
+            JsonRejection::MissingJsonContentType(_) => (
+                "UNSUPPORTED_MEDIA_TYPE",
+                "Expected request with `Content-Type: application/json`",
+            ),
+            _ => ("INVALID_BODY", "Failed to read request body"),
+        };
+        rejection_problem(rejection.status(), code, detail)
+    }
+}
+
+impl From<QueryRejection> for Problem {
+    fn from(rejection: QueryRejection) -> Self {
+        rejection_problem(
+            rejection.status(),
+            "INVALID_QUERY",
+            "Failed to parse query string",
+        )
+    }
+}
+
+impl From<PathRejection> for Problem {
+    fn from(rejection: PathRejection) -> Self {
+        let (code, detail) = match &rejection {
+            PathRejection::MissingPathParams(_) => {
+                ("MISSING_PATH_PARAMS", "Path parameters are missing")
+            }
+            _ => ("INVALID_PATH_PARAMS", "Failed to parse path parameters"),
+        };
+        rejection_problem(rejection.status(), code, detail)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::extract::FromRequest;
+    use axum::http::{Request, header};
+
+    async fn json_rejection(req: Request<Body>) -> JsonRejection {
+        match axum::Json::<serde_json::Value>::from_request(req, &()).await {
+            Ok(_) => panic!("expected a rejection"),
+            Err(rejection) => rejection,
+        }
+    }
+
+    #[tokio::test]
+    async fn json_syntax_error_becomes_bad_request() {
+        let req = Request::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from("{not json"))
+            .unwrap();
+
+        let p = Problem::from(json_rejection(req).await);
+
+        assert_eq!(p.status, StatusCode::BAD_REQUEST);
+        assert_eq!(p.title, "Bad Request");
+        assert_eq!(p.type_url, BAD_REQUEST_V1.type_url);
+        assert_eq!(p.code, "INVALID_JSON");
+        // The rejection text quotes the input and is not echoed back
+        let body = serde_json::to_string(&p).unwrap();
+        assert!(!body.contains("not json"), "{body}");
+        assert!(p.metadata.is_none());
+    }
+
+    #[tokio::test]
+    async fn missing_content_type_becomes_unsupported_media_type() {
+        let req = Request::builder().body(Body::from("{}")).unwrap();
+
+        let p = Problem::from(json_rejection(req).await);
+
+        assert_eq!(p.status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(p.type_url, UNSUPPORTED_MEDIA_TYPE_V1.type_url);
+        assert_eq!(p.code, "UNSUPPORTED_MEDIA_TYPE");
+    }
+
+    #[tokio::test]
+    async fn schema_mismatch_becomes_validation_failed() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Body {
+            #[allow(dead_code)]
+            name: String,
+        }
+
+        let req = Request::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from(r#"{"name": 42}"#))
+            .unwrap();
+        let Err(rejection) = axum::Json::<Body>::from_request(req, &()).await else {
+            panic!("expected a rejection");
+        };
+
+        let p = Problem::from(rejection);
+        assert_eq!(p.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(p.type_url, VALIDATION_FAILED_V1.type_url);
+        assert_eq!(p.code, "INVALID_JSON_DATA");
+    }
+}
@@ -0,0 +1,210 @@
+//! Edge remapping of error codes to an integrator's public taxonomy
+//!
+//! A `CodeRemapper` holds rules keyed by the source error type URI. When a
+//! `Problem` is turned into a response its `type_url` is looked up and, on a
+//! match, replaced by the rule's target (optionally with a new title, status
+//! and code). Problems without a matching rule pass through unchanged.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::problem::{Problem, normalize_status};
+
+/// Target of a remapping rule.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemapRule {
+    /// Public type URI replacing the source one.
+    pub type_url: String,
+    /// Replacement title; the original title is kept when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Replacement HTTP status; the original status is kept when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Replacement machine-readable code; the original code is kept when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+impl RemapRule {
+    pub fn new(type_url: impl Into<String>) -> Self {
+        Self {
+            type_url: type_url.into(),
+            title: None,
+            status: None,
+            code: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    #[must_use]
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+}
+
+/// Set of remapping rules keyed by source type URI.
+///
+/// Deserializes from a plain map, e.g.
+/// `{ "gts.x.core.errors.err.v1~x.odata.errors.invalid_filter.v1": { "type_url": "..." } }`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct CodeRemapper {
+    rules: HashMap<String, RemapRule>,
+}
+
+impl CodeRemapper {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add (or replace) the rule for `source`.
+    #[must_use]
+    pub fn with_rule(mut self, source: impl Into<String>, rule: RemapRule) -> Self {
+        self.rules.insert(source.into(), rule);
+        self
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Rewrite `problem` according to the rule matching its type URI.
+    pub fn apply(&self, mut problem: Problem) -> Problem {
+        let Some(rule) = self.rules.get(&problem.type_url) else {
+            return problem;
+        };
+        problem.type_url.clone_from(&rule.type_url);
+        if let Some(title) = &rule.title {
+            problem.title.clone_from(title);
+        }
+        if let Some(status) = rule.status {
+            problem.status = normalize_status(status).0;
+        }
+        if let Some(code) = &rule.code {
+            problem.code.clone_from(code);
+        }
+        problem
+    }
+}
+
+static REMAPPER: RwLock<Option<Arc<CodeRemapper>>> = RwLock::new(None);
+
+/// Install the process-wide remapper, replacing any previous one.
+///
+/// An empty remapper is equivalent to [`clear_code_remapper`].
+pub fn set_code_remapper(remapper: CodeRemapper) {
+    let mut guard = REMAPPER
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    *guard = (!remapper.is_empty()).then(|| Arc::new(remapper));
+}
+
+/// Remove the process-wide remapper so problems pass through unchanged.
+pub fn clear_code_remapper() {
+    let mut guard = REMAPPER
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    *guard = None;
+}
+
+/// Apply the registered remapper, if any.
+pub fn remap_problem(problem: Problem) -> Problem {
+    let remapper = REMAPPER
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone();
+    match remapper {
+        Some(remapper) => remapper.apply(problem),
+        None => problem,
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use http::StatusCode;
+
+    const INVALID_FILTER: &str = "gts.x.core.errors.err.v1~x.odata.errors.invalid_filter.v1";
+    const INVALID_ORDERBY: &str = "gts.x.core.errors.err.v1~x.odata.errors.invalid_orderby.v1";
+    const PUBLIC_INVALID_QUERY: &str = "https://errors.example.com/invalid-query";
+
+    fn problem(type_url: &str) -> Problem {
+        Problem::new(StatusCode::BAD_REQUEST, "Invalid Filter", "bad $filter")
+            .with_type(type_url)
+            .with_code("invalid_filter")
+    }
+
+    fn remapper() -> CodeRemapper {
+        CodeRemapper::new().with_rule(
+            INVALID_FILTER,
+            RemapRule::new(PUBLIC_INVALID_QUERY)
+                .with_title("Invalid Query")
+                .with_status(422)
+                .with_code("INVALID_QUERY"),
+        )
+    }
+
+    #[test]
+    fn matching_code_is_remapped() {
+        let p = remapper().apply(problem(INVALID_FILTER));
+        assert_eq!(p.type_url, PUBLIC_INVALID_QUERY);
+        assert_eq!(p.title, "Invalid Query");
+        assert_eq!(p.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(p.code, "INVALID_QUERY");
+        assert_eq!(p.detail, "bad $filter");
+    }
+
+    #[test]
+    fn other_codes_pass_through() {
+        let original = problem(INVALID_ORDERBY);
+        let p = remapper().apply(original.clone());
+        assert_eq!(p.type_url, original.type_url);
+        assert_eq!(p.title, original.title);
+        assert_eq!(p.status, original.status);
+        assert_eq!(p.code, original.code);
+    }
+
+    #[test]
+    fn partial_rule_keeps_unset_fields() {
+        let p = CodeRemapper::new()
+            .with_rule(INVALID_FILTER, RemapRule::new(PUBLIC_INVALID_QUERY))
+            .apply(problem(INVALID_FILTER));
+        assert_eq!(p.type_url, PUBLIC_INVALID_QUERY);
+        assert_eq!(p.title, "Invalid Filter");
+        assert_eq!(p.status, StatusCode::BAD_REQUEST);
+        assert_eq!(p.code, "invalid_filter");
+    }
+
+    #[test]
+    fn remapper_deserializes_from_config_map() {
+        let remapper: CodeRemapper = serde_json::from_value(serde_json::json!({
+            INVALID_FILTER: {
+                "type_url": PUBLIC_INVALID_QUERY,
+                "title": "Invalid Query",
+                "status": 422,
+                "code": "INVALID_QUERY"
+            }
+        }))
+        .unwrap();
+        assert_eq!(remapper, self::remapper());
+    }
+}
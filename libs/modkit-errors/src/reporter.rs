@@ -0,0 +1,279 @@
+//! Pluggable sinks for server-side problems
+//!
+//! Integrators can register a `ProblemReporter` to ship every 5xx `Problem`
+//! to an external system (Sentry, an incident tracker, ...) in addition to logs.
+//! Client errors (4xx) and problems marked with `Problem::with_log_suppressed`
+//! are never reported.
+
+use std::sync::{Arc, RwLock};
+
+use crate::problem::{Problem, Severity};
+
+/// Request context passed to reporters alongside the problem.
+#[derive(Debug, Clone, Default)]
+pub struct ReportContext {
+    /// Request path the problem was produced for.
+    pub instance: String,
+    /// Trace id of the request, if known.
+    pub trace_id: Option<String>,
+}
+
+/// Sink for server-side problems.
+pub trait ProblemReporter: Send + Sync {
+    fn report(&self, problem: &Problem, context: &ReportContext);
+}
+
+/// Reporter that discards everything (the default).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopReporter;
+
+impl ProblemReporter for NoopReporter {
+    fn report(&self, _problem: &Problem, _context: &ReportContext) {}
+}
+
+/// Reporter that emits one `tracing` event per problem, at the level matching
+/// its [`Severity`]: `error` for error and critical, `warn` for warning and
+/// `info` for info.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoggingReporter;
+
+impl ProblemReporter for LoggingReporter {
+    fn report(&self, problem: &Problem, context: &ReportContext) {
+        macro_rules! report_at {
+            ($level:expr) => {
+                tracing::event!(
+                    $level,
+                    status = problem.status.as_u16(),
+                    severity = %problem.severity(),
+                    code = %problem.code,
+                    type_url = %problem.type_url,
+                    instance = %context.instance,
+                    trace_id = context.trace_id.as_deref().unwrap_or_default(),
+                    "server problem reported"
+                )
+            };
+        }
+
+        match problem.severity() {
+            Severity::Critical | Severity::Error => report_at!(tracing::Level::ERROR),
+            Severity::Warning => report_at!(tracing::Level::WARN),
+            Severity::Info => report_at!(tracing::Level::INFO),
+        }
+    }
+}
+
+static REPORTER: RwLock<Option<Arc<dyn ProblemReporter>>> = RwLock::new(None);
+
+/// Install the process-wide reporter, replacing any previous one.
+pub fn set_problem_reporter(reporter: Arc<dyn ProblemReporter>) {
+    let mut guard = REPORTER
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    *guard = Some(reporter);
+}
+
+/// Remove the process-wide reporter, restoring the no-op default.
+pub fn clear_problem_reporter() {
+    let mut guard = REPORTER
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    *guard = None;
+}
+
+/// Forward a problem to the registered reporter if it is a server error (5xx)
+/// whose logging is not suppressed.
+pub fn report_problem(problem: &Problem, context: &ReportContext) {
+    if !problem.status.is_server_error() || problem.suppress_log {
+        return;
+    }
+    let reporter = REPORTER
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone();
+    if let Some(reporter) = reporter {
+        reporter.report(problem, context);
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use http::StatusCode;
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
+    #[derive(Default)]
+    struct CapturingReporter {
+        seen: Mutex<Vec<(u16, String)>>,
+    }
+
+    impl ProblemReporter for CapturingReporter {
+        fn report(&self, problem: &Problem, context: &ReportContext) {
+            self.seen
+                .lock()
+                .unwrap()
+                .push((problem.status.as_u16(), context.instance.clone()));
+        }
+    }
+
+    /// Process-wide reporter installed for the guard's lifetime; holding the
+    /// crate's global state lock keeps other tests from swapping it meanwhile.
+    struct ScopedReporter {
+        _lock: MutexGuard<'static, ()>,
+    }
+
+    impl ScopedReporter {
+        fn install(reporter: Arc<dyn ProblemReporter>) -> Self {
+            let lock = crate::GLOBAL_STATE_LOCK
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            set_problem_reporter(reporter);
+            Self { _lock: lock }
+        }
+    }
+
+    impl Drop for ScopedReporter {
+        fn drop(&mut self) {
+            clear_problem_reporter();
+        }
+    }
+
+    #[test]
+    fn only_server_errors_are_reported() {
+        let capture = Arc::new(CapturingReporter::default());
+        let reporter = ScopedReporter::install(capture.clone());
+
+        let server = crate::finalize(
+            Problem::new(StatusCode::INTERNAL_SERVER_ERROR, "Internal", "boom"),
+            "/reporter/v1/fail",
+            Some("trace-1".to_owned()),
+        );
+        let client = crate::finalize(
+            Problem::new(StatusCode::NOT_FOUND, "Not Found", "missing"),
+            "/reporter/v1/missing",
+            None,
+        );
+        let unavailable = crate::finalize(
+            Problem::new(StatusCode::SERVICE_UNAVAILABLE, "Unavailable", "later"),
+            "/reporter/v1/down",
+            None,
+        );
+        assert_eq!(server.trace_id.as_deref(), Some("trace-1"));
+        assert_eq!(client.status, StatusCode::NOT_FOUND);
+        assert_eq!(unavailable.instance, "/reporter/v1/down");
+
+        drop(reporter);
+
+        let seen = capture.seen.lock().unwrap().clone();
+        assert_eq!(
+            seen,
+            vec![
+                (500, "/reporter/v1/fail".to_owned()),
+                (503, "/reporter/v1/down".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn suppressed_problems_are_not_reported() {
+        let capture = Arc::new(CapturingReporter::default());
+        let reporter = ScopedReporter::install(capture.clone());
+
+        let problem = Problem::new(StatusCode::SERVICE_UNAVAILABLE, "Unavailable", "probe");
+        let suppressed = crate::finalize(
+            problem.clone().with_log_suppressed(),
+            "/reporter/v1/suppressed",
+            None,
+        );
+        let logged = crate::finalize(problem, "/reporter/v1/logged", None);
+
+        drop(reporter);
+
+        assert!(suppressed.suppress_log && !logged.suppress_log);
+        assert_eq!(
+            capture.seen.lock().unwrap().clone(),
+            vec![(503, "/reporter/v1/logged".to_owned())]
+        );
+    }
+
+    #[test]
+    fn suppress_log_is_not_serialized() {
+        let p = Problem::new(StatusCode::NOT_FOUND, "Not Found", "probe").with_log_suppressed();
+        let json = serde_json::to_value(&p).unwrap();
+        assert!(json.get("suppress_log").is_none());
+
+        let back: Problem = serde_json::from_value(json).unwrap();
+        assert!(!back.suppress_log);
+    }
+
+    /// Subscriber recording the level of every event.
+    #[derive(Default)]
+    struct Levels(Mutex<Vec<tracing::Level>>);
+
+    impl tracing::Subscriber for Levels {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+        fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+        fn event(&self, event: &tracing::Event<'_>) {
+            self.0.lock().unwrap().push(*event.metadata().level());
+        }
+        fn enter(&self, _span: &tracing::span::Id) {}
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    #[test]
+    fn logging_reporter_logs_at_severity_level() {
+        let levels = Arc::new(Levels::default());
+        let problem = Problem::new(StatusCode::SERVICE_UNAVAILABLE, "Unavailable", "warming up");
+
+        tracing::subscriber::with_default(levels.clone(), || {
+            let context = ReportContext::default();
+            LoggingReporter.report(&problem, &context);
+            LoggingReporter.report(&problem.clone().with_severity(Severity::Warning), &context);
+            LoggingReporter.report(&problem.clone().with_severity(Severity::Critical), &context);
+            LoggingReporter.report(&problem.clone().with_severity(Severity::Info), &context);
+        });
+
+        assert_eq!(
+            *levels.0.lock().unwrap(),
+            [
+                tracing::Level::ERROR,
+                tracing::Level::WARN,
+                tracing::Level::ERROR,
+                tracing::Level::INFO
+            ]
+        );
+    }
+
+    #[test]
+    fn suppressed_problems_emit_no_log_event() {
+        let reporter = ScopedReporter::install(Arc::new(LoggingReporter));
+
+        let problem = Problem::new(StatusCode::SERVICE_UNAVAILABLE, "Unavailable", "probe");
+        let suppressed = Arc::new(Levels::default());
+        tracing::subscriber::with_default(suppressed.clone(), || {
+            _ = crate::finalize(problem.clone().with_log_suppressed(), "/probe", None);
+        });
+        let logged = Arc::new(Levels::default());
+        tracing::subscriber::with_default(logged.clone(), || {
+            _ = crate::finalize(problem, "/probe", None);
+        });
+
+        drop(reporter);
+
+        assert!(suppressed.0.lock().unwrap().is_empty());
+        assert_eq!(*logged.0.lock().unwrap(), [tracing::Level::ERROR]);
+    }
+
+    #[test]
+    fn noop_reporter_accepts_problems() {
+        let p = Problem::new(StatusCode::INTERNAL_SERVER_ERROR, "Internal", "boom");
+        NoopReporter.report(&p, &ReportContext::default());
+        LoggingReporter.report(&p, &ReportContext::default());
+    }
+}
@@ -0,0 +1,71 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+//! Conformance of every error catalog in the tree
+//!
+//! A new catalog is covered once it is added to [`catalogs`].
+
+use modkit_errors::catalog::{ErrDef, register_catalog};
+use modkit_errors::conformance::{check_error_code, check_problem_shape};
+
+/// Every catalog, by name.
+fn catalogs() -> Vec<(&'static str, Vec<ErrDef>)> {
+    vec![
+        ("framework", modkit_errors::FRAMEWORK_ERRORS.to_vec()),
+        (
+            "odata",
+            modkit_odata::errors::ErrorCode::ALL
+                .iter()
+                .map(modkit_odata::errors::ErrorCode::def)
+                .collect(),
+        ),
+        (
+            "simple_user_settings",
+            simple_user_settings::errors::ErrorCode::ALL
+                .iter()
+                .map(simple_user_settings::errors::ErrorCode::def)
+                .collect(),
+        ),
+        (
+            "users_info",
+            users_info::errors::ErrorCode::ALL
+                .iter()
+                .map(users_info::errors::ErrorCode::def)
+                .collect(),
+        ),
+    ]
+}
+
+#[test]
+fn every_catalog_conforms() {
+    for (catalog, defs) in catalogs() {
+        assert!(!defs.is_empty(), "{catalog} declares no errors");
+        register_catalog(defs.iter().copied());
+
+        for def in defs {
+            let problem = def
+                .as_problem("detail")
+                .with_instance("/probe")
+                .with_trace_id("t-1");
+
+            let body = serde_json::to_value(&problem).unwrap();
+            if let Err(violations) = check_problem_shape(&body) {
+                panic!("{catalog}: {} does not conform: {violations:?}", def.code);
+            }
+            // Framework types carry their GTS id as the type, catalogs as the code
+            let gts_id = if def.type_url.starts_with("gts.") {
+                def.type_url
+            } else {
+                def.code
+            };
+            if let Err(violations) = check_error_code(gts_id) {
+                panic!("{catalog}: {gts_id} is malformed: {violations:?}");
+            }
+            if let Err(mismatch) = problem.checked_against_catalog() {
+                panic!(
+                    "{catalog}: {} fails the catalog check: {mismatch}",
+                    def.code
+                );
+            }
+        }
+    }
+}
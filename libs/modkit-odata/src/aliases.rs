@@ -0,0 +1,231 @@
+//! Public-to-internal field name translation
+//!
+//! Clients filter and sort by the stable public names of an API (`createdAt`)
+//! while the query builder works with real column names (`created_at`).
+//! [`FieldAliases`] maps the former onto the latter; once a query has been
+//! resolved against it, only internal names remain. Names without an alias are
+//! rejected the same way unknown fields are. Installed as a request extension,
+//! the map is applied by `modkit`'s `OData` extractor.
+
+use std::collections::HashMap;
+
+use crate::{Error, ODataOrderBy, ODataQuery, ast};
+
+/// Case-insensitive map from public field names to internal ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct FieldAliases {
+    map: HashMap<String, String>,
+}
+
+impl FieldAliases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expose the internal field `internal` under the public name `public`.
+    pub fn with_alias(mut self, public: impl Into<String>, internal: impl Into<String>) -> Self {
+        self.map
+            .insert(public.into().to_lowercase(), internal.into());
+        self
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Internal name for the public field `public`, if it is exposed.
+    #[must_use]
+    pub fn resolve(&self, public: &str) -> Option<&str> {
+        self.map.get(&public.to_lowercase()).map(String::as_str)
+    }
+
+    /// Rewrite every field of `expr` to its internal name.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidFilter` naming the first field without an alias.
+    pub fn resolve_filter(&self, expr: ast::Expr) -> Result<ast::Expr, Error> {
+        use ast::Expr as E;
+
+        let field = |name: String| {
+            self.resolve(&name)
+                .map(str::to_owned)
+                .ok_or_else(|| Error::InvalidFilter(format!("unknown field: {name}")))
+        };
+        let boxed = |e: Box<E>| self.resolve_filter(*e).map(Box::new);
+
+        Ok(match expr {
+            E::And(a, b) => E::And(boxed(a)?, boxed(b)?),
+            E::Or(a, b) => E::Or(boxed(a)?, boxed(b)?),
+            E::Not(x) => E::Not(boxed(x)?),
+            E::Compare(l, op, r) => E::Compare(boxed(l)?, op, boxed(r)?),
+            E::In(l, list) => E::In(boxed(l)?, list),
+            E::Function(name, args) => E::Function(
+                name,
+                args.into_iter()
+                    .map(|a| self.resolve_filter(a))
+                    .collect::<Result<_, _>>()?,
+            ),
+            E::Identifier(name) => E::Identifier(field(name)?),
+            E::CollectionCount(name) => E::CollectionCount(field(name)?),
+            E::Value(v) => E::Value(v),
+        })
+    }
+
+    /// Rewrite every key of `order` to its internal name.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidOrderByField` naming the first field without an alias.
+    pub fn resolve_order(&self, mut order: ODataOrderBy) -> Result<ODataOrderBy, Error> {
+        for key in &mut order.0 {
+            let internal = self
+                .resolve(&key.field)
+                .ok_or_else(|| Error::InvalidOrderByField(key.field.clone()))?;
+            key.field = internal.to_owned();
+        }
+        Ok(order)
+    }
+}
+
+impl ODataQuery {
+    /// Translate the public field names of `$filter` and `$orderby` into
+    /// internal ones. An empty alias map leaves the query untouched.
+    ///
+    /// Sort keys inside a cursor were produced by the server and are already
+    /// internal, so the cursor is left as is.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidFilter` or `Error::InvalidOrderByField` for a
+    /// field without an alias.
+    pub fn resolve_aliases(mut self, aliases: &FieldAliases) -> Result<Self, Error> {
+        if aliases.is_empty() {
+            return Ok(self);
+        }
+        if let Some(filter) = self.filter.take() {
+            self.filter = Some(Box::new(aliases.resolve_filter(*filter)?));
+        }
+        self.order = aliases.resolve_order(self.order)?;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::{OrderKey, SortDir};
+    use ast::{CompareOperator, Expr, Value};
+
+    fn aliases() -> FieldAliases {
+        FieldAliases::new()
+            .with_alias("createdAt", "created_at")
+            .with_alias("displayName", "display_name")
+            .with_alias("roles", "user_roles")
+    }
+
+    fn ident(name: &str) -> Expr {
+        Expr::Identifier(name.to_owned())
+    }
+
+    #[test]
+    fn filter_fields_resolve_to_internal_names() {
+        let filter = Expr::And(
+            Box::new(Expr::Compare(
+                Box::new(ident("createdAt")),
+                CompareOperator::Gt,
+                Box::new(Expr::Value(Value::String("2024-01-01".to_owned()))),
+            )),
+            Box::new(Expr::Function(
+                "contains".to_owned(),
+                vec![
+                    Expr::Identifier("DISPLAYNAME".to_owned()),
+                    Expr::Value(Value::String("ann".to_owned())),
+                ],
+            )),
+        );
+
+        let query = ODataQuery::new()
+            .with_filter(filter)
+            .resolve_aliases(&aliases())
+            .unwrap();
+        let Some(Expr::And(lhs, rhs)) = query.filter() else {
+            panic!("expected And()");
+        };
+        assert!(
+            matches!(&**lhs, Expr::Compare(l, _, _) if matches!(&**l, Expr::Identifier(n) if n == "created_at"))
+        );
+        assert!(
+            matches!(&**rhs, Expr::Function(_, args) if matches!(&args[0], Expr::Identifier(n) if n == "display_name"))
+        );
+
+        let count = Expr::Compare(
+            Box::new(Expr::CollectionCount("roles".to_owned())),
+            CompareOperator::Gt,
+            Box::new(Expr::Value(Value::Null)),
+        );
+        let resolved = aliases().resolve_filter(count).unwrap();
+        assert!(
+            matches!(resolved, Expr::Compare(l, _, _) if matches!(&*l, Expr::CollectionCount(n) if n == "user_roles"))
+        );
+    }
+
+    #[test]
+    fn order_keys_resolve_to_internal_names() {
+        let order = ODataOrderBy(vec![
+            OrderKey {
+                field: "createdAt".to_owned(),
+                dir: SortDir::Desc,
+            },
+            OrderKey {
+                field: "displayName".to_owned(),
+                dir: SortDir::Asc,
+            },
+        ]);
+
+        let query = ODataQuery::new()
+            .with_order(order)
+            .resolve_aliases(&aliases())
+            .unwrap();
+        assert_eq!(query.order.to_signed_tokens(), "-created_at,+display_name");
+    }
+
+    #[test]
+    fn unknown_public_names_are_rejected() {
+        let filter = Expr::Compare(
+            Box::new(ident("created_at")),
+            CompareOperator::Eq,
+            Box::new(Expr::Value(Value::Null)),
+        );
+        let err = ODataQuery::new()
+            .with_filter(filter)
+            .resolve_aliases(&aliases())
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidFilter(ref m) if m == "unknown field: created_at"));
+
+        let order = ODataOrderBy(vec![OrderKey {
+            field: "updatedAt".to_owned(),
+            dir: SortDir::Asc,
+        }]);
+        let err = ODataQuery::new()
+            .with_order(order)
+            .resolve_aliases(&aliases())
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidOrderByField(ref f) if f == "updatedAt"));
+    }
+
+    #[test]
+    fn empty_aliases_leave_query_untouched() {
+        let query = ODataQuery::new()
+            .with_filter(Expr::Compare(
+                Box::new(ident("created_at")),
+                CompareOperator::Eq,
+                Box::new(Expr::Value(Value::Null)),
+            ))
+            .resolve_aliases(&FieldAliases::new())
+            .unwrap();
+        assert!(
+            matches!(query.filter(), Some(Expr::Compare(l, _, _)) if matches!(&**l, Expr::Identifier(n) if n == "created_at"))
+        );
+    }
+}
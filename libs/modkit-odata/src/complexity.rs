@@ -0,0 +1,119 @@
+//! Total query-complexity scoring
+//!
+//! Each part of a query can be valid on its own while the combination is still
+//! abusive (a deep filter, many sort keys, several computed columns and a huge
+//! page). The score sums weighted contributions of every part so a single
+//! budget can cap the whole request.
+
+use crate::{ODataQuery, ast};
+
+/// Weights applied to each part of a query when scoring its complexity.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct ComplexityWeights {
+    /// Points per level of `$filter` nesting (default: 2)
+    pub filter_depth: u64,
+    /// Points per `$orderby` key (default: 5)
+    pub order_key: u64,
+    /// Points per `$compute` item (default: 5)
+    pub compute_item: u64,
+    /// Rows of `limit` that add one point (default: 100)
+    pub limit_rows_per_point: u64,
+}
+
+impl Default for ComplexityWeights {
+    fn default() -> Self {
+        Self {
+            filter_depth: 2,
+            order_key: 5,
+            compute_item: 5,
+            limit_rows_per_point: 100,
+        }
+    }
+}
+
+/// Nesting depth of a filter expression (a leaf has depth 1).
+#[must_use]
+pub fn filter_depth(expr: &ast::Expr) -> u64 {
+    use ast::Expr::{And, CollectionCount, Compare, Function, Identifier, In, Not, Or, Value};
+    match expr {
+        Identifier(_) | Value(_) | CollectionCount(_) => 1,
+        Not(x) => 1 + filter_depth(x),
+        And(a, b) | Or(a, b) | Compare(a, _, b) => 1 + filter_depth(a).max(filter_depth(b)),
+        In(a, list) => {
+            1 + list
+                .iter()
+                .map(filter_depth)
+                .fold(filter_depth(a), u64::max)
+        }
+        Function(_, args) => 1 + args.iter().map(filter_depth).max().unwrap_or(0),
+    }
+}
+
+/// Compute the complexity score of a parsed query.
+#[must_use]
+pub fn query_complexity(query: &ODataQuery, weights: &ComplexityWeights) -> u64 {
+    let filter = query
+        .filter()
+        .map_or(0, |e| filter_depth(e).saturating_mul(weights.filter_depth));
+    let order = (query.order.0.len() as u64).saturating_mul(weights.order_key);
+    let compute = query
+        .computed()
+        .map_or(0, |c| (c.len() as u64).saturating_mul(weights.compute_item));
+    let limit = query
+        .limit
+        .and_then(|l| l.checked_div(weights.limit_rows_per_point))
+        .unwrap_or(0);
+
+    filter
+        .saturating_add(order)
+        .saturating_add(compute)
+        .saturating_add(limit)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::ast::{CompareOperator, Expr, Value};
+    use crate::{ODataOrderBy, OrderKey, SortDir};
+
+    fn cmp(field: &str) -> Expr {
+        Expr::Compare(
+            Box::new(Expr::Identifier(field.to_owned())),
+            CompareOperator::Eq,
+            Box::new(Expr::Value(Value::String("x".to_owned()))),
+        )
+    }
+
+    #[test]
+    fn filter_depth_counts_nesting() {
+        assert_eq!(filter_depth(&cmp("a")), 2);
+        assert_eq!(filter_depth(&cmp("a").and(cmp("b").or(cmp("c")))), 4);
+        assert_eq!(filter_depth(&!cmp("a")), 3);
+    }
+
+    #[test]
+    fn score_sums_weighted_parts() {
+        let query = ODataQuery::new()
+            .with_filter(cmp("a").and(cmp("b")))
+            .with_order(ODataOrderBy(vec![
+                OrderKey {
+                    field: "a".to_owned(),
+                    dir: SortDir::Asc,
+                },
+                OrderKey {
+                    field: "id".to_owned(),
+                    dir: SortDir::Desc,
+                },
+            ]))
+            .with_limit(250);
+
+        // depth 3 * 2 + 2 keys * 5 + 250 / 100
+        assert_eq!(query_complexity(&query, &ComplexityWeights::default()), 18);
+        assert_eq!(
+            query_complexity(&ODataQuery::new(), &ComplexityWeights::default()),
+            0
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! `$compute` support: named arithmetic expressions over numeric fields
+//!
+//! Grammar (minimal subset of `OData` 4.01 `$compute`):
+//!
+//! ```text
+//! compute  = item *( "," item )
+//! item     = operand op operand "as" alias
+//! operand  = field-name | number
+//! op       = "add" | "sub" | "mul" | "div"
+//! ```
+//!
+//! Example: `$compute=price mul quantity as total, price sub discount as net`.
+//!
+//! Parsing only validates syntax and operators. Field existence and types are
+//! checked when the query builder lowers the expression against its field map.
+
+use bigdecimal::BigDecimal;
+use std::str::FromStr;
+
+use crate::Error;
+
+/// Maximum number of computed columns accepted in a single `$compute`.
+pub const MAX_COMPUTE_ITEMS: usize = 10;
+
+/// Arithmetic operator allowed in `$compute`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComputeOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl ComputeOp {
+    fn parse(token: &str) -> Option<Self> {
+        match token.to_ascii_lowercase().as_str() {
+            "add" => Some(ComputeOp::Add),
+            "sub" => Some(ComputeOp::Sub),
+            "mul" => Some(ComputeOp::Mul),
+            "div" => Some(ComputeOp::Div),
+            _ => None,
+        }
+    }
+}
+
+/// One side of a computed expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ComputeOperand {
+    Field(String),
+    Number(BigDecimal),
+}
+
+/// A named computed column: `left op right as alias`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComputeExpr {
+    pub left: ComputeOperand,
+    pub op: ComputeOp,
+    pub right: ComputeOperand,
+    pub alias: String,
+}
+
+impl ComputeExpr {
+    /// Field names referenced by this expression.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        [&self.left, &self.right]
+            .into_iter()
+            .filter_map(|o| match o {
+                ComputeOperand::Field(name) => Some(name.as_str()),
+                ComputeOperand::Number(_) => None,
+            })
+    }
+}
+
+fn is_identifier(token: &str) -> bool {
+    let mut chars = token.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_operand(token: &str) -> Result<ComputeOperand, Error> {
+    if is_identifier(token) {
+        return Ok(ComputeOperand::Field(token.to_lowercase()));
+    }
+    BigDecimal::from_str(token)
+        .map(ComputeOperand::Number)
+        .map_err(|_| Error::InvalidFilter(format!("invalid $compute operand: {token}")))
+}
+
+/// Parse a raw `$compute` string into a list of computed expressions.
+///
+/// # Errors
+/// Returns `Error::InvalidFilter` if the string is empty, malformed, uses an
+/// operator outside `add`/`sub`/`mul`/`div`, or declares duplicate aliases.
+pub fn parse_compute(raw: &str) -> Result<Vec<ComputeExpr>, Error> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err(Error::InvalidFilter("$compute cannot be empty".to_owned()));
+    }
+
+    let mut out: Vec<ComputeExpr> = Vec::new();
+    for item in raw.split(',') {
+        let tokens: Vec<&str> = item.split_whitespace().collect();
+        let [left, op, right, as_kw, alias] = tokens.as_slice() else {
+            return Err(Error::InvalidFilter(format!(
+                "invalid $compute item: {}",
+                item.trim()
+            )));
+        };
+        if !as_kw.eq_ignore_ascii_case("as") || !is_identifier(alias) {
+            return Err(Error::InvalidFilter(format!(
+                "invalid $compute alias in: {}",
+                item.trim()
+            )));
+        }
+        let op = ComputeOp::parse(op)
+            .ok_or_else(|| Error::InvalidFilter(format!("unsupported $compute operator: {op}")))?;
+        let alias = alias.to_lowercase();
+        if out.iter().any(|c| c.alias == alias) {
+            return Err(Error::InvalidFilter(format!(
+                "duplicate $compute alias: {alias}"
+            )));
+        }
+        out.push(ComputeExpr {
+            left: parse_operand(left)?,
+            op,
+            right: parse_operand(right)?,
+            alias,
+        });
+    }
+
+    if out.len() > MAX_COMPUTE_ITEMS {
+        return Err(Error::InvalidFilter(format!(
+            "$compute has too many items (max: {MAX_COMPUTE_ITEMS})"
+        )));
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_arithmetic_compute() {
+        let items =
+            parse_compute("price mul quantity as total, price sub 1.5 as Discounted").unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].op, ComputeOp::Mul);
+        assert_eq!(items[0].alias, "total");
+        assert_eq!(items[0].fields().collect::<Vec<_>>(), ["price", "quantity"]);
+        assert_eq!(items[1].op, ComputeOp::Sub);
+        assert_eq!(items[1].alias, "discounted");
+        assert_eq!(
+            items[1].right,
+            ComputeOperand::Number(BigDecimal::from_str("1.5").unwrap())
+        );
+    }
+
+    #[test]
+    fn rejects_disallowed_operator() {
+        let err = parse_compute("price mod quantity as total").unwrap_err();
+        assert!(matches!(err, Error::InvalidFilter(ref m) if m.contains("mod")));
+    }
+
+    #[test]
+    fn rejects_malformed_items() {
+        assert!(parse_compute("").is_err());
+        assert!(parse_compute("price mul quantity").is_err());
+        assert!(parse_compute("price mul quantity as 1x").is_err());
+        assert!(parse_compute("price mul 'x' as total").is_err());
+        assert!(parse_compute("a add b as t, c add d as t").is_err());
+    }
+}
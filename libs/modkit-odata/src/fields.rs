@@ -0,0 +1,392 @@
+//! Declared query fields
+//!
+//! A module lists the fields its API exposes in a [`FieldRegistry`]: each
+//! [`FieldSpec`] names a field, gives its [`FieldKind`] and says whether it may
+//! be filtered, sorted or selected. Validating a query against the registry
+//! tells an unknown field apart from a known one used where it is not allowed,
+//! and checks filter literals against the declared kinds. Installed as a
+//! request extension, the registry is enforced by `modkit`'s `OData` extractor.
+
+use std::collections::HashMap;
+
+use crate::filter::FieldKind;
+use crate::{Error, ODataOrderBy, ODataQuery, ast};
+
+/// What a query may do with a field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldCapability {
+    Filterable,
+    Sortable,
+    Selectable,
+}
+
+impl std::fmt::Display for FieldCapability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FieldCapability::Filterable => "filterable",
+            FieldCapability::Sortable => "sortable",
+            FieldCapability::Selectable => "selectable",
+        })
+    }
+}
+
+/// One exposed field; filterable, sortable and selectable unless restricted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct FieldSpec {
+    pub name: String,
+    pub kind: FieldKind,
+    pub filterable: bool,
+    pub sortable: bool,
+    pub selectable: bool,
+}
+
+impl FieldSpec {
+    pub fn new(name: impl Into<String>, kind: FieldKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            filterable: true,
+            sortable: true,
+            selectable: true,
+        }
+    }
+
+    pub fn not_filterable(mut self) -> Self {
+        self.filterable = false;
+        self
+    }
+
+    pub fn not_sortable(mut self) -> Self {
+        self.sortable = false;
+        self
+    }
+
+    pub fn not_selectable(mut self) -> Self {
+        self.selectable = false;
+        self
+    }
+
+    #[must_use]
+    pub fn allows(&self, capability: FieldCapability) -> bool {
+        match capability {
+            FieldCapability::Filterable => self.filterable,
+            FieldCapability::Sortable => self.sortable,
+            FieldCapability::Selectable => self.selectable,
+        }
+    }
+}
+
+/// The fields a module exposes to `$filter`, `$orderby` and `$select`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[must_use]
+pub struct FieldRegistry {
+    fields: HashMap<String, FieldSpec>,
+}
+
+impl FieldRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare `spec`, replacing any field of the same name.
+    pub fn with_field(mut self, spec: FieldSpec) -> Self {
+        self.fields.insert(spec.name.clone(), spec);
+        self
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&FieldSpec> {
+        self.fields.get(name)
+    }
+
+    /// Names of every declared field, in no particular order.
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        self.fields.keys().map(String::as_str).collect()
+    }
+
+    /// The declared field `name`, checked for `capability`.
+    ///
+    /// # Errors
+    /// `unknown` builds the error for an undeclared field; a declared field
+    /// lacking `capability` yields `Error::FieldNotAllowed`.
+    fn require(
+        &self,
+        name: &str,
+        capability: FieldCapability,
+        unknown: impl FnOnce() -> Error,
+    ) -> Result<&FieldSpec, Error> {
+        let spec = self.get(name).ok_or_else(unknown)?;
+        if !spec.allows(capability) {
+            return Err(Error::FieldNotAllowed {
+                field: name.to_owned(),
+                capability,
+            });
+        }
+        Ok(spec)
+    }
+
+    fn filter_field(&self, name: &str) -> Result<&FieldSpec, Error> {
+        self.require(name, FieldCapability::Filterable, || {
+            Error::InvalidFilter(format!("unknown field: {name}"))
+        })
+    }
+
+    /// Check every field of `expr` and the literals compared with them.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidFilter` for an unknown field or a literal of the
+    /// wrong kind, and `Error::FieldNotAllowed` for a field that is not
+    /// filterable.
+    pub fn validate_filter(&self, expr: &ast::Expr) -> Result<(), Error> {
+        use ast::Expr as E;
+
+        let check_literal = |spec: &FieldSpec, value: &E| match value {
+            E::Value(ast::Value::Null) => Ok(()),
+            E::Value(v) if !spec.kind.accepts(v) => Err(Error::InvalidFilter(format!(
+                "{} is {}, got {v}",
+                spec.name, spec.kind
+            ))),
+            other => self.validate_filter(other),
+        };
+
+        match expr {
+            E::And(a, b) | E::Or(a, b) => {
+                self.validate_filter(a)?;
+                self.validate_filter(b)
+            }
+            E::Not(x) => self.validate_filter(x),
+            E::Compare(l, _, r) => match (&**l, &**r) {
+                (E::Identifier(name), value) | (value, E::Identifier(name)) => {
+                    check_literal(self.filter_field(name)?, value)
+                }
+                _ => {
+                    self.validate_filter(l)?;
+                    self.validate_filter(r)
+                }
+            },
+            E::In(l, list) => {
+                let E::Identifier(name) = &**l else {
+                    self.validate_filter(l)?;
+                    return list.iter().try_for_each(|v| self.validate_filter(v));
+                };
+                let spec = self.filter_field(name)?;
+                list.iter().try_for_each(|v| check_literal(spec, v))
+            }
+            E::Function(function, args) => {
+                for arg in args {
+                    if let E::Identifier(name) = arg {
+                        let spec = self.filter_field(name)?;
+                        if spec.kind != FieldKind::String {
+                            return Err(Error::InvalidFilter(format!(
+                                "{function}() requires a string field, but {name} is {}",
+                                spec.kind
+                            )));
+                        }
+                    } else {
+                        self.validate_filter(arg)?;
+                    }
+                }
+                Ok(())
+            }
+            E::Identifier(name) | E::CollectionCount(name) => self.filter_field(name).map(|_| ()),
+            E::Value(_) => Ok(()),
+        }
+    }
+
+    /// Check every key of `order`.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidOrderByField` for an unknown field and
+    /// `Error::FieldNotAllowed` for a field that is not sortable.
+    pub fn validate_order(&self, order: &ODataOrderBy) -> Result<(), Error> {
+        for key in &order.0 {
+            self.require(&key.field, FieldCapability::Sortable, || {
+                Error::InvalidOrderByField(key.field.clone())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Check every selected path by its top-level field (`address.city` is
+    /// governed by `address`). Names match case-insensitively, as `$select`
+    /// parsing lowercases them.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidSelectField` for an unknown field and
+    /// `Error::FieldNotAllowed` for a field that is not selectable.
+    pub fn validate_select(&self, select: &[String]) -> Result<(), Error> {
+        for path in select {
+            let field = path.split('.').next().unwrap_or(path);
+            let field = self
+                .fields
+                .keys()
+                .find(|name| name.eq_ignore_ascii_case(field))
+                .map_or(field, String::as_str);
+            self.require(field, FieldCapability::Selectable, || {
+                Error::InvalidSelectField(path.clone())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl ODataQuery {
+    /// Check `$filter`, `$orderby` and `$select` against the fields declared
+    /// in `registry`.
+    ///
+    /// Sort keys inside a cursor were produced by the server, so the cursor is
+    /// not checked.
+    ///
+    /// # Errors
+    /// See [`FieldRegistry::validate_filter`], [`FieldRegistry::validate_order`]
+    /// and [`FieldRegistry::validate_select`].
+    pub fn validate_fields(&self, registry: &FieldRegistry) -> Result<(), Error> {
+        if let Some(filter) = self.filter() {
+            registry.validate_filter(filter)?;
+        }
+        registry.validate_order(&self.order)?;
+        if let Some(select) = &self.select {
+            registry.validate_select(select)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::{OrderKey, SortDir};
+    use ast::{CompareOperator, Expr, Value};
+
+    fn registry() -> FieldRegistry {
+        FieldRegistry::new()
+            .with_field(FieldSpec::new("name", FieldKind::String))
+            .with_field(FieldSpec::new("age", FieldKind::I64).not_sortable())
+            .with_field(FieldSpec::new("created_at", FieldKind::DateTimeUtc).not_filterable())
+            .with_field(FieldSpec::new("password", FieldKind::String).not_selectable())
+    }
+
+    fn compare(field: &str, value: Value) -> Expr {
+        Expr::Compare(
+            Box::new(Expr::Identifier(field.to_owned())),
+            CompareOperator::Eq,
+            Box::new(Expr::Value(value)),
+        )
+    }
+
+    fn order(field: &str) -> ODataOrderBy {
+        ODataOrderBy(vec![OrderKey {
+            field: field.to_owned(),
+            dir: SortDir::Asc,
+        }])
+    }
+
+    fn not_allowed(err: Error) -> (String, FieldCapability) {
+        match err {
+            Error::FieldNotAllowed { field, capability } => (field, capability),
+            other => panic!("expected FieldNotAllowed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn declared_fields_pass() {
+        let query = ODataQuery::new()
+            .with_filter(
+                compare("name", Value::String("ann".to_owned()))
+                    .and(compare("age", Value::Null))
+                    .and(Expr::Function(
+                        "contains".to_owned(),
+                        vec![
+                            Expr::Identifier("name".to_owned()),
+                            Expr::Value(Value::String("n".to_owned())),
+                        ],
+                    )),
+            )
+            .with_order(order("created_at"))
+            .with_select(vec!["name".to_owned(), "age".to_owned()]);
+        query.validate_fields(&registry()).unwrap();
+    }
+
+    #[test]
+    fn selected_fields_match_case_insensitively() {
+        registry()
+            .validate_select(&["NAME".to_owned(), "Age".to_owned()])
+            .unwrap();
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected_per_clause() {
+        let err = registry()
+            .validate_filter(&compare("email", Value::Null))
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidFilter(ref m) if m == "unknown field: email"));
+
+        let err = registry().validate_order(&order("email")).unwrap_err();
+        assert!(matches!(err, Error::InvalidOrderByField(ref f) if f == "email"));
+
+        let err = registry()
+            .validate_select(&["email.domain".to_owned()])
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidSelectField(ref f) if f == "email.domain"));
+    }
+
+    #[test]
+    fn restricted_fields_are_rejected_with_their_capability() {
+        let now = Value::DateTime(chrono::Utc::now());
+        let err = registry()
+            .validate_filter(&compare("created_at", now))
+            .unwrap_err();
+        assert_eq!(
+            not_allowed(err),
+            ("created_at".to_owned(), FieldCapability::Filterable)
+        );
+
+        let err = registry().validate_order(&order("age")).unwrap_err();
+        assert_eq!(
+            not_allowed(err),
+            ("age".to_owned(), FieldCapability::Sortable)
+        );
+
+        let err = registry()
+            .validate_select(&["password".to_owned()])
+            .unwrap_err();
+        let (field, capability) = not_allowed(err.clone());
+        assert_eq!(
+            (field.as_str(), capability),
+            ("password", FieldCapability::Selectable)
+        );
+        assert_eq!(err.to_string(), "field not selectable: password");
+    }
+
+    #[test]
+    fn literals_must_match_the_declared_kind() {
+        let err = registry()
+            .validate_filter(&compare("age", Value::String("ten".to_owned())))
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidFilter(ref m) if m == "age is I64, got string"));
+
+        let list = Expr::In(
+            Box::new(Expr::Identifier("name".to_owned())),
+            vec![Expr::Value(Value::Bool(true))],
+        );
+        assert!(matches!(
+            registry().validate_filter(&list),
+            Err(Error::InvalidFilter(_))
+        ));
+
+        let contains = Expr::Function(
+            "contains".to_owned(),
+            vec![
+                Expr::Identifier("age".to_owned()),
+                Expr::Value(Value::String("1".to_owned())),
+            ],
+        );
+        let err = registry().validate_filter(&contains).unwrap_err();
+        assert!(
+            matches!(err, Error::InvalidFilter(ref m) if m == "contains() requires a string field, but age is I64")
+        );
+    }
+}
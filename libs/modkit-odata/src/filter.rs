@@ -35,6 +35,29 @@ impl fmt::Display for FieldKind {
     }
 }
 
+impl FieldKind {
+    /// Whether a literal `value` can be compared with a field of this kind.
+    /// `null` is handled separately and never matches.
+    #[must_use]
+    pub fn accepts(self, value: &ODataValue) -> bool {
+        use odata_ast::Value as V;
+
+        matches!(
+            (self, value),
+            (FieldKind::String, V::String(_))
+                | (
+                    FieldKind::I64 | FieldKind::F64 | FieldKind::Decimal,
+                    V::Number(_)
+                )
+                | (FieldKind::Bool, V::Bool(_))
+                | (FieldKind::Uuid, V::Uuid(_))
+                | (FieldKind::DateTimeUtc, V::DateTime(_))
+                | (FieldKind::Date, V::Date(_))
+                | (FieldKind::Time, V::Time(_))
+        )
+    }
+}
+
 pub trait FilterField: Copy + Eq + std::hash::Hash + fmt::Debug + 'static {
     const FIELDS: &'static [Self];
 
@@ -136,6 +159,13 @@ pub enum FilterError {
         got: String,
     },
 
+    #[error("{function}() requires a string field, but {field} is {kind}")]
+    NonStringField {
+        function: String,
+        field: String,
+        kind: FieldKind,
+    },
+
     #[error("Unsupported operation: {0}")]
     UnsupportedOperation(String),
 
@@ -164,9 +194,8 @@ pub type FilterResult<T> = Result<T, FilterError>;
 pub fn parse_odata_filter<F: FilterField>(raw: &str) -> FilterResult<FilterNode<F>> {
     #[cfg(feature = "with-odata-params")]
     {
-        use odata_params::filters::parse_str;
-
-        let ast = parse_str(raw).map_err(|e| FilterError::InvalidExpression(format!("{e:?}")))?;
+        let ast =
+            crate::logical::parse(raw).map_err(|e| FilterError::InvalidExpression(e.message))?;
         let ast: odata_ast::Expr = ast.into();
         convert_expr_to_filter_node::<F>(&ast)
     }
@@ -223,8 +252,6 @@ pub fn convert_expr_to_filter_node<F: FilterField>(
             let field = F::from_name(field_name)
                 .ok_or_else(|| FilterError::UnknownField(field_name.to_owned()))?;
 
-            validate_value_type(field, &value)?;
-
             let filter_op = match op {
                 odata_ast::CompareOperator::Eq => FilterOp::Eq,
                 odata_ast::CompareOperator::Ne => FilterOp::Ne,
@@ -234,6 +261,25 @@ pub fn convert_expr_to_filter_node<F: FilterField>(
                 odata_ast::CompareOperator::Le => FilterOp::Le,
             };
 
+            // `eq null` / `ne null` test for a missing value on any field kind
+            if matches!(value, odata_ast::Value::Null) {
+                if !matches!(filter_op, FilterOp::Eq | FilterOp::Ne) {
+                    return Err(FilterError::InvalidExpression(format!(
+                        "'{filter_op}' cannot compare with null; use 'eq null' or 'ne null'"
+                    )));
+                }
+                return Ok(FilterNode::binary(field, filter_op, value));
+            }
+            validate_value_type(field, &value)?;
+
+            if field.kind() == FieldKind::Bool && !matches!(filter_op, FilterOp::Eq | FilterOp::Ne)
+            {
+                return Err(FilterError::UnsupportedOperation(format!(
+                    "'{filter_op}' on boolean field {}",
+                    field.name()
+                )));
+            }
+
             Ok(FilterNode::binary(field, filter_op, value))
         }
 
@@ -241,7 +287,7 @@ pub fn convert_expr_to_filter_node<F: FilterField>(
             let name_lower = func_name.to_ascii_lowercase();
             match (name_lower.as_str(), args.as_slice()) {
                 (
-                    "contains",
+                    name @ ("contains" | "startswith" | "endswith"),
                     [
                         E::Identifier(field_name),
                         E::Value(odata_ast::Value::String(s)),
@@ -251,64 +297,21 @@ pub fn convert_expr_to_filter_node<F: FilterField>(
                         .ok_or_else(|| FilterError::UnknownField(field_name.clone()))?;
 
                     if field.kind() != FieldKind::String {
-                        return Err(FilterError::TypeMismatch {
+                        return Err(FilterError::NonStringField {
+                            function: name.to_owned(),
                             field: field_name.clone(),
-                            expected: FieldKind::String,
-                            got: "non-string".to_owned(),
+                            kind: field.kind(),
                         });
                     }
 
+                    let op = match name {
+                        "contains" => FilterOp::Contains,
+                        "startswith" => FilterOp::StartsWith,
+                        _ => FilterOp::EndsWith,
+                    };
                     Ok(FilterNode::binary(
                         field,
-                        FilterOp::Contains,
-                        odata_ast::Value::String(s.clone()),
-                    ))
-                }
-                (
-                    "startswith",
-                    [
-                        E::Identifier(field_name),
-                        E::Value(odata_ast::Value::String(s)),
-                    ],
-                ) => {
-                    let field = F::from_name(field_name)
-                        .ok_or_else(|| FilterError::UnknownField(field_name.clone()))?;
-
-                    if field.kind() != FieldKind::String {
-                        return Err(FilterError::TypeMismatch {
-                            field: field_name.clone(),
-                            expected: FieldKind::String,
-                            got: "non-string".to_owned(),
-                        });
-                    }
-
-                    Ok(FilterNode::binary(
-                        field,
-                        FilterOp::StartsWith,
-                        odata_ast::Value::String(s.clone()),
-                    ))
-                }
-                (
-                    "endswith",
-                    [
-                        E::Identifier(field_name),
-                        E::Value(odata_ast::Value::String(s)),
-                    ],
-                ) => {
-                    let field = F::from_name(field_name)
-                        .ok_or_else(|| FilterError::UnknownField(field_name.clone()))?;
-
-                    if field.kind() != FieldKind::String {
-                        return Err(FilterError::TypeMismatch {
-                            field: field_name.clone(),
-                            expected: FieldKind::String,
-                            got: "non-string".to_owned(),
-                        });
-                    }
-
-                    Ok(FilterNode::binary(
-                        field,
-                        FilterOp::EndsWith,
+                        op,
                         odata_ast::Value::String(s.clone()),
                     ))
                 }
@@ -322,30 +325,26 @@ pub fn convert_expr_to_filter_node<F: FilterField>(
             "IN operator not yet supported in typed filters".to_owned(),
         )),
 
-        E::Identifier(name) => Err(FilterError::BareIdentifier(name.clone())),
+        E::CollectionCount(name) => Err(FilterError::UnsupportedOperation(format!(
+            "{name}/$count: typed filters have no collection fields"
+        ))),
+
+        // A bare boolean field is shorthand for `field eq true`
+        E::Identifier(name) => match F::from_name(name) {
+            Some(field) if field.kind() == FieldKind::Bool => Ok(FilterNode::binary(
+                field,
+                FilterOp::Eq,
+                odata_ast::Value::Bool(true),
+            )),
+            _ => Err(FilterError::BareIdentifier(name.clone())),
+        },
         E::Value(_) => Err(FilterError::BareLiteral),
     }
 }
 
 fn validate_value_type<F: FilterField>(field: F, value: &odata_ast::Value) -> FilterResult<()> {
-    use odata_ast::Value as V;
-
     let kind = field.kind();
-    let matches = matches!(
-        (kind, value),
-        (FieldKind::String, V::String(_))
-            | (
-                FieldKind::I64 | FieldKind::F64 | FieldKind::Decimal,
-                V::Number(_)
-            )
-            | (FieldKind::Bool, V::Bool(_))
-            | (FieldKind::Uuid, V::Uuid(_))
-            | (FieldKind::DateTimeUtc, V::DateTime(_))
-            | (FieldKind::Date, V::Date(_))
-            | (FieldKind::Time, V::Time(_))
-    );
-
-    if matches {
+    if kind.accepts(value) {
         Ok(())
     } else {
         Err(FilterError::TypeMismatch {
@@ -355,3 +354,175 @@ fn validate_value_type<F: FilterField>(field: F, value: &odata_ast::Value) -> Fi
         })
     }
 }
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use odata_ast::{CompareOperator, Expr, Value};
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum UserField {
+        Name,
+        IsActive,
+    }
+
+    impl FilterField for UserField {
+        const FIELDS: &'static [Self] = &[Self::Name, Self::IsActive];
+
+        fn name(&self) -> &'static str {
+            match self {
+                Self::Name => "name",
+                Self::IsActive => "isActive",
+            }
+        }
+
+        fn kind(&self) -> FieldKind {
+            match self {
+                Self::Name => FieldKind::String,
+                Self::IsActive => FieldKind::Bool,
+            }
+        }
+    }
+
+    fn compare(field: &str, op: CompareOperator, value: Value) -> Expr {
+        Expr::Compare(
+            Box::new(Expr::Identifier(field.to_owned())),
+            op,
+            Box::new(Expr::Value(value)),
+        )
+    }
+
+    fn is_active_eq(node: &FilterNode<UserField>, expected: bool) -> bool {
+        matches!(
+            node,
+            FilterNode::Binary {
+                field: UserField::IsActive,
+                op: FilterOp::Eq,
+                value: Value::Bool(b),
+            } if *b == expected
+        )
+    }
+
+    #[test]
+    fn boolean_equality_is_converted() {
+        let expr = compare("isActive", CompareOperator::Eq, Value::Bool(false));
+        let node = convert_expr_to_filter_node::<UserField>(&expr).unwrap();
+        assert!(is_active_eq(&node, false));
+    }
+
+    #[test]
+    fn bare_boolean_field_means_eq_true() {
+        let expr = Expr::Identifier("isActive".to_owned());
+        let node = convert_expr_to_filter_node::<UserField>(&expr).unwrap();
+        assert!(is_active_eq(&node, true));
+
+        let negated = Expr::Not(Box::new(expr));
+        let node = convert_expr_to_filter_node::<UserField>(&negated).unwrap();
+        assert!(matches!(node, FilterNode::Not(ref inner) if is_active_eq(inner, true)));
+
+        let err = convert_expr_to_filter_node::<UserField>(&Expr::Identifier("name".to_owned()))
+            .unwrap_err();
+        assert!(matches!(err, FilterError::BareIdentifier(ref f) if f == "name"));
+    }
+
+    #[test]
+    fn ordering_operators_reject_booleans() {
+        let expr = compare("isActive", CompareOperator::Gt, Value::Bool(true));
+        let err = convert_expr_to_filter_node::<UserField>(&expr).unwrap_err();
+        assert!(matches!(err, FilterError::UnsupportedOperation(_)), "{err}");
+
+        let expr = compare(
+            "isActive",
+            CompareOperator::Eq,
+            Value::String("yes".to_owned()),
+        );
+        let err = convert_expr_to_filter_node::<UserField>(&expr).unwrap_err();
+        assert!(matches!(err, FilterError::TypeMismatch { .. }));
+    }
+
+    #[test]
+    fn null_comparisons_test_for_missing_values() {
+        for op in [CompareOperator::Eq, CompareOperator::Ne] {
+            let node = convert_expr_to_filter_node::<UserField>(&compare("name", op, Value::Null))
+                .unwrap();
+            assert!(
+                matches!(
+                    node,
+                    FilterNode::Binary {
+                        field: UserField::Name,
+                        value: Value::Null,
+                        ..
+                    }
+                ),
+                "{op}"
+            );
+        }
+
+        let expr = compare("name", CompareOperator::Gt, Value::Null);
+        let err = convert_expr_to_filter_node::<UserField>(&expr).unwrap_err();
+        assert!(matches!(err, FilterError::InvalidExpression(_)), "{err}");
+    }
+
+    #[test]
+    fn string_functions_are_converted() {
+        for (name, op) in [
+            ("contains", FilterOp::Contains),
+            ("startswith", FilterOp::StartsWith),
+            ("endswith", FilterOp::EndsWith),
+        ] {
+            let expr = Expr::Function(
+                name.to_owned(),
+                vec![
+                    Expr::Identifier("name".to_owned()),
+                    Expr::Value(Value::String("ann".to_owned())),
+                ],
+            );
+            let node = convert_expr_to_filter_node::<UserField>(&expr).unwrap();
+            assert!(
+                matches!(node, FilterNode::Binary { field: UserField::Name, op: o, value: Value::String(ref s) } if o == op && s == "ann"),
+                "{name}"
+            );
+        }
+    }
+
+    #[cfg(feature = "with-odata-params")]
+    #[test]
+    fn string_functions_parse_and_check_field_type() {
+        let node = parse_odata_filter::<UserField>("startswith(name,'an') and isActive").unwrap();
+        assert!(matches!(
+            node,
+            FilterNode::Composite {
+                op: FilterOp::And,
+                ..
+            }
+        ));
+
+        let err = parse_odata_filter::<UserField>("contains(isActive,'x')").unwrap_err();
+        assert!(matches!(err, FilterError::NonStringField { .. }));
+        assert_eq!(
+            err.to_string(),
+            "contains() requires a string field, but isActive is Bool"
+        );
+    }
+
+    #[cfg(feature = "with-odata-params")]
+    #[test]
+    fn boolean_filters_parse() {
+        let node = parse_odata_filter::<UserField>("isActive eq true").unwrap();
+        assert!(is_active_eq(&node, true));
+
+        let node = parse_odata_filter::<UserField>("isActive and name eq 'ann'").unwrap();
+        let FilterNode::Composite {
+            op: FilterOp::And,
+            children,
+        } = node
+        else {
+            panic!("expected and()");
+        };
+        assert!(is_active_eq(&children[0], true));
+
+        let err = parse_odata_filter::<UserField>("isActive gt true").unwrap_err();
+        assert!(matches!(err, FilterError::UnsupportedOperation(_)), "{err}");
+    }
+}
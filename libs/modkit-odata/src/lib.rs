@@ -1,18 +1,30 @@
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
+pub mod aliases;
 pub mod builder;
+pub mod complexity;
+pub mod compute;
 pub mod errors;
+pub mod fields;
 pub mod filter;
 pub mod limits;
+#[cfg(feature = "with-odata-params")]
+mod logical;
 pub mod page;
 pub mod pagination;
 pub mod problem_mapping;
 pub mod schema;
+pub mod select;
 
+pub use aliases::FieldAliases;
 pub use builder::QueryBuilder;
-pub use limits::ODataLimits;
-pub use page::{Page, PageInfo};
+pub use complexity::{ComplexityWeights, query_complexity};
+pub use compute::{ComputeExpr, ComputeOp, ComputeOperand, parse_compute};
+pub use fields::{FieldCapability, FieldRegistry, FieldSpec};
+pub use limits::{ODataLimits, parse_count, parse_skip};
+pub use page::{ODataPage, Page, PageInfo};
 pub use pagination::{normalize_filter_for_hash, short_filter_hash};
 pub use schema::{FieldRef, Schema};
+pub use select::Select;
 
 pub mod ast {
     use bigdecimal::BigDecimal;
@@ -29,6 +41,8 @@ pub mod ast {
         Function(String, Vec<Expr>),
         Identifier(String),
         Value(Value),
+        /// Number of elements of a collection field: `roles/$count`.
+        CollectionCount(String),
     }
 
     impl Expr {
@@ -56,6 +70,57 @@ pub mod ast {
         pub fn not(self) -> Expr {
             !self
         }
+
+        /// Value lists of every `in` operator in this expression.
+        #[must_use]
+        pub fn in_lists(&self) -> Vec<&[Expr]> {
+            fn walk<'a>(e: &'a Expr, out: &mut Vec<&'a [Expr]>) {
+                match e {
+                    Expr::And(a, b) | Expr::Or(a, b) | Expr::Compare(a, _, b) => {
+                        walk(a, out);
+                        walk(b, out);
+                    }
+                    Expr::Not(x) => walk(x, out),
+                    Expr::In(x, list) => {
+                        walk(x, out);
+                        out.push(list);
+                    }
+                    Expr::Function(_, args) => args.iter().for_each(|a| walk(a, out)),
+                    Expr::Identifier(_) | Expr::Value(_) | Expr::CollectionCount(_) => {}
+                }
+            }
+
+            let mut out = Vec::new();
+            walk(self, &mut out);
+            out
+        }
+
+        /// First operator comparing against `null` other than `eq`/`ne`, which
+        /// have no meaning for a missing value.
+        #[must_use]
+        pub fn ordered_null_comparison(&self) -> Option<CompareOperator> {
+            match self {
+                Expr::Compare(a, op, b) => {
+                    let is_null = |e: &Expr| matches!(e, Expr::Value(Value::Null));
+                    if !matches!(op, CompareOperator::Eq | CompareOperator::Ne)
+                        && (is_null(a) || is_null(b))
+                    {
+                        return Some(*op);
+                    }
+                    a.ordered_null_comparison()
+                        .or_else(|| b.ordered_null_comparison())
+                }
+                Expr::And(a, b) | Expr::Or(a, b) => a
+                    .ordered_null_comparison()
+                    .or_else(|| b.ordered_null_comparison()),
+                Expr::Not(x) => x.ordered_null_comparison(),
+                Expr::In(x, list) => x
+                    .ordered_null_comparison()
+                    .or_else(|| list.iter().find_map(Expr::ordered_null_comparison)),
+                Expr::Function(_, args) => args.iter().find_map(Expr::ordered_null_comparison),
+                Expr::Identifier(_) | Expr::Value(_) | Expr::CollectionCount(_) => None,
+            }
+        }
     }
 
     impl std::ops::Not for Expr {
@@ -76,8 +141,24 @@ pub mod ast {
         Le,
     }
 
+    impl std::fmt::Display for CompareOperator {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(match self {
+                CompareOperator::Eq => "eq",
+                CompareOperator::Ne => "ne",
+                CompareOperator::Gt => "gt",
+                CompareOperator::Ge => "ge",
+                CompareOperator::Lt => "lt",
+                CompareOperator::Le => "le",
+            })
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub enum Value {
+        /// The `null` literal. Only `field eq null` (IS NULL) and
+        /// `field ne null` (IS NOT NULL) compare with it; ordered comparisons
+        /// are rejected when the filter is parsed.
         Null,
         Bool(bool),
         Number(BigDecimal),
@@ -270,7 +351,7 @@ impl std::fmt::Display for ODataOrderBy {
 /// ## HTTP Mapping
 ///
 /// These errors map to RFC 9457 Problem responses via the catalog in `modkit`:
-/// - `InvalidFilter` → 422 `gts...~hx.odata.errors.invalid_filter.v1`
+/// - `InvalidFilter`, `InvalidFilterAt` → 422 `gts...~hx.odata.errors.invalid_filter.v1`
 /// - `InvalidOrderByField` → 422 `gts...~hx.odata.errors.invalid_orderby.v1`
 /// - Cursor errors → 422 `gts...~hx.odata.errors.invalid_cursor.v1`
 #[derive(thiserror::Error, Debug, Clone)]
@@ -279,10 +360,33 @@ pub enum Error {
     #[error("invalid $filter: {0}")]
     InvalidFilter(String),
 
+    /// A syntax error located in the raw filter: `position` is its byte
+    /// offset and `token` the offending token (empty at the end of input).
+    #[error("invalid $filter: {message}")]
+    InvalidFilterAt {
+        message: String,
+        position: usize,
+        token: String,
+    },
+
     // OrderBy parsing and validation errors
     #[error("unsupported $orderby field: {0}")]
     InvalidOrderByField(String),
 
+    // Select parsing and validation errors
+    #[error("invalid $select: {0}")]
+    InvalidSelect(String),
+
+    #[error("unsupported $select field: {0}")]
+    InvalidSelectField(String),
+
+    /// A declared field used where its `FieldSpec` does not allow it.
+    #[error("field not {capability}: {field}")]
+    FieldNotAllowed {
+        field: String,
+        capability: FieldCapability,
+    },
+
     // Pagination and cursor errors
     #[error("ORDER_MISMATCH")]
     OrderMismatch,
@@ -299,6 +403,24 @@ pub enum Error {
     #[error("ORDER_WITH_CURSOR")]
     OrderWithCursor,
 
+    #[error("invalid $top: {0}")]
+    InvalidTop(String),
+
+    #[error("invalid $skip: {0}")]
+    InvalidSkip(String),
+
+    #[error("SKIP_WITH_CURSOR")]
+    SkipWithCursor,
+
+    #[error("invalid $count: {0}")]
+    InvalidCount(String),
+
+    #[error("query complexity {score} exceeds budget {budget}")]
+    QueryTooComplex { score: u64, budget: u64 },
+
+    #[error("query deadline exceeded")]
+    DeadlineExceeded,
+
     // Cursor parsing errors (previously CursorError variants)
     #[error("invalid cursor: invalid base64url encoding")]
     CursorInvalidBase64,
@@ -325,21 +447,35 @@ pub enum Error {
     // Configuration errors
     #[error("OData parsing unavailable: {0}")]
     ParsingUnavailable(&'static str),
+
+    #[error("invalid pagination keyset: {0}")]
+    InvalidKeyset(String),
 }
 
 /// Validate cursor consistency against effective order and filter hash.
 ///
 /// # Errors
-/// Returns `Error::OrderMismatch` if the cursor's sort order doesn't match the effective order.
+/// Returns `Error::OrderMismatch` if the cursor was issued for other sort keys.
+/// Returns `Error::CursorInvalidDirection` if the sort keys match but a direction differs.
 /// Returns `Error::FilterMismatch` if the cursor's filter hash doesn't match the effective filter.
 pub fn validate_cursor_against(
     cursor: &CursorV1,
     effective_order: &ODataOrderBy,
     effective_filter_hash: Option<&str>,
 ) -> Result<(), Error> {
-    if !effective_order.equals_signed_tokens(&cursor.s) {
+    let cursor_order = cursor.order()?;
+    let same_keys = cursor_order.0.len() == effective_order.0.len()
+        && cursor_order
+            .0
+            .iter()
+            .zip(&effective_order.0)
+            .all(|(a, b)| a.field == b.field);
+    if !same_keys {
         return Err(Error::OrderMismatch);
     }
+    if !effective_order.equals_signed_tokens(&cursor.s) {
+        return Err(Error::CursorInvalidDirection);
+    }
     if let (Some(h), Some(cf)) = (effective_filter_hash, cursor.f.as_deref())
         && h != cf
     {
@@ -348,6 +484,36 @@ pub fn validate_cursor_against(
     Ok(())
 }
 
+/// Cursor wire format written by [`encode_cursor`].
+///
+/// Bump it only when the wire shape changes: nodes still on the previous
+/// release reject the new version during a rolling deploy. Keep the previous
+/// version in [`LEGACY_CURSOR_VERSIONS`] with a migration in [`decode_cursor`]
+/// so pagers holding older tokens keep working.
+pub const CURSOR_VERSION: u8 = 2;
+
+/// Older cursor versions still accepted (and migrated) by [`decode_cursor`]:
+/// - 1: the paging direction `d` is optional and defaults to `"fwd"`.
+pub const LEGACY_CURSOR_VERSIONS: &[u8] = &[1];
+
+/// Encode a cursor as a [`CURSOR_VERSION`] base64url token.
+///
+/// # Errors
+/// Returns a JSON serialization error if encoding fails.
+pub fn encode_cursor(cursor: &CursorV1) -> serde_json::Result<String> {
+    cursor.encode()
+}
+
+/// Decode a cursor token of the current or a legacy version.
+///
+/// # Errors
+/// Returns `Error::CursorInvalidVersion` for versions newer than
+/// [`CURSOR_VERSION`] or no longer supported, and the other `CursorInvalid*`
+/// errors of [`CursorV1::decode`] for malformed tokens.
+pub fn decode_cursor(token: &str) -> Result<CursorV1, Error> {
+    CursorV1::decode(token)
+}
+
 // Cursor v1
 #[derive(Clone, Debug)]
 pub struct CursorV1 {
@@ -359,6 +525,15 @@ pub struct CursorV1 {
 }
 
 impl CursorV1 {
+    /// Sort keys and per-key directions the cursor was issued for, in the
+    /// order of its key values `k`.
+    ///
+    /// # Errors
+    /// Returns `Error::CursorInvalidFields` if the signed sort tokens are malformed.
+    pub fn order(&self) -> Result<ODataOrderBy, Error> {
+        ODataOrderBy::from_signed_tokens(&self.s).map_err(|_| Error::CursorInvalidFields)
+    }
+
     /// Encode cursor to a base64url string.
     ///
     /// # Errors
@@ -379,7 +554,7 @@ impl CursorV1 {
             SortDir::Desc => "desc",
         };
         let w = Wire {
-            v: 1,
+            v: CURSOR_VERSION,
             k: &self.k,
             o,
             s: &self.s,
@@ -389,35 +564,45 @@ impl CursorV1 {
         serde_json::to_vec(&w).map(|x| base64_url::encode(&x))
     }
 
-    /// Decode cursor from base64url token.
+    /// Decode cursor from base64url token, migrating legacy versions.
     ///
     /// # Errors
     /// Returns `Error::CursorInvalidBase64` if base64 decoding fails.
     /// Returns `Error::CursorInvalidJson` if JSON parsing fails.
     /// Returns `Error::CursorInvalidVersion` if the version is unsupported.
+    /// Returns `Error::CursorInvalidKeys` if there are no key values.
+    /// Returns `Error::CursorInvalidFields` if the signed sort tokens are malformed.
     /// Returns `Error::CursorInvalidDirection` if the direction field is invalid.
     pub fn decode(token: &str) -> Result<Self, Error> {
+        // Read the version alone first: a future version may change the shape
         #[derive(serde::Deserialize)]
-        struct Wire {
+        struct Version {
             v: u8,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Wire {
             k: Vec<String>,
             o: String,
             s: String,
             #[serde(default)]
             f: Option<String>,
-            #[serde(default = "default_direction")]
-            d: String,
-        }
-
-        fn default_direction() -> String {
-            "fwd".to_owned()
+            #[serde(default)]
+            d: Option<String>,
         }
 
         let bytes = base64_url::decode(token).map_err(|_| Error::CursorInvalidBase64)?;
-        let w: Wire = serde_json::from_slice(&bytes).map_err(|_| Error::CursorInvalidJson)?;
-        if w.v != 1 {
+        let Version { v } = serde_json::from_slice(&bytes).map_err(|_| Error::CursorInvalidJson)?;
+        if v != CURSOR_VERSION && !LEGACY_CURSOR_VERSIONS.contains(&v) {
             return Err(Error::CursorInvalidVersion);
         }
+        let mut w: Wire = serde_json::from_slice(&bytes).map_err(|_| Error::CursorInvalidJson)?;
+        if v == 1 {
+            w.d.get_or_insert_with(|| "fwd".to_owned());
+        }
+        let Some(d) = w.d else {
+            return Err(Error::CursorInvalidDirection);
+        };
         let o = match w.o.as_str() {
             "asc" => SortDir::Asc,
             "desc" => SortDir::Desc,
@@ -429,8 +614,11 @@ impl CursorV1 {
         if w.s.trim().is_empty() {
             return Err(Error::CursorInvalidFields);
         }
+        if ODataOrderBy::from_signed_tokens(&w.s).is_err() {
+            return Err(Error::CursorInvalidFields);
+        }
         // Validate direction
-        if w.d != "fwd" && w.d != "bwd" {
+        if d != "fwd" && d != "bwd" {
             return Err(Error::CursorInvalidDirection);
         }
         Ok(CursorV1 {
@@ -438,7 +626,7 @@ impl CursorV1 {
             o,
             s: w.s,
             f: w.f,
-            d: w.d,
+            d,
         })
     }
 }
@@ -463,9 +651,14 @@ pub struct ODataQuery {
     pub filter: Option<Box<ast::Expr>>,
     pub order: ODataOrderBy,
     pub limit: Option<u64>,
+    /// Rows to skip before the page (`$skip`); never combined with `cursor`.
+    pub skip: Option<u64>,
+    /// Whether the total number of matching rows is requested (`$count=true`).
+    pub count: bool,
     pub cursor: Option<CursorV1>,
     pub filter_hash: Option<String>,
     pub select: Option<Vec<String>>,
+    pub compute: Option<Vec<ComputeExpr>>,
 }
 
 impl ODataQuery {
@@ -488,6 +681,16 @@ impl ODataQuery {
         self
     }
 
+    pub fn with_skip(mut self, skip: u64) -> Self {
+        self.skip = Some(skip);
+        self
+    }
+
+    pub fn with_count(mut self, count: bool) -> Self {
+        self.count = count;
+        self
+    }
+
     pub fn with_cursor(mut self, cursor: CursorV1) -> Self {
         self.cursor = Some(cursor);
         self
@@ -503,6 +706,11 @@ impl ODataQuery {
         self
     }
 
+    pub fn with_compute(mut self, items: Vec<ComputeExpr>) -> Self {
+        self.compute = Some(items);
+        self
+    }
+
     /// Get filter as AST
     #[must_use]
     pub fn filter(&self) -> Option<&ast::Expr> {
@@ -532,6 +740,12 @@ impl ODataQuery {
     pub fn selected_fields(&self) -> Option<&[String]> {
         self.select.as_deref()
     }
+
+    /// Get computed columns requested via `$compute`
+    #[must_use]
+    pub fn computed(&self) -> Option<&[ComputeExpr]> {
+        self.compute.as_deref()
+    }
 }
 
 impl From<Option<ast::Expr>> for ODataQuery {
@@ -595,6 +809,12 @@ mod convert_odata_params {
                     Box::new((*l).into()),
                     list.into_iter().map(Into::into).collect(),
                 ),
+                Function(n, mut args) if n == super::COLLECTION_COUNT_FN && args.len() == 1 => {
+                    match args.pop() {
+                        Some(Identifier(field)) => Expr::CollectionCount(field),
+                        other => Expr::Function(n, other.into_iter().map(Into::into).collect()),
+                    }
+                }
                 Function(n, args) => Expr::Function(n, args.into_iter().map(Into::into).collect()),
                 Identifier(s) => Expr::Identifier(s),
                 Value(v) => Expr::Value(v.into()),
@@ -603,6 +823,50 @@ mod convert_odata_params {
     }
 }
 
+/// Internal function name standing for a `field/$count` path while parsing.
+///
+/// The underlying grammar has no path segments, so [`rewrite_count_paths`]
+/// turns `roles/$count` into `__count(roles)` before parsing and the AST
+/// conversion maps it to [`ast::Expr::CollectionCount`].
+#[cfg(feature = "with-odata-params")]
+const COLLECTION_COUNT_FN: &str = "__count";
+
+/// Rewrite every `ident/$count` outside string literals into `__count(ident)`.
+#[cfg(feature = "with-odata-params")]
+pub(crate) fn rewrite_count_paths(raw: &str) -> std::borrow::Cow<'_, str> {
+    const COUNT_PATH: &str = "/$count";
+    if !raw.contains(COUNT_PATH) {
+        return std::borrow::Cow::Borrowed(raw);
+    }
+
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(raw.len() + 8);
+    let mut in_string = false;
+    let mut rest = raw;
+    while let Some(c) = rest.chars().next() {
+        if c == '\'' {
+            in_string = !in_string;
+        } else if !in_string
+            && rest.starts_with(COUNT_PATH)
+            && !rest[COUNT_PATH.len()..].starts_with(is_ident)
+        {
+            let start = out.trim_end_matches(is_ident).len();
+            if start < out.len() {
+                let field = out.split_off(start);
+                out.push_str(COLLECTION_COUNT_FN);
+                out.push('(');
+                out.push_str(&field);
+                out.push(')');
+                rest = &rest[COUNT_PATH.len()..];
+                continue;
+            }
+        }
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    std::borrow::Cow::Owned(out)
+}
+
 /// Result of parsing a filter string, including both the AST and complexity metadata.
 #[derive(Clone, Debug)]
 pub struct ParsedFilter {
@@ -633,10 +897,13 @@ impl ParsedFilter {
 /// Parse a raw $filter string into internal AST with complexity metadata.
 ///
 /// This function encapsulates the parsing logic and node counting,
-/// abstracting away the underlying `odata_params` dependency.
+/// abstracting away the underlying `odata_params` dependency. `and` binds
+/// tighter than `or`; parentheses group explicitly.
 ///
 /// # Errors
-/// - `Error::InvalidFilter` if the filter string is malformed or parsing fails
+/// - `Error::InvalidFilterAt` if the filter string is malformed, locating the
+///   offending token
+/// - `Error::InvalidFilter` for an `in` operator with an empty list
 /// - `Error::ParsingUnavailable` if the `with-odata-params` feature is disabled
 ///
 /// # Example
@@ -662,10 +929,20 @@ pub fn parse_filter_string(raw: &str) -> Result<ParsedFilter, Error> {
         }
     }
 
-    let ast_src = od::parse_str(raw).map_err(|e| Error::InvalidFilter(format!("{e:?}")))?;
+    let ast_src = logical::parse(raw)?;
 
     let node_count = count_ast_nodes(&ast_src);
     let expr: ast::Expr = ast_src.into();
+    if expr.in_lists().iter().any(|list| list.is_empty()) {
+        return Err(Error::InvalidFilter(
+            "'in' requires at least one value".to_owned(),
+        ));
+    }
+    if let Some(op) = expr.ordered_null_comparison() {
+        return Err(Error::InvalidFilter(format!(
+            "'{op}' cannot compare with null; use 'eq null' or 'ne null'"
+        )));
+    }
 
     Ok(ParsedFilter { expr, node_count })
 }
@@ -1,12 +1,15 @@
 //! Input validation and safety limits for `OData` parsing
 //!
 //! This module enforces sane caps to prevent abuse and resource exhaustion:
-//! - Maximum `$top` value
+//! - Maximum `$top` value and non-negative `$skip`
 //! - Maximum number of `$orderby` fields
 //! - Maximum filter expression length
+//! - Maximum length of `in` value lists
+//! - Total query-complexity budget
 //! - Cursor integrity checks (HMAC signing)
 
-use crate::Error;
+use crate::complexity::{ComplexityWeights, query_complexity};
+use crate::{Error, ODataQuery, ast};
 
 /// Default configuration for `OData` input limits
 #[derive(Debug, Clone)]
@@ -18,10 +21,16 @@ pub struct ODataLimits {
     pub max_orderby_fields: usize,
     /// Maximum length of $filter expression in characters (default: 2000)
     pub max_filter_length: usize,
+    /// Maximum number of values in an `in (...)` list (default: 100)
+    pub max_in_list_len: usize,
     /// Whether to enforce HMAC signing on cursors (default: false for now)
     pub require_signed_cursors: bool,
     /// HMAC key for cursor signing (if enabled)
     pub cursor_hmac_key: Option<Vec<u8>>,
+    /// Maximum total query-complexity score (default: 100)
+    pub max_complexity: u64,
+    /// Weights used to score query complexity
+    pub complexity_weights: ComplexityWeights,
 }
 
 impl Default for ODataLimits {
@@ -30,8 +39,11 @@ impl Default for ODataLimits {
             max_top: 1000,
             max_orderby_fields: 5,
             max_filter_length: 2000,
+            max_in_list_len: 100,
             require_signed_cursors: false,
             cursor_hmac_key: None,
+            max_complexity: 100,
+            complexity_weights: ComplexityWeights::default(),
         }
     }
 }
@@ -60,6 +72,24 @@ impl ODataLimits {
         self
     }
 
+    /// Set maximum number of values in an `in (...)` list
+    pub fn with_max_in_list_len(mut self, max: usize) -> Self {
+        self.max_in_list_len = max;
+        self
+    }
+
+    /// Set maximum total query-complexity score
+    pub fn with_max_complexity(mut self, max: u64) -> Self {
+        self.max_complexity = max;
+        self
+    }
+
+    /// Set the weights used to score query complexity
+    pub fn with_complexity_weights(mut self, weights: ComplexityWeights) -> Self {
+        self.complexity_weights = weights;
+        self
+    }
+
     /// Enable HMAC-signed cursors with the given key
     pub fn with_signed_cursors(mut self, key: Vec<u8>) -> Self {
         self.require_signed_cursors = true;
@@ -78,6 +108,27 @@ impl ODataLimits {
         Ok(())
     }
 
+    /// Parse a `$top` query parameter: a positive integer of at most `max_top`.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidTop` if `raw` is not such an integer.
+    pub fn parse_top(&self, raw: &str) -> Result<u64, Error> {
+        let top: u64 = raw
+            .trim()
+            .parse()
+            .map_err(|_| Error::InvalidTop(format!("'{raw}' is not a positive integer")))?;
+        if top == 0 {
+            return Err(Error::InvalidTop("must be positive".into()));
+        }
+        if usize::try_from(top).map_or(true, |top| top > self.max_top) {
+            return Err(Error::InvalidTop(format!(
+                "{top} exceeds the maximum of {}",
+                self.max_top
+            )));
+        }
+        Ok(top)
+    }
+
     /// Validate a $filter expression length.
     ///
     /// # Errors
@@ -92,6 +143,25 @@ impl ODataLimits {
         Ok(())
     }
 
+    /// Validate the value lists of every `in` operator in a parsed filter.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidFilter` if a list holds more than `max_in_list_len` values.
+    pub fn validate_in_lists(&self, filter: &ast::Expr) -> Result<(), Error> {
+        if let Some(len) = filter
+            .in_lists()
+            .iter()
+            .map(|list| list.len())
+            .find(|&len| len > self.max_in_list_len)
+        {
+            return Err(Error::InvalidFilter(format!(
+                "'in' list of {len} values exceeds the maximum of {}",
+                self.max_in_list_len
+            )));
+        }
+        Ok(())
+    }
+
     /// Validate number of $orderby fields.
     ///
     /// # Errors
@@ -105,6 +175,45 @@ impl ODataLimits {
         }
         Ok(())
     }
+
+    /// Validate the total complexity of a parsed query, returning its score.
+    ///
+    /// # Errors
+    /// Returns `Error::QueryTooComplex` if the score exceeds `max_complexity`.
+    pub fn validate_complexity(&self, query: &ODataQuery) -> Result<u64, Error> {
+        let score = query_complexity(query, &self.complexity_weights);
+        if score > self.max_complexity {
+            return Err(Error::QueryTooComplex {
+                score,
+                budget: self.max_complexity,
+            });
+        }
+        Ok(score)
+    }
+}
+
+/// Parse a `$skip` query parameter: a non-negative integer.
+///
+/// # Errors
+/// Returns `Error::InvalidSkip` if `raw` is not such an integer.
+pub fn parse_skip(raw: &str) -> Result<u64, Error> {
+    raw.trim()
+        .parse()
+        .map_err(|_| Error::InvalidSkip(format!("'{raw}' is not a non-negative integer")))
+}
+
+/// Parse a `$count` query parameter: `true` or `false`, case-insensitively.
+///
+/// # Errors
+/// Returns `Error::InvalidCount` for any other value.
+pub fn parse_count(raw: &str) -> Result<bool, Error> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(Error::InvalidCount(format!(
+            "'{raw}' is not a boolean (expected true or false)"
+        ))),
+    }
 }
 
 #[cfg(test)]
@@ -118,6 +227,7 @@ mod tests {
         assert_eq!(limits.max_top, 1000);
         assert_eq!(limits.max_orderby_fields, 5);
         assert_eq!(limits.max_filter_length, 2000);
+        assert_eq!(limits.max_in_list_len, 100);
         assert!(!limits.require_signed_cursors);
     }
 
@@ -134,6 +244,60 @@ mod tests {
         assert!(limits.validate_top(1001).is_err());
     }
 
+    #[test]
+    fn test_parse_top_and_skip() {
+        let limits = ODataLimits::default().with_max_top(50);
+        assert_eq!(limits.parse_top(" 50 ").unwrap(), 50);
+        assert_eq!(parse_skip("0").unwrap(), 0);
+        assert_eq!(parse_skip("120").unwrap(), 120);
+    }
+
+    #[test]
+    fn test_parse_top_over_limit() {
+        let limits = ODataLimits::default().with_max_top(50);
+        let err = limits.parse_top("51").unwrap_err();
+        assert!(matches!(err, Error::InvalidTop(ref m) if m == "51 exceeds the maximum of 50"));
+        assert!(matches!(limits.parse_top("0"), Err(Error::InvalidTop(_))));
+        assert!(matches!(limits.parse_top("ten"), Err(Error::InvalidTop(_))));
+    }
+
+    #[test]
+    fn test_parse_skip_negative() {
+        let err = parse_skip("-5").unwrap_err();
+        assert!(matches!(err, Error::InvalidSkip(ref m) if m.contains("'-5'")));
+        assert!(matches!(parse_skip("1.5"), Err(Error::InvalidSkip(_))));
+    }
+
+    #[test]
+    fn test_parse_count() {
+        assert!(parse_count("true").unwrap());
+        assert!(parse_count(" TRUE ").unwrap());
+        assert!(!parse_count("false").unwrap());
+        let err = parse_count("maybe").unwrap_err();
+        assert!(matches!(err, Error::InvalidCount(ref m) if m.contains("'maybe'")));
+    }
+
+    fn id_in(len: u64) -> ast::Expr {
+        ast::Expr::In(
+            Box::new(ast::Expr::Identifier("id".to_owned())),
+            (0..len)
+                .map(|i| ast::Expr::Value(ast::Value::Number(i.into())))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_validate_in_lists() {
+        let limits = ODataLimits::default().with_max_in_list_len(3);
+        assert!(limits.validate_in_lists(&id_in(3)).is_ok());
+
+        let nested = ast::Expr::Not(Box::new(id_in(4)));
+        let err = limits.validate_in_lists(&nested).unwrap_err();
+        assert!(
+            matches!(err, Error::InvalidFilter(ref m) if m == "'in' list of 4 values exceeds the maximum of 3")
+        );
+    }
+
     #[test]
     fn test_validate_filter_ok() {
         let limits = ODataLimits::default();
@@ -171,4 +335,26 @@ mod tests {
         assert_eq!(limits.max_orderby_fields, 3);
         assert_eq!(limits.max_filter_length, 500);
     }
+
+    #[test]
+    fn test_validate_complexity_within_budget() {
+        let limits = ODataLimits::default();
+        let query = ODataQuery::new().with_limit(50);
+        assert_eq!(limits.validate_complexity(&query).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_validate_complexity_exceeds_budget() {
+        let limits = ODataLimits::new().with_max_complexity(20);
+        let query = ODataQuery::new().with_limit(5000);
+
+        let err = limits.validate_complexity(&query).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::QueryTooComplex {
+                score: 50,
+                budget: 20
+            }
+        ));
+    }
 }
@@ -0,0 +1,375 @@
+//! Logical structure of `$filter` expressions
+//!
+//! `odata_params` chains `and`/`or` right-recursively without precedence, so
+//! `a and b or c` would parse as `a and (b or c)`, and its errors carry no
+//! position. This module parses the logical layer itself (`or` binds looser
+//! than `and`, which binds looser than `not`; parentheses group explicitly) and
+//! hands every operand to `odata_params`. When an operand is rejected, its
+//! tokens are checked against the operand shape, then for malformed date and
+//! time literals, to locate the offending token.
+
+use odata_params::filters::{self as od, Expr};
+
+use crate::Error;
+
+/// A malformed filter: what went wrong, at which byte offset and on which
+/// token (empty at the end of input).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub message: String,
+    pub position: usize,
+    pub token: String,
+}
+
+impl std::fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<SyntaxError> for Error {
+    fn from(e: SyntaxError) -> Self {
+        Error::InvalidFilterAt {
+            message: e.message,
+            position: e.position,
+            token: e.token,
+        }
+    }
+}
+
+/// Parse a raw `$filter` string into an `odata_params` expression.
+///
+/// # Errors
+/// Returns a [`SyntaxError`] locating the first malformed part.
+pub fn parse(raw: &str) -> Result<Expr, SyntaxError> {
+    let mut parser = Parser { src: raw, pos: 0 };
+    let expr = parser.or_expr()?;
+    parser.skip_ws();
+    match parser.rest().chars().next() {
+        None => Ok(expr),
+        Some(')') => Err(parser.error(parser.pos, ")", "unbalanced ')'")),
+        Some(c) => Err(parser.unexpected(c)),
+    }
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.src[self.pos..]
+    }
+
+    fn error(&self, position: usize, token: &str, what: &str) -> SyntaxError {
+        let column = self.src[..position].chars().count() + 1;
+        SyntaxError {
+            message: format!("{what} at column {column}"),
+            position,
+            token: token.to_owned(),
+        }
+    }
+
+    fn unexpected(&self, c: char) -> SyntaxError {
+        self.error(self.pos, &c.to_string(), &format!("unexpected '{c}'"))
+    }
+
+    fn skip_ws(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Whether the logical keyword `keyword` starts at byte `at`.
+    fn keyword_at(&self, at: usize, keyword: &str) -> bool {
+        self.src[at..]
+            .strip_prefix(keyword)
+            .is_some_and(|after| after.starts_with(|c: char| c.is_whitespace() || c == '('))
+    }
+
+    /// Consume `keyword` if it is the next token.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.skip_ws();
+        let matched = self.keyword_at(self.pos, keyword);
+        if matched {
+            self.pos += keyword.len();
+        }
+        matched
+    }
+
+    fn or_expr(&mut self) -> Result<Expr, SyntaxError> {
+        let mut lhs = self.and_expr()?;
+        while self.eat_keyword("or") {
+            let rhs = self.and_expr()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, SyntaxError> {
+        let mut lhs = self.unary()?;
+        while self.eat_keyword("and") {
+            let rhs = self.unary()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, SyntaxError> {
+        if self.eat_keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if !self.rest().starts_with('(') {
+            return self.operand();
+        }
+
+        let open = self.pos;
+        self.pos += 1;
+        let inner = self.or_expr()?;
+        self.skip_ws();
+        match self.rest().chars().next() {
+            Some(')') => {
+                self.pos += 1;
+                Ok(inner)
+            }
+            None => Err(self.error(open, "(", "unclosed '('")),
+            Some(c) => Err(self.unexpected(c)),
+        }
+    }
+
+    /// A comparison, function call or other operand, parsed by `odata_params`.
+    fn operand(&mut self) -> Result<Expr, SyntaxError> {
+        let start = self.pos;
+        let end = self.operand_end()?;
+        let text = self.src[start..end].trim_end();
+        if text.is_empty() {
+            let token = self
+                .rest()
+                .chars()
+                .next()
+                .map(String::from)
+                .unwrap_or_default();
+            return Err(self.error(start, &token, "expected an expression"));
+        }
+        self.pos = end;
+        od::parse_str(crate::rewrite_count_paths(text).as_ref()).map_err(|e| {
+            self.diagnose(start, text)
+                .or_else(|| self.bad_temporal_literal(start, text))
+                .unwrap_or_else(|| self.error(start, text, &format!("{e:?}")))
+        })
+    }
+
+    /// End of the operand at the cursor: the next top-level `and`/`or`, an
+    /// unmatched `)`, or the end of input.
+    fn operand_end(&self) -> Result<usize, SyntaxError> {
+        let mut open = Vec::new();
+        let mut in_string = false;
+        let mut after_ws = false;
+        for (i, c) in self.rest().char_indices() {
+            let at = self.pos + i;
+            match c {
+                '\'' => in_string = !in_string,
+                _ if in_string => {}
+                '(' => open.push(at),
+                ')' if open.pop().is_none() => return Ok(at),
+                _ if open.is_empty()
+                    && after_ws
+                    && (self.keyword_at(at, "and") || self.keyword_at(at, "or")) =>
+                {
+                    return Ok(at);
+                }
+                _ => {}
+            }
+            after_ws = c.is_whitespace();
+        }
+        match open.pop() {
+            Some(at) => Err(self.error(at, "(", "unclosed '('")),
+            None => Ok(self.src.len()),
+        }
+    }
+
+    /// Locate the token breaking the operand shape
+    /// `term [(eq|ne|gt|ge|lt|le) term | in (term, ...)]`, where a term is a
+    /// literal, a field or a function call. `None` when the shape is fine and
+    /// a literal itself was rejected.
+    fn diagnose(&self, start: usize, text: &str) -> Option<SyntaxError> {
+        let tokens = tokenize(text, start);
+        let mut shape = Shape {
+            parser: self,
+            tokens: &tokens,
+            next: 0,
+            end: start + text.len(),
+        };
+        shape.operand().err()
+    }
+
+    /// Locate a date, time or datetime literal of the operand that does not
+    /// denote a real instant (`2024-13-40`) or lacks the RFC 3339 UTC offset.
+    fn bad_temporal_literal(&self, start: usize, text: &str) -> Option<SyntaxError> {
+        tokenize(text, start).into_iter().find_map(|(at, token)| {
+            let what = temporal_kind(token)?;
+            let valid = match what {
+                "datetime" => chrono::DateTime::parse_from_rfc3339(token).is_ok(),
+                "date" => chrono::NaiveDate::parse_from_str(token, "%Y-%m-%d").is_ok(),
+                _ => chrono::NaiveTime::parse_from_str(token, "%H:%M:%S%.f").is_ok(),
+            };
+            (!valid).then(|| self.error(at, token, &format!("invalid {what} literal '{token}'")))
+        })
+    }
+}
+
+/// Which temporal literal `token` looks like: `YYYY-MM` starts a date or
+/// (with a `T` part) a datetime, `HH:MM` starts a time.
+fn temporal_kind(token: &str) -> Option<&'static str> {
+    let b = token.as_bytes();
+    let digits = |range: std::ops::Range<usize>| {
+        b.get(range)
+            .is_some_and(|d| d.iter().all(u8::is_ascii_digit))
+    };
+    if digits(0..4) && b.get(4) == Some(&b'-') && digits(5..7) {
+        Some(if token.contains('T') {
+            "datetime"
+        } else {
+            "date"
+        })
+    } else if digits(0..2) && b.get(2) == Some(&b':') && digits(3..5) {
+        Some("time")
+    } else {
+        None
+    }
+}
+
+/// Lexical token of an operand with its byte offset in the whole filter.
+type Token<'a> = (usize, &'a str);
+
+fn tokenize(text: &str, offset: usize) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let end = match c {
+            _ if c.is_whitespace() => continue,
+            '(' | ')' | ',' => i + 1,
+            '\'' => {
+                // A doubled quote is an escaped quote inside the literal
+                let mut end = text.len();
+                while let Some((j, c)) = chars.next() {
+                    if c == '\'' && chars.next_if(|&(_, c)| c == '\'').is_none() {
+                        end = j + 1;
+                        break;
+                    }
+                }
+                end
+            }
+            _ => {
+                let mut end = text.len();
+                while let Some(&(j, c)) = chars.peek() {
+                    if c.is_whitespace() || "(),'".contains(c) {
+                        end = j;
+                        break;
+                    }
+                    chars.next();
+                }
+                end
+            }
+        };
+        tokens.push((offset + i, &text[i..end]));
+    }
+    tokens
+}
+
+const COMPARISON_OPS: [&str; 6] = ["eq", "ne", "gt", "ge", "lt", "le"];
+
+struct Shape<'p, 't> {
+    parser: &'p Parser<'p>,
+    tokens: &'t [Token<'t>],
+    next: usize,
+    end: usize,
+}
+
+impl<'t> Shape<'_, 't> {
+    fn peek(&self) -> Option<&'t str> {
+        self.tokens.get(self.next).map(|&(_, t)| t)
+    }
+
+    fn bump(&mut self) -> Option<Token<'t>> {
+        let token = self.tokens.get(self.next).copied();
+        self.next += 1;
+        token
+    }
+
+    fn unexpected(&self, (at, token): Token<'_>) -> SyntaxError {
+        self.parser
+            .error(at, token, &format!("unexpected '{token}'"))
+    }
+
+    fn missing(&self, what: &str) -> SyntaxError {
+        self.parser.error(self.end, "", what)
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), SyntaxError> {
+        match self.bump() {
+            Some((_, t)) if t == expected => Ok(()),
+            Some(token) => Err(self.unexpected(token)),
+            None => Err(self.missing(&format!("expected '{expected}'"))),
+        }
+    }
+
+    fn operand(&mut self) -> Result<(), SyntaxError> {
+        self.comparison()?;
+        match self.bump() {
+            None => Ok(()),
+            Some(token) => Err(self.unexpected(token)),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<(), SyntaxError> {
+        self.term(None)?;
+        match self.peek() {
+            Some(op) if COMPARISON_OPS.contains(&op) => {
+                self.next += 1;
+                self.term(Some(op))
+            }
+            Some("in") => {
+                self.next += 1;
+                self.expect("(")?;
+                self.list()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Comma-separated comparisons up to the closing `)`.
+    fn list(&mut self) -> Result<(), SyntaxError> {
+        if self.peek() == Some(")") {
+            self.next += 1;
+            return Ok(());
+        }
+        loop {
+            self.comparison()?;
+            match self.bump() {
+                Some((_, ",")) => {}
+                Some((_, ")")) => return Ok(()),
+                Some(token) => return Err(self.unexpected(token)),
+                None => return Err(self.missing("expected ')'")),
+            }
+        }
+    }
+
+    fn term(&mut self, after: Option<&str>) -> Result<(), SyntaxError> {
+        let Some(token) = self.bump() else {
+            return Err(self.missing(&after.map_or_else(
+                || "expected a value".to_owned(),
+                |op| format!("expected a value after '{op}'"),
+            )));
+        };
+        match token.1 {
+            "(" | ")" | "," => Err(self.unexpected(token)),
+            op if COMPARISON_OPS.contains(&op) || op == "in" => Err(self.unexpected(token)),
+            _ if self.peek() == Some("(") => {
+                self.next += 1;
+                self.list()
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -6,6 +6,10 @@ pub struct PageInfo {
     pub next_cursor: Option<String>,
     pub prev_cursor: Option<String>,
     pub limit: u64,
+    /// Total number of rows matching the filter, across all pages; only
+    /// computed when the query asked for it (`$count=true`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
 }
 
 #[cfg_attr(feature = "with-utoipa", derive(utoipa::ToSchema))]
@@ -31,10 +35,18 @@ impl<T> Page<T> {
                 next_cursor: None,
                 prev_cursor: None,
                 limit,
+                total: None,
             },
         }
     }
 
+    /// Attach the total number of matching rows.
+    #[must_use]
+    pub fn with_total(mut self, total: u64) -> Self {
+        self.page_info.total = Some(total);
+        self
+    }
+
     /// Map items while preserving `page_info` (Domain->DTO mapping convenience)
     pub fn map_items<U>(self, mut f: impl FnMut(T) -> U) -> Page<U> {
         Page {
@@ -43,3 +55,147 @@ impl<T> Page<T> {
         }
     }
 }
+
+/// OData-annotated page body: `{ "@odata.count": n, "value": [...], "@odata.nextLink": "..." }`.
+///
+/// An alternative wire shape to [`Page`] for endpoints serving OData-compliant
+/// clients. `@odata.count` is emitted only when the total is known and
+/// `@odata.nextLink` only when a next page exists.
+#[cfg_attr(feature = "with-utoipa", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ODataPage<T> {
+    #[serde(
+        rename = "@odata.count",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub count: Option<u64>,
+    pub value: Vec<T>,
+    #[serde(
+        rename = "@odata.nextLink",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub next_link: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Convert into the OData-annotated shape.
+    ///
+    /// `base` is the request URL the next link is built on (typically its path
+    /// and query). The link keeps the other query options, such as `$filter`,
+    /// and replaces the paging ones: it carries the page `limit` and the
+    /// percent-encoded next `cursor`, while `$top`, `$skip` and `$orderby` are
+    /// dropped because the cursor already fixes them. `count` is the total
+    /// number of matching items, when requested; it defaults to the page's
+    /// own `total`.
+    #[must_use]
+    pub fn into_odata(self, base: &str, count: Option<u64>) -> ODataPage<T> {
+        let limit = self.page_info.limit;
+        let next_link = self.page_info.next_cursor.map(|cursor| {
+            let (path, query) = base.split_once('?').unwrap_or((base, ""));
+            let mut params: Vec<String> = query
+                .split('&')
+                .filter(|pair| !pair.is_empty() && !is_paging_param(pair))
+                .map(str::to_owned)
+                .collect();
+            params.push(format!("limit={limit}"));
+            params.push(format!("cursor={}", urlencoding::encode(&cursor)));
+            format!("{path}?{}", params.join("&"))
+        });
+        ODataPage {
+            count: count.or(self.page_info.total),
+            value: self.items,
+            next_link,
+        }
+    }
+}
+
+/// Whether a raw `key=value` query pair is one the next link replaces.
+fn is_paging_param(pair: &str) -> bool {
+    let key = pair.split_once('=').map_or(pair, |(key, _)| key);
+    let key = urlencoding::decode(key).unwrap_or(std::borrow::Cow::Borrowed(key));
+    matches!(
+        key.as_ref(),
+        "limit" | "cursor" | "$top" | "$skip" | "$orderby"
+    )
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn page(next_cursor: Option<&str>) -> Page<serde_json::Value> {
+        Page::new(
+            vec![json!({"id": 1}), json!({"id": 2})],
+            PageInfo {
+                next_cursor: next_cursor.map(ToOwned::to_owned),
+                prev_cursor: None,
+                limit: 2,
+                total: None,
+            },
+        )
+    }
+
+    #[test]
+    fn odata_page_uses_canonical_annotations() {
+        let body = serde_json::to_string(&page(Some("abc")).into_odata("/users/v1/users", Some(5)))
+            .unwrap();
+        assert_eq!(
+            body,
+            r#"{"@odata.count":5,"value":[{"id":1},{"id":2}],"@odata.nextLink":"/users/v1/users?limit=2&cursor=abc"}"#
+        );
+    }
+
+    #[test]
+    fn odata_page_omits_absent_annotations() {
+        let body = serde_json::to_value(page(None).into_odata("/users/v1/users", None)).unwrap();
+        assert_eq!(body, json!({"value": [{"id": 1}, {"id": 2}]}));
+    }
+
+    #[test]
+    fn total_is_serialized_only_when_counted() {
+        let body = serde_json::to_value(&page(None).page_info).unwrap();
+        assert_eq!(
+            body,
+            json!({"next_cursor": null, "prev_cursor": null, "limit": 2})
+        );
+
+        let counted = page(None).with_total(7);
+        let body = serde_json::to_value(&counted.page_info).unwrap();
+        assert_eq!(body["total"], 7);
+        assert_eq!(counted.into_odata("/users", None).count, Some(7));
+    }
+
+    #[test]
+    fn next_link_extends_an_existing_query() {
+        let odata = page(Some("abc")).into_odata("/users?$filter=age%20gt%203", None);
+        assert_eq!(
+            odata.next_link.as_deref(),
+            Some("/users?$filter=age%20gt%203&limit=2&cursor=abc")
+        );
+    }
+
+    #[test]
+    fn next_link_replaces_paging_options() {
+        let odata = page(Some("abc")).into_odata(
+            "/users?limit=9&%24top=9&$skip=4&$orderby=name&cursor=old&details=true",
+            None,
+        );
+        assert_eq!(
+            odata.next_link.as_deref(),
+            Some("/users?details=true&limit=2&cursor=abc")
+        );
+    }
+
+    #[test]
+    fn next_link_percent_encodes_the_cursor() {
+        let odata = page(Some("a+b/c=")).into_odata("/users", None);
+        assert_eq!(
+            odata.next_link.as_deref(),
+            Some("/users?limit=2&cursor=a%2Bb%2Fc%3D")
+        );
+    }
+}
@@ -54,6 +54,9 @@ pub fn normalize_filter_for_hash(expr: &ast::Expr) -> String {
             ast::Expr::Identifier(name) => {
                 format!("ID({})", name.to_lowercase())
             }
+            ast::Expr::CollectionCount(name) => {
+                format!("COUNT({})", name.to_lowercase())
+            }
             ast::Expr::Value(value) => match value {
                 ast::Value::Null => "NULL".to_owned(),
                 ast::Value::Bool(b) => format!("BOOL({b})"),
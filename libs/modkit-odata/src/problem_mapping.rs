@@ -4,17 +4,19 @@
 //! without HTTP framework dependencies. The HTTP layer in `modkit` adds
 //! instance paths and trace IDs before the Problem is converted to an HTTP response.
 
-use crate::Error;
 use crate::errors::ErrorCode;
+use crate::{Error, FieldCapability};
 use modkit_errors::problem::Problem;
 
 impl From<Error> for Problem {
     fn from(err: Error) -> Self {
         use Error::{
             CursorInvalidBase64, CursorInvalidDirection, CursorInvalidFields, CursorInvalidJson,
-            CursorInvalidKeys, CursorInvalidVersion, Db, FilterMismatch, InvalidCursor,
-            InvalidFilter, InvalidLimit, InvalidOrderByField, OrderMismatch, OrderWithCursor,
-            ParsingUnavailable,
+            CursorInvalidKeys, CursorInvalidVersion, Db, DeadlineExceeded, FieldNotAllowed,
+            FilterMismatch, InvalidCount, InvalidCursor, InvalidFilter, InvalidFilterAt,
+            InvalidKeyset, InvalidLimit, InvalidOrderByField, InvalidSelect, InvalidSelectField,
+            InvalidSkip, InvalidTop, OrderMismatch, OrderWithCursor, ParsingUnavailable,
+            QueryTooComplex, SkipWithCursor,
         };
 
         match err {
@@ -22,10 +24,35 @@ impl From<Error> for Problem {
             InvalidFilter(msg) => ErrorCode::odata_errors_invalid_filter_v1()
                 .as_problem(format!("Invalid $filter: {msg}")),
 
+            InvalidFilterAt {
+                message,
+                position,
+                token,
+            } => ErrorCode::odata_errors_invalid_filter_v1()
+                .as_problem(format!("Invalid $filter: {message}"))
+                .with_metadata("position", position)
+                .with_metadata("token", token),
+
             // OrderBy parsing and validation errors → 422
             InvalidOrderByField(field) => ErrorCode::odata_errors_invalid_orderby_v1()
                 .as_problem(format!("Unsupported $orderby field: {field}")),
 
+            // Select parsing and validation errors → 422
+            InvalidSelect(msg) => ErrorCode::odata_errors_invalid_select_v1()
+                .as_problem(format!("Invalid $select: {msg}")),
+
+            InvalidSelectField(field) => ErrorCode::odata_errors_invalid_select_v1()
+                .as_problem(format!("Unsupported $select field: {field}")),
+
+            // Declared field used outside its capabilities → 422
+            FieldNotAllowed { field, capability } => match capability {
+                FieldCapability::Filterable => ErrorCode::odata_errors_invalid_filter_v1(),
+                FieldCapability::Sortable => ErrorCode::odata_errors_invalid_orderby_v1(),
+                FieldCapability::Selectable => ErrorCode::odata_errors_invalid_select_v1(),
+            }
+            .as_problem(format!("Field not {capability}: {field}"))
+            .with_metadata("field", field),
+
             // All cursor-related errors → 422
             InvalidCursor
             | CursorInvalidBase64
@@ -51,6 +78,31 @@ impl From<Error> for Problem {
             OrderWithCursor => ErrorCode::odata_errors_invalid_cursor_v1()
                 .as_problem("Cannot specify both $orderby and cursor parameters"),
 
+            // Offset pagination errors → 422
+            InvalidTop(msg) => ErrorCode::odata_errors_invalid_pagination_v1()
+                .as_problem(format!("Invalid $top: {msg}")),
+
+            InvalidSkip(msg) => ErrorCode::odata_errors_invalid_pagination_v1()
+                .as_problem(format!("Invalid $skip: {msg}")),
+
+            SkipWithCursor => ErrorCode::odata_errors_invalid_cursor_v1()
+                .as_problem("Cannot specify both $skip and cursor parameters"),
+
+            InvalidCount(msg) => ErrorCode::odata_errors_invalid_pagination_v1()
+                .as_problem(format!("Invalid $count: {msg}")),
+
+            // Complexity budget exceeded → 400
+            QueryTooComplex { score, budget } => ErrorCode::odata_errors_query_too_complex_v1()
+                .as_problem(format!(
+                    "Query complexity {score} exceeds the allowed budget of {budget}"
+                ))
+                .with_metadata("complexity_score", score)
+                .with_metadata("complexity_budget", budget),
+
+            // Query cancelled at its deadline → 504
+            DeadlineExceeded => ErrorCode::odata_errors_query_timeout_v1()
+                .as_problem("The query did not complete before its deadline"),
+
             // Database errors → 500 (should be caught earlier)
             Db(_msg) => {
                 // Use filter error as safe default for unexpected DB errors
@@ -61,6 +113,10 @@ impl From<Error> for Problem {
             // Configuration errors → 500 (feature not enabled)
             ParsingUnavailable(msg) => ErrorCode::odata_errors_internal_v1()
                 .as_problem(format!("OData parsing unavailable: {msg}")),
+
+            // Misconfigured pagination keyset → 500 (server-side bug)
+            InvalidKeyset(_msg) => ErrorCode::odata_errors_internal_v1()
+                .as_problem("An internal error occurred while processing the OData query"),
         }
     }
 }
@@ -70,6 +126,29 @@ impl From<Error> for Problem {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_located_filter_error_carries_position_and_token() {
+        let err = Error::InvalidFilterAt {
+            message: "expected a value after 'eq' at column 8".to_owned(),
+            position: 7,
+            token: String::new(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "invalid $filter: expected a value after 'eq' at column 8"
+        );
+
+        let problem: Problem = err.into();
+        assert_eq!(problem.title, "Invalid Filter");
+        assert_eq!(
+            problem.detail,
+            "Invalid $filter: expected a value after 'eq' at column 8"
+        );
+        let metadata = problem.metadata.unwrap();
+        assert_eq!(metadata["position"], 7);
+        assert_eq!(metadata["token"], "");
+    }
+
     #[test]
     fn test_filter_error_converts_to_problem() {
         use http::StatusCode;
@@ -97,6 +176,70 @@ mod tests {
         assert!(problem.code.contains("invalid_orderby"));
     }
 
+    #[test]
+    fn test_select_error_converts_to_problem() {
+        use http::StatusCode;
+
+        let err = Error::InvalidSelectField("password".to_owned());
+        let problem: Problem = err.into();
+
+        assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(problem.title, "Invalid Select");
+        assert_eq!(problem.detail, "Unsupported $select field: password");
+        assert!(problem.code.contains("invalid_select"));
+
+        let problem: Problem = Error::InvalidSelect("duplicate field id".to_owned()).into();
+        assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(problem.detail, "Invalid $select: duplicate field id");
+    }
+
+    #[test]
+    fn test_field_not_allowed_converts_to_clause_problem() {
+        use http::StatusCode;
+
+        let problem: Problem = Error::FieldNotAllowed {
+            field: "age".to_owned(),
+            capability: FieldCapability::Sortable,
+        }
+        .into();
+        assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(problem.title, "Invalid OrderBy");
+        assert_eq!(problem.detail, "Field not sortable: age");
+        assert_eq!(problem.metadata.unwrap()["field"], "age");
+
+        let problem: Problem = Error::FieldNotAllowed {
+            field: "created_at".to_owned(),
+            capability: FieldCapability::Filterable,
+        }
+        .into();
+        assert!(problem.code.contains("invalid_filter"));
+    }
+
+    #[test]
+    fn test_pagination_errors_convert_to_problem() {
+        use http::StatusCode;
+
+        let problem: Problem = Error::InvalidTop("5000 exceeds the maximum of 1000".into()).into();
+        assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(problem.title, "Invalid Pagination");
+        assert_eq!(
+            problem.detail,
+            "Invalid $top: 5000 exceeds the maximum of 1000"
+        );
+
+        let problem: Problem =
+            Error::InvalidSkip("'-1' is not a non-negative integer".into()).into();
+        assert!(problem.code.contains("invalid_pagination"));
+
+        let problem: Problem = Error::SkipWithCursor.into();
+        assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(problem.code.contains("invalid_cursor"));
+
+        let problem: Problem = Error::InvalidCount("'maybe' is not a boolean".into()).into();
+        assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(problem.detail, "Invalid $count: 'maybe' is not a boolean");
+    }
+
     #[test]
     fn test_cursor_error_converts_to_problem() {
         use http::StatusCode;
@@ -109,4 +252,31 @@ mod tests {
         assert!(problem.code.contains("odata"));
         assert!(problem.code.contains("invalid_cursor"));
     }
+
+    #[test]
+    fn test_complexity_error_carries_score() {
+        use http::StatusCode;
+
+        let err = Error::QueryTooComplex {
+            score: 180,
+            budget: 100,
+        };
+        let problem: Problem = err.into();
+
+        assert_eq!(problem.status, StatusCode::BAD_REQUEST);
+        assert!(problem.code.contains("query_too_complex"));
+        let metadata = problem.metadata.unwrap();
+        assert_eq!(metadata["complexity_score"], 180);
+        assert_eq!(metadata["complexity_budget"], 100);
+    }
+
+    #[test]
+    fn test_deadline_exceeded_converts_to_gateway_timeout() {
+        use http::StatusCode;
+
+        let problem: Problem = Error::DeadlineExceeded.into();
+
+        assert_eq!(problem.status, StatusCode::GATEWAY_TIMEOUT);
+        assert!(problem.code.contains("query_timeout"));
+    }
 }
@@ -0,0 +1,157 @@
+//! `$select` projection
+//!
+//! A `$select` value lists the fields a client wants back, e.g.
+//! `$select=id,displayName`. [`parse_fields`] checks the syntax shared by every
+//! `$select` (non-empty, bounded, no duplicates); [`Select::parse`] additionally
+//! checks every name against the fields an endpoint allows projecting on. Names
+//! match case-insensitively and are returned as spelled in the allowlist.
+
+use crate::Error;
+
+pub const MAX_SELECT_LEN: usize = 2048;
+pub const MAX_SELECT_FIELDS: usize = 100;
+
+/// Split a comma-separated `$select` value into lowercased field names.
+///
+/// # Errors
+/// Returns `Error::InvalidSelect` when `raw` is empty, too long, lists no
+/// field, lists too many fields or repeats one.
+pub fn parse_fields(raw: &str) -> Result<Vec<String>, Error> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err(Error::InvalidSelect("cannot be empty".into()));
+    }
+    if raw.len() > MAX_SELECT_LEN {
+        return Err(Error::InvalidSelect(format!(
+            "longer than {MAX_SELECT_LEN} bytes"
+        )));
+    }
+
+    let fields: Vec<String> = raw
+        .split(',')
+        .map(|f| f.trim().to_lowercase())
+        .filter(|f| !f.is_empty())
+        .collect();
+
+    if fields.is_empty() {
+        return Err(Error::InvalidSelect(
+            "must contain at least one field".into(),
+        ));
+    }
+    if fields.len() > MAX_SELECT_FIELDS {
+        return Err(Error::InvalidSelect(format!(
+            "more than {MAX_SELECT_FIELDS} fields"
+        )));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    if let Some(field) = fields.iter().find(|f| !seen.insert(f.as_str())) {
+        return Err(Error::InvalidSelect(format!("duplicate field {field}")));
+    }
+
+    Ok(fields)
+}
+
+/// Validated `$select` projection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Select {
+    /// Selected fields in request order, as spelled in the allowlist.
+    pub fields: Vec<String>,
+}
+
+impl Select {
+    /// Parse a comma-separated `$select` value against `allowed`.
+    ///
+    /// A nested path (`address.city`) is allowed when its first segment is.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidSelect` for malformed values (see [`parse_fields`])
+    /// and `Error::InvalidSelectField` naming the first field that is not allowed.
+    pub fn parse(raw: &str, allowed: &[&str]) -> Result<Self, Error> {
+        let fields = parse_fields(raw)?
+            .into_iter()
+            .map(|name| {
+                let (head, rest) = name
+                    .split_once('.')
+                    .map_or((name.as_str(), None), |(h, r)| (h, Some(r)));
+                allowed
+                    .iter()
+                    .find(|a| a.eq_ignore_ascii_case(head))
+                    .map(|field| match rest {
+                        Some(rest) => format!("{field}.{rest}"),
+                        None => (*field).to_owned(),
+                    })
+                    .ok_or_else(|| Error::InvalidSelectField(name.clone()))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { fields })
+    }
+
+    /// Whether `field` is part of the projection.
+    #[must_use]
+    pub fn contains(&self, field: &str) -> bool {
+        self.fields.iter().any(|f| f.eq_ignore_ascii_case(field))
+    }
+}
+
+impl From<Select> for Vec<String> {
+    fn from(select: Select) -> Self {
+        select.fields
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    const ALLOWED: &[&str] = &["id", "displayName", "email"];
+
+    #[test]
+    fn valid_selection_uses_allowlist_spelling() {
+        let select = Select::parse(" ID, displayname ", ALLOWED).unwrap();
+        assert_eq!(select.fields, ["id", "displayName"]);
+        assert!(select.contains("displayName"));
+        assert!(!select.contains("email"));
+    }
+
+    #[test]
+    fn nested_paths_are_allowed_by_their_first_segment() {
+        let select = Select::parse("displayname.first", ALLOWED).unwrap();
+        assert_eq!(select.fields, ["displayName.first"]);
+        assert!(matches!(
+            Select::parse("password.hash", ALLOWED).unwrap_err(),
+            Error::InvalidSelectField(ref f) if f == "password.hash"
+        ));
+    }
+
+    #[test]
+    fn unknown_field_is_named() {
+        let err = Select::parse("id,password", ALLOWED).unwrap_err();
+        assert!(matches!(err, Error::InvalidSelectField(ref f) if f == "password"));
+        assert_eq!(err.to_string(), "unsupported $select field: password");
+    }
+
+    #[test]
+    fn malformed_selections_get_distinct_messages() {
+        let message = |raw: &str| Select::parse(raw, ALLOWED).unwrap_err().to_string();
+        assert_eq!(message(""), "invalid $select: cannot be empty");
+        assert_eq!(
+            message(" , "),
+            "invalid $select: must contain at least one field"
+        );
+        assert_eq!(message("id,Id"), "invalid $select: duplicate field id");
+        assert_eq!(
+            message(&"a".repeat(MAX_SELECT_LEN + 1)),
+            format!("invalid $select: longer than {MAX_SELECT_LEN} bytes")
+        );
+        let many = (0..=MAX_SELECT_FIELDS)
+            .map(|i| format!("f{i}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        assert_eq!(
+            message(&many),
+            format!("invalid $select: more than {MAX_SELECT_FIELDS} fields")
+        );
+    }
+}
@@ -62,7 +62,7 @@ mod tests {
     #[test]
     fn test_cursor_v1_decode_invalid_version() {
         let cursor_data = serde_json::json!({
-            "v": 2,
+            "v": 0,
             "k": ["value"],
             "o": "asc",
             "s": "+field"
@@ -72,6 +72,70 @@ mod tests {
         assert!(matches!(result, Err(Error::CursorInvalidVersion)));
     }
 
+    #[test]
+    fn test_cursor_current_version_round_trip() {
+        let cursor = CursorV1 {
+            k: vec!["42".to_owned()],
+            o: SortDir::Desc,
+            s: "-id".to_owned(),
+            f: Some("abc123".to_owned()),
+            d: "bwd".to_owned(),
+        };
+
+        let token = crate::encode_cursor(&cursor).unwrap();
+        let wire: serde_json::Value =
+            serde_json::from_slice(&base64_url::decode(&token).unwrap()).unwrap();
+        assert_eq!(wire["v"], crate::CURSOR_VERSION);
+
+        let decoded = crate::decode_cursor(&token).unwrap();
+        assert_eq!(decoded.k, cursor.k);
+        assert_eq!(decoded.o, cursor.o);
+        assert_eq!(decoded.s, cursor.s);
+        assert_eq!(decoded.f, cursor.f);
+        assert_eq!(decoded.d, cursor.d);
+    }
+
+    #[test]
+    fn test_cursor_legacy_v1_is_migrated() {
+        assert!(crate::LEGACY_CURSOR_VERSIONS.contains(&1));
+        // v1 tokens may omit the paging direction
+        let cursor_data = serde_json::json!({
+            "v": 1,
+            "k": ["42"],
+            "o": "asc",
+            "s": "+id"
+        });
+        let encoded = base64_url::encode(serde_json::to_vec(&cursor_data).unwrap().as_slice());
+        let decoded = crate::decode_cursor(&encoded).unwrap();
+        assert_eq!(decoded.k, ["42"]);
+        assert_eq!(decoded.d, "fwd");
+    }
+
+    #[test]
+    fn test_cursor_current_version_requires_direction() {
+        let cursor_data = serde_json::json!({
+            "v": crate::CURSOR_VERSION,
+            "k": ["42"],
+            "o": "asc",
+            "s": "+id"
+        });
+        let encoded = base64_url::encode(serde_json::to_vec(&cursor_data).unwrap().as_slice());
+        let result = crate::decode_cursor(&encoded);
+        assert!(matches!(result, Err(Error::CursorInvalidDirection)));
+    }
+
+    #[test]
+    fn test_cursor_future_version_is_rejected() {
+        // A future format may change the shape entirely; the version decides
+        let cursor_data = serde_json::json!({
+            "v": crate::CURSOR_VERSION + 1,
+            "keys": [{"id": 42}]
+        });
+        let encoded = base64_url::encode(serde_json::to_vec(&cursor_data).unwrap().as_slice());
+        let result = crate::decode_cursor(&encoded);
+        assert!(matches!(result, Err(Error::CursorInvalidVersion)));
+    }
+
     #[test]
     fn test_cursor_v1_decode_empty_keys() {
         let cursor_data = serde_json::json!({
@@ -111,6 +175,74 @@ mod tests {
         assert!(matches!(result, Err(Error::CursorInvalidDirection)));
     }
 
+    fn created_desc_id_asc() -> ODataOrderBy {
+        ODataOrderBy(vec![
+            OrderKey {
+                field: "created_at".to_owned(),
+                dir: SortDir::Desc,
+            },
+            OrderKey {
+                field: "id".to_owned(),
+                dir: SortDir::Asc,
+            },
+        ])
+    }
+
+    #[test]
+    fn test_cursor_v1_two_key_mixed_direction_round_trip() {
+        let order = created_desc_id_asc();
+        let cursor = CursorV1 {
+            k: vec![
+                "2023-11-14T12:00:00Z".to_owned(),
+                "123e4567-e89b-12d3-a456-426614174000".to_owned(),
+            ],
+            o: SortDir::Desc,
+            s: order.to_signed_tokens(),
+            f: None,
+            d: "fwd".to_owned(),
+        };
+
+        let decoded = CursorV1::decode(&cursor.encode().unwrap()).unwrap();
+
+        assert_eq!(decoded.k, cursor.k);
+        assert_eq!(
+            decoded.order().unwrap().to_signed_tokens(),
+            "-created_at,+id"
+        );
+        assert!(crate::validate_cursor_against(&decoded, &order, None).is_ok());
+    }
+
+    #[test]
+    fn test_cursor_v1_rejects_orderby_changed_mid_pagination() {
+        let cursor = CursorV1 {
+            k: vec!["2023-11-14T12:00:00Z".to_owned(), "42".to_owned()],
+            o: SortDir::Desc,
+            s: created_desc_id_asc().to_signed_tokens(),
+            f: None,
+            d: "fwd".to_owned(),
+        };
+        let cursor = CursorV1::decode(&cursor.encode().unwrap()).unwrap();
+
+        // Other sort keys
+        let by_name = ODataOrderBy::from_signed_tokens("+name,+id").unwrap();
+        assert!(matches!(
+            crate::validate_cursor_against(&cursor, &by_name, None),
+            Err(Error::OrderMismatch)
+        ));
+        // Same keys, tiebreaker dropped
+        let created_only = ODataOrderBy::from_signed_tokens("-created_at").unwrap();
+        assert!(matches!(
+            crate::validate_cursor_against(&cursor, &created_only, None),
+            Err(Error::OrderMismatch)
+        ));
+        // Same keys, one direction flipped
+        let flipped = ODataOrderBy::from_signed_tokens("+created_at,+id").unwrap();
+        assert!(matches!(
+            crate::validate_cursor_against(&cursor, &flipped, None),
+            Err(Error::CursorInvalidDirection)
+        ));
+    }
+
     #[test]
     fn test_odata_order_by_to_signed_tokens() {
         let order = ODataOrderBy(vec![
@@ -47,4 +47,282 @@ mod tests {
             panic!("expected In()");
         }
     }
+
+    #[test]
+    fn parses_collection_count() {
+        let parsed =
+            modkit_odata::parse_filter_string("roles/$count gt 1 and name ne 'a/$count'").unwrap();
+        let Expr::And(lhs, rhs) = parsed.as_expr() else {
+            panic!("expected And()");
+        };
+        let Expr::Compare(l, op, r) = &**lhs else {
+            panic!("expected Compare()");
+        };
+        assert!(matches!(&**l, Expr::CollectionCount(name) if name == "roles"));
+        assert!(matches!(op, CompareOperator::Gt));
+        assert!(matches!(**r, Expr::Value(Value::Number(_))));
+
+        // Inside a string literal `/$count` is plain text.
+        let Expr::Compare(_, _, r) = &**rhs else {
+            panic!("expected Compare()");
+        };
+        assert!(matches!(&**r, Expr::Value(Value::String(s)) if s == "a/$count"));
+    }
+
+    fn compare_field(e: &Expr) -> &str {
+        match e {
+            Expr::Compare(l, CompareOperator::Eq, _) => match &**l {
+                Expr::Identifier(name) => name,
+                other => panic!("expected Identifier(), got {other:?}"),
+            },
+            other => panic!("expected Compare(), got {other:?}"),
+        }
+    }
+
+    fn parse(raw: &str) -> Expr {
+        modkit_odata::parse_filter_string(raw).unwrap().into_expr()
+    }
+
+    fn parse_err(raw: &str) -> String {
+        match modkit_odata::parse_filter_string(raw).unwrap_err() {
+            modkit_odata::Error::InvalidFilter(msg)
+            | modkit_odata::Error::InvalidFilterAt { message: msg, .. } => msg,
+            other => panic!("expected InvalidFilter, got {other:?}"),
+        }
+    }
+
+    fn location(raw: &str) -> (usize, String) {
+        match modkit_odata::parse_filter_string(raw).unwrap_err() {
+            modkit_odata::Error::InvalidFilterAt {
+                position, token, ..
+            } => (position, token),
+            other => panic!("expected InvalidFilterAt, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_conjunction() {
+        let Expr::And(lhs, rhs) = parse("aa eq 1 and bb eq 2") else {
+            panic!("expected And()");
+        };
+        assert_eq!(compare_field(&lhs), "aa");
+        assert_eq!(compare_field(&rhs), "bb");
+    }
+
+    #[test]
+    fn parentheses_group_before_and() {
+        let Expr::And(lhs, rhs) = parse("(aa eq 1 or bb eq 2) and cc eq 3") else {
+            panic!("expected And()");
+        };
+        let Expr::Or(a, b) = *lhs else {
+            panic!("expected Or()");
+        };
+        assert_eq!(compare_field(&a), "aa");
+        assert_eq!(compare_field(&b), "bb");
+        assert_eq!(compare_field(&rhs), "cc");
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let Expr::Or(lhs, rhs) = parse("aa eq 1 and bb eq 2 or cc eq 3") else {
+            panic!("expected Or()");
+        };
+        assert!(matches!(*lhs, Expr::And(_, _)));
+        assert_eq!(compare_field(&rhs), "cc");
+
+        let Expr::Or(lhs, rhs) = parse("aa eq 1 or bb eq 2 and cc eq 3") else {
+            panic!("expected Or()");
+        };
+        assert_eq!(compare_field(&lhs), "aa");
+        assert!(matches!(*rhs, Expr::And(_, _)));
+    }
+
+    #[test]
+    fn not_applies_to_the_next_operand() {
+        let Expr::And(lhs, _) = parse("not contains(name,'x and y') and (age gt 1)") else {
+            panic!("expected And()");
+        };
+        assert!(matches!(*lhs, Expr::Not(_)));
+    }
+
+    #[test]
+    fn malformed_grouping_reports_column() {
+        assert_eq!(
+            parse_err("(aa eq 1 or bb eq 2 and cc eq 3"),
+            "unclosed '(' at column 1"
+        );
+        assert_eq!(
+            parse_err("aa eq 1) or bb eq ')'"),
+            "unbalanced ')' at column 8"
+        );
+        assert_eq!(
+            parse_err("aa eq 1 and contains(name, 'x'"),
+            "unclosed '(' at column 21"
+        );
+        assert_eq!(
+            parse_err("aa eq 1 and "),
+            "expected an expression at column 13"
+        );
+        assert_eq!(
+            parse_err("aa eq 1 and bb eq"),
+            "expected a value after 'eq' at column 18"
+        );
+    }
+
+    #[test]
+    fn syntax_errors_carry_position_and_token() {
+        assert_eq!(location("name eq"), (7, String::new()));
+        assert_eq!(
+            parse_err("name eq"),
+            "expected a value after 'eq' at column 8"
+        );
+
+        assert_eq!(
+            location("name eq 'x' and age gte 3"),
+            (20, "gte".to_owned())
+        );
+        assert_eq!(location("contains(name 'x')"), (14, "'x'".to_owned()));
+        assert_eq!(location("id in (1, 2"), (6, "(".to_owned()));
+        assert_eq!(location("age gt 1)"), (8, ")".to_owned()));
+
+        // A well-shaped operand with a bad literal points at the operand.
+        assert_eq!(
+            location("aa eq 1 and id eq 1234-x"),
+            (12, "id eq 1234-x".to_owned())
+        );
+    }
+
+    fn in_list(raw: &str) -> Vec<Value> {
+        let Expr::In(field, list) = parse(raw) else {
+            panic!("expected In()");
+        };
+        assert!(matches!(*field, Expr::Identifier(ref name) if name == "id"));
+        list.into_iter()
+            .map(|e| match e {
+                Expr::Value(v) => v,
+                other => panic!("expected Value(), got {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_numeric_in_list() {
+        let values = in_list("id in (1, 2, 3)");
+        assert_eq!(values.len(), 3);
+        assert!(values.iter().all(|v| matches!(v, Value::Number(_))));
+    }
+
+    #[test]
+    fn parses_string_in_list() {
+        let values = in_list("id in ('a', 'b,c')");
+        assert!(
+            matches!(&values[..], [Value::String(a), Value::String(b)] if a == "a" && b == "b,c")
+        );
+    }
+
+    #[test]
+    fn rejects_empty_in_list() {
+        assert_eq!(parse_err("id in ()"), "'in' requires at least one value");
+        assert_eq!(
+            parse_err("name eq 'x' or not (id in ())"),
+            "'in' requires at least one value"
+        );
+    }
+
+    #[test]
+    fn parses_null_equality() {
+        for (raw, expected) in [
+            ("name eq null", CompareOperator::Eq),
+            ("name ne null", CompareOperator::Ne),
+        ] {
+            let Expr::Compare(field, op, value) = parse(raw) else {
+                panic!("expected Compare() for {raw}");
+            };
+            assert!(matches!(*field, Expr::Identifier(ref name) if name == "name"));
+            assert_eq!(op, expected);
+            assert!(matches!(*value, Expr::Value(Value::Null)), "{raw}");
+        }
+    }
+
+    #[test]
+    fn rejects_ordered_comparison_with_null() {
+        assert_eq!(
+            parse_err("name gt null"),
+            "'gt' cannot compare with null; use 'eq null' or 'ne null'"
+        );
+        assert_eq!(
+            parse_err("id eq 1 and not (null le score)"),
+            "'le' cannot compare with null; use 'eq null' or 'ne null'"
+        );
+    }
+
+    fn datetime(e: &Expr, expected_op: CompareOperator) -> String {
+        let Expr::Compare(field, op, value) = e else {
+            panic!("expected Compare(), got {e:?}");
+        };
+        assert!(matches!(&**field, Expr::Identifier(name) if name == "created_at"));
+        assert_eq!(*op, expected_op);
+        match &**value {
+            Expr::Value(Value::DateTime(dt)) => dt.to_rfc3339(),
+            other => panic!("expected DateTime, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_datetime_comparison() {
+        let expr = parse("created_at ge 2024-01-01T00:00:00Z");
+        assert_eq!(
+            datetime(&expr, CompareOperator::Ge),
+            "2024-01-01T00:00:00+00:00"
+        );
+
+        // Offsets are normalized to UTC
+        let expr = parse("created_at ge 2024-01-01T02:00:00+02:00");
+        assert_eq!(
+            datetime(&expr, CompareOperator::Ge),
+            "2024-01-01T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn parses_datetime_range() {
+        let Expr::And(from, to) =
+            parse("created_at ge 2024-01-01T00:00:00Z and created_at le 2024-01-31T23:59:59.5Z")
+        else {
+            panic!("expected And()");
+        };
+        assert_eq!(
+            datetime(&from, CompareOperator::Ge),
+            "2024-01-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            datetime(&to, CompareOperator::Le),
+            "2024-01-31T23:59:59.500+00:00"
+        );
+    }
+
+    #[test]
+    fn malformed_datetime_names_the_literal() {
+        assert_eq!(
+            parse_err("created_at ge 2024-13-40T00:00:00Z"),
+            "invalid datetime literal '2024-13-40T00:00:00Z' at column 15"
+        );
+        assert_eq!(
+            location("aa eq 1 and created_at lt 2024-01-01T00:00:00"),
+            (26, "2024-01-01T00:00:00".to_owned())
+        );
+        assert_eq!(
+            parse_err("created_at ge 2024-13-40"),
+            "invalid date literal '2024-13-40' at column 15"
+        );
+        assert_eq!(
+            parse_err("opens_at lt 25:00:00"),
+            "invalid time literal '25:00:00' at column 13"
+        );
+    }
+
+    #[test]
+    fn rejects_dangling_count_segment() {
+        assert!(modkit_odata::parse_filter_string("/$count gt 1").is_err());
+    }
 }
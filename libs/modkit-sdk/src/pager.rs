@@ -378,6 +378,7 @@ mod tests {
                 next_cursor: Some(encoded_cursor.clone()),
                 prev_cursor: None,
                 limit: 2,
+                total: None,
             },
         );
 
@@ -396,6 +397,7 @@ mod tests {
                 next_cursor: None,
                 prev_cursor: Some(encoded_cursor),
                 limit: 2,
+                total: None,
             },
         );
 
@@ -427,6 +429,7 @@ mod tests {
                 next_cursor: None,
                 prev_cursor: None,
                 limit: 10,
+                total: None,
             },
         );
 
@@ -465,6 +468,7 @@ mod tests {
                 next_cursor: Some(encoded_cursor),
                 prev_cursor: None,
                 limit: 1,
+                total: None,
             },
         );
 
@@ -518,6 +522,7 @@ mod tests {
                 next_cursor: Some(encoded_cursor.clone()),
                 prev_cursor: None,
                 limit: 2,
+                total: None,
             },
         );
 
@@ -530,6 +535,7 @@ mod tests {
                 next_cursor: None,
                 prev_cursor: Some(encoded_cursor),
                 limit: 2,
+                total: None,
             },
         );
 
@@ -564,6 +570,7 @@ mod tests {
                 next_cursor: None,
                 prev_cursor: None,
                 limit: 10,
+                total: None,
             },
         );
 
@@ -592,6 +599,7 @@ mod tests {
                 next_cursor: Some("invalid_cursor_string".to_owned()),
                 prev_cursor: None,
                 limit: 1,
+                total: None,
             },
         );
 
@@ -628,6 +636,7 @@ mod tests {
                 next_cursor: Some("invalid_cursor_string".to_owned()),
                 prev_cursor: None,
                 limit: 1,
+                total: None,
             },
         );
 
@@ -675,6 +684,7 @@ mod tests {
                 next_cursor: Some(encoded_cursor),
                 prev_cursor: None,
                 limit: 1,
+                total: None,
             },
         );
 
@@ -4,9 +4,15 @@
 //! and module errors into consistent RFC 9457 Problem+JSON responses, eliminating
 //! per-route boilerplate.
 
-use axum::{extract::Request, http::HeaderMap, middleware::Next, response::Response};
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue, header},
+    middleware::Next,
+    response::Response,
+};
 use http::StatusCode;
 use std::any::Any;
+use std::sync::RwLock;
 
 use crate::api::problem::Problem;
 use crate::config::ConfigError;
@@ -14,23 +20,216 @@ use modkit_odata::Error as ODataError;
 
 /// Middleware function that provides centralized error mapping
 ///
-/// This middleware can be applied to routes to automatically extract request context
-/// and provide it to error handlers. The actual error conversion happens in the
-/// `IntoProblem` trait implementations and `map_error_to_problem` function.
+/// Handlers convert their own errors through the `IntoProblem` trait or
+/// `map_error_to_problem`. Any 4xx/5xx response that still is not
+/// `application/problem+json` (an extractor rejection, a bare status code, a
+/// router fallback) is rewritten here into a Problem for its status, carrying
+/// the request path as instance and the request's trace id. Its other headers
+/// are kept. A short plain-text body of a 4xx response becomes the detail;
+/// 5xx bodies are never echoed back. Problem responses pass through untouched.
 pub async fn error_mapping_middleware(request: Request, next: Next) -> Response {
-    let _uri = request.uri().clone();
-    let _headers = request.headers().clone();
+    let instance = request.uri().path().to_owned();
+    let trace_id = extract_trace_id(request.headers());
 
     let response = next.run(request).await;
 
-    // If the response is already successful or is already a Problem response, pass it through
-    if response.status().is_success() || is_problem_response(&response) {
+    let status = response.status();
+    if !(status.is_client_error() || status.is_server_error()) || is_problem_response(&response) {
         return response;
     }
 
-    // For error responses, the actual error conversion should happen in the handlers
-    // using the IntoProblem trait or map_error_to_problem function
-    // This middleware provides the infrastructure for extracting request context
+    let (parts, body) = response.into_parts();
+    let detail = if status.is_client_error() {
+        plain_text_detail(&parts.headers, body).await
+    } else {
+        None
+    };
+    let mut problem = status_problem(status, detail).with_instance(instance);
+    if let Some(trace_id) = trace_id {
+        problem = problem.with_trace_id(trace_id);
+    }
+
+    let mut converted = axum::response::IntoResponse::into_response(problem);
+    for (name, value) in &parts.headers {
+        if *name != header::CONTENT_TYPE
+            && *name != header::CONTENT_LENGTH
+            && !converted.headers().contains_key(name)
+        {
+            converted.headers_mut().append(name, value.clone());
+        }
+    }
+    converted
+}
+
+/// Longest plain-text error body reused as a problem detail.
+const MAX_TEXT_DETAIL_LEN: usize = 512;
+
+/// The body of a short `text/plain` response, e.g. an extractor rejection.
+async fn plain_text_detail(headers: &HeaderMap, body: axum::body::Body) -> Option<String> {
+    let is_text = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/plain"));
+    if !is_text {
+        return None;
+    }
+    let bytes = axum::body::to_bytes(body, MAX_TEXT_DETAIL_LEN).await.ok()?;
+    let text = String::from_utf8(bytes.to_vec()).ok()?;
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_owned())
+}
+
+/// Problem for a bare error status: the `FRAMEWORK_ERRORS` definition with
+/// that status where one exists, otherwise the canonical reason with a
+/// matching code.
+fn status_problem(status: StatusCode, detail: Option<String>) -> Problem {
+    let reason = status.canonical_reason().unwrap_or("Error");
+    let detail = detail.unwrap_or_else(|| format!("{reason} (HTTP {})", status.as_u16()));
+    match modkit_errors::catalog::FRAMEWORK_ERRORS
+        .iter()
+        .find(|def| def.status == status.as_u16())
+    {
+        Some(def) => def.as_problem(detail),
+        None => Problem::new(status, reason, detail).with_code(
+            reason
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_uppercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect::<String>(),
+        ),
+    }
+}
+
+/// Headers a cross-origin client needs to read on error responses.
+const EXPOSED_ERROR_HEADERS: [axum::http::HeaderName; 2] = [
+    modkit_errors::headers::TRACE_ID,
+    modkit_errors::headers::ERROR_CODE,
+];
+
+/// Middleware letting browser clients read correlation headers on failures.
+///
+/// For cross-origin requests (carrying `Origin`) answered with a 4xx/5xx, adds
+/// `x-trace-id` and `x-error-code` to `Access-Control-Expose-Headers`, keeping
+/// any names already exposed (e.g. by the CORS layer). Must run outside CORS.
+pub async fn expose_error_headers_middleware(request: Request, next: Next) -> Response {
+    let cross_origin = request.headers().contains_key(header::ORIGIN);
+    let mut response = next.run(request).await;
+
+    let status = response.status();
+    if cross_origin && (status.is_client_error() || status.is_server_error()) {
+        let headers = response.headers_mut();
+        let mut exposed: Vec<String> = headers
+            .get_all(header::ACCESS_CONTROL_EXPOSE_HEADERS)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(|name| name.trim().to_owned())
+            .filter(|name| !name.is_empty())
+            .collect();
+        if exposed.iter().any(|name| name == "*") {
+            return response;
+        }
+        for name in EXPOSED_ERROR_HEADERS {
+            if !exposed
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(name.as_str()))
+            {
+                exposed.push(name.as_str().to_owned());
+            }
+        }
+        if let Ok(value) = HeaderValue::from_str(&exposed.join(", ")) {
+            headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, value);
+        }
+    }
+    response
+}
+
+/// Middleware hiding experimental error types from clients that did not opt in.
+///
+/// A problem with a fallback (see `ErrDef::fallback`) is answered as is when
+/// the request sends `X-Accept-Experimental-Errors: true` (or `1`), and as its
+/// stable fallback otherwise.
+pub async fn experimental_errors_middleware(request: Request, next: Next) -> Response {
+    let accepts_experimental = request
+        .headers()
+        .get(modkit_errors::headers::ACCEPT_EXPERIMENTAL_ERRORS)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.trim() == "1" || v.trim().eq_ignore_ascii_case("true"));
+
+    let mut response = next.run(request).await;
+    match response
+        .extensions_mut()
+        .remove::<modkit_errors::problem::FallbackProblem>()
+    {
+        Some(fallback) if !accepts_experimental => replace_problem(response, fallback.0),
+        _ => response,
+    }
+}
+
+/// Swap the problem a response carries for `problem`, in place.
+///
+/// Status, body and the headers the new problem renders replace the old
+/// ones; any other header set along the way (cookies, CORS, caching) is kept,
+/// except `Content-Length`, which no longer matches the body.
+fn replace_problem(response: Response, problem: Problem) -> Response {
+    let (mut parts, _) = response.into_parts();
+    let (rendered, body) = axum::response::IntoResponse::into_response(problem).into_parts();
+    parts.status = rendered.status;
+    parts.headers.remove(header::CONTENT_LENGTH);
+    for name in rendered.headers.keys() {
+        parts.headers.remove(name);
+        for value in rendered.headers.get_all(name) {
+            parts.headers.append(name.clone(), value.clone());
+        }
+    }
+    parts.extensions.extend(rendered.extensions);
+    Response::from_parts(parts, body)
+}
+
+/// Middleware serving localized problem titles.
+///
+/// A problem with localized titles (see `ErrDef::titles`) is re-rendered in
+/// the best language of the request's `Accept-Language`, keeping the default
+/// title when none matches (see `Problem::localized`). Requests without the
+/// header get the default title. Either way such a response varies by
+/// `Accept-Language`, and says so to caches.
+pub async fn problem_language_middleware(request: Request, next: Next) -> Response {
+    let accept_language = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_owned);
+
+    let mut response = next.run(request).await;
+    let Some(localizable) = response
+        .extensions_mut()
+        .remove::<modkit_errors::problem::LocalizableProblem>()
+    else {
+        return response;
+    };
+    let mut response = match accept_language {
+        Some(accept_language) => {
+            replace_problem(response, localizable.0.localized(&accept_language))
+        }
+        None => response,
+    };
+    let varies = response
+        .headers()
+        .get_all(header::VARY)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|name| name.trim().eq_ignore_ascii_case("accept-language"));
+    if !varies {
+        response
+            .headers_mut()
+            .append(header::VARY, HeaderValue::from_static("accept-language"));
+    }
     response
 }
 
@@ -40,114 +239,272 @@ fn is_problem_response(response: &Response) -> bool {
         .headers()
         .get(axum::http::header::CONTENT_TYPE)
         .and_then(|v| v.to_str().ok())
-        .is_some_and(|ct| ct.contains("application/problem+json"))
+        .is_some_and(|ct| ct.contains(modkit_errors::headers::APPLICATION_PROBLEM_JSON))
 }
 
 /// Extract trace ID from headers or generate one
+///
+/// `x-trace-id` wins over `x-request-id`, which wins over the trace-id field
+/// of a W3C `traceparent`. A malformed `traceparent` is ignored.
 pub fn extract_trace_id(headers: &HeaderMap) -> Option<String> {
-    // Try to get trace ID from various common headers
-    headers
-        .get("x-trace-id")
-        .or_else(|| headers.get("x-request-id"))
-        .or_else(|| headers.get("traceparent"))
-        .and_then(|v| v.to_str().ok())
+    let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+    header(modkit_errors::headers::TRACE_ID)
+        .or_else(|| header(modkit_errors::headers::REQUEST_ID))
+        .or_else(|| header(modkit_errors::headers::TRACEPARENT).and_then(traceparent_trace_id))
         .map(ToString::to_string)
-        .or_else(|| {
-            // Try to get from current tracing span
-            tracing::Span::current()
-                .id()
-                .map(|id| id.into_u64().to_string())
-        })
+        .or_else(crate::api::trace_layer::current_trace_id)
+}
+
+/// Trace-id field of a W3C `traceparent` (`version-traceid-parentid-flags`).
+///
+/// Version `00` has exactly four fields; later versions may append more.
+/// Version `ff` and all-zero trace or parent ids are invalid.
+fn traceparent_trace_id(traceparent: &str) -> Option<&str> {
+    let is_hex = |field: &str, len: usize| {
+        field.len() == len
+            && field
+                .bytes()
+                .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    };
+    let is_id = |field: &str, len: usize| is_hex(field, len) && field.bytes().any(|b| b != b'0');
+
+    let fields: Vec<&str> = traceparent.trim().split('-').collect();
+    let [version, trace_id, parent_id, flags, rest @ ..] = fields.as_slice() else {
+        return None;
+    };
+    let valid = is_hex(version, 2)
+        && *version != "ff"
+        && (*version != "00" || rest.is_empty())
+        && is_id(trace_id, 32)
+        && is_id(parent_id, 16)
+        && is_hex(flags, 2);
+    valid.then_some(*trace_id)
+}
+
+/// Trace id of the current request, stored in the request extensions by
+/// [`trace_id_middleware`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestTraceId(pub String);
+
+/// Fresh trace id: 32 lowercase hex digits, the width of an OpenTelemetry
+/// trace id.
+#[must_use]
+pub fn generate_trace_id() -> String {
+    uuid::Uuid::now_v7().simple().to_string()
+}
+
+/// Middleware guaranteeing every request a trace id.
+///
+/// Reuses the id found by [`extract_trace_id`] when it is valid, and otherwise
+/// generates one and sets it as the request's `x-trace-id`, so inner layers
+/// building Problems pick it up. The id is stored as [`RequestTraceId`] and
+/// echoed in the response's `x-trace-id` unless the response already has one.
+pub async fn trace_id_middleware(mut request: Request, next: Next) -> Response {
+    use modkit_errors::problem::validate_trace_id;
+
+    let trace_id = match extract_trace_id(request.headers()) {
+        Some(id) if validate_trace_id(&id).is_ok() => id,
+        _ => {
+            let id = generate_trace_id();
+            if let Ok(value) = HeaderValue::from_str(&id) {
+                request
+                    .headers_mut()
+                    .insert(modkit_errors::headers::TRACE_ID, value);
+            }
+            id
+        }
+    };
+    request
+        .extensions_mut()
+        .insert(RequestTraceId(trace_id.clone()));
+
+    let mut response = next.run(request).await;
+    if !response
+        .headers()
+        .contains_key(modkit_errors::headers::TRACE_ID)
+        && let Ok(value) = HeaderValue::from_str(&trace_id)
+    {
+        response
+            .headers_mut()
+            .insert(modkit_errors::headers::TRACE_ID, value);
+    }
+    response
 }
 
 /// Centralized error mapping function
 ///
 /// This function provides a single place to convert all framework and module errors
 /// into consistent Problem responses with proper trace IDs and instance paths.
+/// The built-in handlers and those added with [`register_error_handler`] are
+/// tried in order; errors none of them recognizes map to `UNKNOWN_ERROR`.
+/// Server errors (5xx) are forwarded to the registered `ProblemReporter`.
 pub fn map_error_to_problem(error: &dyn Any, instance: &str, trace_id: Option<String>) -> Problem {
-    // Try to downcast to known error types
-    if let Some(odata_err) = error.downcast_ref::<ODataError>() {
-        return crate::api::odata::error::odata_error_to_problem(odata_err, instance, trace_id);
+    let problem = build_problem(error, instance, trace_id);
+    if !problem.suppress_log {
+        log_source_error(error, &problem);
     }
+    modkit_errors::report_problem(
+        &problem,
+        &modkit_errors::ReportContext {
+            instance: instance.to_owned(),
+            trace_id: problem.trace_id.clone(),
+        },
+    );
+    problem
+}
 
-    if let Some(config_err) = error.downcast_ref::<ConfigError>() {
-        let mut problem = match config_err {
-            ConfigError::ModuleNotFound { module } => Problem::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Configuration Error",
-                format!("Module '{module}' configuration not found"),
-            )
-            .with_code("CONFIG_MODULE_NOT_FOUND")
-            .with_type("https://errors.example.com/CONFIG_MODULE_NOT_FOUND"),
+/// Log the error a problem was mapped from. Done here rather than in the
+/// handlers so that a problem marked with `with_log_suppressed` stays silent.
+fn log_source_error(error: &dyn Any, problem: &Problem) {
+    if let Some(anyhow_err) = error.downcast_ref::<anyhow::Error>() {
+        tracing::error!(error = %anyhow_err, "Internal server error");
+        return;
+    }
+    #[cfg(feature = "sqlx")]
+    if let Some(sqlx_err) = error.downcast_ref::<sqlx::Error>() {
+        match problem.status {
+            StatusCode::NOT_FOUND => tracing::debug!(error = %sqlx_err, "Database row not found"),
+            StatusCode::CONFLICT => {
+                tracing::warn!(error = %sqlx_err, "Unique constraint violation");
+            }
+            _ => tracing::error!(error = %sqlx_err, "Database error"),
+        }
+        return;
+    }
+    if problem.code == UNKNOWN_ERROR_CODE {
+        tracing::error!("Unknown error type in error mapping layer");
+    }
+}
 
-            ConfigError::InvalidModuleStructure { module } => Problem::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Configuration Error",
-                format!("Module '{module}' has invalid configuration structure"),
-            )
-            .with_code("CONFIG_INVALID_STRUCTURE")
-            .with_type("https://errors.example.com/CONFIG_INVALID_STRUCTURE"),
+/// Converts an error of a type it recognizes into a Problem, `None` for any
+/// other type. Instance and trace id are attached by `map_error_to_problem`.
+pub type ErrorHandler = fn(&dyn Any) -> Option<Problem>;
 
-            ConfigError::MissingConfigSection { module } => Problem::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Configuration Error",
-                format!("Module '{module}' is missing required config section"),
-            )
-            .with_code("CONFIG_MISSING_SECTION")
-            .with_type("https://errors.example.com/CONFIG_MISSING_SECTION"),
+/// Handlers for the framework's own error types, always tried first.
+const BUILTIN_ERROR_HANDLERS: &[ErrorHandler] = &[
+    odata_error_handler,
+    config_error_handler,
+    #[cfg(feature = "sqlx")]
+    sqlx_error_handler,
+    anyhow_error_handler,
+];
+
+static ERROR_HANDLERS: RwLock<Vec<ErrorHandler>> = RwLock::new(Vec::new());
+
+/// Register `handler` for `map_error_to_problem`, tried after the built-in
+/// handlers and earlier registrations. Registering the same handler again has
+/// no effect, so modules may register from code that runs more than once.
+pub fn register_error_handler(handler: ErrorHandler) {
+    let mut handlers = ERROR_HANDLERS
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if !handlers.iter().any(|h| std::ptr::fn_addr_eq(*h, handler)) {
+        handlers.push(handler);
+    }
+}
+
+/// Code of the problem for errors no handler recognizes.
+const UNKNOWN_ERROR_CODE: &str = "UNKNOWN_ERROR";
 
-            ConfigError::InvalidConfig { module, .. } => Problem::new(
+fn build_problem(error: &dyn Any, instance: &str, trace_id: Option<String>) -> Problem {
+    let registered = ERROR_HANDLERS
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone();
+    let mut problem = BUILTIN_ERROR_HANDLERS
+        .iter()
+        .chain(&registered)
+        .find_map(|handler| handler(error))
+        .unwrap_or_else(|| {
+            Problem::new(
                 StatusCode::INTERNAL_SERVER_ERROR,
-                "Configuration Error",
-                format!("Module '{module}' has invalid configuration"),
+                "Unknown Error",
+                "An unknown error occurred",
             )
-            .with_code("CONFIG_INVALID")
-            .with_type("https://errors.example.com/CONFIG_INVALID"),
-        };
-
-        problem = problem.with_instance(instance);
-        if let Some(tid) = trace_id {
-            problem = problem.with_trace_id(tid);
-        }
-        return problem;
+            .with_code(UNKNOWN_ERROR_CODE)
+            .with_type("https://errors.example.com/UNKNOWN_ERROR")
+        })
+        .with_instance(instance);
+    if let Some(tid) = trace_id {
+        problem = problem.with_trace_id(tid);
     }
+    problem
+}
 
-    // Handle anyhow::Error
-    if let Some(anyhow_err) = error.downcast_ref::<anyhow::Error>() {
-        let mut problem = Problem::new(
+fn odata_error_handler(error: &dyn Any) -> Option<Problem> {
+    let odata_err = error.downcast_ref::<ODataError>()?;
+    Some(crate::api::odata::error::odata_error_to_problem(
+        odata_err, "", None,
+    ))
+}
+
+fn config_error_handler(error: &dyn Any) -> Option<Problem> {
+    let config_err = error.downcast_ref::<ConfigError>()?;
+    Some(match config_err {
+        ConfigError::ModuleNotFound { module } => Problem::new(
             StatusCode::INTERNAL_SERVER_ERROR,
-            "Internal Server Error",
-            "An internal error occurred",
+            "Configuration Error",
+            format!("Module '{module}' configuration not found"),
         )
-        .with_code("INTERNAL_ERROR")
-        .with_type("https://errors.example.com/INTERNAL_ERROR");
+        .with_code("CONFIG_MODULE_NOT_FOUND")
+        .with_type("https://errors.example.com/CONFIG_MODULE_NOT_FOUND"),
 
-        problem = problem.with_instance(instance);
-        if let Some(tid) = trace_id {
-            problem = problem.with_trace_id(tid);
-        }
+        ConfigError::InvalidModuleStructure { module } => Problem::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Configuration Error",
+            format!("Module '{module}' has invalid configuration structure"),
+        )
+        .with_code("CONFIG_INVALID_STRUCTURE")
+        .with_type("https://errors.example.com/CONFIG_INVALID_STRUCTURE"),
 
-        // Log the full error for debugging
-        tracing::error!(error = %anyhow_err, "Internal server error");
-        return problem;
-    }
+        ConfigError::MissingConfigSection { module } => Problem::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Configuration Error",
+            format!("Module '{module}' is missing required config section"),
+        )
+        .with_code("CONFIG_MISSING_SECTION")
+        .with_type("https://errors.example.com/CONFIG_MISSING_SECTION"),
 
-    // Fallback for unknown error types
-    let mut problem = Problem::new(
-        StatusCode::INTERNAL_SERVER_ERROR,
-        "Unknown Error",
-        "An unknown error occurred",
+        ConfigError::InvalidConfig { module, .. } => Problem::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Configuration Error",
+            format!("Module '{module}' has invalid configuration"),
+        )
+        .with_code("CONFIG_INVALID")
+        .with_type("https://errors.example.com/CONFIG_INVALID"),
+    })
+}
+
+fn anyhow_error_handler(error: &dyn Any) -> Option<Problem> {
+    // The full error is logged by `map_error_to_problem`
+    error.downcast_ref::<anyhow::Error>()?;
+    Some(
+        Problem::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Internal Server Error",
+            "An internal error occurred",
+        )
+        .with_code("INTERNAL_ERROR")
+        .with_type("https://errors.example.com/INTERNAL_ERROR"),
     )
-    .with_code("UNKNOWN_ERROR")
-    .with_type("https://errors.example.com/UNKNOWN_ERROR");
+}
 
-    problem = problem.with_instance(instance);
-    if let Some(tid) = trace_id {
-        problem = problem.with_trace_id(tid);
-    }
+/// Maps database errors without echoing them: driver messages may quote SQL,
+/// constraint names or row values, so the full error is only logged (by
+/// `map_error_to_problem`).
+#[cfg(feature = "sqlx")]
+fn sqlx_error_handler(error: &dyn Any) -> Option<Problem> {
+    use crate::api::problem::{CONFLICT_V1, INTERNAL_ERROR_V1, NOT_FOUND_V1};
 
-    tracing::error!("Unknown error type in error mapping layer");
-    problem
+    let sqlx_err = error.downcast_ref::<sqlx::Error>()?;
+    let problem = match sqlx_err {
+        sqlx::Error::RowNotFound => NOT_FOUND_V1.as_problem("The requested resource was not found"),
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            CONFLICT_V1.as_problem("The resource conflicts with an existing one")
+        }
+        _ => INTERNAL_ERROR_V1.as_problem("An internal error occurred"),
+    };
+    Some(problem)
 }
 
 /// Helper trait for converting errors to Problem responses with context
@@ -167,6 +524,13 @@ impl IntoProblem for ConfigError {
     }
 }
 
+#[cfg(feature = "sqlx")]
+impl IntoProblem for sqlx::Error {
+    fn into_problem(self, instance: &str, trace_id: Option<String>) -> Problem {
+        map_error_to_problem(&self as &dyn Any, instance, trace_id)
+    }
+}
+
 impl IntoProblem for anyhow::Error {
     fn into_problem(self, instance: &str, trace_id: Option<String>) -> Problem {
         map_error_to_problem(&self as &dyn Any, instance, trace_id)
@@ -213,6 +577,596 @@ mod tests {
         assert_eq!(problem.trace_id, Some("trace456".to_owned()));
     }
 
+    #[derive(Debug)]
+    struct QuotaExhausted(u32);
+
+    struct ProbeUnavailable {
+        expected: bool,
+    }
+
+    fn probe_handler(error: &dyn Any) -> Option<Problem> {
+        let ProbeUnavailable { expected } = error.downcast_ref::<ProbeUnavailable>()?;
+        let problem = Problem::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Unavailable",
+            "dependency is warming up",
+        );
+        Some(if *expected {
+            problem.with_log_suppressed()
+        } else {
+            problem
+        })
+    }
+
+    /// Messages of the events logged while mapping `error`, with the logging
+    /// reporter installed.
+    fn logged_while_mapping(error: &dyn Any) -> Vec<String> {
+        let _lock = crate::test_support::GLOBAL_STATE_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let capture = crate::test_support::EventCapture::all();
+        modkit_errors::set_problem_reporter(std::sync::Arc::new(modkit_errors::LoggingReporter));
+        {
+            let _guard = capture.install();
+            _ = map_error_to_problem(error, "/probe", None);
+        }
+        modkit_errors::reporter::clear_problem_reporter();
+        capture.messages()
+    }
+
+    #[test]
+    fn suppressed_problems_emit_no_log_event() {
+        register_error_handler(probe_handler);
+
+        assert_eq!(
+            logged_while_mapping(&ProbeUnavailable { expected: false }),
+            ["server problem reported"]
+        );
+        assert!(logged_while_mapping(&ProbeUnavailable { expected: true }).is_empty());
+
+        // Errors mapped by the built-in handlers are still logged
+        assert_eq!(
+            logged_while_mapping(&anyhow::anyhow!("boom")),
+            ["Internal server error", "server problem reported"]
+        );
+        assert_eq!(
+            logged_while_mapping(&42_u8),
+            [
+                "Unknown error type in error mapping layer",
+                "server problem reported"
+            ]
+        );
+    }
+
+    fn quota_handler(error: &dyn Any) -> Option<Problem> {
+        let QuotaExhausted(limit) = error.downcast_ref::<QuotaExhausted>()?;
+        Some(
+            Problem::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                "Quota Exhausted",
+                format!("The quota of {limit} requests is used up"),
+            )
+            .with_code("QUOTA_EXHAUSTED"),
+        )
+    }
+
+    #[test]
+    fn registered_handlers_map_module_errors() {
+        register_error_handler(quota_handler);
+        register_error_handler(quota_handler);
+        assert_eq!(
+            ERROR_HANDLERS
+                .read()
+                .unwrap()
+                .iter()
+                .filter(|h| std::ptr::fn_addr_eq(**h, quota_handler as ErrorHandler))
+                .count(),
+            1
+        );
+
+        let problem = map_error_to_problem(&QuotaExhausted(100), "/quota", Some("t-1".into()));
+        assert_eq!(problem.status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(problem.code, "QUOTA_EXHAUSTED");
+        assert_eq!(problem.instance, "/quota");
+        assert_eq!(problem.trace_id.as_deref(), Some("t-1"));
+
+        // Built-in types keep their mapping, unrelated ones fall through
+        let problem = map_error_to_problem(&anyhow::anyhow!("boom"), "/quota", None);
+        assert_eq!(problem.code, "INTERNAL_ERROR");
+        let problem = map_error_to_problem(&42_u8, "/quota", None);
+        assert_eq!(problem.code, "UNKNOWN_ERROR");
+        assert_eq!(problem.instance, "/quota");
+    }
+
+    #[cfg(feature = "sqlx")]
+    mod sqlx_errors {
+        use super::*;
+        use crate::api::problem::{CONFLICT_V1, INTERNAL_ERROR_V1, NOT_FOUND_V1};
+        use sqlx::error::{DatabaseError, ErrorKind};
+
+        const SQL: &str = "duplicate key value violates unique constraint \"users_email_key\": \
+                           INSERT INTO users (email) VALUES ('ann@example.com')";
+
+        #[derive(Debug)]
+        struct FakeDbError {
+            unique: bool,
+        }
+
+        impl std::fmt::Display for FakeDbError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(SQL)
+            }
+        }
+
+        impl std::error::Error for FakeDbError {}
+
+        impl DatabaseError for FakeDbError {
+            fn message(&self) -> &str {
+                SQL
+            }
+
+            fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+                self
+            }
+
+            fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+                self
+            }
+
+            fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+                self
+            }
+
+            fn constraint(&self) -> Option<&str> {
+                Some("users_email_key")
+            }
+
+            fn kind(&self) -> ErrorKind {
+                if self.unique {
+                    ErrorKind::UniqueViolation
+                } else {
+                    ErrorKind::Other
+                }
+            }
+        }
+
+        fn db_error(unique: bool) -> sqlx::Error {
+            sqlx::Error::Database(Box::new(FakeDbError { unique }))
+        }
+
+        fn assert_redacted(problem: &Problem) {
+            let json = serde_json::to_string(problem).unwrap();
+            for leaked in ["INSERT", "users_email_key", "ann@example.com"] {
+                assert!(!json.contains(leaked), "{leaked} leaked: {json}");
+            }
+        }
+
+        #[test]
+        fn unique_violation_is_a_conflict() {
+            let problem = db_error(true).into_problem("/users", Some("t-1".into()));
+            assert_eq!(problem.status, StatusCode::CONFLICT);
+            assert_eq!(problem.code, CONFLICT_V1.code);
+            assert_eq!(problem.instance, "/users");
+            assert_eq!(problem.trace_id.as_deref(), Some("t-1"));
+            assert_redacted(&problem);
+        }
+
+        #[test]
+        fn missing_row_is_not_found() {
+            let problem = sqlx::Error::RowNotFound.into_problem("/users/7", None);
+            assert_eq!(problem.status, StatusCode::NOT_FOUND);
+            assert_eq!(problem.code, NOT_FOUND_V1.code);
+            assert_eq!(problem.instance, "/users/7");
+        }
+
+        #[test]
+        fn other_database_errors_are_internal() {
+            for error in [
+                db_error(false),
+                sqlx::Error::Protocol(SQL.to_owned()),
+                sqlx::Error::PoolTimedOut,
+            ] {
+                let problem = map_error_to_problem(&error, "/users", None);
+                assert_eq!(problem.status, StatusCode::INTERNAL_SERVER_ERROR);
+                assert_eq!(problem.code, INTERNAL_ERROR_V1.code);
+                assert_redacted(&problem);
+            }
+        }
+    }
+
+    async fn call_failing(origin: Option<&str>, exposed: Option<&str>) -> Response {
+        use axum::body::Body;
+        use axum::response::IntoResponse;
+        use tower::ServiceExt;
+
+        let exposed = exposed.map(ToOwned::to_owned);
+        let app = axum::Router::new()
+            .route(
+                "/fail",
+                axum::routing::get(move || async move {
+                    let mut response = crate::api::problem::not_found("missing")
+                        .with_code("USERS_NOT_FOUND")
+                        .with_trace_id("trace-1")
+                        .into_response();
+                    if let Some(exposed) = exposed {
+                        response.headers_mut().insert(
+                            header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                            HeaderValue::from_str(&exposed).unwrap(),
+                        );
+                    }
+                    response
+                }),
+            )
+            .layer(axum::middleware::from_fn(expose_error_headers_middleware));
+
+        let mut request = axum::http::Request::get("/fail");
+        if let Some(origin) = origin {
+            request = request.header(header::ORIGIN, origin);
+        }
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn cross_origin_errors_expose_correlation_headers() {
+        let response = call_failing(Some("https://app.example.com"), Some("etag")).await;
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_EXPOSE_HEADERS],
+            "etag, x-trace-id, x-error-code"
+        );
+        assert_eq!(response.headers()["x-trace-id"], "trace-1");
+        assert_eq!(response.headers()["x-error-code"], "USERS_NOT_FOUND");
+
+        let response = call_failing(Some("https://app.example.com"), None).await;
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_EXPOSE_HEADERS],
+            "x-trace-id, x-error-code"
+        );
+    }
+
+    #[tokio::test]
+    async fn same_origin_errors_are_untouched() {
+        let response = call_failing(None, None).await;
+        assert!(
+            !response
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_EXPOSE_HEADERS)
+        );
+    }
+
+    static STABLE: crate::errors::ErrDef = crate::errors::ErrDef {
+        status: 400,
+        title: "Bad Request",
+        code: "USERS_BAD_REQUEST",
+        type_url: "gts.cf.core.errors.err.v1~cf.core.errors.bad_request.v1~",
+        help_url: None,
+        metadata: &[],
+        transient: None,
+        severity: None,
+        fallback: None,
+        titles: &[("fr", "Demande invalide")],
+    };
+
+    const EXPERIMENTAL: crate::errors::ErrDef = crate::errors::ErrDef {
+        status: 422,
+        title: "Handle Reserved",
+        code: "USERS_HANDLE_RESERVED",
+        type_url: "gts.cf.core.errors.err.v1~cf.users.errors.handle_reserved.v1~",
+        help_url: None,
+        metadata: &[],
+        transient: None,
+        severity: None,
+        fallback: Some(&STABLE),
+        titles: &[],
+    };
+
+    async fn call_experimental(opt_in: Option<&str>) -> serde_json::Value {
+        use axum::body::Body;
+        use tower::ServiceExt;
+
+        let app = axum::Router::new()
+            .route(
+                "/users",
+                axum::routing::post(|| async {
+                    (
+                        [(header::CACHE_CONTROL, "no-store")],
+                        EXPERIMENTAL.as_problem("handle is reserved"),
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn(experimental_errors_middleware));
+
+        let mut request = axum::http::Request::post("/users");
+        if let Some(value) = opt_in {
+            request = request.header("x-accept-experimental-errors", value);
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let code = response.headers()["x-error-code"].clone();
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            modkit_errors::headers::APPLICATION_PROBLEM_JSON_VERSIONED
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], status.as_u16());
+        assert_eq!(json["code"], code.to_str().unwrap());
+        json
+    }
+
+    #[tokio::test]
+    async fn opted_in_clients_get_experimental_errors() {
+        for value in ["true", "1", "TRUE"] {
+            let json = call_experimental(Some(value)).await;
+            assert_eq!(json["code"], "USERS_HANDLE_RESERVED");
+            assert_eq!(json["status"], 422);
+            assert_eq!(json["type"], EXPERIMENTAL.type_url);
+        }
+    }
+
+    #[tokio::test]
+    async fn other_clients_get_the_stable_fallback() {
+        for value in [None, Some("false")] {
+            let json = call_experimental(value).await;
+            assert_eq!(json["code"], "USERS_BAD_REQUEST");
+            assert_eq!(json["status"], 400);
+            assert_eq!(json["type"], STABLE.type_url);
+            assert_eq!(json["title"], "Bad Request");
+            assert_eq!(json["detail"], "handle is reserved");
+        }
+    }
+
+    #[tokio::test]
+    async fn fallback_title_is_localized() {
+        use axum::body::Body;
+        use tower::ServiceExt;
+
+        let app = axum::Router::new()
+            .route(
+                "/users",
+                axum::routing::post(|| async { EXPERIMENTAL.as_problem("handle is reserved") }),
+            )
+            .layer(axum::middleware::from_fn(experimental_errors_middleware))
+            .layer(axum::middleware::from_fn(problem_language_middleware));
+
+        let response = app
+            .oneshot(
+                axum::http::Request::post("/users")
+                    .header("accept-language", "fr-CA, en;q=0.5")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers()[header::CONTENT_LANGUAGE], "fr");
+        assert_eq!(response.headers()[header::VARY], "accept-language");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["title"], "Demande invalide");
+        assert_eq!(json["type"], STABLE.type_url);
+    }
+
+    async fn call_localized(accept_language: Option<&str>) -> Response {
+        use axum::body::Body;
+        use tower::ServiceExt;
+
+        let app = axum::Router::new()
+            .route(
+                "/users",
+                axum::routing::post(|| async {
+                    (
+                        [(header::CACHE_CONTROL, "no-store")],
+                        STABLE.as_problem("handle is reserved"),
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn(problem_language_middleware));
+
+        let mut request = axum::http::Request::post("/users");
+        if let Some(value) = accept_language {
+            request = request.header(header::ACCEPT_LANGUAGE, value);
+        }
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn localized_problem_keeps_handler_headers() {
+        let response = call_localized(Some("fr")).await;
+        assert_eq!(response.headers()[header::CONTENT_LANGUAGE], "fr");
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+        assert_eq!(response.headers()[header::VARY], "accept-language");
+    }
+
+    #[tokio::test]
+    async fn localizable_problem_varies_even_without_accept_language() {
+        let response = call_localized(None).await;
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+        assert_eq!(response.headers()[header::VARY], "accept-language");
+    }
+
+    async fn call_mapped(handler: axum::routing::MethodRouter) -> Response {
+        use axum::body::Body;
+        use tower::ServiceExt;
+
+        let app = axum::Router::new()
+            .route("/users/{id}", handler)
+            .layer(axum::middleware::from_fn(error_mapping_middleware));
+        app.oneshot(
+            axum::http::Request::get("/users/7?token=secret")
+                .header("x-request-id", "req-7")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn problem_body(response: Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn plain_server_error_becomes_problem() {
+        let response = call_mapped(axum::routing::get(|| async {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "db password rejected at 10.0.0.7",
+            )
+        }))
+        .await;
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(is_problem_response(&response));
+        assert_eq!(response.headers()["x-error-code"], "INTERNAL_ERROR");
+        assert_eq!(response.headers()["x-trace-id"], "req-7");
+
+        let json = problem_body(response).await;
+        assert_eq!(json["status"], 500);
+        assert_eq!(json["instance"], "/users/7");
+        assert_eq!(json["detail"], "Internal Server Error (HTTP 500)");
+    }
+
+    #[tokio::test]
+    async fn plain_client_errors_keep_text_and_headers() {
+        let response = call_mapped(axum::routing::get(|| async {
+            (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+                "token expired",
+            )
+        }))
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+        assert_eq!(response.headers()["x-error-code"], "UNAUTHORIZED");
+        let json = problem_body(response).await;
+        assert_eq!(json["detail"], "token expired");
+
+        let response = call_mapped(axum::routing::get(|| async { StatusCode::NOT_FOUND })).await;
+        assert_eq!(response.headers()["x-error-code"], "NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn bare_statuses_use_the_framework_catalog() {
+        use modkit_errors::catalog::{CONFLICT_V1, INTERNAL_ERROR_V1, NOT_FOUND_V1};
+
+        for def in [NOT_FOUND_V1, CONFLICT_V1, INTERNAL_ERROR_V1] {
+            let status = StatusCode::from_u16(def.status).unwrap();
+            let response = call_mapped(axum::routing::get(move || async move { status })).await;
+            assert_eq!(response.status(), status);
+            assert_eq!(response.headers()["x-error-code"], def.code);
+            let json = problem_body(response).await;
+            assert_eq!(json["type"], def.type_url);
+            assert_eq!(json["title"], def.title);
+        }
+
+        let response = call_mapped(axum::routing::get(|| async { StatusCode::GONE })).await;
+        assert_eq!(response.headers()["x-error-code"], "GONE");
+    }
+
+    #[tokio::test]
+    async fn problem_and_success_responses_pass_through() {
+        let response = call_mapped(axum::routing::get(|| async {
+            crate::api::problem::not_found("no such user").with_code("USERS_NOT_FOUND")
+        }))
+        .await;
+        assert_eq!(response.headers()["x-error-code"], "USERS_NOT_FOUND");
+        assert!(!response.headers().contains_key("x-trace-id"));
+        assert_eq!(problem_body(response).await["detail"], "no such user");
+
+        let response = call_mapped(axum::routing::get(|| async { "ok" })).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!is_problem_response(&response));
+    }
+
+    /// Calls `/echo`, answering with the handler's [`RequestTraceId`], and
+    /// `/fail`, failing through `error_mapping_middleware`.
+    async fn call_traced(uri: &str, trace_header: Option<&str>) -> (Response, String) {
+        use axum::body::Body;
+        use axum::extract::Extension;
+        use tower::ServiceExt;
+
+        let app = axum::Router::new()
+            .route(
+                "/echo",
+                axum::routing::get(|Extension(id): Extension<RequestTraceId>| async move { id.0 }),
+            )
+            .route(
+                "/fail",
+                axum::routing::get(|| async { StatusCode::INTERNAL_SERVER_ERROR }),
+            )
+            .layer(axum::middleware::from_fn(error_mapping_middleware))
+            .layer(axum::middleware::from_fn(trace_id_middleware));
+
+        let mut request = axum::http::Request::get(uri);
+        if let Some(value) = trace_header {
+            request = request.header("x-request-id", value);
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let echoed = response.headers()["x-trace-id"]
+            .to_str()
+            .unwrap()
+            .to_owned();
+        (response, echoed)
+    }
+
+    async fn body_text(response: Response) -> String {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn incoming_trace_id_is_reused() {
+        let (response, echoed) = call_traced("/echo", Some("req-42")).await;
+        assert_eq!(echoed, "req-42");
+        assert_eq!(body_text(response).await, "req-42");
+    }
+
+    #[tokio::test]
+    async fn missing_trace_id_is_generated() {
+        let (response, echoed) = call_traced("/echo", None).await;
+        assert_eq!(body_text(response).await, echoed);
+
+        let (response, echoed) = call_traced("/fail", None).await;
+        let problem: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+        assert_eq!(problem["trace_id"], echoed.as_str());
+    }
+
+    #[tokio::test]
+    async fn generated_trace_id_is_32_hex() {
+        let (_, first) = call_traced("/echo", None).await;
+        let (_, second) = call_traced("/echo", None).await;
+        for id in [&first, &second] {
+            assert_eq!(id.len(), 32, "{id}");
+            assert!(
+                id.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')),
+                "{id}"
+            );
+        }
+        assert_ne!(first, second);
+    }
+
     #[test]
     fn test_extract_trace_id_from_headers() {
         let mut headers = HeaderMap::new();
@@ -221,4 +1175,63 @@ mod tests {
         let trace_id = extract_trace_id(&headers);
         assert_eq!(trace_id, Some("test-trace-123".to_owned()));
     }
+
+    #[test]
+    fn trace_id_is_taken_from_a_well_formed_traceparent() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "traceparent",
+            HeaderValue::from_static("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+        assert_eq!(
+            extract_trace_id(&headers).as_deref(),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+
+        headers.insert("x-request-id", HeaderValue::from_static("req-7"));
+        assert_eq!(extract_trace_id(&headers).as_deref(), Some("req-7"));
+    }
+
+    #[test]
+    fn malformed_traceparent_is_ignored() {
+        for traceparent in [
+            "4bf92f3577b34da6a3ce929d0e0e4736",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6-00f067aa0ba902b7-01",
+        ] {
+            assert_eq!(traceparent_trace_id(traceparent), None, "{traceparent}");
+        }
+        assert_eq!(
+            traceparent_trace_id("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-future"),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert("traceparent", HeaderValue::from_static("garbage"));
+        assert_eq!(extract_trace_id(&headers), None);
+        headers.insert("x-trace-id", HeaderValue::from_static("trace-1"));
+        assert_eq!(extract_trace_id(&headers).as_deref(), Some("trace-1"));
+    }
+
+    #[test]
+    fn span_fallback_trace_id_is_32_hex() {
+        let subscriber = tracing_subscriber::registry();
+        let trace_id = tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("request").entered();
+            extract_trace_id(&HeaderMap::new())
+        })
+        .expect("span id fallback");
+        assert_eq!(trace_id.len(), 32, "{trace_id}");
+        assert!(
+            trace_id
+                .bytes()
+                .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')),
+            "{trace_id}"
+        );
+    }
 }
@@ -9,6 +9,7 @@ pub mod error_layer;
 pub mod odata;
 pub mod openapi_registry;
 pub mod operation_builder;
+pub mod panic;
 pub mod problem;
 pub mod response;
 pub mod select;
@@ -19,16 +20,19 @@ pub mod trace_layer;
 mod odata_policy_tests;
 
 pub use error_layer::{
-    IntoProblem, error_mapping_middleware, extract_trace_id, map_error_to_problem,
+    ErrorHandler, IntoProblem, RequestTraceId, error_mapping_middleware,
+    expose_error_headers_middleware, extract_trace_id, generate_trace_id, map_error_to_problem,
+    register_error_handler, trace_id_middleware,
 };
 pub use openapi_registry::{OpenApiInfo, OpenApiRegistry, OpenApiRegistryImpl, ensure_schema};
 pub use operation_builder::{
     Missing, OperationBuilder, OperationSpec, ParamLocation, ParamSpec, Present, RateLimitSpec,
     ResponseSpec, state,
 };
+pub use panic::catch_panic_middleware;
 pub use problem::{
     APPLICATION_PROBLEM_JSON, Problem, ValidationError, bad_request, conflict, internal_error,
-    not_found,
+    not_found, too_many_requests, unauthorized, validation_failed,
 };
 pub use select::{apply_select, page_to_projected_json, project_json};
 pub use trace_layer::{WithRequestContext, WithTraceContext};
@@ -38,11 +42,21 @@ pub mod prelude {
     // Result type (Problem-only)
     pub use crate::result::ApiResult;
 
-    // Problem type for error construction
-    pub use super::problem::Problem;
+    // Problem type, catalog definitions and convenience constructors
+    pub use super::problem::{
+        Problem, bad_request, conflict, internal_error, not_found, too_many_requests, unauthorized,
+        validation_failed,
+    };
+    pub use crate::errors::ErrDef;
+
+    // Error conversion and request-context enrichment traits
+    pub use super::error_layer::IntoProblem;
+    pub use super::trace_layer::{WithRequestContext, WithTraceContext};
 
     // Response sugar
-    pub use super::response::{JsonBody, JsonPage, created_json, no_content, ok_json};
+    pub use super::response::{
+        JsonBody, JsonODataPage, JsonPage, created_json, no_content, odata_page_json, ok_json,
+    };
 
     // OData and field projection
     pub use super::select::apply_select;
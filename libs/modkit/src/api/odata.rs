@@ -19,7 +19,18 @@ pub struct ODataParams {
     pub orderby: Option<String>,
     #[serde(rename = "$select")]
     pub select: Option<String>,
+    #[serde(rename = "$compute")]
+    pub compute: Option<String>,
     pub limit: Option<u64>,
+    /// Page size for offset pagination; an alternative spelling of `limit`.
+    #[serde(rename = "$top")]
+    pub top: Option<String>,
+    /// Rows to skip for offset pagination; cannot be combined with `cursor`.
+    #[serde(rename = "$skip")]
+    pub skip: Option<String>,
+    /// `true` to include the total number of matching rows in the page.
+    #[serde(rename = "$count")]
+    pub count: Option<String>,
     pub cursor: Option<String>,
 }
 
@@ -27,53 +38,20 @@ pub const MAX_FILTER_LEN: usize = 8 * 1024;
 pub const MAX_NODES: usize = 2000;
 pub const MAX_ORDERBY_LEN: usize = 1024;
 pub const MAX_ORDER_FIELDS: usize = 10;
-pub const MAX_SELECT_LEN: usize = 2048;
-pub const MAX_SELECT_FIELDS: usize = 100;
+pub use modkit_odata::select::{MAX_SELECT_FIELDS, MAX_SELECT_LEN};
+pub const MAX_COMPUTE_LEN: usize = 1024;
 
 /// Parse $select string into a list of field names.
 /// Format: "field1, field2, field3, ..."
-/// Field names are case-insensitive and whitespace is trimmed.
+/// Field names are case-insensitive and whitespace is trimmed; the syntax is
+/// checked by [`modkit_odata::select::parse_fields`].
 ///
 /// # Errors
-/// Returns a `Problem` if the select string is invalid.
+/// Returns a 422 `invalid_select` `Problem` if the select string is invalid.
 #[allow(clippy::result_large_err)] // It's used without error in the parsing function, no idea why complains here
 pub fn parse_select(raw: &str) -> Result<Vec<String>, crate::api::problem::Problem> {
-    let raw = raw.trim();
-    if raw.is_empty() {
-        return Err(crate::api::bad_request("$select cannot be empty"));
-    }
-
-    if raw.len() > MAX_SELECT_LEN {
-        return Err(crate::api::bad_request("$select too long"));
-    }
-
-    let fields: Vec<String> = raw
-        .split(',')
-        .map(|f| f.trim().to_lowercase())
-        .filter(|f| !f.is_empty())
-        .collect();
-
-    if fields.is_empty() {
-        return Err(crate::api::bad_request(
-            "$select must contain at least one field",
-        ));
-    }
-
-    if fields.len() > MAX_SELECT_FIELDS {
-        return Err(crate::api::bad_request("$select contains too many fields"));
-    }
-
-    // Check for duplicate fields
-    let mut seen = std::collections::HashSet::new();
-    for field in &fields {
-        if !seen.insert(field.clone()) {
-            return Err(crate::api::bad_request(format!(
-                "duplicate field in $select: {field}",
-            )));
-        }
-    }
-
-    Ok(fields)
+    modkit_odata::select::parse_fields(raw)
+        .map_err(|e| crate::api::odata::odata_error_to_problem(&e, "/", None))
 }
 
 /// Parse $orderby string into `ODataOrderBy`.
@@ -135,10 +113,18 @@ pub fn parse_orderby(raw: &str) -> Result<ODataOrderBy, modkit_odata::Error> {
 }
 
 /// Extract and validate full `OData` query from request parts.
-/// - Parses $filter, $orderby, limit, cursor
-/// - Enforces budgets and validates formats
+/// - Parses $filter, $orderby, $select, $compute, limit/$top, $skip, $count, cursor
+/// - Enforces budgets (including the total complexity budget) and validates formats
 /// - Returns unified `ODataQuery`
 ///
+/// Limits come from an [`modkit_odata::ODataLimits`] request extension when one
+/// is present, e.g. `router.layer(Extension(ODataLimits::default().with_max_top(50)))`;
+/// otherwise the defaults apply. Likewise a [`modkit_odata::FieldRegistry`]
+/// extension restricts `$filter`, `$orderby` and `$select` to the declared
+/// fields, and a [`modkit_odata::FieldAliases`] extension then rewrites the
+/// public names of `$filter` and `$orderby` to internal ones, so the handler
+/// and the filter conversion only see real column names.
+///
 /// # Errors
 /// Returns `Problem` if any `OData` parameter is invalid.
 pub async fn extract_odata_query<S>(
@@ -154,6 +140,11 @@ where
         .unwrap_or_else(|_| Query(ODataParams::default()));
 
     let mut query = ODataQuery::new();
+    let limits = parts
+        .extensions
+        .get::<modkit_odata::ODataLimits>()
+        .cloned()
+        .unwrap_or_default();
 
     // Parse filter
     if let Some(raw_filter) = params.filter.as_ref() {
@@ -163,8 +154,9 @@ where
                 return Err(crate::api::bad_request("Filter too long"));
             }
 
-            // Parse filter string using modkit-odata
-            let parsed = modkit_odata::parse_filter_string(raw).map_err(|e| {
+            // Parse the untrimmed filter so syntax error positions are offsets
+            // into the filter as the client sent it
+            let parsed = modkit_odata::parse_filter_string(raw_filter).map_err(|e| {
                 // Log parser details for debugging (no PII - only length)
                 tracing::debug!(error = %e, filter_len = raw.len(), "OData filter parsing failed");
 
@@ -181,6 +173,9 @@ where
                 );
                 return Err(crate::api::bad_request("Filter too complex"));
             }
+            limits.validate_in_lists(parsed.as_expr()).map_err(|e| {
+                crate::api::odata::odata_error_to_problem(&e, parts.uri.path(), None)
+            })?;
 
             // Generate filter hash for cursor consistency (use non-consuming accessor)
             let filter_hash = modkit_odata::pagination::short_filter_hash(Some(parsed.as_expr()));
@@ -204,6 +199,15 @@ where
         ));
     }
 
+    // Offset and cursor pagination are exclusive
+    if params.cursor.is_some() && params.skip.is_some() {
+        return Err(crate::api::odata::odata_error_to_problem(
+            &ODataError::SkipWithCursor,
+            parts.uri.path(),
+            None,
+        ));
+    }
+
     // Parse cursor first (if present, skip orderby)
     if let Some(cursor_str) = params.cursor.as_ref() {
         let cursor = CursorV1::decode(cursor_str).map_err(|_| {
@@ -231,19 +235,75 @@ where
         query = query.with_limit(limit);
     }
 
-    // Parse select
+    // Parse $top / $skip
+    let to_problem =
+        |e: ODataError| crate::api::odata::odata_error_to_problem(&e, parts.uri.path(), None);
+    if let Some(raw_top) = params.top.as_ref() {
+        if params.limit.is_some() {
+            return Err(to_problem(ODataError::InvalidTop(
+                "cannot be combined with limit".into(),
+            )));
+        }
+        query = query.with_limit(limits.parse_top(raw_top).map_err(to_problem)?);
+    }
+    if let Some(raw_skip) = params.skip.as_ref() {
+        query = query.with_skip(modkit_odata::parse_skip(raw_skip).map_err(to_problem)?);
+    }
+
+    // Parse $count; an invalid $filter has already been rejected above, so a
+    // count is never computed for it
+    if let Some(raw_count) = params.count.as_ref() {
+        query = query.with_count(modkit_odata::parse_count(raw_count).map_err(to_problem)?);
+    }
+
+    // Parse select, against the route's declared fields if any
     if let Some(raw_select) = params.select.as_ref() {
-        let fields = parse_select(raw_select)?;
+        let fields = match parts.extensions.get::<modkit_odata::FieldRegistry>() {
+            Some(registry) => {
+                modkit_odata::select::Select::parse(raw_select, &registry.names()).map(Vec::from)
+            }
+            None => modkit_odata::select::parse_fields(raw_select),
+        }
+        .map_err(|e| crate::api::odata::odata_error_to_problem(&e, parts.uri.path(), None))?;
         query = query.with_select(fields);
     }
 
+    // Parse compute
+    if let Some(raw_compute) = params.compute.as_ref() {
+        if raw_compute.len() > MAX_COMPUTE_LEN {
+            return Err(crate::api::bad_request("$compute too long"));
+        }
+        let items = modkit_odata::parse_compute(raw_compute)
+            .map_err(|e| crate::api::odata::odata_error_to_problem(&e, parts.uri.path(), None))?;
+        query = query.with_compute(items);
+    }
+
+    // Enforce the total complexity budget across all parts of the query
+    limits
+        .validate_complexity(&query)
+        .map_err(|e| crate::api::odata::odata_error_to_problem(&e, parts.uri.path(), None))?;
+
+    // Check fields against the route's declared allowlist, if any
+    if let Some(registry) = parts.extensions.get::<modkit_odata::FieldRegistry>() {
+        query
+            .validate_fields(registry)
+            .map_err(|e| crate::api::odata::odata_error_to_problem(&e, parts.uri.path(), None))?;
+    }
+
+    // Translate public field names into internal ones, if the route maps them
+    if let Some(aliases) = parts.extensions.get::<modkit_odata::FieldAliases>() {
+        query = query
+            .resolve_aliases(aliases)
+            .map_err(|e| crate::api::odata::odata_error_to_problem(&e, parts.uri.path(), None))?;
+    }
+
     Ok(query)
 }
 
 use std::ops::Deref;
 
 /// Simple Axum extractor for full `OData` query parameters.
-/// Parses $filter, $orderby, limit, and cursor parameters.
+/// Parses $filter, $orderby, limit/$top, $skip, $count, and cursor parameters.
 /// Usage in handlers:
 ///   async fn `list_users(OData(query)`: `OData`, /* ... */) { /* use `query` */ }
 #[derive(Debug, Clone)]
@@ -296,6 +356,23 @@ where
     }
 }
 
+/// Validate-only handler: runs the full `OData` extraction (`$filter`,
+/// `$orderby`, `$select`, `$compute`, limits and complexity budget) and answers
+/// `204 No Content` without touching any data. Invalid queries are rejected by
+/// the extractor with the usual problem response.
+///
+/// Fields and literal types are only checked against the endpoint's
+/// [`modkit_odata::FieldRegistry`]: give the route the same registry extension
+/// as the list endpoint, otherwise only syntax and limits are validated.
+///
+/// Mount it next to a list endpoint so clients can check an expensive filter
+/// first, e.g.
+/// `router.route("/users/$validate", get(validate_odata).layer(Extension(fields)))`.
+#[allow(clippy::unused_async)] // axum handlers must be async
+pub async fn validate_odata(OData(_query): OData) -> axum::http::StatusCode {
+    axum::http::StatusCode::NO_CONTENT
+}
+
 #[cfg(test)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 #[path = "odata_tests.rs"]
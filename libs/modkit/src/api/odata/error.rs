@@ -4,16 +4,9 @@
 //! The core Error → Problem mapping is owned by modkit-odata.
 
 use crate::api::problem::Problem;
+use crate::api::trace_layer::current_trace_id;
 use modkit_odata::Error as ODataError;
 
-/// Extract trace ID from current tracing span
-#[inline]
-fn current_trace_id() -> Option<String> {
-    tracing::Span::current()
-        .id()
-        .map(|id| id.into_u64().to_string())
-}
-
 /// Returns a fully contextualized Problem for `OData` errors.
 ///
 /// This function maps all `modkit_odata::Error` variants to appropriate system
@@ -39,6 +32,9 @@ pub fn odata_error_to_problem(
         OE::ParsingUnavailable(msg) => {
             tracing::error!(error = %msg, "OData parsing unavailable");
         }
+        OE::InvalidKeyset(msg) => {
+            tracing::error!(error = %msg, "Invalid OData pagination keyset");
+        }
         _ => {}
     }
 
@@ -109,4 +105,21 @@ mod tests {
         assert!(problem.code.starts_with("gts.hx.core.errors.err.v1~"));
         assert!(problem.code.contains("odata"));
     }
+
+    #[test]
+    fn span_trace_id_matches_the_other_error_paths() {
+        let subscriber = tracing_subscriber::registry();
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("request").entered();
+            let error = ODataError::InvalidFilter("test".to_owned());
+            let problem = odata_error_to_problem(&error, "/user-management/v1/test", None);
+
+            let trace_id = problem.trace_id.expect("trace id from the span");
+            assert_eq!(trace_id.len(), 32, "{trace_id}");
+            assert_eq!(
+                Some(trace_id),
+                crate::api::error_layer::extract_trace_id(&http::HeaderMap::new())
+            );
+        });
+    }
 }
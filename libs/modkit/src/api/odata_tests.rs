@@ -174,6 +174,227 @@ mod tests {
         let _problem_response = result.unwrap_err();
     }
 
+    async fn extract(uri: &str) -> Result<ODataQuery, crate::api::problem::Problem> {
+        let request = Request::builder().uri(uri).body(()).unwrap();
+        let (mut parts, _body) = request.into_parts();
+        extract_odata_query(&mut parts, &()).await
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_top_and_skip() {
+        let query = extract("/users?%24top=20&%24skip=40").await.unwrap();
+        assert_eq!(query.limit, Some(20));
+        assert_eq!(query.skip, Some(40));
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_top_over_limit() {
+        let problem = extract("/users?%24top=5000").await.unwrap_err();
+        assert_eq!(problem.status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(problem.code.contains("invalid_pagination"));
+        assert!(problem.detail.contains("5000 exceeds the maximum"));
+        assert_eq!(problem.instance, "/users");
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_configured_max_top() {
+        let limits = modkit_odata::ODataLimits::default().with_max_top(50);
+        let query = extract_with("/users?%24top=50", limits.clone())
+            .await
+            .unwrap();
+        assert_eq!(query.limit, Some(50));
+
+        let problem = extract_with("/users?%24top=51", limits).await.unwrap_err();
+        assert_eq!(problem.status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(
+            problem.detail.contains("51 exceeds the maximum"),
+            "{problem:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_negative_skip() {
+        let problem = extract("/users?%24skip=-10").await.unwrap_err();
+        assert_eq!(problem.status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(problem.detail.contains("$skip"));
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_in_list_limit() {
+        let ids = |n: usize| (0..n).map(|i| i.to_string()).collect::<Vec<_>>().join(",");
+        let query = extract(&format!("/users?%24filter=id%20in%20({})", ids(100)))
+            .await
+            .unwrap();
+        assert!(query.filter.is_some());
+
+        let problem = extract(&format!("/users?%24filter=id%20in%20({})", ids(101)))
+            .await
+            .unwrap_err();
+        assert_eq!(problem.status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(
+            problem.detail.contains("exceeds the maximum of 100"),
+            "{problem:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_configured_in_list_limit() {
+        let limits = modkit_odata::ODataLimits::default().with_max_in_list_len(3);
+        let query = extract_with("/users?%24filter=id%20in%20(1,2,3)", limits.clone())
+            .await
+            .unwrap();
+        assert!(query.filter.is_some());
+
+        let problem = extract_with("/users?%24filter=id%20in%20(1,2,3,4)", limits)
+            .await
+            .unwrap_err();
+        assert_eq!(problem.status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(
+            problem.detail.contains("exceeds the maximum of 3"),
+            "{problem:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_count() {
+        assert!(extract("/users?%24count=true").await.unwrap().count);
+        assert!(!extract("/users?%24count=false").await.unwrap().count);
+        assert!(!extract("/users").await.unwrap().count);
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_invalid_count() {
+        let problem = extract("/users?%24count=maybe").await.unwrap_err();
+        assert_eq!(problem.status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(problem.detail.contains("$count"));
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_count_with_invalid_filter() {
+        let problem = extract("/users?%24count=true&%24filter=name%20eq")
+            .await
+            .unwrap_err();
+        assert!(problem.code.contains("invalid_filter"), "{problem:?}");
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_skip_with_cursor_conflict() {
+        let cursor = modkit_odata::CursorV1 {
+            k: vec!["1".to_owned()],
+            o: SortDir::Asc,
+            s: "+id".to_owned(),
+            f: None,
+            d: "fwd".to_owned(),
+        }
+        .encode()
+        .unwrap();
+        let problem = extract(&format!("/users?cursor={cursor}&%24skip=10"))
+            .await
+            .unwrap_err();
+        assert_eq!(problem.status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(problem.code.contains("invalid_cursor"));
+        assert_eq!(
+            problem.detail,
+            "Cannot specify both $skip and cursor parameters"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_within_complexity_budget() {
+        let uri = "/?%24filter=email%20eq%20'a'&%24orderby=email%20asc,id%20desc&limit=100";
+
+        let request = Request::builder().uri(uri).body(()).unwrap();
+        let (mut parts, _body) = request.into_parts();
+
+        let result = extract_odata_query(&mut parts, &()).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_complexity_budget_exceeded() {
+        let uri = "/?%24orderby=a,b,c,d,e,f,g,h&limit=10000";
+
+        let request = Request::builder().uri(uri).body(()).unwrap();
+        let (mut parts, _body) = request.into_parts();
+
+        let problem = extract_odata_query(&mut parts, &()).await.unwrap_err();
+        assert_eq!(problem.status, http::StatusCode::BAD_REQUEST);
+        assert!(problem.code.contains("query_too_complex"));
+        let metadata = problem.metadata.unwrap();
+        assert_eq!(metadata["complexity_score"], 140);
+        assert_eq!(metadata["complexity_budget"], 100);
+    }
+
+    async fn extract_with<T: Clone + Send + Sync + 'static>(
+        uri: &str,
+        extension: T,
+    ) -> Result<ODataQuery, crate::api::problem::Problem> {
+        let request = Request::builder().uri(uri).body(()).unwrap();
+        let (mut parts, _body) = request.into_parts();
+        parts.extensions.insert(extension);
+        extract_odata_query(&mut parts, &()).await
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_configured_complexity_budget() {
+        let uri = "/?%24filter=email%20eq%20'a'&%24orderby=email%20asc,id%20desc&limit=100";
+        assert!(extract(uri).await.is_ok());
+
+        let limits = modkit_odata::ODataLimits::default().with_max_complexity(1);
+        let problem = extract_with(uri, limits).await.unwrap_err();
+        assert_eq!(problem.status, http::StatusCode::BAD_REQUEST);
+        assert!(problem.code.contains("query_too_complex"));
+        assert_eq!(problem.metadata.unwrap()["complexity_budget"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_checks_the_field_registry() {
+        use modkit_odata::{FieldRegistry, FieldSpec, filter::FieldKind};
+
+        let registry = FieldRegistry::new()
+            .with_field(FieldSpec::new("email", FieldKind::String))
+            .with_field(FieldSpec::new("id", FieldKind::I64).not_selectable());
+        let query = extract_with(
+            "/users?%24filter=email%20eq%20'a'&%24orderby=id&%24select=Email",
+            registry.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(query.selected_fields(), Some(&["email".to_owned()][..]));
+
+        let problem = extract_with("/users?%24orderby=name", registry.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(problem.status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(problem.code.contains("invalid_orderby"), "{problem:?}");
+
+        let problem = extract_with("/users?%24select=id", registry.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(problem.detail, "Field not selectable: id");
+
+        let problem = extract_with("/users?%24select=password", registry)
+            .await
+            .unwrap_err();
+        assert_eq!(problem.status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(problem.detail, "Unsupported $select field: password");
+
+        assert!(extract("/users?%24orderby=name").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_extract_odata_query_rejects_invalid_select_as_unprocessable() {
+        let problem = extract("/users?%24select=id,%20id").await.unwrap_err();
+        assert_eq!(problem.status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            problem.type_url,
+            modkit_odata::errors::ErrorCode::odata_errors_invalid_select_v1()
+                .def()
+                .type_url
+        );
+        assert_eq!(problem.instance, "/users");
+    }
+
     #[tokio::test]
     async fn test_extract_odata_query_filter_too_long() {
         let long_filter = "email eq '".to_owned() + &"a".repeat(MAX_FILTER_LEN) + "'";
@@ -201,6 +422,18 @@ mod tests {
         let _problem_response = result.unwrap_err();
     }
 
+    #[tokio::test]
+    async fn test_extract_odata_query_filter_error_position_counts_leading_spaces() {
+        let problem = extract("/?%24filter=%20%20name%20eq").await.unwrap_err();
+        let metadata = problem.metadata.unwrap();
+        assert_eq!(metadata["position"], 9);
+        assert!(
+            problem.detail.ends_with("at column 10"),
+            "{}",
+            problem.detail
+        );
+    }
+
     #[tokio::test]
     async fn test_extract_odata_query_invalid_orderby() {
         let uri = "/?%24orderby=field%20invalid_direction";
@@ -260,4 +493,76 @@ mod tests {
         let query_back: ODataQuery = odata.into();
         assert!(query_back.has_filter());
     }
+
+    async fn validate(uri: &str) -> axum::response::Response {
+        validate_on(
+            axum::Router::new().route("/users/$validate", axum::routing::get(validate_odata)),
+            uri,
+        )
+        .await
+    }
+
+    async fn validate_on(router: axum::Router, uri: &str) -> axum::response::Response {
+        use tower::ServiceExt;
+
+        router
+            .oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_validate_odata_accepts_valid_query() {
+        let resp = validate(
+            "/users/$validate?%24filter=score%20gt%2010&%24orderby=name%20desc&%24select=id,name",
+        )
+        .await;
+        assert_eq!(resp.status(), axum::http::StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_validate_odata_rejects_invalid_filter() {
+        let resp = validate("/users/$validate?%24filter=score%20gt").await;
+        assert_eq!(resp.status(), axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], 422);
+        assert_eq!(json["title"], "Invalid Filter");
+    }
+
+    #[tokio::test]
+    async fn test_validate_odata_checks_the_routes_fields() {
+        use modkit_odata::{FieldRegistry, FieldSpec, filter::FieldKind};
+
+        let fields = FieldRegistry::new()
+            .with_field(FieldSpec::new("score", FieldKind::I64))
+            .with_field(FieldSpec::new("name", FieldKind::String));
+        let router = axum::Router::new().route(
+            "/users/$validate",
+            axum::routing::get(validate_odata).layer(axum::Extension(fields)),
+        );
+
+        let resp = validate_on(router.clone(), "/users/$validate?%24filter=score%20gt%2010").await;
+        assert_eq!(resp.status(), axum::http::StatusCode::NO_CONTENT);
+
+        for uri in [
+            "/users/$validate?%24filter=password%20eq%20'x'",
+            "/users/$validate?%24filter=score%20gt%20'ten'",
+        ] {
+            let resp = validate_on(router.clone(), uri).await;
+            assert_eq!(
+                resp.status(),
+                axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+                "{uri}"
+            );
+        }
+    }
 }
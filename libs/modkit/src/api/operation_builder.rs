@@ -188,7 +188,106 @@ pub struct ResponseSpec {
 /// License requirement specification for an operation
 #[derive(Clone, Debug)]
 pub struct LicenseReqSpec {
-    pub license_names: Vec<String>,
+    pub requirement: LicenseRequirement,
+    pub scope: LicenseScope,
+}
+
+/// Entitlement rule over license features.
+///
+/// A feature ending in `*` is a prefix wildcard: `gts.x.core.lic.feat.v1~x.core.*`
+/// is satisfied by any held feature starting with `gts.x.core.lic.feat.v1~x.core.`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LicenseRequirement {
+    /// A single feature (or wildcard) that must be held.
+    Feature(String),
+    /// Every nested requirement must be satisfied; an empty set always is.
+    AllOf(Vec<LicenseRequirement>),
+    /// At least one nested requirement must be satisfied; an empty set never is.
+    AnyOf(Vec<LicenseRequirement>),
+}
+
+impl LicenseRequirement {
+    /// Requirement holding every feature of `features`.
+    pub fn all_of<F: LicenseFeature>(features: impl IntoIterator<Item = F>) -> Self {
+        Self::AllOf(features.into_iter().map(Self::feature).collect())
+    }
+
+    /// Requirement holding at least one feature of `features`.
+    pub fn any_of<F: LicenseFeature>(features: impl IntoIterator<Item = F>) -> Self {
+        Self::AnyOf(features.into_iter().map(Self::feature).collect())
+    }
+
+    fn feature<F: LicenseFeature>(feature: F) -> Self {
+        Self::Feature(feature.as_ref().to_owned())
+    }
+
+    /// Whether the features in `held` satisfy this requirement.
+    #[must_use]
+    pub fn is_satisfied_by(&self, held: &[String]) -> bool {
+        match self {
+            Self::Feature(pattern) => match pattern.strip_suffix('*') {
+                Some(prefix) => held.iter().any(|f| f.starts_with(prefix)),
+                None => held.iter().any(|f| f == pattern),
+            },
+            Self::AllOf(all) => all.iter().all(|r| r.is_satisfied_by(held)),
+            Self::AnyOf(any) => any.iter().any(|r| r.is_satisfied_by(held)),
+        }
+    }
+
+    /// Features (or wildcards) of this requirement not covered by `held`:
+    /// none when it is satisfied, otherwise those of every unsatisfied branch.
+    #[must_use]
+    pub fn missing(&self, held: &[String]) -> Vec<String> {
+        if self.is_satisfied_by(held) {
+            return Vec::new();
+        }
+        match self {
+            Self::Feature(feature) => vec![feature.clone()],
+            Self::AllOf(nested) | Self::AnyOf(nested) => {
+                nested.iter().flat_map(|r| r.missing(held)).collect()
+            }
+        }
+    }
+
+    /// Every feature (or wildcard) the requirement mentions, in order.
+    #[must_use]
+    pub fn features(&self) -> Vec<String> {
+        match self {
+            Self::Feature(feature) => vec![feature.clone()],
+            Self::AllOf(nested) | Self::AnyOf(nested) => {
+                nested.iter().flat_map(Self::features).collect()
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for LicenseRequirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (op, nested) = match self {
+            Self::Feature(feature) => return f.write_str(feature),
+            Self::AllOf(nested) => ("all of", nested),
+            Self::AnyOf(nested) => ("any of", nested),
+        };
+        write!(f, "{op} (")?;
+        for (i, r) in nested.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{r}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// What a license requirement is checked against.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum LicenseScope {
+    /// Features the caller holds regardless of the resource.
+    #[default]
+    Global,
+    /// Features the caller holds for the resource identified by the path
+    /// parameter `path_param` (e.g. a tenant id).
+    Resource { path_param: String },
 }
 
 /// Simplified operation specification for the type-safe builder
@@ -821,19 +920,28 @@ where
     ///
     /// Pass an empty iterator (e.g. `[]`) to explicitly declare that no license feature is required.
     pub fn require_license_features<F>(
-        mut self,
+        self,
         licenses: impl IntoIterator<Item = F>,
     ) -> OperationBuilder<H, R, S, AuthSet, LicenseSet>
     where
         F: LicenseFeature,
     {
-        let license_names: Vec<String> = licenses
-            .into_iter()
-            .map(|l| l.as_ref().to_owned())
-            .collect();
+        self.require_license(LicenseRequirement::all_of(licenses))
+    }
 
-        self.spec.license_requirement =
-            (!license_names.is_empty()).then_some(LicenseReqSpec { license_names });
+    /// Require license features matching `requirement`, an any-of/all-of
+    /// expression that may use prefix wildcards.
+    ///
+    /// An empty all-of requirement declares that no license feature is required.
+    pub fn require_license(
+        mut self,
+        requirement: LicenseRequirement,
+    ) -> OperationBuilder<H, R, S, AuthSet, LicenseSet> {
+        let required = !matches!(&requirement, LicenseRequirement::AllOf(all) if all.is_empty());
+        self.spec.license_requirement = required.then_some(LicenseReqSpec {
+            requirement,
+            scope: LicenseScope::Global,
+        });
 
         OperationBuilder {
             spec: self.spec,
@@ -846,6 +954,27 @@ where
         }
     }
 
+    /// Like [`require_license_features`](Self::require_license_features), but
+    /// the features must be held for the resource identified by the path
+    /// parameter `path_param` rather than globally. A request whose matched
+    /// path lacks that parameter is denied.
+    pub fn require_resource_license_features<F>(
+        self,
+        path_param: &str,
+        licenses: impl IntoIterator<Item = F>,
+    ) -> OperationBuilder<H, R, S, AuthSet, LicenseSet>
+    where
+        F: LicenseFeature,
+    {
+        let mut builder = self.require_license_features(licenses);
+        if let Some(req) = builder.spec.license_requirement.as_mut() {
+            req.scope = LicenseScope::Resource {
+                path_param: path_param.to_owned(),
+            };
+        }
+        builder
+    }
+
     /// Explicitly declare that this operation does not require any license.
     ///
     /// Use this for system/infrastructure endpoints that need authentication
@@ -1864,7 +1993,10 @@ mod tests {
             .license_requirement
             .as_ref()
             .expect("Should have license requirement");
-        assert_eq!(license_req.license_names, vec!["feature_a".to_owned()]);
+        assert_eq!(
+            license_req.requirement,
+            LicenseRequirement::all_of([TestLicenseFeatures::FeatureA])
+        );
     }
 
     #[test]
@@ -1884,8 +2016,99 @@ mod tests {
             .as_ref()
             .expect("Should have license requirement");
         assert_eq!(
-            license_req.license_names,
-            vec!["feature_a".to_owned(), "feature_b".to_owned()]
+            license_req.requirement,
+            LicenseRequirement::all_of([feature_a, feature_b])
+        );
+    }
+
+    #[test]
+    fn require_license_accepts_an_expression() {
+        let requirement = LicenseRequirement::AllOf(vec![
+            LicenseRequirement::all_of([TestLicenseFeatures::FeatureA]),
+            LicenseRequirement::AnyOf(vec![
+                LicenseRequirement::Feature("feature_b".to_owned()),
+                LicenseRequirement::Feature("gts.x.core.*".to_owned()),
+            ]),
+        ]);
+
+        let builder = OperationBuilder::<Missing, Missing, ()>::get("/tests/v1/test")
+            .authenticated()
+            .require_license(requirement.clone())
+            .handler(|| async {})
+            .json_response(http::StatusCode::OK, "OK");
+
+        let license_req = builder
+            .spec
+            .license_requirement
+            .as_ref()
+            .expect("Should have license requirement");
+        assert_eq!(license_req.requirement, requirement);
+        assert_eq!(
+            requirement.to_string(),
+            "all of (all of (feature_a), any of (feature_b, gts.x.core.*))"
+        );
+    }
+
+    #[test]
+    fn license_requirement_evaluation() {
+        let held = ["feature_a".to_owned(), "gts.x.core.reports.v1".to_owned()];
+
+        let and = LicenseRequirement::all_of([
+            TestLicenseFeatures::FeatureA,
+            TestLicenseFeatures::FeatureB,
+        ]);
+        assert!(
+            !and.is_satisfied_by(&held),
+            "AND fails on one missing feature"
+        );
+
+        let or = LicenseRequirement::any_of([
+            TestLicenseFeatures::FeatureB,
+            TestLicenseFeatures::FeatureA,
+        ]);
+        assert!(or.is_satisfied_by(&held), "OR passes on one held feature");
+
+        let wildcard = LicenseRequirement::Feature("gts.x.core.*".to_owned());
+        assert!(wildcard.is_satisfied_by(&held));
+        let other = LicenseRequirement::Feature("gts.x.billing.*".to_owned());
+        assert!(!other.is_satisfied_by(&held));
+
+        assert_eq!(and.missing(&held), ["feature_b"]);
+        assert!(or.missing(&held).is_empty());
+        assert_eq!(
+            LicenseRequirement::AnyOf(vec![other, and]).missing(&held),
+            ["gts.x.billing.*", "feature_b"]
+        );
+
+        assert!(LicenseRequirement::AllOf(Vec::new()).is_satisfied_by(&[]));
+        assert!(!LicenseRequirement::AnyOf(Vec::new()).is_satisfied_by(&held));
+    }
+
+    #[test]
+    fn require_resource_license_features_scopes_the_requirement() {
+        let feature = TestLicenseFeatures::FeatureA;
+
+        let builder =
+            OperationBuilder::<Missing, Missing, ()>::get("/tests/v1/tenants/{tenant_id}")
+                .authenticated()
+                .require_resource_license_features("tenant_id", [&feature])
+                .handler(|| async {})
+                .json_response(http::StatusCode::OK, "OK");
+
+        let license_req = builder
+            .spec
+            .license_requirement
+            .as_ref()
+            .expect("Should have license requirement");
+        assert_eq!(
+            license_req.requirement,
+            LicenseRequirement::all_of([TestLicenseFeatures::FeatureA])
+        );
+        assert_eq!(
+            license_req.scope,
+            LicenseScope::Resource {
+                path_param: "tenant_id".to_owned()
+            }
         );
     }
 
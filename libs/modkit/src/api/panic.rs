@@ -0,0 +1,459 @@
+//! Panic capture on the response path
+//!
+//! [`catch_panic_middleware`] turns a panicking handler into a 500 Problem and
+//! logs the panic exactly once, in the format of
+//! `bootstrap::host::init_panic_tracing`. When that hook is installed it runs
+//! before unwinding and hands its report (with location and backtrace) to the
+//! middleware instead of logging it; otherwise the middleware builds the report
+//! from the caught payload. Reports are logged as separate fields or, for log
+//! pipelines, as one JSON field (see [`PanicLogFormat`]).
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::panic::{AssertUnwindSafe, PanicHookInfo};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::extract::Request;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use futures_util::FutureExt;
+
+use crate::api::error_layer::extract_trace_id;
+use crate::api::problem::INTERNAL_ERROR_V1;
+
+/// Details of a panic as logged by the process panic hook.
+#[derive(Debug, Clone)]
+pub struct PanicReport {
+    pub location: String,
+    pub payload: String,
+    pub backtrace: String,
+    /// Name of the panicking thread, or its id when unnamed.
+    pub thread: String,
+    /// Number of this panic in the process, counting from 1.
+    pub sequence: u64,
+}
+
+static PANIC_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// How [`PanicReport::log`] renders a panic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanicLogFormat {
+    /// One field per detail: `location`, `payload`, `backtrace`, `thread`
+    /// and `sequence`.
+    #[default]
+    Fields,
+    /// A single `panic_json` field holding the report as a JSON object (see
+    /// [`PanicReport::to_json`]), the backtrace cut to `max_backtrace_bytes`
+    /// when set.
+    Json { max_backtrace_bytes: Option<usize> },
+}
+
+static LOG_FORMAT: RwLock<PanicLogFormat> = RwLock::new(PanicLogFormat::Fields);
+
+/// Select the format of every panic logged from now on.
+pub fn set_panic_log_format(format: PanicLogFormat) {
+    *LOG_FORMAT
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = format;
+}
+
+/// The format panics are currently logged in.
+#[must_use]
+pub fn panic_log_format() -> PanicLogFormat {
+    *LOG_FORMAT
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn current_thread() -> String {
+    let thread = std::thread::current();
+    thread
+        .name()
+        .map_or_else(|| format!("{:?}", thread.id()), ToOwned::to_owned)
+}
+
+fn next_sequence() -> u64 {
+    PANIC_SEQUENCE.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+impl PanicReport {
+    /// Build a report from inside a panic hook.
+    #[must_use]
+    pub fn from_hook(info: &PanicHookInfo<'_>) -> Self {
+        let location = info.location().map_or_else(
+            || "unknown location".to_owned(),
+            |loc| format!("{}:{}:{}", loc.file(), loc.line(), loc.column()),
+        );
+        Self {
+            location,
+            payload: payload_to_string(info.payload()),
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+            thread: current_thread(),
+            sequence: next_sequence(),
+        }
+    }
+
+    /// Build a report from a payload caught after unwinding; the panic site is
+    /// no longer known at that point.
+    fn from_payload(payload: &(dyn Any + Send)) -> Self {
+        Self {
+            location: "unknown location".to_owned(),
+            payload: payload_to_string(payload),
+            backtrace: std::backtrace::Backtrace::capture().to_string(),
+            thread: current_thread(),
+            sequence: next_sequence(),
+        }
+    }
+
+    /// The report as a JSON object with `location`, `payload`, `thread`,
+    /// `sequence` and `backtrace`, the backtrace cut to at most
+    /// `max_backtrace_bytes` when given.
+    #[must_use]
+    pub fn to_json(&self, max_backtrace_bytes: Option<usize>) -> String {
+        let mut backtrace = self.backtrace.as_str();
+        if let Some(max) = max_backtrace_bytes
+            && backtrace.len() > max
+        {
+            let mut end = max;
+            while !backtrace.is_char_boundary(end) {
+                end -= 1;
+            }
+            backtrace = &backtrace[..end];
+        }
+        serde_json::json!({
+            "location": self.location,
+            "payload": self.payload,
+            "thread": self.thread,
+            "sequence": self.sequence,
+            "backtrace": backtrace,
+        })
+        .to_string()
+    }
+
+    /// Emit the single `PANIC` error event for this report, in the current
+    /// [`PanicLogFormat`].
+    pub fn log(&self) {
+        if let PanicLogFormat::Json {
+            max_backtrace_bytes,
+        } = panic_log_format()
+        {
+            tracing::error!(panic_json = %self.to_json(max_backtrace_bytes), "PANIC");
+            return;
+        }
+        tracing::error!(
+            location = %self.location,
+            payload = %self.payload,
+            backtrace = %self.backtrace,
+            thread = %self.thread,
+            sequence = self.sequence,
+            "PANIC"
+        );
+    }
+}
+
+fn payload_to_string(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_owned()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "non-string panic payload".to_owned()
+    }
+}
+
+thread_local! {
+    /// Number of `catch_panic_middleware` polls active on this thread.
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+    /// Report handed over by the panic hook to the enclosing middleware.
+    static CAPTURED: RefCell<Option<PanicReport>> = const { RefCell::new(None) };
+}
+
+/// Entry point for the process panic hook.
+///
+/// Logs the report immediately, unless the panic happens while
+/// [`catch_panic_middleware`] polls a handler on this thread: the report is
+/// then left for the middleware, which logs it once it has caught the panic.
+pub fn report_panic(info: &PanicHookInfo<'_>) {
+    let report = PanicReport::from_hook(info);
+    if CATCHING.get() == 0 {
+        report.log();
+        return;
+    }
+    if let Some(previous) = CAPTURED.replace(Some(report)) {
+        previous.log();
+    }
+}
+
+/// Install `hook` in front of the current panic hook instead of replacing it.
+///
+/// On a panic `hook` runs first, then the previously installed hook (the
+/// standard stderr printer if none was set), so hooks installed by test
+/// harnesses or other libraries keep working. The previous hook is skipped
+/// for panics raised under [`catch_panic_middleware`], which already turns
+/// them into a single log event and a 500 response. Swapping hooks is not
+/// atomic: concurrent installations may still lose one of them.
+pub fn chain_panic_hook<F>(hook: F)
+where
+    F: Fn(&PanicHookInfo<'_>) + Send + Sync + 'static,
+{
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        hook(info);
+        if CATCHING.get() == 0 {
+            previous(info);
+        }
+    }));
+}
+
+/// Marks this thread as polling under [`catch_panic_middleware`].
+struct CatchGuard;
+
+impl CatchGuard {
+    fn enter() -> Self {
+        CATCHING.set(CATCHING.get() + 1);
+        Self
+    }
+}
+
+impl Drop for CatchGuard {
+    fn drop(&mut self) {
+        CATCHING.set(CATCHING.get().saturating_sub(1));
+    }
+}
+
+/// Middleware converting a panicking handler into a 500 [`INTERNAL_ERROR_V1`]
+/// Problem carrying the request path and trace id.
+pub async fn catch_panic_middleware(request: Request, next: Next) -> Response {
+    let instance = request.uri().path().to_owned();
+    let trace_id = extract_trace_id(request.headers());
+    let mut future = std::pin::pin!(next.run(request));
+    let guarded = std::future::poll_fn(move |cx| {
+        let poll = {
+            let _guard = CatchGuard::enter();
+            future.as_mut().poll(cx)
+        };
+        // A panic caught further down (not by us) must still be logged.
+        if let Some(report) = CAPTURED.take() {
+            report.log();
+        }
+        poll
+    });
+
+    match AssertUnwindSafe(guarded).catch_unwind().await {
+        Ok(response) => response,
+        Err(payload) => {
+            CAPTURED
+                .take()
+                .unwrap_or_else(|| PanicReport::from_payload(payload.as_ref()))
+                .log();
+            let mut problem = INTERNAL_ERROR_V1
+                .as_problem("The server encountered an unexpected error")
+                .with_instance(instance);
+            if let Some(trace_id) = trace_id {
+                problem = problem.with_trace_id(trace_id);
+            }
+            problem.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::test_support::EventCapture;
+    use axum::Router;
+    use axum::body::Body;
+    use axum::routing::get;
+    use http::StatusCode;
+    use std::sync::{Arc, Mutex};
+    use tower::ServiceExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// Capture of the `PANIC` error events.
+    fn panic_events() -> EventCapture {
+        EventCapture::matching(|_, fields| {
+            fields.contains_key("payload") || fields.contains_key("panic_json")
+        })
+    }
+
+    async fn boom() -> &'static str {
+        panic!("handler exploded")
+    }
+
+    async fn call(app: Router) -> Response {
+        app.oneshot(
+            Request::get("/boom")
+                .header("x-request-id", "req-7")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Serializes tests swapping the process-wide panic hook.
+    static HOOK_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    fn app() -> Router {
+        Router::new()
+            .route("/boom", get(boom))
+            .layer(axum::middleware::from_fn(catch_panic_middleware))
+    }
+
+    #[tokio::test]
+    async fn panicking_handler_yields_one_log_and_problem() {
+        let events = panic_events();
+        let _guard = events.install();
+
+        // Simulates the installed panic hook on this thread.
+        let _hook = HOOK_LOCK.lock().await;
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(report_panic));
+        let response = call(app()).await;
+        std::panic::set_hook(previous);
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.headers()["x-error-code"], INTERNAL_ERROR_V1.code);
+        assert_eq!(
+            response.headers()["content-type"],
+            "application/problem+json; version=1"
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], 500);
+        assert_eq!(json["instance"], "/boom");
+        assert_eq!(json["trace_id"], "req-7");
+
+        let logged = events.events();
+        assert_eq!(logged.len(), 1, "{logged:?}");
+        assert!(logged[0]["payload"].contains("handler exploded"));
+    }
+
+    #[test]
+    fn chained_hook_runs_before_the_previous_one() {
+        let _hook = HOOK_LOCK.blocking_lock();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+
+        let original = std::panic::take_hook();
+        let first = calls.clone();
+        std::panic::set_hook(Box::new(move |_| first.lock().unwrap().push("first")));
+        let second = calls.clone();
+        chain_panic_hook(move |_| second.lock().unwrap().push("second"));
+
+        let result = std::panic::catch_unwind(|| panic!("chained"));
+        std::panic::set_hook(original);
+
+        assert!(result.is_err());
+        assert_eq!(*calls.lock().unwrap(), ["second", "first"]);
+    }
+
+    #[tokio::test]
+    async fn chained_previous_hook_is_skipped_under_the_middleware() {
+        let events = panic_events();
+        let _guard = events.install();
+
+        let _hook = HOOK_LOCK.lock().await;
+        let original = std::panic::take_hook();
+        // A previous hook that logs on its own, like a second panic logger.
+        std::panic::set_hook(Box::new(|info| {
+            tracing::error!(payload = %payload_to_string(info.payload()), "previous hook");
+        }));
+        chain_panic_hook(report_panic);
+        let response = call(app()).await;
+        std::panic::set_hook(original);
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let logged = events.events();
+        assert_eq!(logged.len(), 1, "{logged:?}");
+        assert!(logged[0]["payload"].contains("handler exploded"));
+    }
+
+    #[test]
+    fn hook_logs_directly_outside_the_middleware() {
+        let events = panic_events();
+        let _guard = events.install();
+
+        let _hook = HOOK_LOCK.blocking_lock();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(report_panic));
+        let result = std::panic::catch_unwind(|| panic!("outside"));
+        std::panic::set_hook(previous);
+
+        assert!(result.is_err());
+        assert_eq!(events.events().len(), 1);
+        assert!(CAPTURED.take().is_none());
+    }
+
+    #[test]
+    fn concurrent_panics_are_told_apart() {
+        let events = panic_events();
+        let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(events.clone()));
+
+        let _hook = HOOK_LOCK.blocking_lock();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(report_panic));
+        let workers: Vec<_> = ["worker-a", "worker-b"]
+            .into_iter()
+            .map(|name| {
+                let dispatch = dispatch.clone();
+                std::thread::Builder::new()
+                    .name(name.to_owned())
+                    .spawn(move || {
+                        tracing::dispatcher::with_default(&dispatch, || panic!("{name}"))
+                    })
+                    .unwrap()
+            })
+            .collect();
+        for worker in workers {
+            assert!(worker.join().is_err());
+        }
+        std::panic::set_hook(previous);
+
+        let logged = events.events();
+        let mut threads: Vec<&str> = logged.iter().map(|e| e["thread"].as_str()).collect();
+        threads.sort_unstable();
+        assert_eq!(threads, ["worker-a", "worker-b"]);
+        let sequences: Vec<u64> = logged
+            .iter()
+            .map(|e| e["sequence"].parse().unwrap())
+            .collect();
+        assert!(sequences.iter().all(|&n| n > 0));
+        assert_ne!(sequences[0], sequences[1]);
+    }
+
+    #[test]
+    fn json_format_round_trips_payload_and_location() {
+        let events = panic_events();
+        let _guard = events.install();
+
+        let _hook = HOOK_LOCK.blocking_lock();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(report_panic));
+        set_panic_log_format(PanicLogFormat::Json {
+            max_backtrace_bytes: Some(16),
+        });
+        let line = line!() + 1;
+        let result = std::panic::catch_unwind(|| panic!("json payload"));
+        set_panic_log_format(PanicLogFormat::Fields);
+        std::panic::set_hook(previous);
+        assert!(result.is_err());
+
+        let logged = events.events();
+        assert_eq!(logged.len(), 1, "{logged:?}");
+        assert!(!logged[0].contains_key("payload"), "{logged:?}");
+        let json: serde_json::Value = serde_json::from_str(&logged[0]["panic_json"]).unwrap();
+        assert_eq!(json["payload"], "json payload");
+        let location = json["location"].as_str().unwrap();
+        assert!(
+            location.starts_with(&format!("{}:{line}:", file!())),
+            "{location}"
+        );
+        assert!(json["backtrace"].as_str().unwrap().len() <= 16);
+        assert!(json["sequence"].as_u64().unwrap() > 0);
+        assert!(json["thread"].is_string());
+    }
+}
@@ -2,24 +2,60 @@
 
 use http::StatusCode;
 
+pub use modkit_errors::catalog::{
+    BAD_GATEWAY_V1, BAD_REQUEST_V1, CONFLICT_V1, FORBIDDEN_V1, FRAMEWORK_ERRORS, INTERNAL_ERROR_V1,
+    MISSING_FEATURES_METADATA_KEY, NOT_FOUND_V1, PAYLOAD_TOO_LARGE_V1, PAYMENT_REQUIRED_V1,
+    SERVICE_UNAVAILABLE_V1, TOO_MANY_REQUESTS_V1, UNAUTHORIZED_V1, UNSUPPORTED_MEDIA_TYPE_V1,
+    VALIDATION_FAILED_V1,
+};
 pub use modkit_errors::problem::{
-    APPLICATION_PROBLEM_JSON, Problem, ValidationError, ValidationErrorResponse,
-    ValidationViolation,
+    APPLICATION_PROBLEM_JSON, APPLICATION_PROBLEM_JSON_VERSIONED, ERROR_CODE_HEADER,
+    PROBLEM_SCHEMA_VERSION, PRODUCTION_SAFE_DETAIL, PRODUCTION_SAFE_METADATA_KEYS, Problem,
+    ProblemBuilder, RATE_LIMIT_METADATA_KEYS, RemediationAction, SCHEMA_ID_METADATA_KEY, Severity,
+    TRACE_ID_HEADER, ValidationError, ValidationErrorResponse, ValidationViolation,
+    default_retry_after_secs, is_production_mode, max_error_header_len,
+    set_default_retry_after_secs, set_max_error_header_len, set_production_mode,
 };
+pub use modkit_errors::remap::{CodeRemapper, RemapRule, clear_code_remapper, set_code_remapper};
+
+/// Register the framework's error catalogs ([`FRAMEWORK_ERRORS`] and the
+/// `OData` errors) for `Problem::checked_against_catalog` and
+/// `ProblemRegistry::from_catalog`. Modules register their own catalogs with
+/// `ErrorCode::register_catalog` during `init`.
+pub fn register_framework_catalogs() {
+    modkit_errors::catalog::register_catalog(FRAMEWORK_ERRORS.iter().copied());
+    modkit_odata::errors::ErrorCode::register_catalog();
+}
 
 // Optional convenience constructors that return `Problem` directly
 pub fn bad_request(detail: impl Into<String>) -> Problem {
     Problem::new(StatusCode::BAD_REQUEST, "Bad Request", detail)
 }
 
+pub fn unauthorized(detail: impl Into<String>) -> Problem {
+    UNAUTHORIZED_V1.as_problem(detail)
+}
+
 pub fn not_found(detail: impl Into<String>) -> Problem {
     Problem::new(StatusCode::NOT_FOUND, "Not Found", detail)
 }
 
+/// 422 Validation Failed listing each offending field.
+pub fn validation_failed(detail: impl Into<String>, errors: Vec<ValidationViolation>) -> Problem {
+    VALIDATION_FAILED_V1.as_problem(detail).with_errors(errors)
+}
+
 pub fn conflict(detail: impl Into<String>) -> Problem {
     Problem::new(StatusCode::CONFLICT, "Conflict", detail)
 }
 
+/// 429 telling the client to retry after `retry_after_seconds`.
+pub fn too_many_requests(detail: impl Into<String>, retry_after_seconds: u64) -> Problem {
+    TOO_MANY_REQUESTS_V1
+        .as_problem(detail)
+        .with_retry_after(retry_after_seconds)
+}
+
 pub fn internal_error(detail: impl Into<String>) -> Problem {
     Problem::new(
         StatusCode::INTERNAL_SERVER_ERROR,
@@ -34,6 +70,21 @@ mod tests {
     use super::*;
     use axum::response::IntoResponse;
 
+    #[test]
+    fn framework_catalogs_are_registered() {
+        register_framework_catalogs();
+        let registry = modkit_errors::registry::ProblemRegistry::from_catalog();
+        let invalid_filter =
+            modkit_odata::errors::ErrorCode::odata_errors_invalid_filter_v1().def();
+        assert_eq!(registry.get(invalid_filter.type_url), Some(&invalid_filter));
+        for def in FRAMEWORK_ERRORS {
+            assert_eq!(
+                modkit_errors::catalog::registered_def(def.type_url),
+                Some(*def)
+            );
+        }
+    }
+
     #[test]
     fn problem_into_response_sets_status_and_content_type() {
         use axum::http::StatusCode;
@@ -46,7 +97,8 @@ mod tests {
             .get(axum::http::header::CONTENT_TYPE)
             .and_then(|v| v.to_str().ok())
             .unwrap_or("");
-        assert_eq!(ct, APPLICATION_PROBLEM_JSON);
+        assert_eq!(ct, APPLICATION_PROBLEM_JSON_VERSIONED);
+        assert!(ct.starts_with(APPLICATION_PROBLEM_JSON));
     }
 
     #[test]
@@ -65,6 +117,7 @@ mod tests {
             message: "Email is required".to_owned(),
             field: "email".to_owned(),
             code: None,
+            pointer: None,
         }]);
 
         assert_eq!(p.status, StatusCode::UNPROCESSABLE_ENTITY);
@@ -83,6 +136,19 @@ mod tests {
         assert_eq!(bad_req.status, StatusCode::BAD_REQUEST);
         assert_eq!(bad_req.title, "Bad Request");
 
+        let unauthorized_resp = unauthorized("Token expired");
+        assert_eq!(unauthorized_resp.status, StatusCode::UNAUTHORIZED);
+        assert_eq!(unauthorized_resp.title, "Unauthorized");
+        assert_eq!(unauthorized_resp.type_url, UNAUTHORIZED_V1.type_url);
+
+        let invalid = validation_failed(
+            "1 field is invalid",
+            vec![ValidationViolation::at_pointer("/email", "required")],
+        );
+        assert_eq!(invalid.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(invalid.type_url, VALIDATION_FAILED_V1.type_url);
+        assert_eq!(invalid.errors.unwrap()[0].field, "email");
+
         let not_found_resp = not_found("User not found");
         assert_eq!(not_found_resp.status, StatusCode::NOT_FOUND);
         assert_eq!(not_found_resp.title, "Not Found");
@@ -91,6 +157,10 @@ mod tests {
         assert_eq!(conflict_resp.status, StatusCode::CONFLICT);
         assert_eq!(conflict_resp.title, "Conflict");
 
+        let throttled = too_many_requests("Quota exhausted", 30);
+        assert_eq!(throttled.status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(throttled.metadata.unwrap()["retry_after_seconds"], 30);
+
         let internal_resp = internal_error("Database connection failed");
         assert_eq!(internal_resp.status, StatusCode::INTERNAL_SERVER_ERROR);
         assert_eq!(internal_resp.title, "Internal Server Error");
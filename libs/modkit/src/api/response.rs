@@ -1,30 +1,51 @@
 use axum::{
     Json,
-    http::{StatusCode, Uri, header},
+    http::{HeaderValue, StatusCode, Uri, header},
     response::IntoResponse,
 };
 
 /// Short aliases for JSON responses
 pub type JsonBody<T> = Json<T>;
 pub type JsonPage<T> = Json<modkit_odata::Page<T>>;
+pub type JsonODataPage<T> = Json<modkit_odata::ODataPage<T>>;
 
 /// 200 OK + JSON
 pub fn ok_json<T: serde::Serialize>(value: T) -> impl IntoResponse {
     (StatusCode::OK, Json(value))
 }
 
+/// 200 OK + OData-annotated page (`@odata.count`, `value`, `@odata.nextLink`)
+///
+/// For endpoints serving OData-compliant clients; the next link is built on the
+/// request path and query, with only the paging options replaced. Pass `count`
+/// when the total number of matches is known.
+pub fn odata_page_json<T: serde::Serialize>(
+    page: modkit_odata::Page<T>,
+    uri: &Uri,
+    count: Option<u64>,
+) -> impl IntoResponse + use<T> {
+    let base = uri.path_and_query().map_or(uri.path(), |pq| pq.as_str());
+    (StatusCode::OK, Json(page.into_odata(base, count)))
+}
+
 /// 201 Created + JSON with Location header
+///
+/// `new_id` is percent-encoded as a single path segment, so ids containing
+/// `/`, `?`, spaces or non-ASCII characters still point at the new resource.
 pub fn created_json<T: serde::Serialize>(
     value: T,
     uri: &Uri,
     new_id: &str,
 ) -> impl IntoResponse + use<T> {
-    let location = [uri.path().trim_end_matches('/'), new_id].join("/");
-    (
-        StatusCode::CREATED,
-        [(header::LOCATION, location)],
-        Json(value),
-    )
+    let location = format!(
+        "{}/{}",
+        uri.path().trim_end_matches('/'),
+        urlencoding::encode(new_id)
+    );
+    let location = HeaderValue::from_str(&location)
+        .ok()
+        .map(|location| [(header::LOCATION, location)]);
+    (StatusCode::CREATED, location, Json(value))
 }
 
 /// 204 No Content
@@ -32,3 +53,34 @@ pub fn created_json<T: serde::Serialize>(
 pub fn no_content() -> impl IntoResponse {
     StatusCode::NO_CONTENT
 }
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn created_json_points_at_the_new_resource() {
+        let uri: Uri = "/users/".parse().unwrap();
+        let resp = created_json("ok", &uri, "42").into_response();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers()[header::LOCATION], "/users/42");
+    }
+
+    #[test]
+    fn created_json_percent_encodes_the_id() {
+        let uri: Uri = "/users".parse().unwrap();
+        let resp = created_json("ok", &uri, "42\r\nset-cookie: x").into_response();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(
+            resp.headers()[header::LOCATION],
+            "/users/42%0D%0Aset-cookie%3A%20x"
+        );
+
+        let resp = created_json("ok", &uri, "a/b?c=\u{e9}").into_response();
+        assert_eq!(
+            resp.headers()[header::LOCATION],
+            "/users/a%2Fb%3Fc%3D%C3%A9"
+        );
+    }
+}
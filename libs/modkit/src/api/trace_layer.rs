@@ -5,15 +5,41 @@
 //! - `trace_id`: extracted from the current tracing span
 //! - `instance`: extracted from the request URI
 //!
+//! With the `otel` feature the `trace_id` is the 128-bit OpenTelemetry trace id
+//! of the current span, i.e. the id the collector sees. Spans without a valid
+//! OpenTelemetry context (no OpenTelemetry layer installed, or tracing disabled) fall back
+//! to the local span id, rendered as 32 hex digits.
+//!
 //! This eliminates per-callsite boilerplate and ensures consistent error reporting.
 
 use crate::api::problem::Problem;
 
-/// Extract `trace_id` from the current tracing span
-fn extract_trace_id() -> Option<String> {
-    // Try to extract from the current span's trace_id field
-    // This requires coordination with the tracing subscriber
-    tracing::Span::current().id().map(|id| format!("{id:?}"))
+/// Trace id of the current tracing span (see the module docs for its format).
+///
+/// Every error path that falls back to the span uses this, so a trace id looks
+/// the same whichever layer filled it in.
+pub(crate) fn current_trace_id() -> Option<String> {
+    #[cfg(feature = "otel")]
+    if let Some(trace_id) = otel_trace_id() {
+        return Some(trace_id);
+    }
+    tracing::Span::current()
+        .id()
+        .map(|id| format!("{:032x}", id.into_u64()))
+}
+
+/// Trace id of the OpenTelemetry context attached to the current span.
+#[cfg(feature = "otel")]
+fn otel_trace_id() -> Option<String> {
+    use opentelemetry::trace::TraceContextExt;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    span_context
+        .is_valid()
+        .then(|| span_context.trace_id().to_string())
 }
 
 /// Helper trait for enriching Problem with trace context
@@ -26,7 +52,7 @@ pub trait WithTraceContext {
 impl WithTraceContext for Problem {
     fn with_trace_context(mut self, instance: impl Into<String>) -> Self {
         self = self.with_instance(instance);
-        if let Some(tid) = extract_trace_id() {
+        if let Some(tid) = current_trace_id() {
             self = self.with_trace_id(tid);
         }
         self
@@ -85,4 +111,50 @@ mod tests {
 
         assert_eq!(problem.instance, "/tests/v1/users/123");
     }
+
+    #[test]
+    fn local_span_id_is_the_fallback() {
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry());
+        let span = tracing::info_span!("request");
+        let _entered = span.enter();
+
+        let problem = Problem::new(http::StatusCode::NOT_FOUND, "Not Found", "missing")
+            .with_trace_context("/users");
+        let trace_id = problem.trace_id.expect("span id is used as trace id");
+        assert_eq!(trace_id.len(), 32);
+        assert!(trace_id.bytes().all(|b| b.is_ascii_hexdigit()));
+    }
+
+    #[cfg(feature = "otel")]
+    #[test]
+    fn otel_trace_id_is_picked_up() {
+        use opentelemetry::trace::{
+            SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState, TracerProvider,
+        };
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+
+        let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let remote = SpanContext::new(
+            TraceId::from_hex(TRACE_ID).unwrap(),
+            SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        let span = tracing::info_span!("request");
+        span.set_parent(opentelemetry::Context::new().with_remote_span_context(remote))
+            .unwrap();
+        let _entered = span.enter();
+
+        let problem = Problem::new(http::StatusCode::NOT_FOUND, "Not Found", "missing")
+            .with_trace_context("/users");
+        assert_eq!(problem.trace_id.as_deref(), Some(TRACE_ID));
+    }
 }
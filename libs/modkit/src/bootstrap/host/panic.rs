@@ -1,26 +1,84 @@
-use std::sync::Once;
+use std::sync::OnceLock;
 
-static PANIC_HOOK_INIT: Once = Once::new();
+pub use crate::api::panic::PanicLogFormat;
 
+/// Options for [`init_panic_tracing_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PanicTracingOptions {
+    /// Keep the previously installed panic hook and run it after logging
+    /// (default: `true`), except for panics caught by
+    /// `api::panic::catch_panic_middleware`. When `false` the previous hook is
+    /// replaced.
+    pub chain_previous: bool,
+    /// How panics are logged (default: one field per detail).
+    pub format: PanicLogFormat,
+}
+
+impl Default for PanicTracingOptions {
+    fn default() -> Self {
+        Self {
+            chain_previous: true,
+            format: PanicLogFormat::Fields,
+        }
+    }
+}
+
+static PANIC_HOOK_INIT: OnceLock<PanicTracingOptions> = OnceLock::new();
+
+/// Install a panic hook logging every panic as one `PANIC` error event.
+///
+/// Panics caught by `api::panic::catch_panic_middleware` are handed to the
+/// middleware and logged there, without running the previous hook, so each
+/// panic is logged exactly once.
 pub fn init_panic_tracing() {
-    PANIC_HOOK_INIT.call_once(|| {
-        std::panic::set_hook(Box::new(|panic_info| {
-            let backtrace = std::backtrace::Backtrace::force_capture();
-            let location = panic_info.location().map_or_else(
-                || "unknown location".to_owned(),
-                |loc| format!("{}:{}:{}", loc.file(), loc.line(), loc.column()),
-            );
-            let payload = if let Some(s) = panic_info.payload().downcast_ref::<&str>() {
-                (*s).to_owned()
-            } else if let Some(s) = panic_info.payload().downcast_ref::<String>() {
-                s.clone()
-            } else {
-                "non-string panic payload".to_owned()
-            };
-
-            tracing::error!(%location, %payload, %backtrace, "PANIC");
-        }));
+    init_panic_tracing_with(PanicTracingOptions::default());
+}
 
-        tracing::debug!("tracing of panic is initialized");
+/// Like [`init_panic_tracing`], but replacing the previously installed panic
+/// hook instead of running it after logging.
+pub fn init_panic_tracing_exclusive() {
+    init_panic_tracing_with(PanicTracingOptions {
+        chain_previous: false,
+        ..PanicTracingOptions::default()
     });
 }
+
+/// Like [`init_panic_tracing`], but logging each panic as a single
+/// `panic_json` field holding `{location, payload, thread, sequence,
+/// backtrace}`, for log pipelines that ingest JSON.
+pub fn init_panic_tracing_json() {
+    init_panic_tracing_with(PanicTracingOptions {
+        format: PanicLogFormat::Json {
+            max_backtrace_bytes: None,
+        },
+        ..PanicTracingOptions::default()
+    });
+}
+
+/// Like [`init_panic_tracing`], with explicit options.
+///
+/// The hook is installed once per process. Later calls are no-ops; a later
+/// call asking for different options is reported with a warning and ignored.
+pub fn init_panic_tracing_with(options: PanicTracingOptions) {
+    let mut installed = false;
+    let active = PANIC_HOOK_INIT.get_or_init(|| {
+        installed = true;
+        crate::api::panic::set_panic_log_format(options.format);
+        if options.chain_previous {
+            crate::api::panic::chain_panic_hook(crate::api::panic::report_panic);
+        } else {
+            std::panic::set_hook(Box::new(crate::api::panic::report_panic));
+        }
+        options
+    });
+
+    if installed {
+        tracing::debug!("tracing of panic is initialized");
+    } else if *active != options {
+        tracing::warn!(
+            active = ?active,
+            requested = ?options,
+            "panic tracing already initialized with different options; keeping the first"
+        );
+    }
+}
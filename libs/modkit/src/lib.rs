@@ -158,3 +158,6 @@ pub use runtime::{
 
 #[cfg(feature = "bootstrap")]
 pub mod bootstrap;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_support;
@@ -0,0 +1,104 @@
+//! Helpers for tests of code built on modkit
+//!
+//! Available to modkit's own tests and, through the `test-utils` feature,
+//! to the tests of other crates.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use tracing_subscriber::layer::SubscriberExt;
+
+pub use modkit_errors::GLOBAL_STATE_LOCK;
+
+/// Fields of one captured `tracing` event, rendered as strings.
+pub type EventFields = HashMap<String, String>;
+
+type Accept = dyn Fn(&tracing::Metadata<'_>, &EventFields) -> bool + Send + Sync;
+
+/// Layer recording the fields of the `tracing` events it accepts.
+///
+/// String fields are kept as given; every other field is rendered with
+/// `Debug`.
+#[derive(Clone)]
+pub struct EventCapture {
+    accept: Arc<Accept>,
+    events: Arc<Mutex<Vec<EventFields>>>,
+}
+
+impl EventCapture {
+    /// Capture every event.
+    #[must_use]
+    pub fn all() -> Self {
+        Self::matching(|_, _| true)
+    }
+
+    /// Capture the events logged on `target`.
+    #[must_use]
+    pub fn target(target: &'static str) -> Self {
+        Self::matching(move |metadata, _| metadata.target() == target)
+    }
+
+    /// Capture the events for which `accept` holds.
+    #[must_use]
+    pub fn matching(
+        accept: impl Fn(&tracing::Metadata<'_>, &EventFields) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            accept: Arc::new(accept),
+            events: Arc::default(),
+        }
+    }
+
+    /// Make this layer the thread's default subscriber until the guard drops.
+    #[must_use]
+    pub fn install(&self) -> tracing::subscriber::DefaultGuard {
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(self.clone()))
+    }
+
+    /// The events captured so far.
+    #[must_use]
+    pub fn events(&self) -> Vec<EventFields> {
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// The `message` of each event captured so far, empty when it has none.
+    #[must_use]
+    pub fn messages(&self) -> Vec<String> {
+        self.events()
+            .into_iter()
+            .map(|mut fields| fields.remove("message").unwrap_or_default())
+            .collect()
+    }
+}
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for EventCapture {
+    fn on_event(
+        &self,
+        event: &tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let mut visitor = FieldVisitor(HashMap::new());
+        event.record(&mut visitor);
+        if (self.accept)(event.metadata(), &visitor.0) {
+            self.events
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(visitor.0);
+        }
+    }
+}
+
+struct FieldVisitor(EventFields);
+
+impl tracing::field::Visit for FieldVisitor {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_owned(), format!("{value:?}"));
+    }
+}
@@ -0,0 +1,90 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+//! Public field names are translated before a query reaches the database.
+
+use axum::{
+    Extension, Router,
+    body::to_bytes,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use modkit::api::odata::OData;
+use modkit_db::odata::{FieldMap, ODataExt, ODataOrderExt};
+use modkit_odata::{FieldAliases, filter::FieldKind};
+use sea_orm::{DbBackend, EntityTrait, QueryTrait};
+use tower::ServiceExt;
+
+mod ent {
+    use sea_orm::entity::prelude::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "users")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i64,
+        pub created_at: i64,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Answers with the SQL the query compiles to, keyed by internal names only.
+async fn users(OData(query): OData) -> String {
+    let fields = FieldMap::<ent::Entity>::new()
+        .insert("id", ent::Column::Id, FieldKind::I64)
+        .insert("created_at", ent::Column::CreatedAt, FieldKind::I64);
+    let order = query.order.clone();
+    ent::Entity::find()
+        .apply_odata_filter(query, &fields)
+        .unwrap()
+        .apply_odata_order(&order, &fields)
+        .unwrap()
+        .build(DbBackend::Sqlite)
+        .to_string()
+}
+
+fn app() -> Router {
+    let aliases = FieldAliases::new()
+        .with_alias("id", "id")
+        .with_alias("createdAt", "created_at");
+    Router::new()
+        .route("/users", get(users))
+        .layer(Extension(aliases))
+}
+
+async fn call(uri: &str) -> (StatusCode, String) {
+    let req = Request::builder()
+        .uri(uri)
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let resp = app().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn aliased_fields_reach_the_database_as_columns() {
+    let (status, sql) =
+        call("/users?%24filter=createdAt%20gt%205&%24orderby=createdAt%20desc").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(sql.contains(r#"WHERE "created_at" > 5"#), "{sql}");
+    assert!(
+        sql.contains(r#"ORDER BY "users"."created_at" DESC"#),
+        "{sql}"
+    );
+}
+
+#[tokio::test]
+async fn unknown_public_names_are_rejected() {
+    let (status, body) = call("/users?%24filter=created_at%20gt%205").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body.contains("unknown field: created_at"), "{body}");
+
+    let (status, body) = call("/users?%24orderby=created_at").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body.contains("created_at"), "{body}");
+}
@@ -0,0 +1,87 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+//! Following `@odata.nextLink` must reach the next page of the same listing.
+
+use axum::{
+    Router,
+    body::to_bytes,
+    http::{Request, StatusCode, Uri},
+    response::IntoResponse,
+    routing::get,
+};
+use modkit::api::odata::{OData, odata_error_to_problem};
+use modkit::api::response::odata_page_json;
+use modkit_odata::{CursorV1, ODataOrderBy, Page, PageInfo, SortDir, validate_cursor_against};
+use tower::ServiceExt;
+
+/// Lists 1..=5, two at a time, with cursors bound to the request filter.
+async fn numbers(uri: Uri, OData(query): OData) -> axum::response::Response {
+    let order = ODataOrderBy::from_signed_tokens("+id").unwrap();
+    let start = match &query.cursor {
+        Some(cursor) => {
+            if let Err(e) = validate_cursor_against(cursor, &order, query.filter_hash.as_deref()) {
+                return odata_error_to_problem(&e, uri.path(), None).into_response();
+            }
+            cursor.k[0].parse::<u64>().unwrap() + 1
+        }
+        None => 1,
+    };
+    let limit = query.limit.unwrap_or(2);
+    let items: Vec<u64> = (start..=5).take(usize::try_from(limit).unwrap()).collect();
+    let next_cursor = items.last().filter(|&&last| last < 5).map(|last| {
+        CursorV1 {
+            k: vec![last.to_string()],
+            o: SortDir::Asc,
+            s: order.to_signed_tokens(),
+            f: query.filter_hash.clone(),
+            d: "fwd".to_owned(),
+        }
+        .encode()
+        .unwrap()
+    });
+    let page = Page::new(
+        items,
+        PageInfo {
+            next_cursor,
+            prev_cursor: None,
+            limit,
+            total: None,
+        },
+    );
+    odata_page_json(page, &uri, None).into_response()
+}
+
+async fn get_json(app: &Router, uri: &str) -> serde_json::Value {
+    let req = Request::builder()
+        .uri(uri)
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(
+        status,
+        StatusCode::OK,
+        "GET {uri}: {}",
+        String::from_utf8_lossy(&body)
+    );
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn next_link_with_filter_reaches_the_next_page() {
+    let app = Router::new().route("/numbers", get(numbers));
+
+    let first = get_json(&app, "/numbers?$filter=id%20gt%200&$top=2&details=true").await;
+    assert_eq!(first["value"], serde_json::json!([1, 2]));
+    let next = first["@odata.nextLink"].as_str().unwrap();
+    assert!(next.starts_with("/numbers?$filter=id%20gt%200&details=true&limit=2&cursor="));
+    assert_eq!(next.matches("limit=").count(), 1, "{next}");
+
+    let second = get_json(&app, next).await;
+    assert_eq!(second["value"], serde_json::json!([3, 4]));
+
+    let third = get_json(&app, second["@odata.nextLink"].as_str().unwrap()).await;
+    assert_eq!(third["value"], serde_json::json!([5]));
+    assert!(third.get("@odata.nextLink").is_none());
+}
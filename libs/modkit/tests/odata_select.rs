@@ -54,6 +54,18 @@ fn test_parse_select_duplicate_fields() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_parse_select_invalid_is_unprocessable() {
+    let problem = parse_select("id, id").unwrap_err();
+    assert_eq!(problem.status, http::StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        problem.type_url,
+        modkit_odata::errors::ErrorCode::odata_errors_invalid_select_v1()
+            .def()
+            .type_url
+    );
+}
+
 #[test]
 fn test_parse_select_too_long() {
     let long_string = "a".repeat(3000);
@@ -77,7 +89,11 @@ fn test_odata_params_with_select() {
         filter: None,
         orderby: None,
         select: Some("id, name".to_owned()),
+        compute: None,
         limit: None,
+        top: None,
+        skip: None,
+        count: None,
         cursor: None,
     };
     assert_eq!(params.select, Some("id, name".to_owned()));
@@ -475,6 +491,7 @@ fn test_page_to_projected_json() {
             next_cursor: Some("abc123".to_owned()),
             prev_cursor: None,
             limit: 10,
+            total: None,
         },
     };
 
@@ -531,6 +548,7 @@ fn test_page_to_projected_json_without_fields() {
             next_cursor: None,
             prev_cursor: None,
             limit: 20,
+            total: None,
         },
     };
 
@@ -3,7 +3,7 @@
 
 //! Smoke tests for `init_panic_tracing`.
 
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Once};
 use std::thread;
 
 use tracing::Level;
@@ -55,6 +55,116 @@ impl tracing::field::Visit for FieldCollector {
     }
 }
 
+/// Payloads of the panics seen by the hook installed before
+/// `init_panic_tracing`.
+static SENTINEL_PAYLOADS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Whether the sentinel hook saw a panic carrying `payload`.
+fn sentinel_saw(payload: &str) -> bool {
+    SENTINEL_PAYLOADS
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|seen| seen.contains(payload))
+}
+
+/// Install a sentinel hook, then the tracing hook on top of it, once for the
+/// whole test binary (the tracing hook is process-wide).
+fn install_hooks(dispatch: &tracing::Dispatch) {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        std::panic::set_hook(Box::new(|info| {
+            let payload = info
+                .payload()
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| {
+                    info.payload()
+                        .downcast_ref::<&str>()
+                        .map(|s| (*s).to_owned())
+                })
+                .unwrap_or_default();
+            SENTINEL_PAYLOADS.lock().unwrap().push(payload);
+        }));
+        tracing::dispatcher::with_default(dispatch, init_panic_tracing);
+    });
+}
+
+/// Panic on a thread carrying `dispatch`, so the hook logs to it.
+fn panic_with(dispatch: tracing::Dispatch, payload: &'static str) {
+    let handle = thread::spawn(move || {
+        tracing::dispatcher::with_default(&dispatch, || {
+            panic!("{payload}");
+        });
+    });
+    assert!(handle.join().is_err(), "spawned thread must have panicked");
+}
+
+#[test]
+fn previous_hook_still_runs_after_the_tracing_event() {
+    let captured = CapturedEvents::default();
+    let events = captured.events.clone();
+    let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(captured));
+    install_hooks(&dispatch);
+
+    panic_with(dispatch, "sentinel_panic_payload");
+
+    assert!(
+        sentinel_saw("sentinel_panic_payload"),
+        "the hook installed before init_panic_tracing must still fire"
+    );
+    let combined = events.lock().unwrap().join("\n");
+    assert!(
+        combined.contains("sentinel_panic_payload"),
+        "expected the tracing event alongside the sentinel, got: {combined}"
+    );
+}
+
+#[test]
+fn panics_caught_by_the_middleware_are_logged_once() {
+    use axum::{Router, body::Body, http::Request, routing::get};
+    use tower::ServiceExt;
+
+    async fn boom() -> &'static str {
+        panic!("middleware_caught_payload")
+    }
+
+    let captured = CapturedEvents::default();
+    let events = captured.events.clone();
+    let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(captured));
+    install_hooks(&dispatch);
+
+    let app = Router::new()
+        .route("/boom", get(boom))
+        .layer(axum::middleware::from_fn(
+            modkit::api::panic::catch_panic_middleware,
+        ));
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let status = tracing::dispatcher::with_default(&dispatch, || {
+        runtime.block_on(async {
+            app.oneshot(Request::get("/boom").body(Body::empty()).unwrap())
+                .await
+                .unwrap()
+                .status()
+        })
+    });
+
+    assert_eq!(status, axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(
+        !sentinel_saw("middleware_caught_payload"),
+        "the previous hook must not run for panics the middleware catches"
+    );
+    let logged = events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|event| event.contains("middleware_caught_payload"))
+        .count();
+    assert_eq!(logged, 1, "expected exactly one PANIC event");
+}
+
 #[test]
 fn panic_hook_emits_error_event_with_payload() {
     let captured = CapturedEvents::default();
@@ -67,22 +177,9 @@ fn panic_hook_emits_error_event_with_payload() {
     // on the panicking thread, so we need the dispatch active there.
     let dispatch = tracing::Dispatch::new(subscriber);
 
-    tracing::dispatcher::with_default(&dispatch, || {
-        // Install the panic hook while the test subscriber is active.
-        init_panic_tracing();
-    });
-
-    // Spawn a thread that carries the same dispatch, then panics.
-    let dispatch_clone = dispatch;
-    let handle = thread::spawn(move || {
-        tracing::dispatcher::with_default(&dispatch_clone, || {
-            panic!("test_panic_payload");
-        });
-    });
-
-    // The thread should finish (with a panic).
-    let result = handle.join();
-    assert!(result.is_err(), "spawned thread must have panicked");
+    // Install the panic hook, then panic on a thread carrying the dispatch.
+    install_hooks(&dispatch);
+    panic_with(dispatch, "test_panic_payload");
 
     let captured_events = events.lock().unwrap();
     assert!(
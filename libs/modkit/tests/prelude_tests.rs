@@ -0,0 +1,72 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+//! A handler written against `modkit::api::prelude` alone can build, enrich and
+//! respond with problems.
+
+use modkit::api::prelude::*;
+
+const NOT_FOUND_DEF: ErrDef = ErrDef {
+    status: 404,
+    title: "User not found",
+    code: "USERS_NOT_FOUND",
+    type_url: "gts.x.core.errors.err.v1~x.users.errors.not_found.v1",
+    help_url: None,
+    metadata: &[],
+    transient: None,
+    severity: None,
+    fallback: None,
+    titles: &[],
+};
+
+#[allow(clippy::result_large_err)]
+fn lookup(id: u32) -> ApiResult<Json<u32>> {
+    match id {
+        0 => Err(bad_request("id must be positive").with_trace_context("/users/0")),
+        1 => Ok(Json(1)),
+        2 => Err(conflict("user is being migrated")),
+        3 => Err(internal_error("storage offline")),
+        4 => Err(not_found("no such user")),
+        _ => Err(NOT_FOUND_DEF.as_problem(format!("user {id} does not exist"))),
+    }
+}
+
+#[test]
+fn prelude_covers_problem_construction() {
+    let statuses: Vec<StatusCode> = (0..6)
+        .map(|id| match lookup(id) {
+            Ok(_) => StatusCode::OK,
+            Err(problem) => problem.status,
+        })
+        .collect();
+    assert_eq!(
+        statuses,
+        [
+            StatusCode::BAD_REQUEST,
+            StatusCode::OK,
+            StatusCode::CONFLICT,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::NOT_FOUND,
+            StatusCode::NOT_FOUND,
+        ]
+    );
+
+    let problem = lookup(0).unwrap_err();
+    assert_eq!(problem.instance, "/users/0");
+}
+
+#[test]
+fn prelude_problem_is_a_response() {
+    let response = lookup(9).unwrap_err().into_response();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json; version=1"
+    );
+}
+
+#[test]
+fn prelude_error_conversion_trait() {
+    let problem = modkit_odata::Error::InvalidFilter("bad".to_owned()).into_problem("/users", None);
+    assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(problem.instance, "/users");
+}
@@ -39,6 +39,13 @@ pub fn domain_error_to_problem(err: DomainError) -> Problem {
         DomainError::PathTraversalBlocked { message } => {
             Problem::new(StatusCode::FORBIDDEN, "Path Traversal Blocked", message)
         }
+
+        DomainError::FileTooLarge { max_bytes } => Problem::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "File Too Large",
+            format!("File exceeds maximum of {max_bytes} bytes"),
+        )
+        .with_metadata("max_bytes", max_bytes),
     }
 }
 
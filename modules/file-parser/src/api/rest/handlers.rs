@@ -6,8 +6,10 @@ use axum::extract::{Extension, Query};
 use axum::http::HeaderMap;
 use axum::response::Response;
 use bytes::Bytes;
-use futures_util::stream;
+use futures_util::{TryStreamExt, stream};
 use std::convert::Infallible;
+use tokio::io::AsyncBufReadExt;
+use tokio_util::io::StreamReader;
 use tracing::{field::Empty, info};
 
 use crate::api::rest::dto::{
@@ -88,12 +90,14 @@ pub async fn parse_local(
 }
 
 /// Upload and parse a file
+///
+/// The body is streamed and rejected with `413` once it exceeds the configured
+/// maximum file size, so an oversized upload is never fully buffered.
 #[tracing::instrument(
     skip(svc, body, _ctx, query, headers),
     fields(
         filename = ?query.filename,
         render_markdown = ?query.render_markdown,
-        size = body.len(),
         request_id = Empty
     )
 )]
@@ -103,7 +107,7 @@ pub async fn upload_and_parse(
     Extension(svc): Extension<std::sync::Arc<FileParserService>>,
     Query(query): Query<UploadQuery>,
     headers: HeaderMap,
-    body: Bytes,
+    body: Body,
 ) -> ApiResult<JsonBody<ParsedDocResponseDto>> {
     let render_md = query.render_markdown.unwrap_or(false);
     let filename_opt = query.filename.as_deref();
@@ -118,11 +122,16 @@ pub async fn upload_and_parse(
         filename = ?filename_opt,
         content_type = ?content_type_str,
         render_markdown = render_md,
-        size = body.len(),
         "Uploading and parsing raw file bytes"
     );
 
-    if body.is_empty() {
+    let mut reader = StreamReader::new(body.into_data_stream().map_err(std::io::Error::other));
+    let at_end = reader
+        .fill_buf()
+        .await
+        .map_err(|e| DomainError::io_error(format!("Failed to read request body: {e}")))?
+        .is_empty();
+    if at_end {
         return Err(DomainError::invalid_request(
             "Empty request body, expected file bytes".to_owned(),
         )
@@ -130,7 +139,12 @@ pub async fn upload_and_parse(
     }
 
     let document = svc
-        .parse_bytes(filename_opt, content_type_str.as_deref(), body)
+        .parse_stream(
+            filename_opt,
+            content_type_str.as_deref(),
+            reader,
+            svc.max_file_size_bytes(),
+        )
         .await?;
 
     // Optionally render markdown
@@ -202,45 +216,40 @@ pub async fn upload_and_parse_markdown(
 ) -> ApiResult<Response> {
     info!("Uploading and parsing file, streaming Markdown");
 
-    // Extract the first file field (reuse logic from upload_and_parse)
-    let mut file_name: Option<String> = None;
-    let mut file_bytes: Option<bytes::Bytes> = None;
+    // Parse the first file field, streaming it so oversized uploads are
+    // rejected before being fully buffered
+    let mut document = None;
 
     while let Some(field) = multipart.next_field().await.map_err(|e| {
         Problem::from(DomainError::invalid_request(format!(
             "Multipart error: {e}"
         )))
     })? {
-        let field_name = field.name().unwrap_or("").to_owned();
-        if field_name == "file" {
-            file_name = field.file_name().map(ToString::to_string);
-            file_bytes = Some(field.bytes().await.map_err(|e| {
-                Problem::from(DomainError::io_error(format!("Failed to read file: {e}")))
-            })?);
+        if field.name() == Some("file") {
+            let file_name = field.file_name().map(ToString::to_string).ok_or_else(|| {
+                Problem::from(DomainError::invalid_request(
+                    "No file name found in multipart request",
+                ))
+            })?;
+            info!(
+                file_name = %file_name,
+                "Processing uploaded file for Markdown streaming"
+            );
+            let reader = StreamReader::new(field.map_err(std::io::Error::other));
+            document = Some(
+                svc.parse_stream(Some(&file_name), None, reader, svc.max_file_size_bytes())
+                    .await?,
+            );
             break;
         }
     }
 
-    let file_name = file_name.ok_or_else(|| {
+    let document = document.ok_or_else(|| {
         Problem::from(DomainError::invalid_request(
             "No file field found in multipart request",
         ))
     })?;
 
-    let file_bytes = file_bytes.ok_or_else(|| {
-        Problem::from(DomainError::invalid_request(
-            "No file data found in multipart request",
-        ))
-    })?;
-
-    info!(
-        file_name = %file_name,
-        size = file_bytes.len(),
-        "Processing uploaded file for Markdown streaming"
-    );
-
-    let document = svc.parse_bytes(Some(&file_name), None, file_bytes).await?;
-
     // Create streaming response - render_iter takes ownership of document
     let stream = stream::iter(
         MarkdownRenderer::render_iter(document)
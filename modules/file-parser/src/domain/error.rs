@@ -25,6 +25,9 @@ pub enum DomainError {
 
     #[error("Path traversal blocked: {message}")]
     PathTraversalBlocked { message: String },
+
+    #[error("File exceeds maximum of {max_bytes} bytes")]
+    FileTooLarge { max_bytes: usize },
 }
 
 impl DomainError {
@@ -67,4 +70,9 @@ impl DomainError {
             message: message.into(),
         }
     }
+
+    #[must_use]
+    pub fn file_too_large(max_bytes: usize) -> Self {
+        Self::FileTooLarge { max_bytes }
+    }
 }
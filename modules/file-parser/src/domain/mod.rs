@@ -3,6 +3,7 @@ pub mod ir;
 pub mod markdown;
 pub mod parser;
 pub mod service;
+pub mod sniff;
 
 pub use error::*;
 pub use ir::*;
@@ -3,11 +3,13 @@ use std::sync::Arc;
 
 use bytes::Bytes;
 use modkit_macros::domain_model;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tracing::{debug, info, instrument, warn};
 
 use crate::domain::error::DomainError;
 use crate::domain::ir::ParsedDocument;
 use crate::domain::parser::FileParserBackend;
+use crate::domain::sniff::sniff_extension;
 
 /// Mapping of file extensions to MIME types
 /// Format: `(extension, mime_type)`
@@ -58,6 +60,12 @@ impl FileParserService {
         Self { parsers, config }
     }
 
+    /// Largest file accepted for parsing, in bytes
+    #[must_use]
+    pub fn max_file_size_bytes(&self) -> usize {
+        self.config.max_file_size_bytes
+    }
+
     /// Get information about available parsers
     #[instrument(skip(self))]
     pub fn info(&self) -> FileParserInfo {
@@ -178,46 +186,37 @@ impl FileParserService {
 
         // Check file size
         if bytes.len() > self.config.max_file_size_bytes {
-            return Err(DomainError::invalid_request(format!(
-                "File size {} exceeds maximum of {} bytes",
-                bytes.len(),
-                self.config.max_file_size_bytes
-            )));
+            return Err(DomainError::file_too_large(self.config.max_file_size_bytes));
         }
 
-        // Determine extension by priority:
-        // 1. From filename (if provided and has extension)
-        // 2. From Content-Type (if provided and recognized)
+        // Determine the parser by priority:
+        // 1. From the extension in the filename or, lacking one, the Content-Type
+        // 2. Sniffed from the leading bytes when the declared type has no parser
         // 3. Error if both fail
-        let extension_from_name = filename_hint
-            .and_then(|name| Path::new(name).extension())
-            .and_then(|s| s.to_str())
-            .map(ToString::to_string);
+        let declared = Self::declared_extension(filename_hint, content_type);
+        let declared_parser = declared
+            .as_ref()
+            .ok()
+            .and_then(|ext| self.find_parser_by_extension(ext));
 
-        let extension = if let Some(ext) = extension_from_name {
-            // Priority 1: Use extension from filename
-            ext
-        } else if let Some(ct) = content_type {
-            // Priority 2: Try to infer from Content-Type
-            if let Some(ext) = Self::extension_from_content_type(ct) {
-                ext
-            } else {
-                return Err(DomainError::unsupported_file_type(
-                    "no extension and unknown content-type",
-                ));
-            }
+        let (parser, content_type) = if let Some(parser) = declared_parser {
+            (parser, content_type)
         } else {
-            // Both failed
-            return Err(DomainError::unsupported_file_type(
-                "no extension and no content-type",
-            ));
+            let sniffed = sniff_extension(&bytes)
+                .and_then(|ext| Some((ext, self.find_parser_by_extension(ext)?)));
+            let Some((extension, parser)) = sniffed else {
+                return Err(match declared {
+                    Ok(ext) => DomainError::no_parser_available(&ext),
+                    Err(e) => e,
+                });
+            };
+            debug!(extension, "Inferred file type from content");
+            (
+                parser,
+                Self::mime_type_from_extension(extension).or(content_type),
+            )
         };
 
-        // Find parser
-        let parser = self
-            .find_parser_by_extension(&extension)
-            .ok_or_else(|| DomainError::no_parser_available(&extension))?;
-
         // Parse the file
         let document = parser
             .parse_bytes(filename_hint, content_type, bytes)
@@ -231,6 +230,68 @@ impl FileParserService {
         Ok(document)
     }
 
+    /// Parse a file read incrementally from `reader`.
+    ///
+    /// At most `limit` bytes (capped by the configured maximum file size) are
+    /// accepted: reading stops one byte past it and fails with
+    /// `DomainError::FileTooLarge`, so an oversized input is never fully
+    /// buffered.
+    #[instrument(skip(self, reader), fields(filename_hint = ?filename_hint, content_type = ?content_type))]
+    pub async fn parse_stream<R>(
+        &self,
+        filename_hint: Option<&str>,
+        content_type: Option<&str>,
+        reader: R,
+        limit: usize,
+    ) -> Result<ParsedDocument, DomainError>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        let limit = limit.min(self.config.max_file_size_bytes);
+        let mut bytes = Vec::new();
+        reader
+            .take(u64::try_from(limit).unwrap_or(u64::MAX).saturating_add(1))
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|e| DomainError::io_error(format!("Failed to read file: {e}")))?;
+        if bytes.len() > limit {
+            return Err(DomainError::file_too_large(limit));
+        }
+
+        self.parse_bytes(filename_hint, content_type, Bytes::from(bytes))
+            .await
+    }
+
+    /// Extension declared by the filename or, lacking one, by the Content-Type.
+    fn declared_extension(
+        filename_hint: Option<&str>,
+        content_type: Option<&str>,
+    ) -> Result<String, DomainError> {
+        let extension_from_name = filename_hint
+            .and_then(|name| Path::new(name).extension())
+            .and_then(|s| s.to_str());
+
+        if let Some(ext) = extension_from_name {
+            Ok(ext.to_owned())
+        } else if let Some(ct) = content_type {
+            Self::extension_from_content_type(ct).ok_or_else(|| {
+                DomainError::unsupported_file_type("no extension and unknown content-type")
+            })
+        } else {
+            Err(DomainError::unsupported_file_type(
+                "no extension and no content-type",
+            ))
+        }
+    }
+
+    /// MIME type of a file extension, if known
+    fn mime_type_from_extension(ext: &str) -> Option<&'static str> {
+        EXTENSION_MIME_MAPPINGS
+            .iter()
+            .find(|(e, _)| *e == ext)
+            .map(|(_, mime_type)| *mime_type)
+    }
+
     /// Extract file extension from Content-Type header
     #[must_use]
     pub fn extension_from_content_type(ct: &str) -> Option<String> {
@@ -0,0 +1,85 @@
+//! Content-type sniffing from magic bytes
+//!
+//! Uploads often arrive without a reliable extension. [`sniff_extension`]
+//! infers the file type from the leading bytes instead, naming it by the
+//! extension its parser is registered under.
+
+const PDF_MAGIC: &[u8] = b"%PDF-";
+const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG_MAGIC: &[u8] = b"\xFF\xD8\xFF";
+const GIF_MAGICS: [&[u8]; 2] = [b"GIF87a", b"GIF89a"];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Part-name prefixes telling the OOXML formats apart, which all share the
+/// ZIP container. Local file headers store part names uncompressed.
+const OOXML_PARTS: [(&[u8], &str); 3] = [(b"word/", "docx"), (b"xl/", "xlsx"), (b"ppt/", "pptx")];
+
+/// Extension of the file type `bytes` starts like, `None` when unrecognized.
+#[must_use]
+pub fn sniff_extension(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(PDF_MAGIC) {
+        Some("pdf")
+    } else if bytes.starts_with(PNG_MAGIC) {
+        Some("png")
+    } else if bytes.starts_with(JPEG_MAGIC) {
+        Some("jpg")
+    } else if GIF_MAGICS.iter().any(|magic| bytes.starts_with(magic)) {
+        Some("gif")
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        Some("webp")
+    } else if bytes.starts_with(ZIP_MAGIC) {
+        OOXML_PARTS
+            .iter()
+            .find(|(part, _)| contains(bytes, part))
+            .map(|&(_, ext)| ext)
+    } else if looks_like_html(bytes) {
+        Some("html")
+    } else {
+        None
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+fn looks_like_html(bytes: &[u8]) -> bool {
+    let text = bytes
+        .strip_prefix(UTF8_BOM)
+        .unwrap_or(bytes)
+        .trim_ascii_start();
+    let head = &text[..text.len().min(14)];
+    head.eq_ignore_ascii_case(b"<!doctype html")
+        || head
+            .get(..5)
+            .is_some_and(|tag| tag.eq_ignore_ascii_case(b"<html"))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ooxml_formats_are_told_apart_by_part_names() {
+        let zip = |part: &str| [ZIP_MAGIC, b"\x14\x00\x00\x00", part.as_bytes()].concat();
+        assert_eq!(sniff_extension(&zip("word/document.xml")), Some("docx"));
+        assert_eq!(sniff_extension(&zip("xl/workbook.xml")), Some("xlsx"));
+        assert_eq!(sniff_extension(&zip("ppt/presentation.xml")), Some("pptx"));
+        assert_eq!(sniff_extension(&zip("mimetype")), None);
+    }
+
+    #[test]
+    fn html_is_recognized_by_its_opening_tag() {
+        assert_eq!(
+            sniff_extension(b"\xEF\xBB\xBF  <!DOCTYPE html><p>hi"),
+            Some("html")
+        );
+        assert_eq!(sniff_extension(b"<HTML><body>"), Some("html"));
+        assert_eq!(sniff_extension(b"<xml/>"), None);
+        assert_eq!(sniff_extension(b""), None);
+    }
+}
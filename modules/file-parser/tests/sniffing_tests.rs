@@ -0,0 +1,79 @@
+#![allow(clippy::unwrap_used, clippy::expect_used, clippy::use_debug)]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use file_parser::domain::error::DomainError;
+use file_parser::domain::ir::ParsedBlock;
+use file_parser::domain::parser::FileParserBackend;
+use file_parser::domain::service::{FileParserService, ServiceConfig};
+use file_parser::infra::parsers::{ImageParser, PlainTextParser};
+
+const PNG_BYTES: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR";
+
+fn build_service() -> FileParserService {
+    let parsers: Vec<Arc<dyn FileParserBackend>> = vec![
+        Arc::new(PlainTextParser::new()),
+        Arc::new(ImageParser::new()),
+    ];
+    let config = ServiceConfig {
+        max_file_size_bytes: 1024 * 1024,
+        allowed_local_base_dir: PathBuf::from("/nonexistent"),
+    };
+    FileParserService::new(parsers, config)
+}
+
+fn image_uri(blocks: &[ParsedBlock]) -> Option<&str> {
+    blocks.iter().find_map(|block| match block {
+        ParsedBlock::Image { src, .. } => src.as_deref(),
+        _ => None,
+    })
+}
+
+#[tokio::test]
+async fn correct_extension_selects_its_parser() {
+    let document = build_service()
+        .parse_bytes(Some("logo.png"), None, Bytes::from_static(PNG_BYTES))
+        .await
+        .unwrap();
+
+    let uri = image_uri(&document.blocks).expect("image block");
+    assert!(uri.starts_with("data:image/png;base64,"), "{uri}");
+}
+
+#[tokio::test]
+async fn wrong_extension_falls_back_to_sniffing() {
+    for name in [Some("upload.bin"), Some("upload"), None] {
+        let document = build_service()
+            .parse_bytes(name, None, Bytes::from_static(PNG_BYTES))
+            .await
+            .unwrap_or_else(|e| panic!("{name:?}: {e:?}"));
+
+        let uri = image_uri(&document.blocks).expect("image block");
+        assert!(uri.starts_with("data:image/png;base64,"), "{name:?}: {uri}");
+    }
+}
+
+#[tokio::test]
+async fn unknowable_blob_is_still_unsupported() {
+    let blob = Bytes::from_static(b"\x00\x01\x02 not a known format");
+
+    let err = build_service()
+        .parse_bytes(None, None, blob.clone())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, DomainError::UnsupportedFileType { .. }),
+        "expected UnsupportedFileType, got: {err:?}"
+    );
+
+    let err = build_service()
+        .parse_bytes(Some("blob.bin"), None, blob)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, DomainError::NoParserAvailable { ref extension } if extension == "bin"),
+        "expected NoParserAvailable, got: {err:?}"
+    );
+}
@@ -0,0 +1,131 @@
+#![allow(clippy::unwrap_used, clippy::expect_used, clippy::use_debug)]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use file_parser::api::rest::domain_error_to_problem;
+use file_parser::domain::error::DomainError;
+use file_parser::domain::parser::FileParserBackend;
+use file_parser::domain::service::{FileParserService, ServiceConfig};
+use file_parser::infra::parsers::PlainTextParser;
+use http::StatusCode;
+
+/// Build a minimal `FileParserService` accepting files up to `max_file_size_bytes`.
+fn build_service(max_file_size_bytes: usize) -> FileParserService {
+    let parsers: Vec<Arc<dyn FileParserBackend>> = vec![Arc::new(PlainTextParser::new())];
+    let config = ServiceConfig {
+        max_file_size_bytes,
+        allowed_local_base_dir: PathBuf::from("/nonexistent"),
+    };
+    FileParserService::new(parsers, config)
+}
+
+#[tokio::test]
+async fn under_limit_stream_parses() {
+    let svc = build_service(1024);
+    let content: &[u8] = b"hello from a stream";
+
+    let document = svc
+        .parse_stream(Some("notes.txt"), None, content, content.len())
+        .await
+        .expect("stream within the limit should parse");
+
+    assert_eq!(
+        document.meta.original_filename.as_deref(),
+        Some("notes.txt")
+    );
+    assert!(!document.blocks.is_empty());
+}
+
+#[tokio::test]
+async fn over_limit_stream_is_rejected_with_413() {
+    let svc = build_service(1024);
+    let content = vec![b'a'; 64];
+
+    let err = svc
+        .parse_stream(Some("notes.txt"), None, content.as_slice(), 16)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, DomainError::FileTooLarge { max_bytes: 16 }),
+        "expected FileTooLarge, got: {err:?}"
+    );
+
+    let problem = domain_error_to_problem(err);
+    assert_eq!(problem.status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(problem.metadata.unwrap()["max_bytes"], 16);
+}
+
+#[tokio::test]
+async fn configured_maximum_caps_the_limit() {
+    let svc = build_service(8);
+    let content = vec![b'a'; 9];
+
+    let err = svc
+        .parse_stream(Some("notes.txt"), None, content.as_slice(), usize::MAX)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, DomainError::FileTooLarge { max_bytes: 8 }),
+        "expected FileTooLarge, got: {err:?}"
+    );
+}
+
+#[tokio::test]
+async fn zero_byte_stream_parses_to_an_empty_document() {
+    let svc = build_service(1024);
+
+    let document = svc
+        .parse_stream(Some("empty.txt"), None, tokio::io::empty(), 1024)
+        .await
+        .expect("an empty stream is within any limit");
+
+    assert!(document.blocks.is_empty(), "{:?}", document.blocks);
+}
+
+#[tokio::test]
+async fn oversized_bytes_are_rejected_with_413() {
+    let svc = build_service(8);
+
+    let err = svc
+        .parse_bytes(
+            Some("notes.txt"),
+            None,
+            bytes::Bytes::from_static(b"123456789"),
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, DomainError::FileTooLarge { max_bytes: 8 }),
+        "expected FileTooLarge, got: {err:?}"
+    );
+    assert_eq!(
+        domain_error_to_problem(err).status,
+        StatusCode::PAYLOAD_TOO_LARGE
+    );
+}
+
+#[tokio::test]
+async fn oversized_upload_is_rejected_with_413() {
+    use axum::extract::{Extension, Query};
+    use file_parser::api::rest::{UploadQuery, upload_and_parse};
+
+    let upload = |size: usize| {
+        upload_and_parse(
+            Extension(modkit_security::SecurityContext::anonymous()),
+            Extension(Arc::new(build_service(8))),
+            Query(UploadQuery {
+                render_markdown: None,
+                filename: Some("notes.txt".to_owned()),
+            }),
+            http::HeaderMap::new(),
+            axum::body::Body::from(vec![b'a'; size]),
+        )
+    };
+
+    let problem = upload(9).await.unwrap_err();
+    assert_eq!(problem.status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(problem.metadata.unwrap()["max_bytes"], 8);
+
+    assert!(upload(8).await.is_ok());
+}
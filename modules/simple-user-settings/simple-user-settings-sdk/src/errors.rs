@@ -13,6 +13,12 @@ pub enum SettingsError {
     #[error("Access forbidden")]
     Forbidden,
 
+    #[error("Settings version mismatch: {message}")]
+    PreconditionFailed { message: String },
+
+    #[error("Settings were modified concurrently")]
+    Conflict,
+
     #[error("Internal error")]
     Internal,
 }
@@ -36,6 +42,18 @@ impl SettingsError {
         Self::Forbidden
     }
 
+    #[must_use]
+    pub fn precondition_failed(message: impl Into<String>) -> Self {
+        Self::PreconditionFailed {
+            message: message.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn conflict() -> Self {
+        Self::Conflict
+    }
+
     #[must_use]
     pub fn internal() -> Self {
         Self::Internal
@@ -17,6 +17,8 @@ pub struct SimpleUserSettings {
     pub tenant_id: Uuid,
     pub theme: Option<String>,
     pub language: Option<String>,
+    /// Incremented on every write; `0` until the settings are first saved.
+    pub version: i64,
 }
 
 /// Partial update data for user settings.
@@ -10,6 +10,9 @@ pub struct SimpleUserSettingsDto {
     pub tenant_id: Uuid,
     pub theme: Option<String>,
     pub language: Option<String>,
+    /// Also sent as the strong `ETag`; pass it back in `If-Match` to update
+    /// only this version.
+    pub version: i64,
 }
 
 impl From<SimpleUserSettings> for SimpleUserSettingsDto {
@@ -19,6 +22,7 @@ impl From<SimpleUserSettings> for SimpleUserSettingsDto {
             tenant_id: settings.tenant_id,
             theme: settings.theme,
             language: settings.language,
+            version: settings.version,
         }
     }
 }
@@ -14,6 +14,7 @@ mod tests {
             tenant_id,
             theme: Some("dark".to_owned()),
             language: Some("en".to_owned()),
+            version: 4,
         };
 
         let dto: dto::SimpleUserSettingsDto = settings.into();
@@ -22,6 +23,7 @@ mod tests {
         assert_eq!(dto.tenant_id, tenant_id);
         assert_eq!(dto.theme, Some("dark".to_owned()));
         assert_eq!(dto.language, Some("en".to_owned()));
+        assert_eq!(dto.version, 4);
     }
 
     #[test]
@@ -71,11 +73,13 @@ mod tests {
             tenant_id,
             theme: Some("dark".to_owned()),
             language: Some("en".to_owned()),
+            version: 1,
         };
 
         let json = serde_json::to_string(&dto).unwrap();
         assert!(json.contains("\"theme\":\"dark\""));
         assert!(json.contains("\"language\":\"en\""));
+        assert!(json.contains("\"version\":1"));
         assert!(json.contains("\"user_id\"")); // snake_case
         assert!(json.contains("\"tenant_id\"")); // snake_case
     }
@@ -1,25 +1,71 @@
-use modkit::api::problem::Problem;
+use std::sync::Arc;
+
+use modkit::api::problem::{FORBIDDEN_V1, Problem};
+use modkit_security::SecurityContext;
 
 use crate::domain::error::DomainError;
 use crate::errors::ErrorCode;
 
-/// Map domain error to RFC9457 Problem using the GTS error catalog
-pub fn domain_error_to_problem(e: &DomainError, instance: &str) -> Problem {
-    let trace_id = tracing::Span::current()
-        .id()
-        .map(|id| id.into_u64().to_string());
-
-    match e {
-        DomainError::NotFound => build_not_found_problem(instance, trace_id),
-        DomainError::Validation { field, message } => {
-            build_validation_problem(field, message, instance, trace_id)
+/// How much of a domain error the REST API reveals to each caller.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProblemMapping {
+    /// Subject types whose access denials are reported as `forbidden` with
+    /// their scope detail; everyone else gets them masked as `not_found` so
+    /// the scope is not leaked.
+    trusted_subject_types: Arc<[String]>,
+}
+
+impl ProblemMapping {
+    /// Expose access denials to callers of the given subject types only.
+    pub fn trusting<I, S>(subject_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            trusted_subject_types: subject_types.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    fn trusts(&self, ctx: &SecurityContext) -> bool {
+        ctx.subject_type().is_some_and(|t| {
+            self.trusted_subject_types
+                .iter()
+                .any(|trusted| trusted == t)
+        })
+    }
+
+    /// Map domain error to RFC9457 Problem using the GTS error catalog,
+    /// revealing access denials only if `ctx` is a trusted caller
+    pub fn to_problem(&self, ctx: &SecurityContext, e: &DomainError, instance: &str) -> Problem {
+        let trace_id = tracing::Span::current()
+            .id()
+            .map(|id| id.into_u64().to_string());
+
+        match e {
+            DomainError::NotFound => build_not_found_problem(instance, trace_id),
+            DomainError::Validation { field, message } => {
+                build_validation_problem(field, message, instance, trace_id)
+            }
+            DomainError::Forbidden(msg) if self.trusts(ctx) => {
+                build_exposed_forbidden_problem(e, msg, instance, trace_id)
+            }
+            DomainError::Forbidden(msg) => build_forbidden_problem(e, msg, instance, trace_id),
+            DomainError::PreconditionFailed(msg) => {
+                build_precondition_failed_problem(msg, instance, trace_id)
+            }
+            DomainError::Conflict(msg) => build_conflict_problem(msg, instance, trace_id),
+            DomainError::Internal(msg) => build_internal_problem(e, msg, instance, trace_id),
+            DomainError::Database(_) => build_database_problem(e, instance, trace_id),
         }
-        DomainError::Forbidden(msg) => build_forbidden_problem(e, msg, instance, trace_id),
-        DomainError::Internal(msg) => build_internal_problem(e, msg, instance, trace_id),
-        DomainError::Database(_) => build_database_problem(e, instance, trace_id),
     }
 }
 
+/// Map domain error to RFC9457 Problem, masking access denials as `not_found`
+pub fn domain_error_to_problem(e: &DomainError, instance: &str) -> Problem {
+    ProblemMapping::default().to_problem(&SecurityContext::anonymous(), e, instance)
+}
+
 fn build_not_found_problem(instance: &str, trace_id: Option<String>) -> Problem {
     ErrorCode::settings_simple_user_settings_not_found_v1().with_context(
         "Settings not found",
@@ -48,8 +94,7 @@ fn build_forbidden_problem(
     trace_id: Option<String>,
 ) -> Problem {
     tracing::warn!(error = ?e, "Access forbidden: {}", msg);
-    // TODO: Add settings_simple_user_settings_forbidden_v1 to errors catalog
-    // For now, use not_found to avoid exposing sensitive scope information
+    // Use not_found to avoid exposing sensitive scope information
     ErrorCode::settings_simple_user_settings_not_found_v1().with_context(
         "Settings not found or not accessible",
         instance,
@@ -57,6 +102,44 @@ fn build_forbidden_problem(
     )
 }
 
+fn build_exposed_forbidden_problem(
+    e: &DomainError,
+    msg: &str,
+    instance: &str,
+    trace_id: Option<String>,
+) -> Problem {
+    tracing::warn!(error = ?e, "Access forbidden: {}", msg);
+    let mut problem = FORBIDDEN_V1
+        .as_problem(format!("Access forbidden: {msg}"))
+        .with_instance(instance)
+        .with_metadata("scope_detail", msg);
+    if let Some(tid) = trace_id {
+        problem = problem.with_trace_id(tid);
+    }
+    problem
+}
+
+fn build_precondition_failed_problem(
+    message: &str,
+    instance: &str,
+    trace_id: Option<String>,
+) -> Problem {
+    ErrorCode::settings_simple_user_settings_precondition_failed_v1().with_context(
+        format!("Settings version mismatch: {message}"),
+        instance,
+        trace_id,
+    )
+}
+
+fn build_conflict_problem(message: &str, instance: &str, trace_id: Option<String>) -> Problem {
+    tracing::debug!("Concurrent settings update: {}", message);
+    ErrorCode::settings_simple_user_settings_conflict_v1().with_context(
+        "Settings were modified concurrently; re-read them and retry",
+        instance,
+        trace_id,
+    )
+}
+
 fn build_internal_problem(
     e: &DomainError,
     msg: &str,
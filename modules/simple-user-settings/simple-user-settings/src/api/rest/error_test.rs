@@ -1,9 +1,20 @@
 #[cfg(test)]
 mod tests {
-    use super::super::error::domain_error_to_problem;
+    use super::super::error::{ProblemMapping, domain_error_to_problem};
     use crate::domain::error::DomainError;
     use axum::http::StatusCode;
-    use modkit::api::problem::Problem;
+    use modkit::api::problem::{FORBIDDEN_V1, Problem};
+    use modkit_security::SecurityContext;
+    use uuid::Uuid;
+
+    fn caller(subject_type: &str) -> SecurityContext {
+        SecurityContext::builder()
+            .subject_id(Uuid::new_v4())
+            .subject_tenant_id(Uuid::new_v4())
+            .subject_type(subject_type)
+            .build()
+            .unwrap()
+    }
 
     #[test]
     fn test_not_found_error_to_problem() {
@@ -29,6 +40,84 @@ mod tests {
         assert!(problem.detail.contains("exceeds max length"));
     }
 
+    #[test]
+    fn test_forbidden_error_is_masked_as_not_found_by_default() {
+        let error = DomainError::forbidden("tenant t-1 not in scope");
+        let problem = domain_error_to_problem(&error, "/api/settings");
+
+        assert_eq!(problem.status, StatusCode::NOT_FOUND);
+        assert!(problem.code.contains("not_found"));
+        assert!(!problem.detail.contains("t-1"));
+        assert!(problem.metadata.is_none());
+    }
+
+    #[test]
+    fn test_forbidden_error_is_exposed_for_trusted_callers() {
+        let error = DomainError::forbidden("tenant t-1 not in scope");
+        let problem = ProblemMapping::trusting(["service"]).to_problem(
+            &caller("service"),
+            &error,
+            "/api/settings",
+        );
+
+        assert_eq!(problem.status, StatusCode::FORBIDDEN);
+        assert_eq!(problem.code, FORBIDDEN_V1.code);
+        assert!(problem.detail.contains("tenant t-1 not in scope"));
+        assert_eq!(
+            problem.metadata.unwrap()["scope_detail"],
+            "tenant t-1 not in scope"
+        );
+    }
+
+    #[test]
+    fn test_forbidden_error_is_masked_for_untrusted_callers() {
+        let error = DomainError::forbidden("tenant t-1 not in scope");
+        let problem = ProblemMapping::trusting(["service"]).to_problem(
+            &caller("user"),
+            &error,
+            "/api/settings",
+        );
+
+        assert_eq!(problem.status, StatusCode::NOT_FOUND);
+        assert!(!problem.detail.contains("t-1"));
+    }
+
+    #[test]
+    fn test_exposing_forbidden_leaves_other_errors_unchanged() {
+        let mapping = ProblemMapping::trusting(["service"]);
+        let trusted = caller("service");
+        for error in [
+            DomainError::NotFound,
+            DomainError::validation("theme", "too long"),
+            DomainError::internal("boom"),
+        ] {
+            let exposed = mapping.to_problem(&trusted, &error, "/api/settings");
+            let masked = domain_error_to_problem(&error, "/api/settings");
+            assert_eq!(exposed.status, masked.status);
+            assert_eq!(exposed.code, masked.code);
+        }
+    }
+
+    #[test]
+    fn test_precondition_failed_error_to_problem() {
+        let error = DomainError::precondition_failed("expected version 1, found 2");
+        let problem = domain_error_to_problem(&error, "/api/settings");
+
+        assert_eq!(problem.status, StatusCode::PRECONDITION_FAILED);
+        assert!(problem.code.contains("precondition_failed"));
+        assert!(problem.detail.contains("expected version 1, found 2"));
+    }
+
+    #[test]
+    fn test_conflict_error_to_problem() {
+        let error = DomainError::conflict("version 2 was replaced");
+        let problem = domain_error_to_problem(&error, "/api/settings");
+
+        assert_eq!(problem.status, StatusCode::CONFLICT);
+        assert!(problem.code.contains("conflict"));
+        assert!(!problem.detail.contains("version 2"));
+    }
+
     #[test]
     fn test_database_error_to_problem() {
         let error = DomainError::Database(modkit_db::DbError::InvalidConfig(
@@ -69,4 +158,11 @@ mod tests {
 
         assert_eq!(problem.status, StatusCode::INTERNAL_SERVER_ERROR);
     }
+    #[test]
+    fn test_from_domain_error_for_problem_precondition_failed() {
+        let error = DomainError::precondition_failed("stale");
+        let problem: Problem = error.into();
+
+        assert_eq!(problem.status, StatusCode::PRECONDITION_FAILED);
+    }
 }
@@ -1,43 +1,107 @@
 use std::sync::Arc;
 
+use axum::http::{HeaderMap, header};
 use axum::{Json, extract::Extension};
 use modkit::api::prelude::*;
 use modkit_security::SecurityContext;
-use simple_user_settings_sdk::models::SimpleUserSettingsUpdate;
+use simple_user_settings_sdk::models::{SimpleUserSettings, SimpleUserSettingsUpdate};
 
-use crate::api::rest::routes::ConcreteService;
+use crate::api::rest::error::ProblemMapping;
+use crate::api::rest::routes::{ConcreteService, SETTINGS_PATH};
+use crate::domain::error::DomainError;
 
 use super::dto::{
     PatchSimpleUserSettingsRequest, SimpleUserSettingsDto, UpdateSimpleUserSettingsRequest,
 };
 
+/// Strong `ETag` of a settings version.
+pub(crate) fn etag(version: i64) -> String {
+    format!("\"{version}\"")
+}
+
+/// The settings versions accepted by the `If-Match` header.
+///
+/// Without the header, or with `If-Match: *`, the write applies to whatever
+/// is stored. Otherwise it applies while the stored version matches any tag
+/// of the list. Weak or malformed tags never match a settings version, so a
+/// list holding nothing else fails the precondition.
+pub(crate) fn expected_versions(headers: &HeaderMap) -> Result<Option<Vec<i64>>, DomainError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let raw = value.to_str().unwrap_or_default().trim();
+    if raw == "*" {
+        return Ok(None);
+    }
+    let versions: Vec<i64> = raw
+        .split(',')
+        .filter_map(|tag| {
+            tag.trim()
+                .strip_prefix('"')
+                .and_then(|tag| tag.strip_suffix('"'))
+                .and_then(|version| version.parse().ok())
+        })
+        .collect();
+    if versions.is_empty() {
+        return Err(DomainError::precondition_failed(format!(
+            "If-Match {raw} holds no settings ETag"
+        )));
+    }
+    Ok(Some(versions))
+}
+
+fn with_etag(settings: SimpleUserSettings) -> impl IntoResponse {
+    let etag = etag(settings.version);
+    (
+        [(header::ETAG, etag)],
+        Json(SimpleUserSettingsDto::from(settings)),
+    )
+}
+
 pub async fn get_settings(
     Extension(ctx): Extension<SecurityContext>,
     Extension(svc): Extension<Arc<ConcreteService>>,
-) -> ApiResult<JsonBody<SimpleUserSettingsDto>> {
-    let settings = svc.get_settings(&ctx).await?;
-    Ok(Json(settings.into()))
+    Extension(problems): Extension<ProblemMapping>,
+) -> ApiResult<impl IntoResponse> {
+    let settings = svc
+        .get_settings(&ctx)
+        .await
+        .map_err(|e| problems.to_problem(&ctx, &e, SETTINGS_PATH))?;
+    Ok(with_etag(settings))
 }
 
 pub async fn update_settings(
     Extension(ctx): Extension<SecurityContext>,
     Extension(svc): Extension<Arc<ConcreteService>>,
+    Extension(problems): Extension<ProblemMapping>,
+    headers: HeaderMap,
     Json(req): Json<UpdateSimpleUserSettingsRequest>,
 ) -> ApiResult<impl IntoResponse> {
+    let expected_versions =
+        expected_versions(&headers).map_err(|e| problems.to_problem(&ctx, &e, SETTINGS_PATH))?;
     let update = SimpleUserSettingsUpdate {
         theme: req.theme,
         language: req.language,
     };
-    let settings = svc.update_settings(&ctx, update).await?;
-    let dto: SimpleUserSettingsDto = settings.into();
-    Ok((StatusCode::OK, Json(dto)))
+    let settings = svc
+        .update_settings(&ctx, update, expected_versions.as_deref())
+        .await
+        .map_err(|e| problems.to_problem(&ctx, &e, SETTINGS_PATH))?;
+    Ok((StatusCode::OK, with_etag(settings)))
 }
 
 pub async fn patch_settings(
     Extension(ctx): Extension<SecurityContext>,
     Extension(svc): Extension<Arc<ConcreteService>>,
+    Extension(problems): Extension<ProblemMapping>,
+    headers: HeaderMap,
     Json(req): Json<PatchSimpleUserSettingsRequest>,
-) -> ApiResult<JsonBody<SimpleUserSettingsDto>> {
-    let settings = svc.patch_settings(&ctx, req.into()).await?;
-    Ok(Json(settings.into()))
+) -> ApiResult<impl IntoResponse> {
+    let expected_versions =
+        expected_versions(&headers).map_err(|e| problems.to_problem(&ctx, &e, SETTINGS_PATH))?;
+    let settings = svc
+        .patch_settings(&ctx, req.into(), expected_versions.as_deref())
+        .await
+        .map_err(|e| problems.to_problem(&ctx, &e, SETTINGS_PATH))?;
+    Ok(with_etag(settings))
 }
@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use super::super::handlers::{etag, expected_versions};
+    use crate::domain::error::DomainError;
+    use axum::http::{HeaderMap, HeaderValue, header};
+
+    fn if_match(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MATCH, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_etag_round_trips_through_if_match() {
+        assert_eq!(etag(7), "\"7\"");
+        assert_eq!(
+            expected_versions(&if_match(&etag(7))).unwrap(),
+            Some(vec![7])
+        );
+    }
+
+    #[test]
+    fn test_missing_or_wildcard_if_match_is_unconditional() {
+        assert_eq!(expected_versions(&HeaderMap::new()).unwrap(), None);
+        assert_eq!(expected_versions(&if_match("*")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_if_match_list_accepts_any_of_its_versions() {
+        assert_eq!(
+            expected_versions(&if_match("\"1\", \"2\"")).unwrap(),
+            Some(vec![1, 2])
+        );
+        assert_eq!(
+            expected_versions(&if_match("W/\"1\", \"seven\",\"3\"")).unwrap(),
+            Some(vec![3])
+        );
+    }
+
+    #[test]
+    fn test_weak_or_malformed_if_match_fails_the_precondition() {
+        for value in ["W/\"7\"", "7", "\"seven\"", "W/\"1\", \"two\""] {
+            let err = expected_versions(&if_match(value)).unwrap_err();
+            assert!(
+                matches!(err, DomainError::PreconditionFailed(_)),
+                "{value}: {err:?}"
+            );
+        }
+    }
+}
@@ -7,3 +7,5 @@ pub mod routes;
 mod dto_test;
 #[cfg(test)]
 mod error_test;
+#[cfg(test)]
+mod handlers_test;
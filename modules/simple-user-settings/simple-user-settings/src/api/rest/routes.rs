@@ -1,12 +1,19 @@
+use crate::api::rest::error::ProblemMapping;
 use crate::api::rest::{dto, handlers};
 use crate::domain::service::Service;
 use crate::infra::storage::sea_orm_repo::SeaOrmSettingsRepository;
 use axum::http::StatusCode;
 use axum::{Extension, Router};
-use modkit::api::operation_builder::LicenseFeature;
+use modkit::api::operation_builder::{LicenseFeature, ParamLocation, ParamSpec};
 use modkit::api::{OpenApiRegistry, OperationBuilder};
 use std::sync::Arc;
 
+pub(crate) const SETTINGS_PATH: &str = "/simple-user-settings/v1/settings";
+
+/// Access denials reach only trusted callers as 403; see `ProblemMapping`.
+const FORBIDDEN_DESCRIPTION: &str =
+    "Access forbidden (trusted callers only; other callers receive 404 Not Found)";
+
 /// Type alias for the concrete service type.
 pub type ConcreteService = Service<SeaOrmSettingsRepository>;
 
@@ -20,15 +27,31 @@ impl AsRef<str> for License {
 
 impl LicenseFeature for License {}
 
+fn if_match_param() -> ParamSpec {
+    ParamSpec {
+        name: "If-Match".to_owned(),
+        location: ParamLocation::Header,
+        required: false,
+        description: Some(
+            "ETag of the settings version to replace; without it the write is unconditional"
+                .to_owned(),
+        ),
+        param_type: "string".to_owned(),
+    }
+}
+
 pub fn register_routes(
     mut router: Router,
     openapi: &dyn OpenApiRegistry,
     service: Arc<ConcreteService>,
+    problems: ProblemMapping,
 ) -> Router {
-    router = OperationBuilder::get("/simple-user-settings/v1/settings")
+    router = OperationBuilder::get(SETTINGS_PATH)
         .operation_id("simple_user_settings.get_settings")
         .summary("Get user settings")
-        .description("Retrieve settings for the authenticated user")
+        .description(
+            "Retrieve settings for the authenticated user; the ETag header carries their version",
+        )
         .tag("Settings")
         .authenticated()
         .require_license_features::<License>([])
@@ -39,14 +62,18 @@ pub fn register_routes(
             "Settings retrieved",
         )
         .error_401(openapi)
-        .error_403(openapi)
+        .problem_response(openapi, StatusCode::FORBIDDEN, FORBIDDEN_DESCRIPTION)
+        .error_404(openapi)
         .error_500(openapi)
         .register(router, openapi);
 
-    router = OperationBuilder::post("/simple-user-settings/v1/settings")
+    router = OperationBuilder::post(SETTINGS_PATH)
         .operation_id("simple_user_settings.update_settings")
         .summary("Update user settings")
-        .description("Full update of user settings (POST semantics)")
+        .description(
+            "Full update of user settings (POST semantics). Send the ETag from a previous read in If-Match to update only that version",
+        )
+        .param(if_match_param())
         .tag("Settings")
         .authenticated()
         .require_license_features::<License>([])
@@ -59,15 +86,25 @@ pub fn register_routes(
         )
         .error_400(openapi)
         .error_401(openapi)
-        .error_403(openapi)
+        .problem_response(openapi, StatusCode::FORBIDDEN, FORBIDDEN_DESCRIPTION)
+        .error_404(openapi)
+        .error_409(openapi)
+        .problem_response(
+            openapi,
+            StatusCode::PRECONDITION_FAILED,
+            "Precondition Failed",
+        )
         .error_422(openapi)
         .error_500(openapi)
         .register(router, openapi);
 
-    router = OperationBuilder::patch("/simple-user-settings/v1/settings")
+    router = OperationBuilder::patch(SETTINGS_PATH)
         .operation_id("simple_user_settings.patch_settings")
         .summary("Partially update user settings")
-        .description("Partial update of user settings (PATCH semantics)")
+        .description(
+            "Partial update of user settings (PATCH semantics). Send the ETag from a previous read in If-Match to update only that version",
+        )
+        .param(if_match_param())
         .tag("Settings")
         .authenticated()
         .require_license_features::<License>([])
@@ -80,12 +117,19 @@ pub fn register_routes(
         )
         .error_400(openapi)
         .error_401(openapi)
-        .error_403(openapi)
+        .problem_response(openapi, StatusCode::FORBIDDEN, FORBIDDEN_DESCRIPTION)
+        .error_404(openapi)
+        .error_409(openapi)
+        .problem_response(
+            openapi,
+            StatusCode::PRECONDITION_FAILED,
+            "Precondition Failed",
+        )
         .error_422(openapi)
         .error_500(openapi)
         .register(router, openapi);
 
-    router = router.layer(Extension(service));
+    router = router.layer(Extension(service)).layer(Extension(problems));
 
     router
 }
@@ -4,12 +4,19 @@ use serde::Deserialize;
 pub struct SettingsConfig {
     #[serde(default = "default_max_field_length")]
     pub max_field_length: usize,
+    /// Subject types (see `SecurityContext::subject_type`) whose access
+    /// denials are reported as `forbidden` with their scope detail. Every
+    /// other caller gets them masked as `not_found`: the detail reveals how
+    /// access is scoped.
+    #[serde(default)]
+    pub trusted_subject_types: Vec<String>,
 }
 
 impl Default for SettingsConfig {
     fn default() -> Self {
         Self {
             max_field_length: default_max_field_length(),
+            trusted_subject_types: Vec::new(),
         }
     }
 }
@@ -14,6 +14,14 @@ pub enum DomainError {
     #[error("Access forbidden: {0}")]
     Forbidden(String),
 
+    /// The caller's `If-Match` version is not the stored one.
+    #[error("Precondition failed: {0}")]
+    PreconditionFailed(String),
+
+    /// The stored version changed between the version check and the write.
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 
@@ -33,6 +41,14 @@ impl DomainError {
         Self::Forbidden(message.into())
     }
 
+    pub fn precondition_failed(message: impl Into<String>) -> Self {
+        Self::PreconditionFailed(message.into())
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::Conflict(message.into())
+    }
+
     pub fn internal(message: impl Into<String>) -> Self {
         Self::Internal(message.into())
     }
@@ -55,6 +71,8 @@ impl From<DomainError> for SettingsError {
             DomainError::NotFound => Self::not_found(),
             DomainError::Validation { field, message } => Self::validation(field, message),
             DomainError::Forbidden(_) => Self::forbidden(),
+            DomainError::PreconditionFailed(message) => Self::precondition_failed(message),
+            DomainError::Conflict(_) => Self::conflict(),
             DomainError::Internal(_) | DomainError::Database(_) => Self::internal(),
         }
     }
@@ -19,6 +19,13 @@ where
         scope: &AccessScope,
     ) -> Result<Option<SimpleUserSettings>, DomainError>;
 
+    /// Replace the settings, creating them if missing, and bump the version.
+    ///
+    /// With `expected_versions`, the write only happens while the stored
+    /// version (`0` when nothing is stored) is one of them: any other version
+    /// is `PreconditionFailed`, and losing a race to a concurrent write after
+    /// the check is `Conflict`.
+    #[allow(clippy::too_many_arguments)]
     async fn upsert_full<C: DBRunner>(
         &self,
         conn: &C,
@@ -27,8 +34,11 @@ where
         tenant_id: Uuid,
         theme: Option<String>,
         language: Option<String>,
+        expected_versions: Option<&[i64]>,
     ) -> Result<SimpleUserSettings, DomainError>;
 
+    /// Merge `patch` into the stored settings; `expected_versions` as in
+    /// [`Self::upsert_full`].
     async fn upsert_patch<C: DBRunner>(
         &self,
         conn: &C,
@@ -36,5 +46,6 @@ where
         user_id: Uuid,
         tenant_id: Uuid,
         patch: SimpleUserSettingsPatch,
+        expected_versions: Option<&[i64]>,
     ) -> Result<SimpleUserSettings, DomainError>;
 }
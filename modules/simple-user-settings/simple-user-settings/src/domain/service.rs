@@ -101,14 +101,25 @@ impl<R: SettingsRepository> Service<R> {
                 tenant_id,
                 theme: None,
                 language: None,
+                version: 0,
             })
         }
     }
 
+    /// Replace the caller's settings.
+    ///
+    /// With `expected_versions`, the update only applies while the stored
+    /// version (`0` before the first save) is one of them.
+    ///
+    /// # Errors
+    /// `PreconditionFailed` when the stored version is not in
+    /// `expected_versions`, `Conflict` when another write lands between the
+    /// version check and this one.
     pub async fn update_settings(
         &self,
         ctx: &SecurityContext,
         update: SimpleUserSettingsUpdate,
+        expected_versions: Option<&[i64]>,
     ) -> Result<SimpleUserSettings, DomainError> {
         self.validate_field(SettingsFields::THEME, &update.theme)?;
         self.validate_field(SettingsFields::LANGUAGE, &update.language)?;
@@ -138,15 +149,22 @@ impl<R: SettingsRepository> Service<R> {
                 tenant_id,
                 Some(update.theme),
                 Some(update.language),
+                expected_versions,
             )
             .await?;
         Ok(settings)
     }
 
+    /// Merge `patch` into the caller's settings; `expected_versions` as in
+    /// [`Self::update_settings`].
+    ///
+    /// # Errors
+    /// See [`Self::update_settings`].
     pub async fn patch_settings(
         &self,
         ctx: &SecurityContext,
         patch: SimpleUserSettingsPatch,
+        expected_versions: Option<&[i64]>,
     ) -> Result<SimpleUserSettings, DomainError> {
         if let Some(ref theme) = patch.theme {
             self.validate_field(SettingsFields::THEME, theme)?;
@@ -173,7 +191,7 @@ impl<R: SettingsRepository> Service<R> {
 
         let settings = self
             .repo
-            .upsert_patch(&conn, &scope, user_id, tenant_id, patch)
+            .upsert_patch(&conn, &scope, user_id, tenant_id, patch, expected_versions)
             .await?;
         Ok(settings)
     }
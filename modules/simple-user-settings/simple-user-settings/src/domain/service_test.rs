@@ -150,6 +150,7 @@ mod tests {
                     theme: "dark".to_owned(),
                     language: "en".to_owned(),
                 },
+                None,
             )
             .await
             .unwrap();
@@ -178,6 +179,7 @@ mod tests {
                     theme: "light".to_owned(),
                     language: "es".to_owned(),
                 },
+                None,
             )
             .await
             .unwrap();
@@ -207,6 +209,7 @@ mod tests {
                     theme: too_long,
                     language: "en".to_owned(),
                 },
+                None,
             )
             .await;
 
@@ -234,6 +237,7 @@ mod tests {
                     theme: "dark".to_owned(),
                     language: too_long,
                 },
+                None,
             )
             .await;
 
@@ -260,6 +264,7 @@ mod tests {
                     theme: "dark".to_owned(),
                     language: "en".to_owned(),
                 },
+                None,
             )
             .await
             .unwrap();
@@ -272,6 +277,7 @@ mod tests {
                     theme: Some("light".to_owned()),
                     language: None,
                 },
+                None,
             )
             .await
             .unwrap();
@@ -299,6 +305,7 @@ mod tests {
                     theme: None,
                     language: Some(too_long),
                 },
+                None,
             )
             .await;
 
@@ -321,6 +328,7 @@ mod tests {
                     theme: "dark".to_owned(),
                     language: "en".to_owned(),
                 },
+                None,
             )
             .await
             .unwrap();
@@ -333,6 +341,7 @@ mod tests {
                     theme: None,
                     language: None,
                 },
+                None,
             )
             .await
             .unwrap();
@@ -356,6 +365,7 @@ mod tests {
                     theme: Some("dark".to_owned()),
                     language: None,
                 },
+                None,
             )
             .await
             .unwrap();
@@ -393,6 +403,7 @@ mod tests {
                     theme: "dark".to_owned(),
                     language: "en".to_owned(),
                 },
+                None,
             )
             .await
             .unwrap();
@@ -429,6 +440,7 @@ mod tests {
                     theme: "dark".to_owned(),
                     language: "en".to_owned(),
                 },
+                None,
             )
             .await
             .unwrap();
@@ -439,4 +451,175 @@ mod tests {
         assert_eq!(result.language, None);
         assert_eq!(result.tenant_id, tenant2.subject_tenant_id());
     }
+    // =========================================================================
+    // optimistic concurrency tests
+    // =========================================================================
+
+    fn update(theme: &str) -> SimpleUserSettingsUpdate {
+        SimpleUserSettingsUpdate {
+            theme: theme.to_owned(),
+            language: "en".to_owned(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_write_bumps_the_version() {
+        let db = inmem_db().await;
+        let service = build_service(db, ServiceConfig::default());
+        let ctx = create_test_context();
+
+        assert_eq!(service.get_settings(&ctx).await.unwrap().version, 0);
+
+        let created = service
+            .update_settings(&ctx, update("dark"), None)
+            .await
+            .unwrap();
+        assert_eq!(created.version, 1);
+
+        let patched = service
+            .patch_settings(&ctx, SimpleUserSettingsPatch::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(patched.version, 2);
+        assert_eq!(service.get_settings(&ctx).await.unwrap().version, 2);
+    }
+
+    #[tokio::test]
+    async fn test_update_with_matching_version_succeeds() {
+        let db = inmem_db().await;
+        let service = build_service(db, ServiceConfig::default());
+        let ctx = create_test_context();
+
+        let created = service
+            .update_settings(&ctx, update("dark"), Some(&[0]))
+            .await
+            .unwrap();
+        assert_eq!(created.version, 1);
+
+        let updated = service
+            .update_settings(&ctx, update("light"), Some(&[created.version]))
+            .await
+            .unwrap();
+        assert_eq!(updated.version, 2);
+        assert_eq!(updated.theme, Some("light".to_owned()));
+
+        let patched = service
+            .patch_settings(
+                &ctx,
+                SimpleUserSettingsPatch {
+                    theme: None,
+                    language: Some("fr".to_owned()),
+                },
+                Some(&[updated.version]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(patched.version, 3);
+        assert_eq!(patched.theme, Some("light".to_owned()));
+        assert_eq!(patched.language, Some("fr".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn test_update_with_stale_version_fails_with_412() {
+        let db = inmem_db().await;
+        let service = build_service(db, ServiceConfig::default());
+        let ctx = create_test_context();
+
+        let first = service
+            .update_settings(&ctx, update("dark"), None)
+            .await
+            .unwrap();
+        let _ = service
+            .update_settings(&ctx, update("light"), Some(&[first.version]))
+            .await
+            .unwrap();
+
+        let err = service
+            .update_settings(&ctx, update("blue"), Some(&[first.version]))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, DomainError::PreconditionFailed(ref msg) if msg == "expected version 1, found 2"),
+            "{err:?}"
+        );
+        let problem = crate::api::rest::error::domain_error_to_problem(&err, "/settings");
+        assert_eq!(problem.status, axum::http::StatusCode::PRECONDITION_FAILED);
+
+        let err = service
+            .patch_settings(
+                &ctx,
+                SimpleUserSettingsPatch::default(),
+                Some(&[first.version]),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::PreconditionFailed(_)), "{err:?}");
+
+        let stored = service.get_settings(&ctx).await.unwrap();
+        assert_eq!(stored.theme, Some("light".to_owned()));
+        assert_eq!(stored.version, 2);
+    }
+
+    #[tokio::test]
+    async fn test_update_matches_any_of_several_versions() {
+        let db = inmem_db().await;
+        let service = build_service(db, ServiceConfig::default());
+        let ctx = create_test_context();
+
+        let first = service
+            .update_settings(&ctx, update("dark"), None)
+            .await
+            .unwrap();
+
+        let updated = service
+            .update_settings(&ctx, update("light"), Some(&[7, first.version]))
+            .await
+            .unwrap();
+        assert_eq!(updated.version, 2);
+
+        let err = service
+            .update_settings(&ctx, update("blue"), Some(&[first.version, 5]))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, DomainError::PreconditionFailed(ref msg) if msg == "expected version 1 or 5, found 2"),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_expecting_version_zero_fails_once_settings_exist() {
+        let db = inmem_db().await;
+        let service = build_service(db, ServiceConfig::default());
+        let ctx = create_test_context();
+
+        let _ = service
+            .update_settings(&ctx, update("dark"), Some(&[0]))
+            .await
+            .unwrap();
+
+        let err = service
+            .update_settings(&ctx, update("light"), Some(&[0]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::PreconditionFailed(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn test_update_without_version_overwrites_any_version() {
+        let db = inmem_db().await;
+        let service = build_service(db, ServiceConfig::default());
+        let ctx = create_test_context();
+
+        for theme in ["dark", "light", "blue"] {
+            let _ = service
+                .update_settings(&ctx, update(theme), None)
+                .await
+                .unwrap();
+        }
+
+        let stored = service.get_settings(&ctx).await.unwrap();
+        assert_eq!(stored.theme, Some("blue".to_owned()));
+        assert_eq!(stored.version, 3);
+    }
 }
@@ -12,6 +12,7 @@ pub struct Model {
     pub user_id: Uuid,
     pub theme: Option<String>,
     pub language: Option<String>,
+    pub version: i64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = match backend {
+            sea_orm::DatabaseBackend::Postgres | sea_orm::DatabaseBackend::MySql => {
+                "ALTER TABLE settings ADD COLUMN version BIGINT NOT NULL DEFAULT 0;"
+            }
+            sea_orm::DatabaseBackend::Sqlite => {
+                "ALTER TABLE settings ADD COLUMN version INTEGER NOT NULL DEFAULT 0;"
+            }
+        };
+
+        conn.execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        let sql = "ALTER TABLE settings DROP COLUMN version;";
+        conn.execute_unprepared(sql).await?;
+        Ok(())
+    }
+}
@@ -1,12 +1,16 @@
 use sea_orm_migration::prelude::*;
 
 pub mod initial_001;
+pub mod m20261015_000002_add_version;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(initial_001::Migration)]
+        vec![
+            Box::new(initial_001::Migration),
+            Box::new(m20261015_000002_add_version::Migration),
+        ]
     }
 }
@@ -1,7 +1,10 @@
 use async_trait::async_trait;
-use modkit_db::secure::{DBRunner, ScopeError, SecureEntityExt, SecureInsertExt, SecureOnConflict};
+use modkit_db::secure::{
+    DBRunner, ScopeError, SecureEntityExt, SecureInsertExt, SecureOnConflict, SecureUpdateExt,
+};
 use modkit_security::AccessScope;
-use sea_orm::{ActiveValue, EntityTrait};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveValue, ColumnTrait, Condition, EntityTrait, SqlErr};
 use simple_user_settings_sdk::models::{SimpleUserSettings, SimpleUserSettingsPatch};
 use uuid::Uuid;
 
@@ -37,69 +40,167 @@ fn map_scope_error(e: ScopeError) -> DomainError {
     }
 }
 
-#[async_trait]
-impl SettingsRepository for SeaOrmSettingsRepository {
-    async fn find_by_user<C: DBRunner>(
-        &self,
+/// Map errors of a versioned insert: a row created concurrently is a conflict.
+fn map_insert_error(e: ScopeError) -> DomainError {
+    match e {
+        ScopeError::Db(ref db)
+            if matches!(db.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) =>
+        {
+            DomainError::conflict("settings were created concurrently")
+        }
+        other => map_scope_error(other),
+    }
+}
+
+fn active_model(
+    tenant_id: Uuid,
+    user_id: Uuid,
+    theme: Option<String>,
+    language: Option<String>,
+    version: i64,
+) -> entity::ActiveModel {
+    entity::ActiveModel {
+        tenant_id: ActiveValue::Set(tenant_id),
+        user_id: ActiveValue::Set(user_id),
+        theme: ActiveValue::Set(theme),
+        language: ActiveValue::Set(language),
+        version: ActiveValue::Set(version),
+    }
+}
+
+impl SeaOrmSettingsRepository {
+    async fn find_model<C: DBRunner>(
         conn: &C,
         scope: &AccessScope,
-    ) -> Result<Option<SimpleUserSettings>, DomainError> {
-        let result = SettingsEntity::find()
+    ) -> Result<Option<entity::Model>, DomainError> {
+        SettingsEntity::find()
             .secure()
             .scope_with(scope)
             .one(conn)
             .await
-            .map_err(map_scope_error)?;
-
-        Ok(result.map(Into::into))
+            .map_err(map_scope_error)
     }
 
-    async fn upsert_full<C: DBRunner>(
-        &self,
+    /// Insert or overwrite the settings whatever their version, bumping it.
+    async fn write<C: DBRunner>(
         conn: &C,
         scope: &AccessScope,
-        user_id: Uuid,
-        tenant_id: Uuid,
-        theme: Option<String>,
-        language: Option<String>,
-    ) -> Result<SimpleUserSettings, DomainError> {
-        let active_model = entity::ActiveModel {
-            tenant_id: ActiveValue::Set(tenant_id),
-            user_id: ActiveValue::Set(user_id),
-            theme: ActiveValue::Set(theme.clone()),
-            language: ActiveValue::Set(language.clone()),
-        };
-
+        model: entity::ActiveModel,
+    ) -> Result<i64, DomainError> {
         // Full replacement - overwrites all columns (SecureOnConflict validates tenant immutability)
         let on_conflict = SecureOnConflict::<SettingsEntity>::columns([
             entity::Column::TenantId,
             entity::Column::UserId,
         ])
         .update_columns([entity::Column::Theme, entity::Column::Language])
+        .and_then(|c| {
+            c.value(
+                entity::Column::Version,
+                Expr::col((SettingsEntity, entity::Column::Version)).add(1),
+            )
+        })
         .map_err(map_scope_error)?;
 
-        SettingsEntity::insert(active_model)
+        SettingsEntity::insert(model.clone())
             .secure()
-            .scope_with_model(
-                scope,
-                &entity::ActiveModel {
-                    tenant_id: ActiveValue::Set(tenant_id),
-                    user_id: ActiveValue::Set(user_id),
-                    theme: ActiveValue::Set(theme.clone()),
-                    language: ActiveValue::Set(language.clone()),
-                },
-            )
+            .scope_with_model(scope, &model)
             .map_err(map_scope_error)?
             .on_conflict(on_conflict)
+            .exec_with_returning(conn)
+            .await
+            .map(|written| written.version)
+            .map_err(map_scope_error)
+    }
+
+    /// Write only while the stored version (`0` when nothing is stored) is
+    /// one of `expected`, bumping it.
+    async fn write_if_version<C: DBRunner>(
+        conn: &C,
+        scope: &AccessScope,
+        model: entity::ActiveModel,
+        expected: &[i64],
+    ) -> Result<i64, DomainError> {
+        let found = Self::find_model(conn, scope).await?.map(|m| m.version);
+        let current = found.unwrap_or(0);
+        if !expected.contains(&current) {
+            let expected = expected
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" or ");
+            return Err(DomainError::precondition_failed(format!(
+                "expected version {expected}, found {current}"
+            )));
+        }
+
+        let next = current + 1;
+        if found.is_none() {
+            SettingsEntity::insert(model.clone())
+                .secure()
+                .scope_with_model(scope, &model)
+                .map_err(map_scope_error)?
+                .exec(conn)
+                .await
+                .map_err(map_insert_error)?;
+            return Ok(next);
+        }
+
+        let (ActiveValue::Set(theme), ActiveValue::Set(language)) = (model.theme, model.language)
+        else {
+            return Err(DomainError::internal("settings write without values"));
+        };
+        let result = SettingsEntity::update_many()
+            .col_expr(entity::Column::Theme, Expr::value(theme))
+            .col_expr(entity::Column::Language, Expr::value(language))
+            .col_expr(entity::Column::Version, Expr::value(next))
+            .secure()
+            .filter(Condition::all().add(entity::Column::Version.eq(current)))
+            .scope_with(scope)
             .exec(conn)
             .await
             .map_err(map_scope_error)?;
+        if result.rows_affected == 0 {
+            return Err(DomainError::conflict(format!(
+                "version {current} was replaced before the write"
+            )));
+        }
+        Ok(next)
+    }
+}
+
+#[async_trait]
+impl SettingsRepository for SeaOrmSettingsRepository {
+    async fn find_by_user<C: DBRunner>(
+        &self,
+        conn: &C,
+        scope: &AccessScope,
+    ) -> Result<Option<SimpleUserSettings>, DomainError> {
+        let result = Self::find_model(conn, scope).await?;
+        Ok(result.map(Into::into))
+    }
+
+    async fn upsert_full<C: DBRunner>(
+        &self,
+        conn: &C,
+        scope: &AccessScope,
+        user_id: Uuid,
+        tenant_id: Uuid,
+        theme: Option<String>,
+        language: Option<String>,
+        expected_versions: Option<&[i64]>,
+    ) -> Result<SimpleUserSettings, DomainError> {
+        let model = active_model(tenant_id, user_id, theme.clone(), language.clone(), 1);
+        let version = match expected_versions {
+            Some(expected) => Self::write_if_version(conn, scope, model, expected).await?,
+            None => Self::write(conn, scope, model).await?,
+        };
 
         Ok(SimpleUserSettings {
             user_id,
             tenant_id,
             theme,
             language,
+            version,
         })
     }
 
@@ -110,15 +211,11 @@ impl SettingsRepository for SeaOrmSettingsRepository {
         user_id: Uuid,
         tenant_id: Uuid,
         patch: SimpleUserSettingsPatch,
+        expected_versions: Option<&[i64]>,
     ) -> Result<SimpleUserSettings, DomainError> {
         // Read existing settings to merge with patch
         // This approach is database-agnostic and avoids SQLite COALESCE type issues
-        let existing = SettingsEntity::find()
-            .secure()
-            .scope_with(scope)
-            .one(conn)
-            .await
-            .map_err(map_scope_error)?;
+        let existing = Self::find_model(conn, scope).await?;
 
         // Merge patch with existing values
         let (theme, language) = match existing {
@@ -134,7 +231,15 @@ impl SettingsRepository for SeaOrmSettingsRepository {
         };
 
         // Use upsert_full with merged values
-        self.upsert_full(conn, scope, user_id, tenant_id, theme, language)
-            .await
+        self.upsert_full(
+            conn,
+            scope,
+            user_id,
+            tenant_id,
+            theme,
+            language,
+            expected_versions,
+        )
+        .await
     }
 }
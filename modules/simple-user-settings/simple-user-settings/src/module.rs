@@ -12,6 +12,7 @@ use authz_resolver_sdk::{AuthZResolverClient, PolicyEnforcer};
 
 use simple_user_settings_sdk::SimpleUserSettingsClientV1;
 
+use crate::api::rest::error::ProblemMapping;
 use crate::api::rest::routes;
 use crate::config::SettingsConfig;
 use crate::domain::local_client::LocalClient;
@@ -51,6 +52,8 @@ impl Module for SettingsModule {
     async fn init(&self, ctx: &ModuleCtx) -> anyhow::Result<()> {
         info!("Initializing {} module", Self::MODULE_NAME);
 
+        crate::errors::ErrorCode::register_catalog();
+
         let cfg: SettingsConfig = ctx.config()?;
 
         let db: Arc<DBProvider<DbError>> = Arc::new(ctx.db_required()?);
@@ -86,18 +89,20 @@ impl Module for SettingsModule {
 impl modkit::contracts::RestApiCapability for SettingsModule {
     fn register_rest(
         &self,
-        _ctx: &ModuleCtx,
+        ctx: &ModuleCtx,
         router: Router,
         openapi: &dyn OpenApiRegistry,
     ) -> anyhow::Result<Router> {
         info!("Settings module: register_rest called");
+        let cfg: SettingsConfig = ctx.config()?;
         let service = self
             .service
             .get()
             .ok_or_else(|| anyhow::anyhow!("Service not initialized"))?
             .clone();
 
-        let router = routes::register_routes(router, openapi, service);
+        let problems = ProblemMapping::trusting(cfg.trusted_subject_types);
+        let router = routes::register_routes(router, openapi, service, problems);
         info!("Settings module: REST routes registered successfully");
         Ok(router)
     }
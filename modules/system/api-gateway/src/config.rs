@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::middleware::license_validation::LicenseDenialStatus;
+
 fn default_require_auth_by_default() -> bool {
     true
 }
@@ -8,6 +10,18 @@ fn default_body_limit_bytes() -> usize {
     16 * 1024 * 1024
 }
 
+fn default_license_check_timeout_ms() -> u64 {
+    2_000
+}
+
+fn default_license_cache_ttl_ms() -> u64 {
+    30_000
+}
+
+fn default_license_cache_capacity() -> usize {
+    10_000
+}
+
 /// API gateway configuration - reused from `api_gateway` module
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(deny_unknown_fields)]
@@ -41,6 +55,26 @@ pub struct ApiGatewayConfig {
     /// If true, routes without explicit security requirement still require authentication (AuthN-only).
     #[serde(default = "default_require_auth_by_default")]
     pub require_auth_by_default: bool,
+
+    /// Scrub server errors (5xx) before they reach clients: generic title and
+    /// no metadata beyond an incident/correlation id. Client errors are untouched.
+    #[serde(default)]
+    pub production_errors: bool,
+
+    /// Longest value an error response echoes in `x-trace-id`/`x-error-code`;
+    /// longer values are dropped. Default: 256 bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_error_header_len: Option<usize>,
+
+    /// Delay, in seconds, suggested by the `retry_after` action of rate-limit
+    /// errors that carry no explicit delay. Default: 30 seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_retry_after_secs: Option<u64>,
+
+    /// Rewrite selected error type URIs to a public taxonomy at the edge,
+    /// keyed by source type URI. Unmatched errors pass through unchanged.
+    #[serde(default)]
+    pub error_code_remap: modkit::api::problem::CodeRemapper,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -50,6 +84,17 @@ pub struct Defaults {
     pub rate_limit: RateLimitDefaults,
     /// Global request body size limit in bytes
     pub body_limit_bytes: usize,
+    /// Upper bound on a single license check in milliseconds; a check still
+    /// running after it denies the request with 503 (fail closed)
+    pub license_check_timeout_ms: u64,
+    /// How long a caller's held license features are reused, in milliseconds;
+    /// 0 disables the cache
+    pub license_cache_ttl_ms: u64,
+    /// Upper bound on callers whose held license features are cached
+    pub license_cache_capacity: usize,
+    /// Status answering callers lacking a required license feature:
+    /// `forbidden` (403) or `payment_required` (402)
+    pub license_denial_status: LicenseDenialStatus,
 }
 
 impl Default for Defaults {
@@ -57,6 +102,10 @@ impl Default for Defaults {
         Self {
             rate_limit: RateLimitDefaults::default(),
             body_limit_bytes: default_body_limit_bytes(),
+            license_check_timeout_ms: default_license_check_timeout_ms(),
+            license_cache_ttl_ms: default_license_cache_ttl_ms(),
+            license_cache_capacity: default_license_cache_capacity(),
+            license_denial_status: LicenseDenialStatus::default(),
         }
     }
 }
@@ -0,0 +1,114 @@
+//! Structured access log
+//!
+//! Every request handled by the gateway emits exactly one event on
+//! [`ACCESS_LOG_TARGET`] at info level, whether it succeeded or failed. Failed
+//! requests also carry the `x-error-code` of their Problem response, so access
+//! logs can be joined with error reports by trace id and code. Query strings
+//! are never recorded.
+
+use std::time::Instant;
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use modkit::api::error_layer::extract_trace_id;
+use modkit::api::problem::{ERROR_CODE_HEADER, TRACE_ID_HEADER};
+
+/// Tracing target of access-log events.
+pub const ACCESS_LOG_TARGET: &str = "api_gateway::access";
+
+/// Middleware emitting one access-log event per request.
+pub async fn access_log_middleware(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = request.method().clone();
+    let path = request.uri().path().to_owned();
+    let request_trace_id = extract_trace_id(request.headers());
+
+    let response = next.run(request).await;
+
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty())
+    };
+    let trace_id = header(TRACE_ID_HEADER).or(request_trace_id.as_deref());
+    let status = response.status();
+    let error_code = (status.is_client_error() || status.is_server_error())
+        .then(|| header(ERROR_CODE_HEADER))
+        .flatten();
+
+    tracing::info!(
+        target: ACCESS_LOG_TARGET,
+        method = %method,
+        path = %path,
+        status = status.as_u16(),
+        latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+        trace_id,
+        error_code,
+        "request completed"
+    );
+
+    response
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use modkit::test_support::{EventCapture, EventFields};
+    use tower::ServiceExt;
+
+    async fn call(uri: &str) -> EventFields {
+        let capture = EventCapture::target(ACCESS_LOG_TARGET);
+        let _guard = capture.install();
+
+        let app = Router::new()
+            .route("/users", get(|| async { StatusCode::OK }))
+            .route(
+                "/users/{id}",
+                get(|| async {
+                    modkit::api::problem::not_found("no such user")
+                        .with_code("USERS_NOT_FOUND")
+                        .with_trace_id("trace-404")
+                }),
+            )
+            .layer(axum::middleware::from_fn(access_log_middleware));
+
+        app.oneshot(
+            Request::get(uri)
+                .header("x-request-id", "req-1")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let mut events = capture.events();
+        assert_eq!(events.len(), 1, "{events:?}");
+        events.pop().unwrap()
+    }
+
+    #[tokio::test]
+    async fn failing_request_records_error_code() {
+        let event = call("/users/42?token=secret").await;
+        assert_eq!(event["method"], "GET");
+        assert_eq!(event["path"], "/users/42");
+        assert_eq!(event["status"], "404");
+        assert_eq!(event["trace_id"], "trace-404");
+        assert_eq!(event["error_code"], "USERS_NOT_FOUND");
+        assert!(event.contains_key("latency_ms"));
+        assert!(event.values().all(|v| !v.contains("secret")), "{event:?}");
+    }
+
+    #[tokio::test]
+    async fn successful_request_has_no_error_code() {
+        let event = call("/users").await;
+        assert_eq!(event["status"], "200");
+        assert_eq!(event["trace_id"], "req-1");
+        assert!(!event.contains_key("error_code"), "{event:?}");
+    }
+}
@@ -0,0 +1,225 @@
+//! Structured audit events for access denials
+//!
+//! Every 401/403 decision taken by the gateway middleware, and every access
+//! check that failed closed (503), emits one event on
+//! [`AUDIT_TARGET`] at info level, so a subscriber can route denials to an
+//! audit sink independently of regular logs. Events carry the principal id,
+//! the denied resource and the required vs held entitlements; credentials and
+//! query strings are never recorded.
+
+use axum::http::Method;
+use uuid::Uuid;
+
+/// Tracing target of access-denial audit events.
+pub const AUDIT_TARGET: &str = "api_gateway::audit";
+
+/// Outcome recorded in an audit event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditDecision {
+    /// The caller could not be authenticated (401).
+    Unauthorized,
+    /// The caller is authenticated but lacks an entitlement (403).
+    Forbidden,
+    /// The entitlement check failed or timed out, so access was denied (503).
+    Unavailable,
+}
+
+impl AuditDecision {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Unauthorized => "unauthorized",
+            Self::Forbidden => "forbidden",
+            Self::Unavailable => "unavailable",
+        }
+    }
+}
+
+/// A single access denial to be recorded.
+#[derive(Debug)]
+pub struct AccessDenial<'a> {
+    pub decision: AuditDecision,
+    /// Authenticated subject, `None` when the caller is anonymous.
+    pub principal: Option<Uuid>,
+    pub method: &'a Method,
+    /// Concrete request path (the denied resource), without the query string.
+    pub path: &'a str,
+    /// Matched route template, when known.
+    pub route: Option<&'a str>,
+    /// Features or scopes the endpoint requires.
+    pub required: &'a [String],
+    /// Features or scopes the caller holds.
+    pub held: &'a [String],
+    pub reason: &'a str,
+}
+
+/// Emit the audit event for a denial.
+pub fn emit_denial(denial: &AccessDenial<'_>) {
+    let principal = denial
+        .principal
+        .filter(|id| !id.is_nil())
+        .map_or_else(|| "anonymous".to_owned(), |id| id.to_string());
+
+    tracing::info!(
+        target: AUDIT_TARGET,
+        decision = denial.decision.as_str(),
+        principal = %principal,
+        method = %denial.method,
+        path = denial.path,
+        route = denial.route.unwrap_or(denial.path),
+        required = ?denial.required,
+        held = ?denial.held,
+        reason = denial.reason,
+        "access denied"
+    );
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::middleware::license_validation::{
+        LicenseChecker, LicenseRequirementMap, license_validation_middleware,
+    };
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use modkit::api::operation_builder::{
+        LicenseReqSpec, LicenseRequirement, LicenseScope, OperationSpec, VendorExtensions,
+    };
+    use modkit::test_support::EventCapture;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    fn capture() -> (EventCapture, tracing::subscriber::DefaultGuard) {
+        let capture = EventCapture::target(AUDIT_TARGET);
+        let guard = capture.install();
+        (capture, guard)
+    }
+
+    fn spec_requiring(feature: &str) -> OperationSpec {
+        OperationSpec {
+            method: Method::GET,
+            path: "/reports/{id}".to_owned(),
+            operation_id: None,
+            summary: None,
+            description: None,
+            tags: Vec::new(),
+            params: Vec::new(),
+            request_body: None,
+            responses: Vec::new(),
+            handler_id: "get_report".to_owned(),
+            authenticated: true,
+            is_public: false,
+            rate_limit: None,
+            allowed_request_content_types: None,
+            vendor_extensions: VendorExtensions::default(),
+            license_requirement: Some(LicenseReqSpec {
+                requirement: LicenseRequirement::Feature(feature.to_owned()),
+                scope: LicenseScope::Global,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn license_denial_emits_audit_event() {
+        let (capture, _guard) = capture();
+
+        let map = LicenseRequirementMap::from_specs(&[spec_requiring("gts.x.premium~")]);
+        let app = Router::new()
+            .route("/reports/{id}", get(|| async { StatusCode::OK }))
+            .layer(axum::middleware::from_fn(move |req, next| {
+                license_validation_middleware(map.clone(), req, next)
+            }));
+
+        let response = app
+            .oneshot(
+                Request::get("/reports/42?token=secret")
+                    .header("authorization", "Bearer secret-token")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let events = capture.events();
+        assert_eq!(events.len(), 1, "{events:?}");
+        let event = &events[0];
+        assert_eq!(event["decision"], "forbidden");
+        assert_eq!(event["principal"], "anonymous");
+        assert_eq!(event["method"], "GET");
+        assert_eq!(event["path"], "/reports/42");
+        assert_eq!(event["route"], "/reports/{id}");
+        assert_eq!(event["required"], r#"["gts.x.premium~"]"#);
+        assert!(event["held"].contains("global.base"));
+        assert!(
+            event.values().all(|v| !v.contains("secret")),
+            "credentials leaked into audit event: {event:?}"
+        );
+    }
+
+    /// Checker that never answers in time.
+    struct HangingChecker;
+
+    #[async_trait::async_trait]
+    impl LicenseChecker for HangingChecker {
+        async fn held_features(
+            &self,
+            _ctx: Option<&modkit_security::SecurityContext>,
+        ) -> anyhow::Result<Vec<String>> {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn license_check_timeout_emits_fail_closed_audit_event() {
+        let (capture, _guard) = capture();
+
+        let map = LicenseRequirementMap::from_specs(&[spec_requiring("gts.x.premium~")])
+            .with_checker(Arc::new(HangingChecker))
+            .with_check_timeout(std::time::Duration::from_millis(10));
+        let app = Router::new()
+            .route("/reports/{id}", get(|| async { StatusCode::OK }))
+            .layer(axum::middleware::from_fn(move |req, next| {
+                license_validation_middleware(map.clone(), req, next)
+            }));
+
+        let response = app
+            .oneshot(Request::get("/reports/7").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let events = capture.events();
+        assert_eq!(events.len(), 1, "{events:?}");
+        assert_eq!(events[0]["decision"], "unavailable");
+        assert_eq!(events[0]["reason"], "license check timed out");
+        assert_eq!(events[0]["route"], "/reports/{id}");
+    }
+
+    #[test]
+    fn unauthorized_denial_records_principal() {
+        let (capture, _guard) = capture();
+        let subject = Uuid::new_v4();
+
+        emit_denial(&AccessDenial {
+            decision: AuditDecision::Unauthorized,
+            principal: Some(subject),
+            method: &Method::POST,
+            path: "/users",
+            route: None,
+            required: &[],
+            held: &[],
+            reason: "authentication failed",
+        });
+
+        let events = capture.events();
+        let event = &events[0];
+        assert_eq!(event["decision"], "unauthorized");
+        assert_eq!(event["principal"], subject.to_string());
+        assert_eq!(event["route"], "/users");
+        assert_eq!(event["reason"], "authentication failed");
+    }
+}
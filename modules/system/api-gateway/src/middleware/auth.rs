@@ -6,6 +6,8 @@ use authn_resolver_sdk::{AuthNResolverClient, AuthNResolverError};
 use modkit::api::Problem;
 use modkit_security::SecurityContext;
 
+use super::audit::{AccessDenial, AuditDecision, emit_denial};
+
 /// Route matcher for a specific HTTP method (authenticated routes).
 #[derive(Clone)]
 pub struct RouteMatcher {
@@ -213,6 +215,7 @@ pub async fn authn_middleware(
         }
         AuthRequirement::Required => {
             let Some(token) = extract_bearer_token(req.headers()) else {
+                audit_unauthorized(&req, "missing bearer token");
                 return Problem::new(
                     axum::http::StatusCode::UNAUTHORIZED,
                     "Unauthorized",
@@ -226,12 +229,31 @@ pub async fn authn_middleware(
                     req.extensions_mut().insert(result.security_context);
                     next.run(req).await
                 }
-                Err(err) => authn_error_to_response(&err),
+                Err(err) => {
+                    if matches!(err, AuthNResolverError::Unauthorized(_)) {
+                        audit_unauthorized(&req, "authentication failed");
+                    }
+                    authn_error_to_response(&err)
+                }
             }
         }
     }
 }
 
+/// Record a 401 decision in the audit log. The token itself is never recorded.
+fn audit_unauthorized(req: &axum::extract::Request, reason: &str) {
+    emit_denial(&AccessDenial {
+        decision: AuditDecision::Unauthorized,
+        principal: None,
+        method: req.method(),
+        path: req.uri().path(),
+        route: None,
+        required: &[],
+        held: &[],
+        reason,
+    });
+}
+
 /// Convert `AuthNResolverError` to an RFC-9457 Problem Details response.
 fn authn_error_to_response(err: &AuthNResolverError) -> axum::response::Response {
     log_authn_error(err);
@@ -1,20 +1,155 @@
-use axum::extract::Request;
-use axum::http::StatusCode;
+use axum::RequestExt;
+use axum::extract::{RawPathParams, Request};
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
 use dashmap::DashMap;
 use http::Method;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use modkit::api::{OperationSpec, Problem};
+use modkit::api::OperationSpec;
+use modkit::api::operation_builder::{LicenseReqSpec, LicenseRequirement, LicenseScope};
+use modkit::api::problem::{
+    FORBIDDEN_V1, MISSING_FEATURES_METADATA_KEY, PAYMENT_REQUIRED_V1, SERVICE_UNAVAILABLE_V1,
+};
+use modkit_security::SecurityContext;
+use uuid::Uuid;
+
+use super::audit::{AccessDenial, AuditDecision, emit_denial};
 
 const BASE_FEATURE: &str = "gts.x.core.lic.feat.v1~x.core.global.base.v1";
 
+/// Upper bound on a single license check unless configured otherwise.
+pub const DEFAULT_LICENSE_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long held features are reused unless configured otherwise.
+pub const DEFAULT_LICENSE_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Upper bound on cached callers unless configured otherwise.
+pub const DEFAULT_LICENSE_CACHE_CAPACITY: usize = 10_000;
+
 type LicenseKey = (Method, String);
 
+/// Status answering a caller who lacks a required license feature.
+///
+/// `Forbidden` (403) treats the feature as a permission; `PaymentRequired`
+/// (402) as a purchase, listing the missing features so clients can offer an
+/// upgrade. A check that fails or times out is answered with 503 either way.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseDenialStatus {
+    #[default]
+    Forbidden,
+    PaymentRequired,
+}
+
+/// Whose features a check asked for: the caller's subject and tenant (`None`
+/// when anonymous) and the resource of a scoped requirement.
+type HolderKey = (Option<(Uuid, Uuid)>, Option<String>);
+
+/// Held features answered by the checker, reused for `ttl`.
+///
+/// The cache keys on whose features were asked for, not on the requirement:
+/// every route a caller hits within the window is decided from one answer.
+/// Failed checks are never cached. When `capacity` callers are cached, expired
+/// entries are swept and, if none expired, new answers are not cached. The
+/// sweep only runs once the oldest entry has expired, so a cache full of fresh
+/// entries costs a miss nothing.
+struct HeldFeaturesCache {
+    entries: DashMap<HolderKey, (Instant, Vec<String>)>,
+    /// No entry was stored before this instant; `None` while empty.
+    oldest: Mutex<Option<Instant>>,
+    ttl: Duration,
+    capacity: usize,
+}
+
+impl HeldFeaturesCache {
+    fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            entries: DashMap::new(),
+            oldest: Mutex::new(None),
+            ttl,
+            capacity,
+        }
+    }
+
+    fn get(&self, key: &HolderKey) -> Option<Vec<String>> {
+        let entry = self.entries.get(key)?;
+        let (stored, held) = entry.value();
+        (stored.elapsed() < self.ttl).then(|| held.clone())
+    }
+
+    fn insert(&self, key: HolderKey, held: Vec<String>) {
+        if self.ttl.is_zero() || self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            let mut oldest = self.oldest.lock();
+            if oldest.is_some_and(|at| at.elapsed() < self.ttl) {
+                return;
+            }
+            let mut remaining: Option<Instant> = None;
+            self.entries.retain(|_, (stored, _)| {
+                let fresh = stored.elapsed() < self.ttl;
+                if fresh {
+                    remaining = Some(remaining.map_or(*stored, |at| at.min(*stored)));
+                }
+                fresh
+            });
+            *oldest = remaining;
+            if self.entries.len() >= self.capacity {
+                return;
+            }
+        }
+        let now = Instant::now();
+        self.oldest.lock().get_or_insert(now);
+        self.entries.insert(key, (now, held));
+    }
+}
+
+/// Source of the license features held by a caller.
+///
+/// A license plugin registers its implementation in the `ClientHub` as
+/// `dyn LicenseChecker`; the gateway then consults it for every route whose
+/// requirement goes beyond the base feature.
+#[async_trait::async_trait]
+pub trait LicenseChecker: Send + Sync {
+    /// Global features held by the caller, `None` when the caller is anonymous.
+    async fn held_features(&self, ctx: Option<&SecurityContext>) -> anyhow::Result<Vec<String>>;
+
+    /// Features held by the caller for the resource `resource` (e.g. a tenant
+    /// id). Checkers without resource-scoped licensing hold none.
+    async fn held_resource_features(
+        &self,
+        ctx: Option<&SecurityContext>,
+        resource: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let _ = (ctx, resource);
+        Ok(Vec::new())
+    }
+}
+
+/// Checker granting only the base feature to everyone, used while no license
+/// plugin is registered.
+pub struct BaseFeatureChecker;
+
+#[async_trait::async_trait]
+impl LicenseChecker for BaseFeatureChecker {
+    async fn held_features(&self, _ctx: Option<&SecurityContext>) -> anyhow::Result<Vec<String>> {
+        Ok(vec![BASE_FEATURE.to_owned()])
+    }
+}
+
 #[derive(Clone)]
 pub struct LicenseRequirementMap {
-    requirements: Arc<DashMap<LicenseKey, Vec<String>>>,
+    requirements: Arc<DashMap<LicenseKey, LicenseReqSpec>>,
+    checker: Arc<dyn LicenseChecker>,
+    check_timeout: Duration,
+    cache: Arc<HeldFeaturesCache>,
+    denial_status: LicenseDenialStatus,
 }
 
 impl LicenseRequirementMap {
@@ -24,28 +159,70 @@ impl LicenseRequirementMap {
 
         for spec in specs {
             if let Some(req) = spec.license_requirement.as_ref() {
-                requirements.insert(
-                    (spec.method.clone(), spec.path.clone()),
-                    req.license_names.clone(),
-                );
+                requirements.insert((spec.method.clone(), spec.path.clone()), req.clone());
             }
         }
 
         Self {
             requirements: Arc::new(requirements),
+            checker: Arc::new(BaseFeatureChecker),
+            check_timeout: DEFAULT_LICENSE_CHECK_TIMEOUT,
+            cache: Arc::new(HeldFeaturesCache::new(
+                DEFAULT_LICENSE_CACHE_TTL,
+                DEFAULT_LICENSE_CACHE_CAPACITY,
+            )),
+            denial_status: LicenseDenialStatus::default(),
         }
     }
 
-    fn get(&self, method: &Method, path: &str) -> Option<Vec<String>> {
+    /// Replace the checker consulted for held features.
+    #[must_use]
+    pub fn with_checker(mut self, checker: Arc<dyn LicenseChecker>) -> Self {
+        self.checker = checker;
+        self
+    }
+
+    /// Bound each check; a check still running after `timeout` denies access.
+    #[must_use]
+    pub fn with_check_timeout(mut self, timeout: Duration) -> Self {
+        self.check_timeout = timeout;
+        self
+    }
+
+    /// Answer callers lacking a required feature with `status`.
+    #[must_use]
+    pub fn with_denial_status(mut self, status: LicenseDenialStatus) -> Self {
+        self.denial_status = status;
+        self
+    }
+
+    /// Reuse a caller's held features for `ttl`, caching at most `capacity`
+    /// callers. A zero `ttl` or `capacity` disables caching.
+    #[must_use]
+    pub fn with_cache(mut self, ttl: Duration, capacity: usize) -> Self {
+        self.cache = Arc::new(HeldFeaturesCache::new(ttl, capacity));
+        self
+    }
+
+    fn get(&self, method: &Method, path: &str) -> Option<LicenseReqSpec> {
         self.requirements
             .get(&(method.clone(), path.to_owned()))
             .map(|v| v.value().clone())
     }
 }
 
+/// Enforce the license requirement of the matched route.
+///
+/// A resource-scoped requirement is checked for the resource named by its
+/// path parameter; a request whose matched path lacks that parameter is
+/// forbidden without consulting the checker.
+///
+/// The checker is bounded by the configured timeout. A check that fails or
+/// times out fails closed: the request is denied with 503 and the decision is
+/// audited, it is neither granted nor left hanging.
 pub async fn license_validation_middleware(
     map: LicenseRequirementMap,
-    req: Request,
+    mut req: Request,
     next: Next,
 ) -> Response {
     let method = req.method().clone();
@@ -54,23 +231,627 @@ pub async fn license_validation_middleware(
         .get::<axum::extract::MatchedPath>()
         .map_or_else(|| req.uri().path().to_owned(), |p| p.as_str().to_owned());
 
-    let Some(required) = map.get(&method, &path) else {
+    let Some(requirement) = map.get(&method, &path) else {
+        return next.run(req).await;
+    };
+    // Everyone holds the base feature: no need to ask the checker
+    if requirement
+        .requirement
+        .is_satisfied_by(&[BASE_FEATURE.to_owned()])
+    {
         return next.run(req).await;
+    }
+    let required = requirement.requirement.features();
+
+    let resource = scoped_resource(&mut req, &requirement.scope).await;
+
+    let ctx = req.extensions().get::<SecurityContext>();
+    let request_path = req.uri().path().to_owned();
+    let denial = |decision, held: &[String], reason| {
+        emit_denial(&AccessDenial {
+            decision,
+            principal: ctx.map(SecurityContext::subject_id),
+            method: &method,
+            path: &request_path,
+            route: Some(&path),
+            required: &required,
+            held,
+            reason,
+        });
+    };
+
+    let resource = match resource {
+        Ok(resource) => resource,
+        Err(path_param) => {
+            denial(AuditDecision::Forbidden, &[], "resource identifier missing");
+            return FORBIDDEN_V1
+                .as_problem(format!(
+                    "Endpoint requires license features for the resource identified by '{path_param}', which the request does not name",
+                ))
+                .into_response();
+        }
+    };
+    let held = match bounded_check(&map, ctx, resource.as_deref(), &path).await {
+        Ok(held) => held,
+        Err(reason) => {
+            denial(AuditDecision::Unavailable, &[], reason);
+            return license_check_unavailable();
+        }
     };
 
-    // TODO: this is a stub implementation
-    // We need first to implement plugin and get its client from client_hub
-    // Plugin should provide an interface to get a list of global features (features that are not scoped to particular resource)
-    if required.iter().any(|r| r != BASE_FEATURE) {
-        return Problem::new(
-            StatusCode::FORBIDDEN,
-            "Forbidden",
-            format!(
-                "Endpoint requires unsupported license features '{required:?}'; only '{BASE_FEATURE}' is allowed",
-            ),
-        )
-        .into_response();
+    if !requirement.requirement.is_satisfied_by(&held) {
+        denial(
+            AuditDecision::Forbidden,
+            &held,
+            "unsupported license features",
+        );
+        return unlicensed(map.denial_status, &requirement.requirement, &held);
     }
 
     next.run(req).await
 }
+
+/// Response to a caller whose `held` features do not satisfy `requirement`.
+///
+/// Only the missing features are named; the caller's held features are not
+/// echoed back.
+fn unlicensed(
+    status: LicenseDenialStatus,
+    requirement: &LicenseRequirement,
+    held: &[String],
+) -> Response {
+    let missing = requirement.missing(held);
+    let detail = format!(
+        "Endpoint requires license features {requirement}; missing {}",
+        missing.join(", ")
+    );
+    match status {
+        LicenseDenialStatus::Forbidden => FORBIDDEN_V1.as_problem(detail),
+        LicenseDenialStatus::PaymentRequired => PAYMENT_REQUIRED_V1.as_problem_with(
+            detail,
+            HashMap::from([(
+                MISSING_FEATURES_METADATA_KEY.to_owned(),
+                serde_json::json!(missing),
+            )]),
+        ),
+    }
+    .into_response()
+}
+
+/// Features the caller holds globally, or for `resource` when given: a
+/// cached answer while fresh, otherwise the checker's within the configured
+/// timeout. A failure is logged and reported as the reason to audit.
+async fn bounded_check(
+    map: &LicenseRequirementMap,
+    ctx: Option<&SecurityContext>,
+    resource: Option<&str>,
+    route: &str,
+) -> Result<Vec<String>, &'static str> {
+    let key: HolderKey = (
+        ctx.map(|c| (c.subject_id(), c.subject_tenant_id())),
+        resource.map(str::to_owned),
+    );
+    if let Some(held) = map.cache.get(&key) {
+        return Ok(held);
+    }
+    let check = async {
+        match resource {
+            Some(id) => map.checker.held_resource_features(ctx, id).await,
+            None => map.checker.held_features(ctx).await,
+        }
+    };
+    match tokio::time::timeout(map.check_timeout, check).await {
+        Ok(Ok(held)) => {
+            map.cache.insert(key, held.clone());
+            Ok(held)
+        }
+        Ok(Err(err)) => {
+            tracing::warn!(error = %err, route = %route, "license check failed");
+            Err("license check failed")
+        }
+        Err(_) => {
+            tracing::warn!(
+                timeout_ms = u64::try_from(map.check_timeout.as_millis()).unwrap_or(u64::MAX),
+                route = %route,
+                "license check timed out"
+            );
+            Err("license check timed out")
+        }
+    }
+}
+
+/// Identifier of the resource a requirement is scoped to: `Ok(None)` for a
+/// global requirement, `Err(path_param)` when the matched path lacks the
+/// parameter naming the resource.
+async fn scoped_resource<'a>(
+    req: &mut Request,
+    scope: &'a LicenseScope,
+) -> Result<Option<String>, &'a str> {
+    let LicenseScope::Resource { path_param } = scope else {
+        return Ok(None);
+    };
+    let params = req.extract_parts::<RawPathParams>().await.ok();
+    params
+        .as_ref()
+        .and_then(|params| params.iter().find(|(name, _)| name == path_param))
+        .map(|(_, value)| Some(value.to_owned()))
+        .ok_or(path_param.as_str())
+}
+
+fn license_check_unavailable() -> Response {
+    SERVICE_UNAVAILABLE_V1
+        .as_problem("License check could not be completed; access denied")
+        .into_response()
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use modkit::api::Problem;
+    use modkit::api::operation_builder::VendorExtensions;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    /// Checker answering with `features` after `delay`.
+    struct SlowChecker {
+        delay: Duration,
+        features: Vec<String>,
+    }
+
+    #[async_trait::async_trait]
+    impl LicenseChecker for SlowChecker {
+        async fn held_features(
+            &self,
+            _ctx: Option<&SecurityContext>,
+        ) -> anyhow::Result<Vec<String>> {
+            tokio::time::sleep(self.delay).await;
+            Ok(self.features.clone())
+        }
+    }
+
+    /// Checker that must not be consulted.
+    struct FailingChecker;
+
+    #[async_trait::async_trait]
+    impl LicenseChecker for FailingChecker {
+        async fn held_features(
+            &self,
+            _ctx: Option<&SecurityContext>,
+        ) -> anyhow::Result<Vec<String>> {
+            anyhow::bail!("license plugin offline")
+        }
+    }
+
+    fn spec_requiring(feature: &str) -> OperationSpec {
+        spec_with(LicenseRequirement::Feature(feature.to_owned()))
+    }
+
+    fn spec_with(requirement: LicenseRequirement) -> OperationSpec {
+        OperationSpec {
+            method: Method::GET,
+            path: "/reports/{id}".to_owned(),
+            operation_id: None,
+            summary: None,
+            description: None,
+            tags: Vec::new(),
+            params: Vec::new(),
+            request_body: None,
+            responses: Vec::new(),
+            handler_id: "get_report".to_owned(),
+            authenticated: true,
+            is_public: false,
+            rate_limit: None,
+            allowed_request_content_types: None,
+            vendor_extensions: VendorExtensions::default(),
+            license_requirement: Some(LicenseReqSpec {
+                requirement,
+                scope: LicenseScope::Global,
+            }),
+        }
+    }
+
+    async fn call(checker: SlowChecker) -> (StatusCode, usize) {
+        let (response, served) = call_requiring("gts.x.premium~", Arc::new(checker)).await;
+        (response.status(), served)
+    }
+
+    async fn call_requiring(feature: &str, checker: Arc<dyn LicenseChecker>) -> (Response, usize) {
+        call_with(spec_requiring(feature), checker).await
+    }
+
+    /// Status of a request to a route requiring `requirement` from a caller
+    /// holding `held`.
+    async fn status_holding(requirement: LicenseRequirement, held: &[&str]) -> StatusCode {
+        let checker = SlowChecker {
+            delay: Duration::ZERO,
+            features: held.iter().map(|&f| f.to_owned()).collect(),
+        };
+        call_with(spec_with(requirement), Arc::new(checker))
+            .await
+            .0
+            .status()
+    }
+
+    async fn call_with(spec: OperationSpec, checker: Arc<dyn LicenseChecker>) -> (Response, usize) {
+        let served = Arc::new(AtomicUsize::new(0));
+        let map = LicenseRequirementMap::from_specs(&[spec])
+            .with_checker(checker)
+            .with_check_timeout(Duration::from_millis(50));
+        let counter = served.clone();
+        let app = Router::new()
+            .route(
+                "/reports/{id}",
+                get(move || async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    StatusCode::OK
+                }),
+            )
+            .layer(axum::middleware::from_fn(move |req, next| {
+                license_validation_middleware(map.clone(), req, next)
+            }));
+
+        let response = app
+            .oneshot(Request::get("/reports/42").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        (response, served.load(Ordering::SeqCst))
+    }
+
+    #[tokio::test]
+    async fn slow_checker_fails_closed() {
+        let (status, served) = call(SlowChecker {
+            delay: Duration::from_secs(30),
+            features: vec!["gts.x.premium~".to_owned()],
+        })
+        .await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(served, 0, "access must not be granted");
+    }
+
+    #[tokio::test]
+    async fn timely_checker_decides() {
+        let (status, served) = call(SlowChecker {
+            delay: Duration::ZERO,
+            features: vec!["gts.x.premium~".to_owned()],
+        })
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(served, 1);
+
+        let (status, served) = call(SlowChecker {
+            delay: Duration::ZERO,
+            features: vec![BASE_FEATURE.to_owned()],
+        })
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(served, 0);
+    }
+
+    #[tokio::test]
+    async fn base_feature_skips_the_checker() {
+        let (response, served) = call_requiring(BASE_FEATURE, Arc::new(FailingChecker)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(served, 1);
+
+        let (response, served) = call_requiring("gts.x.premium~", Arc::new(FailingChecker)).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers()["x-error-code"],
+            SERVICE_UNAVAILABLE_V1.code
+        );
+        assert_eq!(served, 0);
+    }
+
+    #[tokio::test]
+    async fn missing_feature_is_forbidden_v1() {
+        let checker = SlowChecker {
+            delay: Duration::ZERO,
+            features: vec![BASE_FEATURE.to_owned(), "gts.x.basic~".to_owned()],
+        };
+        let (response, _) = call_requiring("gts.x.premium~", Arc::new(checker)).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(response.headers()["x-error-code"], FORBIDDEN_V1.code);
+    }
+
+    fn features(names: &[&str]) -> Vec<LicenseRequirement> {
+        names
+            .iter()
+            .map(|&f| LicenseRequirement::Feature(f.to_owned()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn all_of_fails_on_one_missing_feature() {
+        let requirement = LicenseRequirement::AllOf(features(&["gts.x.reports~", "gts.x.export~"]));
+        assert_eq!(
+            status_holding(requirement.clone(), &["gts.x.reports~", "gts.x.export~"]).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_holding(requirement, &["gts.x.reports~"]).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn any_of_passes_on_one_held_feature() {
+        let requirement = LicenseRequirement::AnyOf(features(&["gts.x.premium~", "gts.x.trial~"]));
+        assert_eq!(
+            status_holding(requirement.clone(), &["gts.x.trial~"]).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_holding(requirement, &["gts.x.basic~"]).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn wildcard_matches_by_prefix() {
+        let requirement =
+            LicenseRequirement::Feature("gts.x.core.lic.feat.v1~x.reports.*".to_owned());
+        assert_eq!(
+            status_holding(
+                requirement.clone(),
+                &["gts.x.core.lic.feat.v1~x.reports.pdf.v1"]
+            )
+            .await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_holding(requirement, &["gts.x.core.lic.feat.v1~x.billing.v1"]).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn wildcard_covering_the_base_feature_skips_the_checker() {
+        let requirement = LicenseRequirement::Feature("gts.x.core.lic.feat.v1~x.core.*".to_owned());
+        let (response, served) = call_with(spec_with(requirement), Arc::new(FailingChecker)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(served, 1);
+    }
+
+    /// Checker granting `gts.x.premium~` and counting how often it is asked.
+    #[derive(Default)]
+    struct CountingChecker {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl LicenseChecker for CountingChecker {
+        async fn held_features(
+            &self,
+            _ctx: Option<&SecurityContext>,
+        ) -> anyhow::Result<Vec<String>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(vec!["gts.x.premium~".to_owned()])
+        }
+    }
+
+    /// Sends `requests` requests through one map caching for `ttl`, pausing
+    /// `pause` between them, and returns how often the checker was asked.
+    async fn checker_calls(ttl: Duration, requests: usize, pause: Duration) -> usize {
+        let checker = Arc::new(CountingChecker::default());
+        let map = LicenseRequirementMap::from_specs(&[spec_requiring("gts.x.premium~")])
+            .with_checker(checker.clone())
+            .with_cache(ttl, 16);
+        let app = Router::new()
+            .route("/reports/{id}", get(|| async { StatusCode::OK }))
+            .layer(axum::middleware::from_fn(move |req, next| {
+                license_validation_middleware(map.clone(), req, next)
+            }));
+        for _ in 0..requests {
+            let response = app
+                .clone()
+                .oneshot(Request::get("/reports/42").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            tokio::time::sleep(pause).await;
+        }
+        checker.calls.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn cache_hit_skips_the_checker() {
+        assert_eq!(
+            checker_calls(Duration::from_secs(60), 3, Duration::ZERO).await,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn cache_entries_expire_after_the_ttl() {
+        assert_eq!(
+            checker_calls(Duration::from_millis(20), 2, Duration::from_millis(40)).await,
+            2
+        );
+        assert_eq!(checker_calls(Duration::ZERO, 2, Duration::ZERO).await, 2);
+    }
+
+    #[test]
+    fn full_cache_of_fresh_entries_is_not_swept() {
+        let key = |n: u128| (Some((Uuid::from_u128(n), Uuid::nil())), None);
+        let cache = HeldFeaturesCache::new(Duration::from_secs(60), 2);
+        cache.insert(key(1), Vec::new());
+        cache.insert(key(2), Vec::new());
+        // Make the recorded oldest entry fresher than the ones stored: a sweep
+        // would evict them, so surviving entries prove no sweep ran
+        let stale = Instant::now()
+            .checked_sub(Duration::from_secs(120))
+            .unwrap();
+        for mut entry in cache.entries.iter_mut() {
+            entry.value_mut().0 = stale;
+        }
+        cache.insert(key(3), Vec::new());
+        assert_eq!(cache.entries.len(), 2);
+        assert!(!cache.entries.contains_key(&key(3)));
+
+        // Once the oldest entry has expired, the sweep frees its space
+        *cache.oldest.lock() = Some(stale);
+        cache.insert(key(3), Vec::new());
+        assert_eq!(cache.entries.len(), 1);
+        assert!(cache.get(&key(3)).is_some());
+    }
+
+    #[test]
+    fn full_cache_sweeps_expired_entries_only() {
+        let key = |n: u128| (Some((Uuid::from_u128(n), Uuid::nil())), None);
+        let cache = HeldFeaturesCache::new(Duration::from_secs(60), 2);
+        cache.insert(key(1), Vec::new());
+        cache.insert(key(2), Vec::new());
+        cache.insert(key(3), Vec::new());
+        assert!(cache.get(&key(3)).is_none(), "full of fresh entries");
+        assert!(cache.get(&key(1)).is_some());
+
+        let cache = HeldFeaturesCache::new(Duration::from_millis(1), 1);
+        cache.insert(key(1), Vec::new());
+        std::thread::sleep(Duration::from_millis(5));
+        cache.insert(key(2), Vec::new());
+        assert_eq!(cache.entries.len(), 1);
+        assert!(cache.entries.contains_key(&key(2)));
+    }
+
+    async fn denied_with(status: LicenseDenialStatus) -> Problem {
+        let checker = SlowChecker {
+            delay: Duration::ZERO,
+            features: vec!["gts.x.reports~".to_owned(), "gts.x.internal~".to_owned()],
+        };
+        let requirement = LicenseRequirement::AllOf(features(&["gts.x.reports~", "gts.x.export~"]));
+        let map = LicenseRequirementMap::from_specs(&[spec_with(requirement)])
+            .with_checker(Arc::new(checker))
+            .with_denial_status(status);
+        let app = Router::new()
+            .route("/reports/{id}", get(|| async { StatusCode::OK }))
+            .layer(axum::middleware::from_fn(move |req, next| {
+                license_validation_middleware(map.clone(), req, next)
+            }));
+        let response = app
+            .oneshot(Request::get("/reports/42").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn entitlement_failure_is_forbidden_by_default() {
+        let problem = denied_with(LicenseDenialStatus::default()).await;
+        assert_eq!(problem.status, StatusCode::FORBIDDEN);
+        assert_eq!(problem.code, FORBIDDEN_V1.code);
+    }
+
+    #[tokio::test]
+    async fn entitlement_failure_names_only_missing_features() {
+        let problem = denied_with(LicenseDenialStatus::default()).await;
+        assert!(
+            problem.detail.ends_with("; missing gts.x.export~"),
+            "{}",
+            problem.detail
+        );
+        assert!(!problem.detail.contains("gts.x.internal~"));
+    }
+
+    #[tokio::test]
+    async fn entitlement_failure_can_require_payment() {
+        let problem = denied_with(LicenseDenialStatus::PaymentRequired).await;
+        assert_eq!(problem.status, StatusCode::PAYMENT_REQUIRED);
+        assert_eq!(problem.code, PAYMENT_REQUIRED_V1.code);
+        assert_eq!(
+            problem.metadata.unwrap()[MISSING_FEATURES_METADATA_KEY],
+            serde_json::json!(["gts.x.export~"])
+        );
+    }
+
+    /// Checker holding `features` for the resource `acme` only.
+    struct TenantChecker {
+        features: Vec<String>,
+    }
+
+    #[async_trait::async_trait]
+    impl LicenseChecker for TenantChecker {
+        async fn held_features(
+            &self,
+            _ctx: Option<&SecurityContext>,
+        ) -> anyhow::Result<Vec<String>> {
+            Ok(vec![BASE_FEATURE.to_owned()])
+        }
+
+        async fn held_resource_features(
+            &self,
+            _ctx: Option<&SecurityContext>,
+            resource: &str,
+        ) -> anyhow::Result<Vec<String>> {
+            Ok(if resource == "acme" {
+                self.features.clone()
+            } else {
+                Vec::new()
+            })
+        }
+    }
+
+    /// Calls `uri` on the route `route`, which requires `gts.x.premium~` for
+    /// the resource named by its `tenant_id` path parameter.
+    async fn call_scoped(route: &str, uri: &str) -> (StatusCode, usize) {
+        let served = Arc::new(AtomicUsize::new(0));
+        let mut spec = spec_requiring("gts.x.premium~");
+        route.clone_into(&mut spec.path);
+        spec.license_requirement = Some(LicenseReqSpec {
+            requirement: LicenseRequirement::Feature("gts.x.premium~".to_owned()),
+            scope: LicenseScope::Resource {
+                path_param: "tenant_id".to_owned(),
+            },
+        });
+        let map =
+            LicenseRequirementMap::from_specs(&[spec]).with_checker(Arc::new(TenantChecker {
+                features: vec!["gts.x.premium~".to_owned()],
+            }));
+        let counter = served.clone();
+        let app = Router::new()
+            .route(
+                route,
+                get(move || async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    StatusCode::OK
+                }),
+            )
+            .layer(axum::middleware::from_fn(move |req, next| {
+                license_validation_middleware(map.clone(), req, next)
+            }));
+
+        let response = app
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        (response.status(), served.load(Ordering::SeqCst))
+    }
+
+    #[tokio::test]
+    async fn scoped_requirement_is_checked_for_the_resource() {
+        let route = "/tenants/{tenant_id}/reports";
+        assert_eq!(
+            call_scoped(route, "/tenants/acme/reports").await,
+            (StatusCode::OK, 1)
+        );
+        assert_eq!(
+            call_scoped(route, "/tenants/globex/reports").await,
+            (StatusCode::FORBIDDEN, 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn scoped_requirement_without_path_param_is_forbidden() {
+        assert_eq!(
+            call_scoped("/reports/{id}", "/reports/acme").await,
+            (StatusCode::FORBIDDEN, 0)
+        );
+    }
+}
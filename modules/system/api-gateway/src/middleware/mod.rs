@@ -1,3 +1,5 @@
+pub mod access_log;
+pub mod audit;
 pub mod auth;
 pub mod license_validation;
 pub mod mime_validation;
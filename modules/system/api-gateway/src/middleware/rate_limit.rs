@@ -9,6 +9,7 @@ use axum::{
 use governor::clock::Clock;
 use governor::middleware::StateInformationMiddleware;
 use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use modkit::api::Problem;
 use std::collections::HashMap;
 use std::num::NonZeroU32;
 use std::sync::Arc;
@@ -28,6 +29,7 @@ struct BucketMapEntry {
     bucket: DefaultDirectRateLimiter<StateInformationMiddleware>,
     policy: HeaderValue,
     burst: HeaderValue,
+    limit: u32,
 }
 
 impl BucketMapEntry {
@@ -43,6 +45,7 @@ impl BucketMapEntry {
             bucket,
             policy,
             burst: burst.into(),
+            limit: burst,
         })
     }
 }
@@ -112,7 +115,17 @@ pub async fn rate_limit_middleware(map: RateLimiterMap, mut req: Request, next:
             Err(not_until) => {
                 let wait = not_until.wait_time_from(bucker_map_entry.bucket.clock().now());
                 headers.insert(header::RETRY_AFTER, wait.as_secs().into());
-                return StatusCode::TOO_MANY_REQUESTS.into_response();
+                let mut response = Problem::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "Too Many Requests",
+                    "Rate limit exceeded for this route",
+                )
+                .with_rate_limit(u64::from(bucker_map_entry.limit), 0, wait.as_secs())
+                .into_response();
+                response
+                    .headers_mut()
+                    .insert(header::RETRY_AFTER, wait.as_secs().into());
+                return response;
             }
         }
     }
@@ -7,7 +7,7 @@ pub struct XRequestId(pub String);
 
 #[must_use]
 pub fn header() -> HeaderName {
-    HeaderName::from_static("x-request-id")
+    modkit_errors::headers::REQUEST_ID
 }
 
 #[derive(Clone, Default)]
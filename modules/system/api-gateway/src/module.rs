@@ -29,6 +29,7 @@ use authn_resolver_sdk::AuthNResolverClient;
 
 use crate::config::ApiGatewayConfig;
 use crate::middleware::auth;
+use crate::middleware::license_validation::LicenseChecker;
 use modkit_security::SecurityContext;
 use modkit_security::constants::{DEFAULT_SUBJECT_ID, DEFAULT_TENANT_ID};
 
@@ -55,6 +56,8 @@ pub struct ApiGateway {
     pub(crate) final_router: Mutex<Option<axum::Router>>,
     // AuthN Resolver client (resolved during init, None when auth_disabled)
     pub(crate) authn_client: Mutex<Option<Arc<dyn AuthNResolverClient>>>,
+    // License checker registered by a license plugin (resolved during init, if any)
+    pub(crate) license_checker: Mutex<Option<Arc<dyn LicenseChecker>>>,
 
     // Duplicate detection (per (method, path) and per handler id)
     pub(crate) registered_routes: DashMap<(Method, String), ()>,
@@ -70,6 +73,7 @@ impl Default for ApiGateway {
             router_cache: RouterCache::new(default_router),
             final_router: Mutex::new(None),
             authn_client: Mutex::new(None),
+            license_checker: Mutex::new(None),
             registered_routes: DashMap::new(),
             registered_handlers: DashMap::new(),
         }
@@ -87,6 +91,7 @@ impl ApiGateway {
             router_cache: RouterCache::new(default_router),
             final_router: Mutex::new(None),
             authn_client: Mutex::new(None),
+            license_checker: Mutex::new(None),
             registered_routes: DashMap::new(),
             registered_handlers: DashMap::new(),
         }
@@ -171,8 +176,9 @@ impl ApiGateway {
         // becomes the **outermost** layer and therefore runs **first** on the request path.
         //
         // Desired request execution order (outermost -> innermost):
-        // SetRequestId -> PropagateRequestId -> Trace -> push_req_id_to_extensions
-        // -> Timeout -> BodyLimit -> CORS -> MIME validation -> RateLimit -> ErrorMapping -> Auth -> Router
+        // SetRequestId -> PropagateRequestId -> Trace -> push_req_id_to_extensions -> TraceId
+        // -> Timeout -> BodyLimit -> ExposeErrorHeaders -> CORS -> MIME validation -> RateLimit -> CatchPanic -> ErrorMapping
+        // -> Auth -> Router
         //
         // Therefore we must add layers in the reverse order (innermost -> outermost) below.
         // Due future refactoring, this order must be maintained.
@@ -188,7 +194,19 @@ impl ApiGateway {
             .collect();
 
         // 11) License validation
-        let license_map = middleware::license_validation::LicenseRequirementMap::from_specs(&specs);
+        let mut license_map =
+            middleware::license_validation::LicenseRequirementMap::from_specs(&specs)
+                .with_check_timeout(Duration::from_millis(
+                    config.defaults.license_check_timeout_ms,
+                ))
+                .with_cache(
+                    Duration::from_millis(config.defaults.license_cache_ttl_ms),
+                    config.defaults.license_cache_capacity,
+                )
+                .with_denial_status(config.defaults.license_denial_status);
+        if let Some(checker) = self.license_checker.lock().clone() {
+            license_map = license_map.with_checker(checker);
+        }
         router = router.layer(from_fn(
             move |req: axum::extract::Request, next: axum::middleware::Next| {
                 let map = license_map.clone();
@@ -234,6 +252,19 @@ impl ApiGateway {
         // 9) Error mapping (outer to auth so it can translate auth/handler errors)
         router = router.layer(from_fn(modkit::api::error_layer::error_mapping_middleware));
 
+        // 9a) Experimental error types fall back to stable ones unless the client opts in
+        router = router.layer(from_fn(
+            modkit::api::error_layer::experimental_errors_middleware,
+        ));
+
+        // 9a') Localized problem titles per Accept-Language (outer to 9a: fallbacks are localized too)
+        router = router.layer(from_fn(
+            modkit::api::error_layer::problem_language_middleware,
+        ));
+
+        // 9b) Panic capture (outer to auth and handlers: a panic becomes a 500 Problem, logged once)
+        router = router.layer(from_fn(modkit::api::panic::catch_panic_middleware));
+
         // 8) Per-route rate limiting & in-flight limits
         let rate_map = middleware::rate_limit::RateLimiterMap::from_specs(&specs, &config)?;
         router = router.layer(from_fn(
@@ -257,6 +288,14 @@ impl ApiGateway {
             router = router.layer(crate::cors::build_cors_layer(&config));
         }
 
+        // 5b) Expose correlation headers on cross-origin errors (outer to CORS so it
+        // merges with the expose list CORS may set)
+        if config.cors_enabled {
+            router = router.layer(from_fn(
+                modkit::api::error_layer::expose_error_headers_middleware,
+            ));
+        }
+
         // 5) Body limit
         router = router.layer(RequestBodyLimitLayer::new(config.defaults.body_limit_bytes));
         router = router.layer(DefaultBodyLimit::max(config.defaults.body_limit_bytes));
@@ -267,9 +306,16 @@ impl ApiGateway {
             Duration::from_secs(30),
         ));
 
+        // 3b) Guarantee a trace id (inner to Trace so an active span's id is reused; outer to
+        // everything that can build a Problem, so every error carries the echoed x-trace-id)
+        router = router.layer(from_fn(modkit::api::error_layer::trace_id_middleware));
+
         // 3) Record request_id into span + extensions (requires span to exist first => must be inner to Trace)
         router = router.layer(from_fn(middleware::request_id::push_req_id_to_extensions));
 
+        // 2b) One access-log event per request (inner to Trace so it is emitted in the request span)
+        router = router.layer(from_fn(middleware::access_log::access_log_middleware));
+
         // 2) Trace (outer to push_req_id_to_extensions)
         router = router.layer({
             use modkit_http::otel;
@@ -544,6 +590,16 @@ impl modkit::Module for ApiGateway {
             self.config.load()
         );
 
+        modkit::api::problem::register_framework_catalogs();
+        modkit::api::problem::set_production_mode(cfg.production_errors);
+        if let Some(len) = cfg.max_error_header_len {
+            modkit::api::problem::set_max_error_header_len(len);
+        }
+        if let Some(seconds) = cfg.default_retry_after_secs {
+            modkit::api::problem::set_default_retry_after_secs(seconds);
+        }
+        modkit::api::problem::set_code_remapper(cfg.error_code_remap.clone());
+
         if cfg.auth_disabled {
             tracing::info!(
                 tenant_id = %DEFAULT_TENANT_ID,
@@ -556,6 +612,12 @@ impl modkit::Module for ApiGateway {
             tracing::info!("AuthN Resolver client resolved from ClientHub");
         }
 
+        // A license plugin is optional; without one only the base feature is granted
+        if let Ok(checker) = ctx.client_hub().get::<dyn LicenseChecker>() {
+            *self.license_checker.lock() = Some(checker);
+            tracing::info!("License checker resolved from ClientHub");
+        }
+
         Ok(())
     }
 }
@@ -1,6 +1,7 @@
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 
 use anyhow::Result;
+use api_gateway::middleware::license_validation::LicenseChecker;
 use async_trait::async_trait;
 use axum::{
     Router,
@@ -16,6 +17,7 @@ use modkit::{
     context::ModuleCtx,
     contracts::{ApiGatewayCapability, OpenApiRegistry, RestApiCapability},
 };
+use modkit_security::SecurityContext;
 use serde_json::json;
 use std::sync::Arc;
 use tower::ServiceExt;
@@ -32,8 +34,10 @@ impl ConfigProvider for TestConfigProvider {
 }
 
 fn create_api_gateway_ctx(config: serde_json::Value) -> ModuleCtx {
-    let hub = Arc::new(ClientHub::new());
+    create_api_gateway_ctx_with_hub(config, Arc::new(ClientHub::new()))
+}
 
+fn create_api_gateway_ctx_with_hub(config: serde_json::Value, hub: Arc<ClientHub>) -> ModuleCtx {
     ModuleCtx::new(
         "api-gateway",
         Uuid::new_v4(),
@@ -252,3 +256,66 @@ async fn allows_no_license_requirement() {
 
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+/// License plugin granting a fixed feature set.
+struct GrantingChecker(Vec<String>);
+
+#[async_trait]
+impl LicenseChecker for GrantingChecker {
+    async fn held_features(&self, _ctx: Option<&SecurityContext>) -> Result<Vec<String>> {
+        Ok(self.0.clone())
+    }
+}
+
+async fn call_with_checker(uri: &str, granted: &[&str]) -> StatusCode {
+    let config = json!({
+        "api-gateway": {
+            "config": {
+                "bind_addr": "0.0.0.0:8080",
+                "enable_docs": false,
+                "cors_enabled": false,
+                "auth_disabled": true
+            }
+        }
+    });
+
+    let hub = Arc::new(ClientHub::new());
+    let checker: Arc<dyn LicenseChecker> = Arc::new(GrantingChecker(
+        granted.iter().map(|f| (*f).to_owned()).collect(),
+    ));
+    hub.register::<dyn LicenseChecker>(checker);
+    let api_ctx = create_api_gateway_ctx_with_hub(config, hub);
+    let test_ctx = create_test_module_ctx();
+
+    let api_gateway = api_gateway::ApiGateway::default();
+    api_gateway.init(&api_ctx).await.expect("Failed to init");
+
+    let router = TestLicenseModule
+        .register_rest(&test_ctx, Router::new(), &api_gateway)
+        .expect("Failed to register routes");
+    let router = api_gateway
+        .rest_finalize(&api_ctx, router)
+        .expect("Failed to finalize");
+
+    router
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .expect("Request failed")
+        .status()
+}
+
+#[tokio::test]
+async fn registered_checker_grants_features() {
+    let status = call_with_checker("/tests/v1/license/bad", &["some_other_feature"]).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn registered_checker_denies_missing_features() {
+    let status = call_with_checker("/tests/v1/license/bad", &["yet_another_feature"]).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // The base feature never reaches the checker
+    let status = call_with_checker("/tests/v1/license/good", &[]).await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -115,6 +115,13 @@ async fn real_middlewares_observe_documented_order() -> Result<()> {
         res1.headers().get("access-control-allow-origin").is_some(),
         "CORS header must be present on 415 => CORS wraps MIME validation"
     );
+    assert_eq!(
+        res1.headers()
+            .get("x-trace-id")
+            .and_then(|v| v.to_str().ok()),
+        Some("fixed-req-1"),
+        "x-trace-id must be set on errors => trace id wraps MIME validation"
+    );
 
     // --------------------
     // Req2: valid Content-Type -> should pass MIME + consume rate-limit token.
@@ -132,6 +139,10 @@ async fn real_middlewares_observe_documented_order() -> Result<()> {
         )
         .await?;
     assert_eq!(res2.status(), StatusCode::OK);
+    assert!(
+        res2.headers().get("x-trace-id").is_some(),
+        "x-trace-id must be set on success"
+    );
     let res2_rid = res2
         .headers()
         .get("x-request-id")
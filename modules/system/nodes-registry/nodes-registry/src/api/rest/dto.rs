@@ -83,6 +83,8 @@ pub struct BatteryInfoDto {
 #[modkit_macros::api_dto(request, response)]
 pub struct NodeSysCapDto {
     pub node_id: uuid::Uuid,
+    /// Version of the capability key vocabulary used in `capabilities`
+    pub schema_version: u32,
     pub capabilities: Vec<SysCapDto>,
     pub collected_at: chrono::DateTime<chrono::Utc>,
 }
@@ -2,7 +2,10 @@ use axum::{
     Extension,
     extract::{Path, Query},
 };
+use modkit::api::odata::{OData, odata_error_to_problem};
 use modkit::api::prelude::*;
+use modkit_odata::Page;
+use nodes_registry_sdk::Node;
 use serde::Deserialize;
 use std::sync::Arc;
 
@@ -33,26 +36,51 @@ pub async fn list_nodes(
 
     if query.details {
         // Include sysinfo and syscap for each node
-        let mut detailed_nodes = Vec::new();
-        for node in nodes {
-            let node_id = node.id;
-            let sysinfo = svc.get_node_sysinfo(node_id).ok().map(Into::into);
-            let syscap = svc
-                .get_node_syscap(node_id, query.force_refresh)
-                .ok()
-                .map(Into::into);
-
-            let mut node_dto: NodeDto = node.into();
-            node_dto.sysinfo = sysinfo;
-            node_dto.syscap = syscap;
-            detailed_nodes.push(node_dto);
-        }
-        Ok(Json(detailed_nodes))
+        Ok(Json(
+            nodes
+                .into_iter()
+                .map(|node| with_details(&svc, node, query.force_refresh))
+                .collect(),
+        ))
     } else {
         Ok(Json(nodes.into_iter().map(Into::into).collect()))
     }
 }
 
+/// List nodes, filtered, sorted and paged with `OData`
+pub async fn list_nodes_page(
+    Extension(svc): Extension<Arc<Service>>,
+    OData(odata): OData,
+    Query(query): Query<DetailsQuery>,
+) -> ApiResult<Json<Page<NodeDto>>> {
+    let page = svc
+        .list_nodes_page(&odata)
+        .map_err(|e| odata_error_to_problem(&e, "/nodes-registry/v2/nodes", None))?;
+
+    if query.details {
+        Ok(Json(page.map_items(|node| {
+            with_details(&svc, node, query.force_refresh)
+        })))
+    } else {
+        Ok(Json(page.map_items(Into::into)))
+    }
+}
+
+/// Convert a node to a DTO carrying its sysinfo and syscap
+fn with_details(svc: &Service, node: Node, force_refresh: bool) -> NodeDto {
+    let node_id = node.id;
+    let sysinfo = svc.get_node_sysinfo(node_id).ok().map(Into::into);
+    let syscap = svc
+        .get_node_syscap(node_id, force_refresh)
+        .ok()
+        .map(Into::into);
+
+    let mut node_dto: NodeDto = node.into();
+    node_dto.sysinfo = sysinfo;
+    node_dto.syscap = syscap;
+    node_dto
+}
+
 /// Get a node by ID
 pub async fn get_node(
     Extension(svc): Extension<Arc<Service>>,
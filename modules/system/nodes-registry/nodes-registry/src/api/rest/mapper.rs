@@ -2,6 +2,7 @@ use super::dto::{
     BatteryInfoDto, CpuInfoDto, GpuInfoDto, HostInfoDto, MemoryInfoDto, NodeDto, NodeSysCapDto,
     NodeSysInfoDto, OsInfoDto, SysCapDto,
 };
+use crate::domain::capability_schema::CAPABILITY_SCHEMA_VERSION;
 use nodes_registry_sdk::{
     BatteryInfo, CpuInfo, GpuInfo, HostInfo, MemoryInfo, Node, NodeSysCap, NodeSysInfo, OsInfo,
     SysCap,
@@ -105,6 +106,7 @@ impl From<NodeSysCap> for NodeSysCapDto {
     fn from(cap: NodeSysCap) -> Self {
         Self {
             node_id: cap.node_id,
+            schema_version: CAPABILITY_SCHEMA_VERSION,
             capabilities: cap.capabilities.into_iter().map(Into::into).collect(),
             collected_at: cap.collected_at,
         }
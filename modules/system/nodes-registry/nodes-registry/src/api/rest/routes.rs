@@ -1,10 +1,12 @@
 use axum::http;
 use axum::{Extension, Router};
+use modkit::api::operation_builder::OperationBuilderODataExt;
 use modkit::api::{Missing, OpenApiRegistry, OperationBuilder};
 use std::sync::Arc;
 
 use super::dto::{NodeDto, NodeSysCapDto, NodeSysInfoDto};
 use super::handlers;
+use crate::domain::node_query::NodeFilterField;
 use crate::domain::service::Service;
 
 /// Register all REST routes for the nodes registry module
@@ -27,6 +29,25 @@ pub fn register_routes(
         .error_500(openapi)
         .register(router, openapi);
 
+    // GET /v2/nodes - List nodes with OData filtering and cursor pagination
+    router = OperationBuilder::<Missing, Missing, ()>::get("/nodes-registry/v2/nodes")
+        .operation_id("nodes_registry.list_nodes_page")
+        .summary("List nodes with cursor pagination")
+        .description("Get a paginated list of the nodes in the deployment. Supports $filter on id, hostname, ip_address, created_at, updated_at, status (ready, stale or pending) and capability, and $orderby on all of them but capability. $select and $compute are not supported. Use ?details=true to include sysinfo and syscap. Use ?force_refresh=true to invalidate syscap cache.")
+        .tag("nodes")
+        .public()
+        .query_param("details", false, "Include detailed system information and capabilities")
+        .query_param("force_refresh", false, "Force refresh syscap, ignoring cache (only applies when details=true)")
+        .query_param_typed("limit", false, "Maximum number of nodes to return", "integer")
+        .query_param("cursor", false, "Cursor for pagination")
+        .handler(handlers::list_nodes_page)
+        .json_response_with_schema::<modkit_odata::Page<NodeDto>>(openapi, http::StatusCode::OK, "Paginated list of nodes")
+        .with_odata_filter::<NodeFilterField>()
+        .with_odata_orderby::<NodeFilterField>()
+        .error_422(openapi)
+        .error_500(openapi)
+        .register(router, openapi);
+
     // GET /nodes/{id} - Get a specific node
     router = OperationBuilder::<Missing, Missing, ()>::get("/nodes-registry/v1/nodes/{id}")
         .operation_id("nodes_registry.get_node")
@@ -0,0 +1,93 @@
+//! Canonical capability key vocabulary
+//!
+//! Agents of different versions have reported the same capability under
+//! different keys. System-collected capabilities are normalized on ingest so
+//! `$filter` queries can target one stable vocabulary. Custom capabilities set
+//! through the service interface keep their keys as given.
+//! - legacy keys are rewritten to their canonical form;
+//! - keys outside the known namespaces are kept under `raw:` instead of dropped.
+
+use nodes_registry_sdk::SysCap;
+
+/// Version of the canonical capability key vocabulary.
+///
+/// Bump whenever a canonical key is renamed or a namespace is added.
+pub const CAPABILITY_SCHEMA_VERSION: u32 = 1;
+
+/// Namespace for capability keys that do not belong to the canonical vocabulary.
+pub const RAW_NAMESPACE: &str = "raw";
+
+/// Namespaces that make up the canonical vocabulary.
+const CANONICAL_NAMESPACES: &[&str] = &["hardware", "os"];
+
+/// Legacy key -> canonical key.
+const LEGACY_KEYS: &[(&str, &str)] = &[
+    ("hardware:memory", "hardware:ram"),
+    ("hardware:mem", "hardware:ram"),
+    ("hardware:processor", "hardware:cpu"),
+    ("hardware:gpu0", "hardware:gpu"),
+];
+
+/// Legacy namespace prefix -> canonical namespace prefix.
+const LEGACY_NAMESPACES: &[(&str, &str)] = &[("hw:", "hardware:"), ("platform:", "os:")];
+
+/// Map a reported capability key onto the canonical vocabulary.
+#[must_use]
+pub fn normalize_capability_key(key: &str) -> String {
+    let key = key.trim().to_ascii_lowercase();
+
+    let key = LEGACY_NAMESPACES
+        .iter()
+        .find_map(|(legacy, canonical)| {
+            key.strip_prefix(legacy)
+                .map(|rest| format!("{canonical}{rest}"))
+        })
+        .unwrap_or(key);
+
+    if let Some((_, canonical)) = LEGACY_KEYS.iter().find(|(legacy, _)| *legacy == key) {
+        return (*canonical).to_owned();
+    }
+
+    match key.split_once(':') {
+        Some((ns, rest))
+            if !rest.is_empty() && (ns == RAW_NAMESPACE || CANONICAL_NAMESPACES.contains(&ns)) =>
+        {
+            key
+        }
+        _ => format!("{RAW_NAMESPACE}:{key}"),
+    }
+}
+
+/// Normalize the key of a capability, keeping all other fields intact.
+#[must_use]
+pub fn normalize_syscap(mut cap: SysCap) -> SysCap {
+    cap.key = normalize_capability_key(&cap.key);
+    cap
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_keys_are_unchanged() {
+        assert_eq!(normalize_capability_key("hardware:ram"), "hardware:ram");
+        assert_eq!(normalize_capability_key("os:linux"), "os:linux");
+        assert_eq!(normalize_capability_key("raw:custom"), "raw:custom");
+    }
+
+    #[test]
+    fn legacy_namespace_and_key_are_rewritten() {
+        assert_eq!(normalize_capability_key("hw:memory"), "hardware:ram");
+        assert_eq!(normalize_capability_key("HW:CPU"), "hardware:cpu");
+        assert_eq!(normalize_capability_key("platform:macos"), "os:macos");
+    }
+
+    #[test]
+    fn unknown_keys_move_to_raw_namespace() {
+        assert_eq!(normalize_capability_key("feature_x"), "raw:feature_x");
+        assert_eq!(normalize_capability_key("vendor:thing"), "raw:vendor:thing");
+        assert_eq!(normalize_capability_key("hardware:"), "raw:hardware:");
+    }
+}
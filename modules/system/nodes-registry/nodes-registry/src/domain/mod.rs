@@ -1,4 +1,6 @@
+pub mod capability_schema;
 pub mod error;
 pub mod local_client;
+pub mod node_query;
 pub mod node_storage;
 pub mod service;
@@ -0,0 +1,427 @@
+//! `OData` listing of registered nodes
+//!
+//! Nodes are kept in memory, so `$filter`, `$orderby` and cursor paging are
+//! evaluated here over the node list instead of being pushed down to a
+//! database. Sorting always ends with `id` so every page boundary is a unique
+//! position, and cursors carry the sort keys of the last node returned.
+//!
+//! Besides the node's own fields, a listing can be filtered on the node's
+//! derived `status` and on its `capability` keys. Both come from the cached
+//! syscap, so a node whose capabilities were never collected is `pending`
+//! and has no capabilities. `capability` is a collection: it can be
+//! filtered on but not sorted by.
+
+use std::cmp::Ordering;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use modkit_macros::domain_model;
+use modkit_odata::FieldCapability;
+use modkit_odata::filter::{
+    FieldKind, FilterError, FilterField, FilterNode, FilterOp, ODataValue,
+    convert_expr_to_filter_node,
+};
+use modkit_odata::{
+    CursorV1, Error as ODataError, ODataOrderBy, ODataQuery, Page, PageInfo, SortDir,
+    validate_cursor_against,
+};
+use nodes_registry_sdk::Node;
+use uuid::Uuid;
+
+/// Page size used when the query does not set `limit`/`$top`.
+pub const DEFAULT_PAGE_LIMIT: u64 = 25;
+
+/// Largest page a single request may ask for.
+pub const MAX_PAGE_LIMIT: u64 = 1000;
+
+/// Health of a node's capability data, as exposed to `$filter`.
+#[domain_model]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NodeStatus {
+    /// Capabilities are collected and none has outlived its cache TTL.
+    Ready,
+    /// At least one collected capability has outlived its cache TTL.
+    Stale,
+    /// No capabilities have been collected yet.
+    Pending,
+}
+
+impl NodeStatus {
+    pub const ALL: &'static [Self] = &[Self::Ready, Self::Stale, Self::Pending];
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ready => "ready",
+            Self::Stale => "stale",
+            Self::Pending => "pending",
+        }
+    }
+}
+
+/// A node together with the derived data the listing filters on.
+#[domain_model]
+#[derive(Clone, Debug)]
+pub struct NodeListing {
+    pub node: Node,
+    pub status: NodeStatus,
+    /// Keys of the capabilities the node has (`present` is true).
+    pub capabilities: Vec<String>,
+}
+
+/// Node fields exposed to `$filter` and `$orderby`.
+#[domain_model]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NodeFilterField {
+    Id,
+    Hostname,
+    IpAddress,
+    CreatedAt,
+    UpdatedAt,
+    Status,
+    Capability,
+}
+
+impl FilterField for NodeFilterField {
+    const FIELDS: &'static [Self] = &[
+        Self::Id,
+        Self::Hostname,
+        Self::IpAddress,
+        Self::CreatedAt,
+        Self::UpdatedAt,
+        Self::Status,
+        Self::Capability,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Hostname => "hostname",
+            Self::IpAddress => "ip_address",
+            Self::CreatedAt => "created_at",
+            Self::UpdatedAt => "updated_at",
+            Self::Status => "status",
+            Self::Capability => "capability",
+        }
+    }
+
+    fn kind(&self) -> FieldKind {
+        match self {
+            Self::Id => FieldKind::Uuid,
+            Self::Hostname | Self::IpAddress | Self::Status | Self::Capability => FieldKind::String,
+            Self::CreatedAt | Self::UpdatedAt => FieldKind::DateTimeUtc,
+        }
+    }
+}
+
+/// A node's value for one field; `Null` sorts first.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Key {
+    Null,
+    Uuid(Uuid),
+    Text(String),
+    Time(DateTime<Utc>),
+}
+
+impl Key {
+    /// The value of a scalar field; `capability` has none and is `Null`.
+    fn of(listing: &NodeListing, field: NodeFilterField) -> Self {
+        let node = &listing.node;
+        match field {
+            NodeFilterField::Id => Self::Uuid(node.id),
+            NodeFilterField::Hostname => Self::Text(node.hostname.clone()),
+            NodeFilterField::IpAddress => node.ip_address.clone().map_or(Self::Null, Self::Text),
+            NodeFilterField::CreatedAt => Self::Time(node.created_at),
+            NodeFilterField::UpdatedAt => Self::Time(node.updated_at),
+            NodeFilterField::Status => Self::Text(listing.status.as_str().to_owned()),
+            NodeFilterField::Capability => Self::Null,
+        }
+    }
+
+    fn from_literal(value: &ODataValue) -> Option<Self> {
+        match value {
+            ODataValue::Null => Some(Self::Null),
+            ODataValue::Uuid(id) => Some(Self::Uuid(*id)),
+            ODataValue::String(s) => Some(Self::Text(s.clone())),
+            ODataValue::DateTime(at) => Some(Self::Time(*at)),
+            _ => None,
+        }
+    }
+
+    /// Cursor encoding. Values of the nullable `ip_address` are written
+    /// with a `=` prefix so that a missing address (empty string) and an
+    /// empty one (`=`) stay distinct.
+    fn encode(&self, field: NodeFilterField) -> String {
+        match self {
+            Self::Null => String::new(),
+            Self::Uuid(id) => id.to_string(),
+            Self::Text(s) if field == NodeFilterField::IpAddress => format!("={s}"),
+            Self::Text(s) => s.clone(),
+            Self::Time(at) => at.to_rfc3339_opts(SecondsFormat::Nanos, true),
+        }
+    }
+
+    fn decode(field: NodeFilterField, raw: &str) -> Result<Self, ODataError> {
+        Ok(match field.kind() {
+            FieldKind::Uuid => Self::Uuid(raw.parse().map_err(|_| ODataError::CursorInvalidKeys)?),
+            FieldKind::DateTimeUtc => Self::Time(
+                DateTime::parse_from_rfc3339(raw)
+                    .map_err(|_| ODataError::CursorInvalidKeys)?
+                    .with_timezone(&Utc),
+            ),
+            _ if field == NodeFilterField::IpAddress => match raw.strip_prefix('=') {
+                Some(ip) => Self::Text(ip.to_owned()),
+                None if raw.is_empty() => Self::Null,
+                None => return Err(ODataError::CursorInvalidKeys),
+            },
+            _ => Self::Text(raw.to_owned()),
+        })
+    }
+}
+
+/// Whether `listing` satisfies `filter`. A missing value matches only
+/// `eq null`, and `ne null` matches only a present one.
+fn matches(listing: &NodeListing, filter: &FilterNode<NodeFilterField>) -> bool {
+    match filter {
+        FilterNode::Binary {
+            field: NodeFilterField::Capability,
+            op,
+            value,
+        } => Key::from_literal(value).is_some_and(|literal| has_capability(listing, *op, &literal)),
+        FilterNode::Binary { field, op, value } => Key::from_literal(value)
+            .is_some_and(|literal| compare(&Key::of(listing, *field), *op, &literal)),
+        FilterNode::Composite {
+            op: FilterOp::And,
+            children,
+        } => children.iter().all(|child| matches(listing, child)),
+        FilterNode::Composite {
+            op: FilterOp::Or,
+            children,
+        } => children.iter().any(|child| matches(listing, child)),
+        FilterNode::Composite { .. } => false,
+        FilterNode::Not(inner) => !matches(listing, inner),
+    }
+}
+
+/// `capability eq 'k'` holds when the node has capability `k`, and `ne`
+/// when it does not; string functions hold when any key satisfies them.
+/// `eq null` matches a node without capabilities.
+fn has_capability(listing: &NodeListing, op: FilterOp, literal: &Key) -> bool {
+    let keys = &listing.capabilities;
+    match (op, literal) {
+        (FilterOp::Eq, Key::Null) => keys.is_empty(),
+        (FilterOp::Ne, Key::Null) => !keys.is_empty(),
+        (FilterOp::Ne, _) => !has_capability(listing, FilterOp::Eq, literal),
+        _ => keys
+            .iter()
+            .any(|key| compare(&Key::Text(key.clone()), op, literal)),
+    }
+}
+
+/// Reject `status` literals that no node can ever have.
+fn check_status_literals(filter: &FilterNode<NodeFilterField>) -> Result<(), ODataError> {
+    match filter {
+        FilterNode::Binary {
+            field: NodeFilterField::Status,
+            op: FilterOp::Eq | FilterOp::Ne,
+            value: ODataValue::String(status),
+        } if !NodeStatus::ALL.iter().any(|s| s.as_str() == status) => {
+            Err(ODataError::InvalidFilter(format!(
+                "unknown status: {status} (expected one of ready, stale, pending)"
+            )))
+        }
+        FilterNode::Binary { .. } => Ok(()),
+        FilterNode::Composite { children, .. } => {
+            children.iter().try_for_each(check_status_literals)
+        }
+        FilterNode::Not(inner) => check_status_literals(inner),
+    }
+}
+
+fn compare(key: &Key, op: FilterOp, literal: &Key) -> bool {
+    if *literal == Key::Null {
+        return match op {
+            FilterOp::Eq => *key == Key::Null,
+            FilterOp::Ne => *key != Key::Null,
+            _ => false,
+        };
+    }
+    if *key == Key::Null {
+        return false;
+    }
+    match (op, key, literal) {
+        (FilterOp::Eq, ..) => key == literal,
+        (FilterOp::Ne, ..) => key != literal,
+        (FilterOp::Gt, ..) => key > literal,
+        (FilterOp::Ge, ..) => key >= literal,
+        (FilterOp::Lt, ..) => key < literal,
+        (FilterOp::Le, ..) => key <= literal,
+        (FilterOp::Contains, Key::Text(s), Key::Text(part)) => s.contains(part.as_str()),
+        (FilterOp::StartsWith, Key::Text(s), Key::Text(part)) => s.starts_with(part.as_str()),
+        (FilterOp::EndsWith, Key::Text(s), Key::Text(part)) => s.ends_with(part.as_str()),
+        _ => false,
+    }
+}
+
+fn filter_error(err: FilterError) -> ODataError {
+    match err {
+        FilterError::UnknownField(name) => {
+            ODataError::InvalidFilter(format!("unknown field: {name}"))
+        }
+        other => ODataError::InvalidFilter(other.to_string()),
+    }
+}
+
+/// Resolved sort keys, always ending with the `id` tiebreaker.
+struct SortKeys {
+    order: ODataOrderBy,
+    fields: Vec<(NodeFilterField, SortDir)>,
+}
+
+impl SortKeys {
+    /// The query's `$orderby`, or the order a cursor was issued for.
+    fn for_query(query: &ODataQuery) -> Result<Self, ODataError> {
+        let order = match &query.cursor {
+            Some(cursor) => cursor.order()?,
+            None => query.order.clone().ensure_tiebreaker("id", SortDir::Asc),
+        };
+        let fields = order
+            .0
+            .iter()
+            .map(|key| match NodeFilterField::from_name(&key.field) {
+                Some(NodeFilterField::Capability) => Err(ODataError::FieldNotAllowed {
+                    field: key.field.clone(),
+                    capability: FieldCapability::Sortable,
+                }),
+                Some(field) => Ok((field, key.dir)),
+                None => Err(ODataError::InvalidOrderByField(key.field.clone())),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { order, fields })
+    }
+
+    fn keys(&self, listing: &NodeListing) -> Vec<Key> {
+        self.fields
+            .iter()
+            .map(|&(field, _)| Key::of(listing, field))
+            .collect()
+    }
+
+    fn compare(&self, a: &[Key], b: &[Key]) -> Ordering {
+        self.fields
+            .iter()
+            .zip(a.iter().zip(b))
+            .map(|(&(_, dir), (a, b))| match dir {
+                SortDir::Asc => a.cmp(b),
+                SortDir::Desc => b.cmp(a),
+            })
+            .find(|ord| ord.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    /// Decode the keys of a forward cursor issued for these sort keys.
+    fn decode_cursor(&self, cursor: &CursorV1) -> Result<Vec<Key>, ODataError> {
+        if cursor.d != "fwd" || cursor.k.len() != self.fields.len() {
+            return Err(ODataError::InvalidCursor);
+        }
+        self.fields
+            .iter()
+            .zip(&cursor.k)
+            .map(|(&(field, _), raw)| Key::decode(field, raw))
+            .collect()
+    }
+
+    fn cursor_after(&self, keys: &[Key], filter_hash: Option<&str>) -> Result<String, ODataError> {
+        let cursor = CursorV1 {
+            k: self
+                .fields
+                .iter()
+                .zip(keys)
+                .map(|(&(field, _), key)| key.encode(field))
+                .collect(),
+            o: self.fields.first().map_or(SortDir::Asc, |&(_, dir)| dir),
+            s: self.order.to_signed_tokens(),
+            f: filter_hash.map(str::to_owned),
+            d: "fwd".to_owned(),
+        };
+        cursor.encode().map_err(|_| ODataError::InvalidCursor)
+    }
+}
+
+/// One page of `nodes` matching `query`.
+///
+/// Forward cursors only: `next_cursor` is set while more nodes follow, and
+/// `prev_cursor` is always empty.
+///
+/// # Errors
+/// Returns an `OData` error (422 once mapped to a problem) for an unknown
+/// `$filter` or `$orderby` field, a literal of the wrong kind, an unknown
+/// `status`, sorting by `capability`, `$select` or `$compute` (the listing
+/// always returns whole nodes), or a cursor issued for another sort order
+/// or filter.
+pub fn paginate_nodes(
+    nodes: Vec<NodeListing>,
+    query: &ODataQuery,
+) -> Result<Page<Node>, ODataError> {
+    if query.has_select() {
+        return Err(ODataError::InvalidSelect(
+            "not supported by the nodes listing".to_owned(),
+        ));
+    }
+    if query.computed().is_some() {
+        return Err(ODataError::InvalidSelect(
+            "$compute is not supported by the nodes listing".to_owned(),
+        ));
+    }
+    let filter = query
+        .filter()
+        .map(convert_expr_to_filter_node::<NodeFilterField>)
+        .transpose()
+        .map_err(filter_error)?;
+    if let Some(filter) = &filter {
+        check_status_literals(filter)?;
+    }
+    let sort = SortKeys::for_query(query)?;
+
+    let mut matching: Vec<(Vec<Key>, Node)> = nodes
+        .into_iter()
+        .filter(|listing| filter.as_ref().is_none_or(|f| matches(listing, f)))
+        .map(|listing| (sort.keys(&listing), listing.node))
+        .collect();
+    let total = query.count.then_some(matching.len() as u64);
+    matching.sort_by(|(a, _), (b, _)| sort.compare(a, b));
+
+    let start = match &query.cursor {
+        Some(cursor) => {
+            validate_cursor_against(cursor, &sort.order, query.filter_hash.as_deref())?;
+            let after = sort.decode_cursor(cursor)?;
+            matching.partition_point(|(keys, _)| sort.compare(keys, &after).is_le())
+        }
+        None => usize::try_from(query.skip.unwrap_or(0)).unwrap_or(usize::MAX),
+    };
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_LIMIT)
+        .clamp(1, MAX_PAGE_LIMIT);
+    let mut window = matching.into_iter().skip(start);
+    let page: Vec<(Vec<Key>, Node)> = window
+        .by_ref()
+        .take(usize::try_from(limit).unwrap_or(usize::MAX))
+        .collect();
+    let next_cursor = match page.last() {
+        Some((keys, _)) if window.next().is_some() => {
+            Some(sort.cursor_after(keys, query.filter_hash.as_deref())?)
+        }
+        _ => None,
+    };
+
+    Ok(Page::new(
+        page.into_iter().map(|(_, node)| node).collect(),
+        PageInfo {
+            next_cursor,
+            prev_cursor: None,
+            limit,
+            total,
+        },
+    ))
+}
@@ -1,3 +1,5 @@
+use crate::domain::capability_schema::{normalize_capability_key, normalize_syscap};
+use crate::domain::node_query::NodeStatus;
 use modkit_macros::domain_model;
 use nodes_registry_sdk::{Node, NodeSysCap, NodeSysInfo, SysCap};
 use std::collections::HashMap;
@@ -97,7 +99,14 @@ impl NodeStorage {
     }
 
     /// Update system-collected syscap for a node
-    pub fn update_syscap_system(&self, node_id: Uuid, syscap: NodeSysCap) -> bool {
+    ///
+    /// Capability keys are normalized to the canonical vocabulary.
+    pub fn update_syscap_system(&self, node_id: Uuid, mut syscap: NodeSysCap) -> bool {
+        syscap.capabilities = syscap
+            .capabilities
+            .into_iter()
+            .map(normalize_syscap)
+            .collect();
         if let Ok(mut nodes) = self.nodes.write() {
             if let Some(data) = nodes.get_mut(&node_id) {
                 data.syscap_system = Some(syscap);
@@ -205,6 +214,7 @@ impl NodeStorage {
                 && let Some(ref syscap_system) = data.syscap_system
             {
                 let now = chrono::Utc::now();
+                let key = normalize_capability_key(key);
 
                 return syscap_system
                     .capabilities
@@ -219,6 +229,31 @@ impl NodeStorage {
         }
     }
 
+    /// Derive a node's status from its system-collected syscap
+    pub fn get_status(&self, node_id: Uuid) -> NodeStatus {
+        if let Ok(nodes) = self.nodes.read() {
+            let Some(syscap_system) = nodes
+                .get(&node_id)
+                .and_then(|data| data.syscap_system.as_ref())
+            else {
+                return NodeStatus::Pending;
+            };
+            let now = chrono::Utc::now();
+            if syscap_system
+                .capabilities
+                .iter()
+                .any(|cap| cap.cache_is_expired(now))
+            {
+                NodeStatus::Stale
+            } else {
+                NodeStatus::Ready
+            }
+        } else {
+            warn!("RwLock is poisoned in get_status, cannot access node");
+            NodeStatus::Pending
+        }
+    }
+
     /// Get all system syscap entries that need refresh
     pub fn get_expired_syscap_keys(&self, node_id: Uuid) -> Vec<String> {
         if let Ok(nodes) = self.nodes.read() {
@@ -1,7 +1,9 @@
 use crate::domain::error::DomainError;
+use crate::domain::node_query::{NodeListing, paginate_nodes};
 use crate::domain::node_storage::NodeStorage;
 use modkit_macros::domain_model;
 use modkit_node_info::NodeInfoCollector;
+use modkit_odata::{Error as ODataError, ODataQuery, Page};
 use nodes_registry_sdk::{Node, NodeSysCap, NodeSysInfo, SysCap};
 use std::sync::Arc;
 
@@ -64,6 +66,39 @@ impl Service {
         self.storage.list_nodes()
     }
 
+    /// List the nodes matching an `OData` query, one page at a time
+    ///
+    /// Status and capabilities come from the cached syscap; nothing is
+    /// collected while listing.
+    pub fn list_nodes_page(&self, query: &ODataQuery) -> Result<Page<Node>, ODataError> {
+        let listings = self
+            .storage
+            .list_nodes()
+            .into_iter()
+            .map(|node| {
+                let status = self.storage.get_status(node.id);
+                let capabilities = self
+                    .storage
+                    .get_syscap(node.id)
+                    .map(|syscap| {
+                        syscap
+                            .capabilities
+                            .into_iter()
+                            .filter(|cap| cap.present)
+                            .map(|cap| cap.key)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                NodeListing {
+                    node,
+                    status,
+                    capabilities,
+                }
+            })
+            .collect();
+        paginate_nodes(listings, query)
+    }
+
     /// Get system information for a node (with caching)
     pub fn get_node_sysinfo(&self, node_id: uuid::Uuid) -> Result<NodeSysInfo, DomainError> {
         // Check if node exists
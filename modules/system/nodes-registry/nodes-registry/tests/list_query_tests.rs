@@ -0,0 +1,280 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+//! Tests for `OData` filtering, sorting and cursor paging of the node list
+
+use axum::http::StatusCode;
+use modkit::api::odata::odata_error_to_problem;
+use modkit_odata::{CursorV1, ODataOrderBy, ODataQuery, OrderKey, SortDir, short_filter_hash};
+use nodes_registry::Node;
+use nodes_registry::domain::node_query::{NodeListing, NodeStatus, paginate_nodes};
+use uuid::Uuid;
+
+fn node(hostname: &str, ip_address: Option<&str>) -> NodeListing {
+    NodeListing {
+        node: Node {
+            id: Uuid::new_v4(),
+            hostname: hostname.to_owned(),
+            ip_address: ip_address.map(str::to_owned),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        },
+        status: NodeStatus::Pending,
+        capabilities: Vec::new(),
+    }
+}
+
+fn with_status(mut listing: NodeListing, status: NodeStatus, capabilities: &[&str]) -> NodeListing {
+    listing.status = status;
+    listing.capabilities = capabilities.iter().map(|&key| key.to_owned()).collect();
+    listing
+}
+
+fn fleet() -> Vec<NodeListing> {
+    vec![
+        with_status(
+            node("gpu-b", Some("10.0.0.2")),
+            NodeStatus::Ready,
+            &["hardware:gpu", "os:linux"],
+        ),
+        with_status(
+            node("cpu-a", Some("10.0.0.1")),
+            NodeStatus::Ready,
+            &["os:linux"],
+        ),
+        with_status(node("gpu-a", None), NodeStatus::Stale, &["hardware:gpu"]),
+        node("cpu-b", Some("10.0.0.3")),
+        with_status(
+            node("gpu-c", Some("10.0.0.4")),
+            NodeStatus::Ready,
+            &["hardware:gpu"],
+        ),
+    ]
+}
+
+fn with_filter(query: ODataQuery, raw: &str) -> ODataQuery {
+    let expr = modkit_odata::parse_filter_string(raw).unwrap().into_expr();
+    let hash = short_filter_hash(Some(&expr)).unwrap();
+    query.with_filter(expr).with_filter_hash(hash)
+}
+
+fn by_hostname() -> ODataOrderBy {
+    ODataOrderBy(vec![OrderKey {
+        field: "hostname".to_owned(),
+        dir: SortDir::Asc,
+    }])
+}
+
+fn hostnames(nodes: &[Node]) -> Vec<&str> {
+    nodes.iter().map(|n| n.hostname.as_str()).collect()
+}
+
+#[test]
+fn filter_selects_matching_nodes() {
+    let query = with_filter(
+        ODataQuery::new().with_order(by_hostname()),
+        "startswith(hostname, 'gpu') and ip_address ne null",
+    );
+
+    let page = paginate_nodes(fleet(), &query).unwrap();
+
+    assert_eq!(hostnames(&page.items), ["gpu-b", "gpu-c"]);
+    assert!(page.page_info.next_cursor.is_none());
+}
+
+#[test]
+fn filter_by_id_finds_one_node() {
+    let nodes = fleet();
+    let id = nodes[3].node.id;
+    let query = with_filter(ODataQuery::new(), &format!("id eq {id}"));
+
+    let page = paginate_nodes(nodes, &query).unwrap();
+
+    assert_eq!(hostnames(&page.items), ["cpu-b"]);
+}
+
+#[test]
+fn unknown_fields_are_rejected_with_422() {
+    let query = with_filter(ODataQuery::new(), "region eq 'eu'");
+    let err = paginate_nodes(fleet(), &query).unwrap_err();
+    let problem = odata_error_to_problem(&err, "/nodes-registry/v2/nodes", None);
+    assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(
+        problem.detail.contains("unknown field: region"),
+        "{}",
+        problem.detail
+    );
+
+    let query = ODataQuery::new().with_order(ODataOrderBy(vec![OrderKey {
+        field: "region".to_owned(),
+        dir: SortDir::Asc,
+    }]));
+    let err = paginate_nodes(fleet(), &query).unwrap_err();
+    let problem = odata_error_to_problem(&err, "/nodes-registry/v2/nodes", None);
+    assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn filter_by_status() {
+    let query = with_filter(
+        ODataQuery::new().with_order(by_hostname()),
+        "status eq 'ready'",
+    );
+    let page = paginate_nodes(fleet(), &query).unwrap();
+    assert_eq!(hostnames(&page.items), ["cpu-a", "gpu-b", "gpu-c"]);
+
+    let query = with_filter(
+        ODataQuery::new().with_order(by_hostname()),
+        "status ne 'ready'",
+    );
+    let page = paginate_nodes(fleet(), &query).unwrap();
+    assert_eq!(hostnames(&page.items), ["cpu-b", "gpu-a"]);
+}
+
+#[test]
+fn unknown_status_is_rejected_with_422() {
+    let query = with_filter(ODataQuery::new(), "status eq 'online'");
+    let err = paginate_nodes(fleet(), &query).unwrap_err();
+    let problem = odata_error_to_problem(&err, "/nodes-registry/v2/nodes", None);
+    assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(
+        problem.detail.contains("unknown status: online"),
+        "{}",
+        problem.detail
+    );
+}
+
+#[test]
+fn filter_by_capability() {
+    let query = with_filter(
+        ODataQuery::new().with_order(by_hostname()),
+        "capability eq 'hardware:gpu' and status eq 'ready'",
+    );
+    let page = paginate_nodes(fleet(), &query).unwrap();
+    assert_eq!(hostnames(&page.items), ["gpu-b", "gpu-c"]);
+
+    let query = with_filter(
+        ODataQuery::new().with_order(by_hostname()),
+        "capability ne 'hardware:gpu'",
+    );
+    let page = paginate_nodes(fleet(), &query).unwrap();
+    assert_eq!(hostnames(&page.items), ["cpu-a", "cpu-b"]);
+
+    let query = with_filter(
+        ODataQuery::new().with_order(by_hostname()),
+        "startswith(capability, 'os:')",
+    );
+    let page = paginate_nodes(fleet(), &query).unwrap();
+    assert_eq!(hostnames(&page.items), ["cpu-a", "gpu-b"]);
+
+    let query = with_filter(ODataQuery::new(), "capability eq null");
+    let page = paginate_nodes(fleet(), &query).unwrap();
+    assert_eq!(hostnames(&page.items), ["cpu-b"]);
+}
+
+#[test]
+fn sorting_by_capability_is_rejected_with_422() {
+    let query = ODataQuery::new().with_order(ODataOrderBy(vec![OrderKey {
+        field: "capability".to_owned(),
+        dir: SortDir::Asc,
+    }]));
+    let err = paginate_nodes(fleet(), &query).unwrap_err();
+    let problem = odata_error_to_problem(&err, "/nodes-registry/v2/nodes", None);
+    assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(
+        problem.detail.contains("not sortable: capability"),
+        "{}",
+        problem.detail
+    );
+}
+
+#[test]
+fn select_and_compute_are_rejected_with_422() {
+    let query = ODataQuery::new().with_select(vec!["id".to_owned()]);
+    let err = paginate_nodes(fleet(), &query).unwrap_err();
+    let problem = odata_error_to_problem(&err, "/nodes-registry/v2/nodes", None);
+    assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let query = ODataQuery::new()
+        .with_compute(modkit_odata::parse_compute("created_at add 1 as later").unwrap());
+    let err = paginate_nodes(fleet(), &query).unwrap_err();
+    let problem = odata_error_to_problem(&err, "/nodes-registry/v2/nodes", None);
+    assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(problem.detail.contains("$compute"), "{}", problem.detail);
+}
+
+#[test]
+fn cursor_round_trips_across_two_pages() {
+    let first = ODataQuery::new()
+        .with_order(ODataOrderBy(vec![OrderKey {
+            field: "hostname".to_owned(),
+            dir: SortDir::Desc,
+        }]))
+        .with_limit(3)
+        .with_count(true);
+    let nodes = fleet();
+
+    let page = paginate_nodes(nodes.clone(), &first).unwrap();
+    assert_eq!(hostnames(&page.items), ["gpu-c", "gpu-b", "gpu-a"]);
+    assert_eq!(page.page_info.total, Some(5));
+    let token = page.page_info.next_cursor.expect("a second page follows");
+
+    let second = ODataQuery::new()
+        .with_cursor(CursorV1::decode(&token).unwrap())
+        .with_limit(3);
+    let page = paginate_nodes(nodes, &second).unwrap();
+    assert_eq!(hostnames(&page.items), ["cpu-b", "cpu-a"]);
+    assert!(page.page_info.next_cursor.is_none());
+}
+
+#[test]
+fn cursor_is_bound_to_its_filter() {
+    let first = with_filter(
+        ODataQuery::new().with_order(by_hostname()).with_limit(1),
+        "startswith(hostname, 'gpu')",
+    );
+    let token = paginate_nodes(fleet(), &first)
+        .unwrap()
+        .page_info
+        .next_cursor
+        .unwrap();
+
+    let other_filter = with_filter(
+        ODataQuery::new().with_cursor(CursorV1::decode(&token).unwrap()),
+        "startswith(hostname, 'cpu')",
+    );
+    let err = paginate_nodes(fleet(), &other_filter).unwrap_err();
+    assert!(
+        matches!(err, modkit_odata::Error::FilterMismatch),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn cursor_keeps_missing_and_empty_ip_addresses_apart() {
+    let nodes = vec![
+        node("no-ip", None),
+        node("empty-ip", Some("")),
+        node("with-ip", Some("10.0.0.1")),
+    ];
+    let mut query = ODataQuery::new()
+        .with_order(ODataOrderBy(vec![OrderKey {
+            field: "ip_address".to_owned(),
+            dir: SortDir::Asc,
+        }]))
+        .with_limit(1);
+
+    let mut seen = Vec::new();
+    loop {
+        let page = paginate_nodes(nodes.clone(), &query).unwrap();
+        seen.extend(page.items.into_iter().map(|n| n.hostname));
+        let Some(token) = page.page_info.next_cursor else {
+            break;
+        };
+        assert!(seen.len() < 3, "paging must stop after three nodes");
+        query = ODataQuery::new()
+            .with_cursor(CursorV1::decode(&token).unwrap())
+            .with_limit(1);
+    }
+
+    assert_eq!(seen, ["no-ip", "empty-ip", "with-ip"]);
+}
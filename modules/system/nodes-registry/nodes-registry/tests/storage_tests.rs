@@ -4,6 +4,7 @@
 //!
 //! These tests verify storage operations, concurrency, and edge cases.
 
+use nodes_registry::domain::node_query::NodeStatus;
 use nodes_registry::domain::node_storage::NodeStorage;
 use nodes_registry::{Node, SysCap};
 use std::sync::Arc;
@@ -237,8 +238,8 @@ fn test_storage_clear_custom_syscap_preserves_system() {
     let system_syscap = nodes_registry::NodeSysCap {
         node_id,
         capabilities: vec![SysCap {
-            key: "system.test".to_owned(),
-            category: "system".to_owned(),
+            key: "hardware:test".to_owned(),
+            category: "hardware".to_owned(),
             name: "test".to_owned(),
             display_name: "System Test".to_owned(),
             present: true,
@@ -276,7 +277,7 @@ fn test_storage_clear_custom_syscap_preserves_system() {
 
     // System syscap should remain
     assert!(
-        syscap.capabilities.iter().any(|c| c.key == "system.test"),
+        syscap.capabilities.iter().any(|c| c.key == "hardware:test"),
         "System capability should remain"
     );
 
@@ -286,3 +287,77 @@ fn test_storage_clear_custom_syscap_preserves_system() {
         "Custom capability should be removed"
     );
 }
+
+fn system_cap(key: &str) -> SysCap {
+    SysCap {
+        key: key.to_owned(),
+        category: "hardware".to_owned(),
+        name: key.to_owned(),
+        display_name: key.to_owned(),
+        present: true,
+        version: None,
+        amount: None,
+        amount_dimension: None,
+        details: None,
+        cache_ttl_secs: 3600,
+        fetched_at_secs: chrono::Utc::now().timestamp(),
+    }
+}
+
+#[test]
+fn test_storage_normalizes_system_capability_keys() {
+    let storage = NodeStorage::new();
+    let node_id = Uuid::new_v4();
+
+    storage.upsert_node(Node {
+        id: node_id,
+        hostname: "test".to_owned(),
+        ip_address: None,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    });
+    storage.update_syscap_system(
+        node_id,
+        nodes_registry::NodeSysCap {
+            node_id,
+            capabilities: vec![system_cap("hw:memory"), system_cap("acme_accelerator")],
+            collected_at: chrono::Utc::now(),
+        },
+    );
+
+    let syscap = storage.get_syscap(node_id).unwrap();
+    let mut keys: Vec<&str> = syscap.capabilities.iter().map(|c| c.key.as_str()).collect();
+    keys.sort_unstable();
+
+    // Legacy key is mapped to its canonical form, unknown key is preserved under `raw:`
+    assert_eq!(keys, ["hardware:ram", "raw:acme_accelerator"]);
+    assert!(!storage.needs_syscap_refresh(node_id, "hw:memory"));
+}
+
+#[test]
+fn test_storage_status_follows_system_capability_freshness() {
+    let storage = NodeStorage::new();
+    let node_id = Uuid::new_v4();
+
+    storage.upsert_node(Node {
+        id: node_id,
+        hostname: "test".to_owned(),
+        ip_address: None,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    });
+    assert_eq!(storage.get_status(node_id), NodeStatus::Pending);
+
+    let syscap = |cap: SysCap| nodes_registry::NodeSysCap {
+        node_id,
+        capabilities: vec![cap],
+        collected_at: chrono::Utc::now(),
+    };
+    storage.update_syscap_system(node_id, syscap(system_cap("hardware:cpu")));
+    assert_eq!(storage.get_status(node_id), NodeStatus::Ready);
+
+    let mut expired = system_cap("hardware:cpu");
+    expired.fetched_at_secs -= 7200;
+    storage.update_syscap_system(node_id, syscap(expired));
+    assert_eq!(storage.get_status(node_id), NodeStatus::Stale);
+}
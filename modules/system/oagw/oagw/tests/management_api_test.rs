@@ -267,7 +267,7 @@ async fn error_mapper_produces_problem_details() {
 
     let resp = h.api_v1().get_upstream(&fake_id).expect_status(404).await;
 
-    resp.assert_header("content-type", "application/problem+json");
+    resp.assert_header("content-type", "application/problem+json; version=1");
     let json = resp.json();
     assert!(json.get("type").is_some());
     assert!(json.get("title").is_some());
@@ -52,7 +52,7 @@ impl Module for SingleTenantTrPlugin {
             id: instance_id.clone(),
             vendor: VENDOR.to_owned(),
             priority: PRIORITY,
-            properties: TenantResolverPluginSpecV1,
+            properties: TenantResolverPluginSpecV1::current(),
         };
         let instance_json = serde_json::to_value(&instance)?;
 
@@ -63,7 +63,7 @@ impl Module for StaticTrPlugin {
             id: instance_id.clone(),
             vendor: cfg.vendor.clone(),
             priority: cfg.priority,
-            properties: TenantResolverPluginSpecV1,
+            properties: TenantResolverPluginSpecV1::current(),
         };
         let instance_json = serde_json::to_value(&instance)?;
 
@@ -7,6 +7,8 @@
 use gts_macros::struct_to_gts_schema;
 use modkit::gts::BaseModkitPluginV1;
 
+use crate::plugin_api::TENANT_RESOLVER_PLUGIN_API_VERSION;
+
 /// GTS type definition for tenant resolver plugin instances.
 ///
 /// Each plugin registers an instance of this type with its vendor-specific
@@ -31,17 +33,36 @@ use modkit::gts::BaseModkitPluginV1;
 /// let instance = BaseModkitPluginV1::<TenantResolverPluginSpecV1> {
 ///     id: instance_id.clone(),
 ///     priority: 100,
-///     properties: TenantResolverPluginSpecV1,
+///     properties: TenantResolverPluginSpecV1::current(),
 /// };
 ///
 /// // Register with types-registry
 /// registry.register(&ctx, vec![serde_json::to_value(&instance)?]).await?;
 /// ```
+///
+/// # API Version
+///
+/// `api_version` declares the plugin API version the plugin was built
+/// against. The gateway checks it when selecting an instance and rejects
+/// plugins outside the range it supports.
 #[struct_to_gts_schema(
     dir_path = "schemas",
     base = BaseModkitPluginV1,
     schema_id = "gts.x.core.modkit.plugin.v1~x.core.tenant_resolver.plugin.v1~",
     description = "Tenant Resolver plugin specification",
-    properties = ""
+    properties = "api_version"
 )]
-pub struct TenantResolverPluginSpecV1;
+pub struct TenantResolverPluginSpecV1 {
+    /// Plugin API version the plugin was built against.
+    pub api_version: u32,
+}
+
+impl TenantResolverPluginSpecV1 {
+    /// Spec declaring the plugin API version of this SDK.
+    #[must_use]
+    pub fn current() -> Self {
+        Self {
+            api_version: TENANT_RESOLVER_PLUGIN_API_VERSION,
+        }
+    }
+}
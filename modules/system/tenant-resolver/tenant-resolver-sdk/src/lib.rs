@@ -52,4 +52,4 @@ pub use models::{
     GetDescendantsResponse, GetTenantsOptions, HasStatus, IsAncestorOptions, TenantId, TenantInfo,
     TenantRef, TenantStatus, matches_status,
 };
-pub use plugin_api::TenantResolverPluginClient;
+pub use plugin_api::{TENANT_RESOLVER_PLUGIN_API_VERSION, TenantResolverPluginClient};
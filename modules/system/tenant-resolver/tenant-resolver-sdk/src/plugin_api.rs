@@ -13,6 +13,14 @@ use crate::models::{
     GetTenantsOptions, IsAncestorOptions, TenantId, TenantInfo,
 };
 
+/// Version of the plugin API defined by this SDK.
+///
+/// Bump whenever the semantics of [`TenantResolverPluginClient`] change in a
+/// way an older gateway or plugin could misinterpret. Plugins declare the
+/// version they were built against in
+/// [`TenantResolverPluginSpecV1`](crate::TenantResolverPluginSpecV1).
+pub const TENANT_RESOLVER_PLUGIN_API_VERSION: u32 = 1;
+
 /// Plugin API trait for tenant resolver implementations.
 ///
 /// Each plugin registers this trait with a scoped `ClientHub` entry
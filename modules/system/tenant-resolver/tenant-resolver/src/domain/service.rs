@@ -3,18 +3,20 @@
 //! Plugin discovery is lazy: resolved on first API call after
 //! types-registry is ready.
 
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 use std::time::Duration;
 
 use modkit::client_hub::{ClientHub, ClientScope};
+use modkit::gts::BaseModkitPluginV1;
 use modkit::plugins::{GtsPluginSelector, choose_plugin_instance};
 use modkit::telemetry::ThrottledLog;
 use modkit_macros::domain_model;
 use modkit_security::SecurityContext;
 use tenant_resolver_sdk::{
     GetAncestorsOptions, GetAncestorsResponse, GetDescendantsOptions, GetDescendantsResponse,
-    GetTenantsOptions, IsAncestorOptions, TenantId, TenantInfo, TenantResolverPluginClient,
-    TenantResolverPluginSpecV1,
+    GetTenantsOptions, IsAncestorOptions, TENANT_RESOLVER_PLUGIN_API_VERSION, TenantId, TenantInfo,
+    TenantResolverPluginClient, TenantResolverPluginSpecV1,
 };
 use tracing::info;
 use types_registry_sdk::{ListQuery, TypesRegistryClient};
@@ -24,6 +26,36 @@ use super::error::DomainError;
 /// Throttle interval for unavailable plugin warnings.
 const UNAVAILABLE_LOG_THROTTLE: Duration = Duration::from_secs(10);
 
+/// Plugin API versions this gateway can talk to.
+const SUPPORTED_PLUGIN_API_VERSIONS: RangeInclusive<u32> = 1..=TENANT_RESOLVER_PLUGIN_API_VERSION;
+
+/// Reject a plugin instance whose declared API version is outside `supported`.
+fn check_plugin_api_version(
+    gts_id: &str,
+    content: &serde_json::Value,
+    supported: &RangeInclusive<u32>,
+) -> Result<(), DomainError> {
+    let instance: BaseModkitPluginV1<TenantResolverPluginSpecV1> =
+        serde_json::from_value(content.clone()).map_err(|e| {
+            DomainError::InvalidPluginInstance {
+                gts_id: gts_id.to_owned(),
+                reason: e.to_string(),
+            }
+        })?;
+    let version = instance.properties.api_version;
+    if supported.contains(&version) {
+        return Ok(());
+    }
+    Err(DomainError::InvalidPluginInstance {
+        gts_id: gts_id.to_owned(),
+        reason: format!(
+            "plugin API version {version} is outside the supported range {}..={}",
+            supported.start(),
+            supported.end()
+        ),
+    })
+}
+
 /// Tenant resolver service.
 ///
 /// Discovers plugins via types-registry and delegates API calls.
@@ -106,6 +138,9 @@ impl Service {
             &self.vendor,
             instances.iter().map(|e| (e.gts_id.as_str(), &e.content)),
         )?;
+        if let Some(entry) = instances.iter().find(|e| e.gts_id == gts_id) {
+            check_plugin_api_version(&gts_id, &entry.content, &SUPPORTED_PLUGIN_API_VERSIONS)?;
+        }
         info!(plugin_gts_id = %gts_id, "Selected tenant resolver plugin instance");
 
         Ok(gts_id)
@@ -212,3 +247,62 @@ impl Service {
             .map_err(DomainError::from)
     }
 }
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    const INSTANCE_ID: &str =
+        "gts.x.core.modkit.plugin.v1~x.core.tenant_resolver.plugin.v1~test.plugin.v1";
+
+    /// Registry content of a plugin instance declaring `api_version`.
+    fn instance_content(api_version: u32) -> serde_json::Value {
+        serde_json::json!({
+            "id": INSTANCE_ID,
+            "vendor": "test",
+            "priority": 0,
+            "properties": { "api_version": api_version },
+        })
+    }
+
+    #[test]
+    fn compatible_plugin_is_accepted() {
+        let content = instance_content(TENANT_RESOLVER_PLUGIN_API_VERSION);
+        assert!(
+            check_plugin_api_version(INSTANCE_ID, &content, &SUPPORTED_PLUGIN_API_VERSIONS).is_ok()
+        );
+    }
+
+    #[test]
+    fn incompatible_plugin_version_is_rejected() {
+        let content = instance_content(TENANT_RESOLVER_PLUGIN_API_VERSION + 1);
+        let err = check_plugin_api_version(INSTANCE_ID, &content, &SUPPORTED_PLUGIN_API_VERSIONS)
+            .unwrap_err();
+        match err {
+            DomainError::InvalidPluginInstance { gts_id, reason } => {
+                assert_eq!(gts_id, INSTANCE_ID);
+                assert!(reason.contains("outside the supported range"), "{reason}");
+            }
+            other => panic!("expected InvalidPluginInstance, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn undeclared_plugin_version_is_rejected() {
+        let mut content = instance_content(TENANT_RESOLVER_PLUGIN_API_VERSION);
+        content["properties"] = serde_json::json!({});
+        let err = check_plugin_api_version(INSTANCE_ID, &content, &SUPPORTED_PLUGIN_API_VERSIONS)
+            .unwrap_err();
+        assert!(matches!(err, DomainError::InvalidPluginInstance { .. }));
+    }
+
+    #[test]
+    fn version_range_bounds_are_inclusive() {
+        let check =
+            |version| check_plugin_api_version(INSTANCE_ID, &instance_content(version), &(1..=2));
+        assert!(check(1).is_ok());
+        assert!(check(2).is_ok());
+        assert!(check(0).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 //! REST error mapping for the Types Registry module.
 
 use modkit::api::prelude::StatusCode;
-use modkit::api::problem::Problem;
+use modkit::api::problem::{Problem, Severity, ValidationViolation};
 
 use crate::domain::error::DomainError;
 
@@ -30,7 +30,8 @@ impl From<DomainError> for Problem {
                 "Entity already exists",
                 format!("Entity with GTS ID already exists: {id}"),
             ),
-            DomainError::ValidationFailed(msg) => (
+            DomainError::ValidationFailed(msg)
+            | DomainError::SchemaValidationFailed { message: msg, .. } => (
                 StatusCode::UNPROCESSABLE_ENTITY,
                 "TYPES_REGISTRY_VALIDATION_FAILED",
                 "Validation failed",
@@ -77,6 +78,30 @@ impl From<DomainError> for Problem {
             problem = problem.with_trace_id(id);
         }
 
+        if let Some(schema_id) = e.schema_id() {
+            problem = problem.with_schema_id(schema_id);
+        }
+
+        // One entry per failing entity, so each activation failure is actionable.
+        if let Some(errors) = e.validation_errors() {
+            problem = problem
+                .with_errors(
+                    errors
+                        .iter()
+                        .map(|err| {
+                            ValidationViolation::new(&err.gts_id, &err.message)
+                                .with_code("TYPES_REGISTRY_VALIDATION_FAILED")
+                        })
+                        .collect(),
+                )
+                .with_metadata("error_count", errors.len());
+        }
+
+        // Expected while the registry is warming up; retrying later succeeds.
+        if matches!(e, DomainError::NotInReadyMode) {
+            problem = problem.with_severity(Severity::Warning);
+        }
+
         problem
     }
 }
@@ -113,11 +138,43 @@ mod tests {
         assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
     }
 
+    #[test]
+    fn test_schema_validation_failure_carries_schema_id() {
+        let err = DomainError::schema_validation_failed(
+            "gts.x.core.events.type.v1~",
+            "missing required property",
+        );
+        let problem: Problem = err.into();
+        assert_eq!(problem.status, StatusCode::UNPROCESSABLE_ENTITY);
+        let metadata = problem.metadata.expect("schema_id metadata");
+        assert_eq!(metadata["schema_id"], "gts.x.core.events.type.v1~");
+    }
+
+    #[test]
+    fn test_validation_failure_without_schema_omits_schema_id() {
+        let problem: Problem = DomainError::validation_failed("bad content").into();
+        assert!(problem.metadata.is_none());
+    }
+
     #[test]
     fn test_domain_error_to_problem_not_in_ready_mode() {
         let err = DomainError::NotInReadyMode;
         let problem: Problem = err.into();
         assert_eq!(problem.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(problem.is_retryable());
+        assert_eq!(problem.severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn test_only_unavailable_and_internal_errors_are_retryable() {
+        let internal: Problem = DomainError::Internal(anyhow::anyhow!("db down")).into();
+        assert!(internal.is_retryable());
+        assert_eq!(internal.severity(), Severity::Error);
+
+        let invalid: Problem = DomainError::validation_failed("bad content").into();
+        assert!(!invalid.is_retryable());
+        // Status-derived classification adds nothing to the payload.
+        assert!(invalid.metadata.is_none());
     }
 
     #[test]
@@ -130,6 +187,11 @@ mod tests {
         ]);
         let problem: Problem = err.into();
         assert_eq!(problem.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(problem.metadata.as_ref().unwrap()["error_count"], 3);
+        let errors = problem.errors.expect("per-entity errors");
+        let ids: Vec<&str> = errors.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(ids, ["gts.test1~", "gts.test2~", "gts.test3~"]);
+        assert_eq!(errors[1].message, "error2");
     }
 
     #[test]
@@ -62,6 +62,10 @@ pub enum DomainError {
     #[error("Validation failed: {0}")]
     ValidationFailed(String),
 
+    /// The entity content does not conform to the schema identified by `schema_id`.
+    #[error("Validation failed: {message}")]
+    SchemaValidationFailed { schema_id: String, message: String },
+
     /// The operation requires ready mode but registry is in configuration mode.
     #[error("Not in ready mode")]
     NotInReadyMode,
@@ -100,6 +104,27 @@ impl DomainError {
         Self::ValidationFailed(message.into())
     }
 
+    /// Creates a `SchemaValidationFailed` error.
+    #[must_use]
+    pub fn schema_validation_failed(
+        schema_id: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::SchemaValidationFailed {
+            schema_id: schema_id.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Returns the `$id` of the violated schema, when known.
+    #[must_use]
+    pub fn schema_id(&self) -> Option<&str> {
+        match self {
+            Self::SchemaValidationFailed { schema_id, .. } => Some(schema_id),
+            _ => None,
+        }
+    }
+
     /// Returns the list of validation errors if this is a `ReadyCommitFailed` error.
     #[must_use]
     pub fn validation_errors(&self) -> Option<&[ValidationError]> {
@@ -117,6 +142,9 @@ impl From<DomainError> for TypesRegistryError {
             DomainError::NotFound(id) => TypesRegistryError::not_found(id),
             DomainError::AlreadyExists(id) => TypesRegistryError::already_exists(id),
             DomainError::ValidationFailed(msg) => TypesRegistryError::validation_failed(msg),
+            DomainError::SchemaValidationFailed { message, .. } => {
+                TypesRegistryError::validation_failed(message)
+            }
             DomainError::NotInReadyMode => TypesRegistryError::not_in_ready_mode(),
             DomainError::ReadyCommitFailed(errors) => {
                 let error_strings: Vec<String> = errors
@@ -151,6 +179,11 @@ mod tests {
 
         let err = DomainError::validation_failed("schema invalid");
         assert!(matches!(err, DomainError::ValidationFailed(_)));
+        assert_eq!(err.schema_id(), None);
+
+        let err = DomainError::schema_validation_failed("gts.x.core.events.type.v1~", "bad");
+        assert!(matches!(err, DomainError::SchemaValidationFailed { .. }));
+        assert_eq!(err.schema_id(), Some("gts.x.core.events.type.v1~"));
     }
 
     #[test]
@@ -167,6 +200,10 @@ mod tests {
         let sdk_err: TypesRegistryError = domain_err.into();
         assert!(sdk_err.is_validation_failed());
 
+        let domain_err = DomainError::schema_validation_failed("gts.x.core.events.type.v1~", "bad");
+        let sdk_err: TypesRegistryError = domain_err.into();
+        assert!(sdk_err.is_validation_failed());
+
         let domain_err = DomainError::invalid_gts_id("bad format");
         let sdk_err: TypesRegistryError = domain_err.into();
         assert!(sdk_err.is_invalid_gts_id());
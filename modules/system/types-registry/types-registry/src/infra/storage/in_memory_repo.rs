@@ -88,6 +88,18 @@ impl InMemoryGtsRepository {
         None
     }
 
+    /// The `$id` of the schema an entity is validated against: the type part
+    /// of an instance id, or the `$schema` of a schema.
+    fn violated_schema_id(gts_id: &str, entity: &serde_json::Value) -> Option<String> {
+        if gts_id.ends_with('~') {
+            return entity
+                .get("$schema")
+                .and_then(|v| v.as_str())
+                .map(ToOwned::to_owned);
+        }
+        gts_id.rfind('~').map(|idx| gts_id[..=idx].to_owned())
+    }
+
     /// Checks if an entity matches the given query filters.
     fn matches_query(entity: &GtsEntity, query: &ListQuery) -> bool {
         if let Some(ref pattern) = query.pattern
@@ -147,7 +159,14 @@ impl GtsRepository for InMemoryGtsRepository {
 
         GtsID::new(&gts_id).map_err(|e| DomainError::invalid_gts_id(e.to_string()))?;
 
+        // Read the phase under the temporary lock: `switch_to_ready` holds it
+        // for the whole commit, so a create racing the switch either lands in
+        // temporary before it is committed or sees ready mode and checks
+        // persistent for duplicates. The lookup and insert below share one
+        // lock, so of two concurrent creates of an id only the first inserts.
+        let mut temporary = self.temporary.lock();
         if self.is_ready.load(Ordering::SeqCst) {
+            drop(temporary);
             let mut persistent = self.persistent.lock();
 
             if let Some(existing) = persistent.store.get(&gts_id) {
@@ -170,13 +189,16 @@ impl GtsRepository for InMemoryGtsRepository {
                         &mut persistent,
                     );
                 }
-                return Err(DomainError::validation_failed(result.error));
+                return Err(match Self::violated_schema_id(&gts_id, entity) {
+                    Some(schema_id) => {
+                        DomainError::schema_validation_failed(schema_id, result.error)
+                    }
+                    None => DomainError::validation_failed(result.error),
+                });
             }
 
             Self::to_gts_entity(&gts_id, entity)
         } else {
-            let mut temporary = self.temporary.lock();
-
             if let Some(existing) = temporary.store.get(&gts_id) {
                 if existing.content == *entity {
                     return Self::to_gts_entity(&gts_id, entity);
@@ -232,38 +254,36 @@ impl GtsRepository for InMemoryGtsRepository {
     fn switch_to_ready(&self) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
 
+        // Held until the ready flag is set so no create slips into temporary
+        // storage after its contents were committed
+        let mut temporary = self.temporary.lock();
+
         // Collect all GTS IDs, separating schemas (ending with ~) from instances
-        let (schema_ids, instance_ids): (Vec<String>, Vec<String>) = {
-            let temporary = self.temporary.lock();
-            temporary
-                .store
-                .items()
-                .map(|(id, _)| id.clone())
-                .partition(|id| id.ends_with('~'))
-        };
+        let (schema_ids, instance_ids): (Vec<String>, Vec<String>) = temporary
+            .store
+            .items()
+            .map(|(id, _)| id.clone())
+            .partition(|id| id.ends_with('~'));
 
         // Validate all entities in temporary storage
-        {
-            let mut temporary = self.temporary.lock();
-            for gts_id in schema_ids.iter().chain(instance_ids.iter()) {
-                let result = temporary.validate_entity(gts_id);
-                if !result.ok {
-                    // Debug logging for validation failure
-                    if let Some(entity) = temporary.store.get(gts_id) {
-                        let content = entity.content.clone();
-                        if gts_id.ends_with('~') {
-                            log_schema_validation_failure(gts_id, &content, &result.error);
-                        } else {
-                            log_instance_validation_failure(
-                                gts_id,
-                                &content,
-                                &result.error,
-                                &mut temporary,
-                            );
-                        }
+        for gts_id in schema_ids.iter().chain(instance_ids.iter()) {
+            let result = temporary.validate_entity(gts_id);
+            if !result.ok {
+                // Debug logging for validation failure
+                if let Some(entity) = temporary.store.get(gts_id) {
+                    let content = entity.content.clone();
+                    if gts_id.ends_with('~') {
+                        log_schema_validation_failure(gts_id, &content, &result.error);
+                    } else {
+                        log_instance_validation_failure(
+                            gts_id,
+                            &content,
+                            &result.error,
+                            &mut temporary,
+                        );
                     }
-                    errors.push(format!("{gts_id}: {}", result.error));
                 }
+                errors.push(format!("{gts_id}: {}", result.error));
             }
         }
 
@@ -274,7 +294,6 @@ impl GtsRepository for InMemoryGtsRepository {
         // Move to persistent: schemas first, then instances
         // This ensures schemas are available when validating instances
         {
-            let mut temporary = self.temporary.lock();
             let mut persistent = self.persistent.lock();
 
             // Add schemas first (with validation)
@@ -667,6 +686,31 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    #[test]
+    fn test_invalid_instance_reports_violated_schema_id() {
+        let repo = InMemoryGtsRepository::new(default_config());
+        repo.switch_to_ready().unwrap();
+
+        let schema = json!({
+            "$id": "gts://gts.acme.core.models.employee.v1~",
+            "$schema": JSON_SCHEMA_DRAFT_07,
+            "type": "object",
+            "properties": { "salary": { "type": "number" } },
+            "required": ["salary"]
+        });
+        repo.register(&schema, true).unwrap();
+
+        let instance = json!({
+            "id": "gts.acme.core.models.employee.v1~acme.core.instances.emp1.v1"
+        });
+        let err = repo.register(&instance, true).unwrap_err();
+        assert!(
+            matches!(err, DomainError::SchemaValidationFailed { .. }),
+            "{err:?}"
+        );
+        assert_eq!(err.schema_id(), Some("gts.acme.core.models.employee.v1~"));
+    }
+
     #[test]
     fn test_register_with_description() {
         let repo = InMemoryGtsRepository::new(default_config());
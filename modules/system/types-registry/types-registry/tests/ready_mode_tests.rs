@@ -5,6 +5,7 @@
 mod common;
 
 use common::create_service;
+use modkit::api::problem::Problem;
 use serde_json::json;
 use types_registry_sdk::ListQuery;
 
@@ -513,6 +514,40 @@ async fn test_switch_to_ready_error_contains_gts_ids() {
     );
 }
 
+#[tokio::test]
+async fn test_switch_to_ready_problem_lists_each_failing_entity() {
+    let service = create_service();
+
+    _ = service.register(vec![json!({
+        "$id": "gts://gts.acme.core.models.gadget.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "properties": {
+            "name": { "type": "string" }
+        },
+        "required": ["name"]
+    })]);
+
+    let failing: Vec<String> = (1..=3)
+        .map(|i| format!("gts.acme.core.models.gadget.v1~acme.core.instances.gadget{i}.v1"))
+        .collect();
+    let results = service.register(failing.iter().map(|id| json!({ "id": id })).collect());
+    assert!(
+        results
+            .iter()
+            .all(types_registry_sdk::RegisterResult::is_ok)
+    );
+
+    let problem: Problem = service.switch_to_ready().unwrap_err().into();
+
+    assert_eq!(problem.metadata.as_ref().unwrap()["error_count"], 3);
+    let errors = problem.errors.expect("per-entity errors");
+    let mut ids: Vec<&str> = errors.iter().map(|v| v.field.as_str()).collect();
+    ids.sort_unstable();
+    assert_eq!(ids, failing);
+    assert!(errors.iter().all(|v| !v.message.is_empty()), "{errors:?}");
+}
+
 #[tokio::test]
 async fn test_switch_to_ready_success_with_valid_types_only() {
     let service = create_service();
@@ -5,10 +5,13 @@
 mod common;
 
 use axum::http::StatusCode;
+use std::sync::Barrier;
+
 use common::create_service;
 use serde_json::json;
 use types_registry::api::rest::dto::RegisterEntitiesRequest;
-use types_registry_sdk::ListQuery;
+use types_registry::domain::service::TypesRegistryService;
+use types_registry_sdk::{ListQuery, RegisterResult};
 
 // =============================================================================
 // Anonymous Entity Rejection Tests
@@ -242,6 +245,115 @@ async fn test_registration_in_ready_mode() {
     assert!(retrieved.is_ok());
 }
 
+/// Register `entities` from one thread each, released together.
+fn register_concurrently(
+    service: &TypesRegistryService,
+    entities: Vec<serde_json::Value>,
+) -> Vec<RegisterResult> {
+    let barrier = Barrier::new(entities.len());
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = entities
+            .into_iter()
+            .map(|entity| {
+                let barrier = &barrier;
+                scope.spawn(move || {
+                    barrier.wait();
+                    service.register_validated(vec![entity]).remove(0)
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
+
+#[test]
+fn test_concurrent_conflicting_creates_yield_one_conflict() {
+    let service = create_service();
+    service.switch_to_ready().unwrap();
+
+    for round in 0..20 {
+        let gts_id = format!("gts.acme.core.events.contested_{round}.v1~");
+        let entity = |description: &str| {
+            json!({
+                "$id": format!("gts://{gts_id}"),
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "description": description
+            })
+        };
+
+        let results = register_concurrently(&service, vec![entity("first"), entity("second")]);
+
+        let winners: Vec<_> = results.iter().filter(|r| r.is_ok()).collect();
+        let conflicts = results
+            .iter()
+            .filter(|r| matches!(r, RegisterResult::Err { error, .. } if error.is_already_exists()))
+            .count();
+        assert_eq!(winners.len(), 1, "round {round}: {results:?}");
+        assert_eq!(conflicts, 1, "round {round}: {results:?}");
+
+        let stored = service.get(&gts_id).unwrap();
+        let RegisterResult::Ok(winner) = winners[0] else {
+            unreachable!()
+        };
+        assert_eq!(stored.content, winner.content);
+    }
+}
+
+#[test]
+fn test_concurrent_identical_creates_are_idempotent() {
+    let service = create_service();
+    service.switch_to_ready().unwrap();
+
+    let entity = json!({
+        "$id": "gts://gts.acme.core.events.twin.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object"
+    });
+    let results = register_concurrently(&service, vec![entity.clone(), entity]);
+
+    assert!(results.iter().all(RegisterResult::is_ok), "{results:?}");
+    assert_eq!(service.list(&ListQuery::default()).unwrap().len(), 1);
+}
+
+#[test]
+fn test_creates_racing_ready_switch_are_kept() {
+    let service = create_service();
+
+    let start = Barrier::new(2);
+    let created: Vec<RegisterResult> = std::thread::scope(|scope| {
+        let creator = scope.spawn(|| {
+            start.wait();
+            (0..200)
+                .map(|i| {
+                    service
+                        .register(vec![json!({
+                            "$id": format!("gts://gts.acme.core.events.racer_{i}.v1~"),
+                            "$schema": "http://json-schema.org/draft-07/schema#",
+                            "type": "object"
+                        })])
+                        .remove(0)
+                })
+                .collect()
+        });
+        start.wait();
+        service.switch_to_ready().unwrap();
+        creator.join().unwrap()
+    });
+
+    // Every create that succeeded is visible, whichever phase it landed in
+    for result in &created {
+        let RegisterResult::Ok(entity) = result else {
+            panic!("create failed: {result:?}");
+        };
+        assert!(
+            service.get(&entity.gts_id).is_ok(),
+            "{} lost",
+            entity.gts_id
+        );
+    }
+}
+
 #[tokio::test]
 async fn test_empty_batch_registration() {
     let service = create_service();
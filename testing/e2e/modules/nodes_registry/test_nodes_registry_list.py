@@ -185,3 +185,56 @@ async def test_list_nodes_with_details(base_url, auth_headers):
                     assert isinstance(cap["present"], bool)
                     assert "cache_ttl_secs" in cap
                     assert "fetched_at_secs" in cap
+
+
+@pytest.mark.asyncio
+async def test_list_nodes_v2_pages_with_cursor(base_url, auth_headers):
+    """
+    Test cursor paging of GET /nodes-registry/v2/nodes.
+
+    Following next_cursor with limit=1 must visit every node exactly once.
+    """
+    async with httpx.AsyncClient(timeout=10.0) as client:
+        params = {"limit": "1"}
+        seen = []
+        while True:
+            response = await client.get(
+                f"{base_url}/nodes-registry/v2/nodes",
+                headers=auth_headers,
+                params=params,
+            )
+
+            if response.status_code in (401, 403) and not auth_headers:
+                pytest.skip("Endpoint requires authentication")
+
+            assert response.status_code == 200, response.text
+            page = response.json()
+            assert len(page["items"]) <= 1
+            assert page["page_info"]["limit"] == 1
+            seen.extend(node["id"] for node in page["items"])
+
+            next_cursor = page["page_info"].get("next_cursor")
+            if not next_cursor:
+                break
+            params = {"limit": "1", "cursor": next_cursor}
+
+        assert len(seen) >= 1, "At least one node should be registered"
+        assert len(seen) == len(set(seen)), "No node should be listed twice"
+
+
+@pytest.mark.asyncio
+async def test_list_nodes_v2_rejects_select(base_url, auth_headers):
+    """
+    Test that GET /nodes-registry/v2/nodes rejects $select with a 422 problem.
+    """
+    async with httpx.AsyncClient(timeout=10.0) as client:
+        response = await client.get(
+            f"{base_url}/nodes-registry/v2/nodes",
+            headers=auth_headers,
+            params={"$select": "id"},
+        )
+
+        if response.status_code in (401, 403) and not auth_headers:
+            pytest.skip("Endpoint requires authentication")
+
+        assert response.status_code == 422, response.text